@@ -0,0 +1,4 @@
+// Rebuild when migrations change so the embedded migration set stays current
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -87,6 +87,11 @@ self.addEventListener('fetch', (event) => {
     return;
   }
 
+  // Authentication requests always go to the network and are never cached
+  if (url.pathname.startsWith('/api/auth/')) {
+    return;
+  }
+
   // API requests - Network first, cache fallback
   if (url.pathname.startsWith('/api/')) {
     event.respondWith(
@@ -163,7 +168,7 @@ async function syncTransactions() {
 
     // Send each pending transaction to the server
     for (const transaction of pendingTransactions) {
-      await fetch('/api/transactions', {
+      const response = await fetch('/api/transactions', {
         method: 'POST',
         headers: {
           'Content-Type': 'application/json',
@@ -171,6 +176,11 @@ async function syncTransactions() {
         body: JSON.stringify(transaction),
       });
 
+      // Keep the transaction queued if the session has expired; it is retried after login
+      if (!response.ok) {
+        continue;
+      }
+
       // Remove from pending after successful sync
       await removePendingTransaction(transaction.id);
     }
@@ -1,3 +1,4 @@
+import { useState, useEffect } from 'react'
 import { BrowserRouter as Router, Routes, Route, Link } from 'react-router-dom'
 import './App.css'
 import { ThemeProvider } from './context/ThemeProvider'
@@ -26,6 +27,9 @@ import RuleEdit from './components/rules/RuleEdit'
 import LedgerLayout from './components/LedgerLayout'
 import ReportsList from './components/reports/ReportsList'
 import SettingsPage from './components/settings/SettingsPage'
+import Login from './components/auth/Login'
+import { authApi, UNAUTHORIZED_EVENT } from './services/api'
+import type { CurrentUser } from './services/api'
 
 // Theme toggle button component
 const ThemeToggle = () => {
@@ -45,6 +49,45 @@ const ThemeToggle = () => {
 // App content component (separated to use the theme context)
 const AppContent = () => {
   const { theme } = useTheme();
+  // undefined while the session is being checked, null when logged out
+  const [currentUser, setCurrentUser] = useState<CurrentUser | null | undefined>(undefined);
+
+  useEffect(() => {
+    authApi.getCurrentUser()
+      .then(setCurrentUser)
+      .catch((err) => {
+        console.error('Error fetching current user:', err);
+        setCurrentUser(null);
+      });
+
+    // Show the login screen again when the session expires
+    const handleUnauthorized = () => setCurrentUser(null);
+    window.addEventListener(UNAUTHORIZED_EVENT, handleUnauthorized);
+    return () => window.removeEventListener(UNAUTHORIZED_EVENT, handleUnauthorized);
+  }, []);
+
+  const handleLogout = async () => {
+    try {
+      await authApi.logout();
+    } catch (err) {
+      console.error('Error logging out:', err);
+    }
+    setCurrentUser(null);
+  };
+
+  if (currentUser === undefined) {
+    return <div className={`app ${theme}-theme`} />;
+  }
+
+  if (currentUser === null) {
+    return (
+      <div className={`app ${theme}-theme`}>
+        <main className="container">
+          <Login onLogin={setCurrentUser} />
+        </main>
+      </div>
+    );
+  }
 
   return (
     <div className={`app ${theme}-theme`}>
@@ -65,6 +108,13 @@ const AppContent = () => {
             </ul>
           </nav>
           <ThemeToggle />
+          <button
+            onClick={handleLogout}
+            className="secondary small"
+            title={`Logged in as ${currentUser.user.username}`}
+          >
+            Log out
+          </button>
         </div>
       </header>
 
@@ -0,0 +1,105 @@
+import { useState, useEffect } from 'react';
+import { authApi } from '../../services/api';
+import type { CurrentUser } from '../../services/api';
+
+interface LoginProps {
+  onLogin: (currentUser: CurrentUser) => void;
+}
+
+const Login = ({ onLogin }: LoginProps) => {
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [registrationOpen, setRegistrationOpen] = useState(false);
+  const [registering, setRegistering] = useState(false);
+
+  // Form state
+  const [username, setUsername] = useState('');
+  const [password, setPassword] = useState('');
+
+  // Only offer registration when the server accepts new users
+  useEffect(() => {
+    authApi.getStatus()
+      .then((status) => setRegistrationOpen(status.registration_open))
+      .catch((err) => console.error('Error fetching authentication status:', err));
+  }, []);
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+
+    if (!username || !password) {
+      setError('Username and password are required');
+      return;
+    }
+
+    try {
+      setLoading(true);
+      setError(null);
+
+      const currentUser = registering
+        ? await authApi.register(username, password)
+        : await authApi.login(username, password);
+      onLogin(currentUser);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to log in. Please try again.');
+      console.error('Error logging in:', err);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div className="login">
+      <h1>{registering ? 'Create Account' : 'Log In'}</h1>
+
+      {error && <div className="error">{error}</div>}
+
+      <form onSubmit={handleSubmit}>
+        <div className="form-group">
+          <label htmlFor="username">Username</label>
+          <input
+            type="text"
+            id="username"
+            autoComplete="username"
+            value={username}
+            onChange={(e) => setUsername(e.target.value)}
+            required
+          />
+        </div>
+
+        <div className="form-group">
+          <label htmlFor="password">Password</label>
+          <input
+            type="password"
+            id="password"
+            autoComplete={registering ? 'new-password' : 'current-password'}
+            value={password}
+            onChange={(e) => setPassword(e.target.value)}
+            minLength={registering ? 8 : undefined}
+            required
+          />
+        </div>
+
+        <div className="form-actions">
+          <button type="submit" disabled={loading}>
+            {loading ? 'Please wait...' : registering ? 'Create Account' : 'Log In'}
+          </button>
+          {registrationOpen && (
+            <button
+              type="button"
+              className="secondary"
+              onClick={() => {
+                setRegistering(!registering);
+                setError(null);
+              }}
+              disabled={loading}
+            >
+              {registering ? 'I already have an account' : 'Create an account'}
+            </button>
+          )}
+        </div>
+      </form>
+    </div>
+  );
+};
+
+export default Login;
@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { budgetsApi, budgetGroupsApi } from '../../services/api';
-import type { CategoryGroup as BudgetGroup } from '../../services/api';
+import type { CategoryGroup as BudgetGroup, BudgetGoalType } from '../../services/api';
 
 const BudgetEdit = () => {
   const { id } = useParams<{ id: string }>();
@@ -16,6 +16,9 @@ const BudgetEdit = () => {
   const [amount, setAmount] = useState('0');
   const [startDate, setStartDate] = useState('');
   const [endDate, setEndDate] = useState('');
+  const [goalType, setGoalType] = useState<BudgetGoalType | ''>('');
+  const [goalAmount, setGoalAmount] = useState('');
+  const [goalTargetDate, setGoalTargetDate] = useState('');
 
   // Budget groups
   const [budgetGroups, setBudgetGroups] = useState<BudgetGroup[]>([]);
@@ -50,6 +53,9 @@ const BudgetEdit = () => {
         setStartDate(formatDateForInput(budget.start_date));
         setEndDate(formatDateForInput(budget.end_date));
         setSelectedGroupId(budget.group_id || '');
+        setGoalType(budget.goal_type || '');
+        setGoalAmount(budget.goal_amount != null ? budget.goal_amount.toString() : '');
+        setGoalTargetDate(budget.goal_target_date || '');
 
         setLoading(false);
         setGroupsLoading(false);
@@ -95,6 +101,10 @@ const BudgetEdit = () => {
         start_date: startDateISO,
         end_date: endDateISO,
         group_id: selectedGroupId || undefined,
+        // An empty goal type removes the goal
+        goal_type: goalType,
+        goal_amount: goalType ? parseFloat(goalAmount) : undefined,
+        goal_target_date: goalType === 'target_balance' ? goalTargetDate : undefined,
       });
 
       // Redirect to budget view on success
@@ -197,6 +207,48 @@ const BudgetEdit = () => {
           <small className="form-text">Leave blank for ongoing budgets</small>
         </div>
 
+        <div className="form-group">
+          <label htmlFor="goal-type">Goal (Optional)</label>
+          <select
+            id="goal-type"
+            value={goalType}
+            onChange={(e) => setGoalType(e.target.value as BudgetGoalType | '')}
+          >
+            <option value="">No Goal</option>
+            <option value="monthly_funding">Fund a monthly amount</option>
+            <option value="target_balance">Save a balance by a date</option>
+            <option value="spending_cap">Cap monthly spending</option>
+          </select>
+        </div>
+
+        {goalType && (
+          <div className="form-group">
+            <label htmlFor="goal-amount">Goal Amount</label>
+            <input
+              type="number"
+              id="goal-amount"
+              value={goalAmount}
+              onChange={(e) => setGoalAmount(e.target.value)}
+              step="0.01"
+              min="0"
+              required
+            />
+          </div>
+        )}
+
+        {goalType === 'target_balance' && (
+          <div className="form-group">
+            <label htmlFor="goal-target-date">Target Date</label>
+            <input
+              type="date"
+              id="goal-target-date"
+              value={goalTargetDate}
+              onChange={(e) => setGoalTargetDate(e.target.value)}
+              required
+            />
+          </div>
+        )}
+
         <div className="form-actions">
           <button type="submit" disabled={saving}>
             {saving ? 'Saving...' : 'Save Changes'}
@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { budgetsApi, budgetGroupsApi } from '../../services/api';
-import type { CategoryGroup as BudgetGroup } from '../../services/api';
+import type { CategoryGroup as BudgetGroup, BudgetGoalType } from '../../services/api';
 
 const BudgetNew = () => {
   const navigate = useNavigate();
@@ -14,6 +14,9 @@ const BudgetNew = () => {
   const [amount, setAmount] = useState('0');
   const [startDate, setStartDate] = useState(new Date().toISOString().split('T')[0]); // Today's date in YYYY-MM-DD format
   const [endDate, setEndDate] = useState(''); // Empty string for no end date (ongoing budget)
+  const [goalType, setGoalType] = useState<BudgetGoalType | ''>('');
+  const [goalAmount, setGoalAmount] = useState('');
+  const [goalTargetDate, setGoalTargetDate] = useState('');
 
   // Budget groups
   const [budgetGroups, setBudgetGroups] = useState<BudgetGroup[]>([]);
@@ -65,6 +68,9 @@ const BudgetNew = () => {
         start_date: startDateISO,
         end_date: endDateISO,
         group_id: selectedGroupId || undefined,
+        goal_type: goalType || undefined,
+        goal_amount: goalType ? parseFloat(goalAmount) : undefined,
+        goal_target_date: goalType === 'target_balance' ? goalTargetDate : undefined,
       });
 
       // Redirect to budgets list on success
@@ -159,6 +165,48 @@ const BudgetNew = () => {
           <small className="form-text">Leave blank for ongoing budgets</small>
         </div>
 
+        <div className="form-group">
+          <label htmlFor="goal-type">Goal (Optional)</label>
+          <select
+            id="goal-type"
+            value={goalType}
+            onChange={(e) => setGoalType(e.target.value as BudgetGoalType | '')}
+          >
+            <option value="">No Goal</option>
+            <option value="monthly_funding">Fund a monthly amount</option>
+            <option value="target_balance">Save a balance by a date</option>
+            <option value="spending_cap">Cap monthly spending</option>
+          </select>
+        </div>
+
+        {goalType && (
+          <div className="form-group">
+            <label htmlFor="goal-amount">Goal Amount</label>
+            <input
+              type="number"
+              id="goal-amount"
+              value={goalAmount}
+              onChange={(e) => setGoalAmount(e.target.value)}
+              step="0.01"
+              min="0"
+              required
+            />
+          </div>
+        )}
+
+        {goalType === 'target_balance' && (
+          <div className="form-group">
+            <label htmlFor="goal-target-date">Target Date</label>
+            <input
+              type="date"
+              id="goal-target-date"
+              value={goalTargetDate}
+              onChange={(e) => setGoalTargetDate(e.target.value)}
+              required
+            />
+          </div>
+        )}
+
         <div className="form-actions">
           <button type="submit" disabled={loading}>
             {loading ? 'Creating...' : 'Create Budget'}
@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import { useParams, Link, useNavigate } from 'react-router-dom';
 import { budgetsApi } from '../../services/api';
 import type { Transaction } from '../../services/api';
-import type { Budget } from '../../services/api';
+import type { Budget, BudgetTransfer } from '../../services/api';
 
 const BudgetView = () => {
   const { id } = useParams<{ id: string }>();
@@ -12,6 +12,8 @@ const BudgetView = () => {
   const [remaining, setRemaining] = useState<number>(0);
   const [loading, setLoading] = useState(true);
   const [transactions, setTransactions] = useState<Transaction[]>([]);
+  const [transfers, setTransfers] = useState<BudgetTransfer[]>([]);
+  const [budgetNames, setBudgetNames] = useState<Map<string, string>>(new Map());
   const [error, setError] = useState<string | null>(null);
 
   useEffect(() => {
@@ -33,6 +35,15 @@ const BudgetView = () => {
           console.error('Error fetching budget transactions:', e);
         }
 
+        // Fetch the money moved to and from this budget
+        try {
+          const [moved, budgets] = await Promise.all([budgetsApi.getBudgetTransfers(id), budgetsApi.getBudgets()]);
+          setTransfers(moved);
+          setBudgetNames(new Map(budgets.map(b => [b.id, b.name])));
+        } catch (e) {
+          console.error('Error fetching budget transfers:', e);
+        }
+
         // Fetch spent amount
         const spentAmount = await budgetsApi.getBudgetSpent(id);
         setSpent(spentAmount);
@@ -171,6 +182,38 @@ const BudgetView = () => {
           </table>
         )}
       </div>
+
+      <div className="budget-transactions">
+        <h2>Money Moved</h2>
+        {transfers.length === 0 ? (
+          <p className="note">No money was moved to or from this budget.</p>
+        ) : (
+          <table className="transactions-table">
+            <thead>
+              <tr>
+                <th>Month</th>
+                <th>From / To</th>
+                <th>Note</th>
+                <th>Amount</th>
+              </tr>
+            </thead>
+            <tbody>
+              {transfers.map(transfer => {
+                const incoming = transfer.to_budget_id === id;
+                const other = incoming ? transfer.from_budget_id : transfer.to_budget_id;
+                return (
+                  <tr key={transfer.id}>
+                    <td>{transfer.month.slice(0, 7)}</td>
+                    <td>{incoming ? 'From' : 'To'} {budgetNames.get(other) || 'another budget'}</td>
+                    <td>{transfer.note || '—'}</td>
+                    <td style={{ textAlign: 'right' }}>{(incoming ? transfer.amount : -transfer.amount).toFixed(2)}</td>
+                  </tr>
+                );
+              })}
+            </tbody>
+          </table>
+        )}
+      </div>
     </div>
   );
 };
@@ -1,13 +1,26 @@
 import { useState, useEffect } from 'react';
 import { Link } from 'react-router-dom';
 import { budgetsApi, settingsApi, budgetGroupsApi } from '../../services/api';
-import type { Budget, MonthlyBudgetStatus, CategoryGroup as BudgetGroup } from '../../services/api';
+import type { Budget, BudgetAmountSource, MonthlyBudgetStatus, ReadyToAssign, CategoryGroup as BudgetGroup } from '../../services/api';
 import './BudgetsList.css';
 
+// Budget with its amounts in the selected month
 interface BudgetWithSpent extends Budget {
+  assigned: number;
   spent: number;
+  available: number;
 }
 
+// Short description of how a budget is doing against its goal this month
+const goalStatus = (budget: Budget): string | null => {
+  const progress = budget.goal_progress;
+  if (!progress) return null;
+  if (progress.over_cap) return `Over cap by ${(progress.spent - (budget.goal_amount ?? 0)).toFixed(2)}`;
+  if (progress.underfunded) return `Needs ${progress.still_needed.toFixed(2)} more this month`;
+  if (budget.goal_type === 'spending_cap') return `${Math.round(progress.progress * 100)}% of cap spent`;
+  return 'Goal funded';
+};
+
 const BudgetsList = () => {
   const [budgets, setBudgets] = useState<BudgetWithSpent[]>([]);
   const [budgetGroups, setBudgetGroups] = useState<BudgetGroup[]>([]);
@@ -19,6 +32,7 @@ const BudgetsList = () => {
   const [unbudgetedSpentLoading, setUnbudgetedSpentLoading] = useState(true);
   const [monthlyStatus, setMonthlyStatus] = useState<MonthlyBudgetStatus | null>(null);
   const [statusLoading, setStatusLoading] = useState(true);
+  const [readyToAssign, setReadyToAssign] = useState<ReadyToAssign | null>(null);
   const [isEditingForecast, setIsEditingForecast] = useState(false);
   const [forecastedIncome, setForecastedIncome] = useState<string>('');
 
@@ -28,6 +42,19 @@ const BudgetsList = () => {
   const [creatingGroup, setCreatingGroup] = useState<boolean>(false);
   const [createGroupError, setCreateGroupError] = useState<string | null>(null);
 
+  // Move money between budgets form state
+  const [moveFromId, setMoveFromId] = useState<string>('');
+  const [moveToId, setMoveToId] = useState<string>('');
+  const [moveAmount, setMoveAmount] = useState<string>('');
+  const [moveNote, setMoveNote] = useState<string>('');
+  const [moveError, setMoveError] = useState<string | null>(null);
+
+  // Next month's budgets from the selected month, with an amount source per group
+  const [copyAmountSource, setCopyAmountSource] = useState<BudgetAmountSource>('source_month');
+  const [copyGroupSources, setCopyGroupSources] = useState<Record<string, BudgetAmountSource>>({});
+  const [copyResult, setCopyResult] = useState<string | null>(null);
+  const [copying, setCopying] = useState<boolean>(false);
+
   // Drag & Drop state for moving budgets between groups
   const [draggedBudget, setDraggedBudget] = useState<BudgetWithSpent | null>(null);
   const [dragOverGroupId, setDragOverGroupId] = useState<string | null>(null); // null => Ungrouped
@@ -88,21 +115,26 @@ const BudgetsList = () => {
     const fetchBudgets = async () => {
       try {
         setLoading(true);
-        const data = await budgetsApi.getBudgets();
-
-        // Fetch spent amount for each budget
-        const budgetsWithSpent = await Promise.all(
-          data.map(async (budget) => {
-            const spent = await budgetsApi.getBudgetSpent(budget.id, selectedYear, selectedMonth);
-            return { ...budget, spent };
-          })
-        );
+        const [data, budgetMonth] = await Promise.all([
+          budgetsApi.getBudgets(),
+          budgetsApi.getBudgetMonth(selectedYear, selectedMonth),
+        ]);
+
+        // Assigned, spent and available amounts (with carry-over) of each budget in the month
+        const lines = new Map(budgetMonth.budgets.map(line => [line.budget_id, line]));
+        const budgetsWithSpent = data.map(budget => {
+          const line = lines.get(budget.id);
+          return {
+            ...budget,
+            assigned: line?.assigned ?? 0,
+            spent: line?.activity ?? 0,
+            available: line?.available ?? 0,
+          };
+        });
 
         setBudgets(budgetsWithSpent);
-
-        // Calculate total allocated amount
-        const total = data.reduce((sum, budget) => sum + budget.amount, 0);
-        setTotalAllocated(total);
+        setTotalAllocated(budgetMonth.assigned);
+        setReadyToAssign(await budgetsApi.getReadyToAssign());
 
         setLoading(false);
       } catch (err) {
@@ -163,7 +195,7 @@ const BudgetsList = () => {
         // Recalculate total allocated amount
         const total = budgets
           .filter(budget => budget.id !== id)
-          .reduce((sum, budget) => sum + budget.amount, 0);
+          .reduce((sum, budget) => sum + budget.assigned, 0);
         setTotalAllocated(total);
       } catch (err) {
         setError('Failed to delete budget. Please try again later.');
@@ -172,6 +204,62 @@ const BudgetsList = () => {
     }
   };
 
+  // Move money between two budgets in the selected month
+  const handleMoveMoney = async (e: React.FormEvent) => {
+    e.preventDefault();
+    const amount = parseFloat(moveAmount);
+    if (!moveFromId || !moveToId || moveFromId === moveToId || isNaN(amount) || amount <= 0) {
+      setMoveError('Choose two different budgets and a positive amount');
+      return;
+    }
+
+    try {
+      setMoveError(null);
+      await budgetsApi.createBudgetTransfer({
+        from_budget_id: moveFromId,
+        to_budget_id: moveToId,
+        month: `${selectedYear}-${String(selectedMonth).padStart(2, '0')}`,
+        amount,
+        note: moveNote.trim() || undefined,
+      });
+      setBudgets(prev => prev.map(b => {
+        if (b.id === moveFromId) return { ...b, available: b.available - amount };
+        if (b.id === moveToId) return { ...b, available: b.available + amount };
+        return b;
+      }));
+      setMoveAmount('');
+      setMoveNote('');
+    } catch (err) {
+      console.error('Error moving money between budgets:', err);
+      setMoveError('Failed to move money. Please try again.');
+    }
+  };
+
+  // Create next month's budgets from the selected month and switch to it
+  const handleCopyToNextMonth = async (e: React.FormEvent) => {
+    e.preventDefault();
+    const next = new Date(selectedYear, selectedMonth, 1);
+    const month = `${next.getFullYear()}-${String(next.getMonth() + 1).padStart(2, '0')}`;
+
+    try {
+      setCopying(true);
+      setCopyResult(null);
+      const copied = await budgetsApi.copyBudgetMonth(month, {
+        source_month: `${selectedYear}-${String(selectedMonth).padStart(2, '0')}`,
+        amount_source: copyAmountSource,
+        group_amount_sources: copyGroupSources,
+      });
+      setCopyResult(`Created ${copied.created.length} budgets for ${getMonthName(next.getMonth() + 1)}`
+        + (copied.skipped.length > 0 ? `, ${copied.skipped.length} already existed` : ''));
+      changeMonth(1);
+    } catch (err) {
+      console.error('Error copying budgets to the next month:', err);
+      setCopyResult('Failed to create next month\'s budgets. Please try again.');
+    } finally {
+      setCopying(false);
+    }
+  };
+
   // Create a new budget group from this page
   const handleCreateBudgetGroup = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -310,9 +398,9 @@ const BudgetsList = () => {
 
   // Precompute ungrouped budgets for reuse
   const ungroupedBudgets = budgets.filter(b => !b.group_id);
-  const ungroupedAmount = ungroupedBudgets.reduce((sum, b) => sum + b.amount, 0);
+  const ungroupedAmount = ungroupedBudgets.reduce((sum, b) => sum + b.assigned, 0);
   const ungroupedSpent = ungroupedBudgets.reduce((sum, b) => sum + (b.spent || 0), 0);
-  const ungroupedRemaining = ungroupedAmount - ungroupedSpent;
+  const ungroupedRemaining = ungroupedBudgets.reduce((sum, b) => sum + b.available, 0);
 
   return (
     <div className="budgets-list">
@@ -345,6 +433,80 @@ const BudgetsList = () => {
         </form>
       </div>
 
+      {/* Move money between budgets in the selected month */}
+      <div className="move-money" style={{ margin: '16px 0' }}>
+        <h2 style={{ marginTop: 0 }}>Move Money</h2>
+        {moveError && <div className="error">{moveError}</div>}
+        <form onSubmit={handleMoveMoney} style={{ display: 'flex', gap: '8px', alignItems: 'center', flexWrap: 'wrap' }}>
+          <select value={moveFromId} onChange={(e) => setMoveFromId(e.target.value)} required>
+            <option value="">From budget</option>
+            {budgets.map(b => <option key={b.id} value={b.id}>{b.name}</option>)}
+          </select>
+          <select value={moveToId} onChange={(e) => setMoveToId(e.target.value)} required>
+            <option value="">To budget</option>
+            {budgets.map(b => <option key={b.id} value={b.id}>{b.name}</option>)}
+          </select>
+          <input
+            type="number"
+            step="0.01"
+            min="0.01"
+            placeholder="Amount"
+            value={moveAmount}
+            onChange={(e) => setMoveAmount(e.target.value)}
+            required
+          />
+          <input
+            type="text"
+            placeholder="Note (optional)"
+            value={moveNote}
+            onChange={(e) => setMoveNote(e.target.value)}
+          />
+          <button type="submit" className="button">Move</button>
+        </form>
+      </div>
+
+      {/* Next month's budgets from this month's */}
+      <div className="copy-month" style={{ margin: '16px 0' }}>
+        <h2 style={{ marginTop: 0 }}>Start Next Month</h2>
+        {copyResult && <p>{copyResult}</p>}
+        <form onSubmit={handleCopyToNextMonth} style={{ display: 'flex', gap: '8px', alignItems: 'center', flexWrap: 'wrap' }}>
+          <label htmlFor="copy-amount-source">Amounts from</label>
+          <select
+            id="copy-amount-source"
+            value={copyAmountSource}
+            onChange={(e) => setCopyAmountSource(e.target.value as BudgetAmountSource)}
+          >
+            <option value="source_month">{getMonthName(selectedMonth)}'s budgets</option>
+            <option value="last_month_spending">Last month's spending</option>
+            <option value="three_month_average">3-month average spending</option>
+          </select>
+          {budgetGroups.map(group => (
+            <select
+              key={group.id}
+              value={copyGroupSources[group.id] ?? ''}
+              onChange={(e) => {
+                const value = e.target.value as BudgetAmountSource | '';
+                setCopyGroupSources(prev => {
+                  const sources = { ...prev };
+                  if (value) sources[group.id] = value;
+                  else delete sources[group.id];
+                  return sources;
+                });
+              }}
+              aria-label={`Amounts for ${group.name}`}
+            >
+              <option value="">{group.name}: same</option>
+              <option value="source_month">{group.name}: budgets</option>
+              <option value="last_month_spending">{group.name}: last month's spending</option>
+              <option value="three_month_average">{group.name}: 3-month average</option>
+            </select>
+          ))}
+          <button type="submit" className="button" disabled={copying}>
+            {copying ? 'Creating...' : 'Create Next Month\'s Budgets'}
+          </button>
+        </form>
+      </div>
+
       {/* Monthly Budget Status */}
       <div className="monthly-budget-status">
         <div className="month-nav" style={{ display: 'flex', alignItems: 'center', gap: '8px' }}>
@@ -443,6 +605,19 @@ const BudgetsList = () => {
       </div>
 
       <div className="summary-section">
+        {readyToAssign && (
+          <div className="summary-box">
+            <h2>Ready to Assign</h2>
+            <p className={`remaining-amount ${readyToAssign.ready_to_assign >= 0 ? 'positive' : 'negative'}`}>
+              {readyToAssign.ready_to_assign.toFixed(2)}
+            </p>
+            <p className="subtitle">
+              On budget {readyToAssign.on_budget_balance.toFixed(2)} minus {readyToAssign.available_in_budgets.toFixed(2)} in budgets
+              {readyToAssign.overspent < 0 && ` and ${(-readyToAssign.overspent).toFixed(2)} overspent in earlier months`}
+            </p>
+          </div>
+        )}
+
         <div className="summary-box">
           <h2>Total Allocated</h2>
           <p className="total-allocated">{totalAllocated.toFixed(2)}</p>
@@ -520,8 +695,15 @@ const BudgetsList = () => {
                       onDragEnd={handleBudgetDragEnd}
                       className={draggedBudget?.id === budget.id ? 'dragging' : ''}
                     >
-                      <td className="category-cell"><Link to={`/budgets/${budget.id}`}>{budget.name}</Link></td>
-                      <td className="numeric">{budget.amount.toFixed(2)}</td>
+                      <td className="category-cell">
+                        <Link to={`/budgets/${budget.id}`}>{budget.name}</Link>
+                        {goalStatus(budget) && (
+                          <small className={`goal-status ${budget.goal_progress?.underfunded || budget.goal_progress?.over_cap ? 'negative' : 'positive'}`}>
+                            {goalStatus(budget)}
+                          </small>
+                        )}
+                      </td>
+                      <td className="numeric">{budget.assigned.toFixed(2)}</td>
                       <td className="numeric">
                         {(() => {
                           const startDate = `${selectedYear}-${String(selectedMonth).padStart(2, '0')}-01`;
@@ -535,7 +717,7 @@ const BudgetsList = () => {
                           );
                         })()}
                       </td>
-                      <td className="numeric">{(budget.amount - budget.spent).toFixed(2)}</td>
+                      <td className="numeric">{budget.available.toFixed(2)}</td>
                       <td>
                         <div className="actions">
                           <Link to={`/budgets/${budget.id}`} className="button small">View</Link>
@@ -553,9 +735,9 @@ const BudgetsList = () => {
           {/* Budgets grouped by budget groups */}
           {budgetGroups.map(group => {
             const groupBudgets = budgets.filter(b => b.group_id === group.id);
-            const groupAmount = groupBudgets.reduce((sum, b) => sum + b.amount, 0);
+            const groupAmount = groupBudgets.reduce((sum, b) => sum + b.assigned, 0);
             const groupSpent = groupBudgets.reduce((sum, b) => sum + (b.spent || 0), 0);
-            const groupRemaining = groupAmount - groupSpent;
+            const groupRemaining = groupBudgets.reduce((sum, b) => sum + b.available, 0);
             return (
               <div
                 key={group.id}
@@ -572,6 +754,11 @@ const BudgetsList = () => {
                       <span className="group-total">Total: {groupAmount.toFixed(2)}</span>
                       <span className="group-spent">Spent: {groupSpent.toFixed(2)}</span>
                       <span className={`remaining-amount ${groupRemaining >= 0 ? 'positive' : 'negative'}`}>Remaining: {groupRemaining.toFixed(2)}</span>
+                      {group.goals && group.goals.underfunded_budgets > 0 && (
+                        <span className="group-goals negative">
+                          Underfunded: {group.goals.underfunded_budgets} (needs {group.goals.still_needed.toFixed(2)})
+                        </span>
+                      )}
                     </div>
                     <button onClick={() => handleDeleteBudgetGroup(group.id)} className="button small danger">Delete Group</button>
                   </div>
@@ -599,8 +786,15 @@ const BudgetsList = () => {
                           onDragEnd={handleBudgetDragEnd}
                           className={draggedBudget?.id === budget.id ? 'dragging' : ''}
                         >
-                          <td className="category-cell"><Link to={`/budgets/${budget.id}`}>{budget.name}</Link></td>
-                          <td className="numeric">{budget.amount.toFixed(2)}</td>
+                          <td className="category-cell">
+                            <Link to={`/budgets/${budget.id}`}>{budget.name}</Link>
+                            {goalStatus(budget) && (
+                              <small className={`goal-status ${budget.goal_progress?.underfunded || budget.goal_progress?.over_cap ? 'negative' : 'positive'}`}>
+                                {goalStatus(budget)}
+                              </small>
+                            )}
+                          </td>
+                          <td className="numeric">{budget.assigned.toFixed(2)}</td>
                           <td className="numeric">
                             {(() => {
                               const startDate = `${selectedYear}-${String(selectedMonth).padStart(2, '0')}-01`;
@@ -614,7 +808,7 @@ const BudgetsList = () => {
                               );
                             })()}
                           </td>
-                          <td className="numeric">{(budget.amount - budget.spent).toFixed(2)}</td>
+                          <td className="numeric">{budget.available.toFixed(2)}</td>
                           <td>
                             <div className="actions">
                               <Link to={`/budgets/${budget.id}`} className="button small">View</Link>
@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { rulesApi } from '../../services/api';
-import type { Rule, RuleCondition, RuleAction, ConditionType, ActionType } from '../../services/api';
+import type { Rule, RuleConditionNode, RuleAction, RuleTrigger, ActionType } from '../../services/api';
 import RuleForm from './RuleForm';
 
 const RuleEdit = () => {
@@ -39,27 +39,20 @@ const RuleEdit = () => {
     description?: string;
     is_active: boolean;
     priority?: number;
-    conditions: Array<{
-      condition_type: string;
-      value: string;
-    }>;
+    conditions: RuleConditionNode[];
     actions: Array<{
       action_type: string;
       value: string;
     }>;
+    triggers: RuleTrigger[];
   }) => {
     if (!id) return;
 
     try {
       // Convert string types to proper enum types
-      const typedConditions: RuleCondition[] = ruleData.conditions.map(c => ({
-        condition_type: c.condition_type as ConditionType,
-        value: c.value
-      }));
-
       const typedActions: RuleAction[] = ruleData.actions.map(a => ({
-        action_type: a.action_type as ActionType,
-        value: a.value
+        ...a,
+        action_type: a.action_type as ActionType
       }));
 
       // Extract only the fields that updateRule expects
@@ -68,8 +61,9 @@ const RuleEdit = () => {
         description: ruleData.description,
         is_active: ruleData.is_active,
         priority: ruleData.priority,
-        conditions: typedConditions,
-        actions: typedActions
+        conditions: ruleData.conditions,
+        actions: typedActions,
+        triggers: ruleData.triggers
       };
 
       await rulesApi.updateRule(id, updateData);
@@ -7,10 +7,13 @@ import {
   rulesApi,
   budgetGroupsApi
 } from '../../services/api';
+import { isRuleConditionGroup } from '../../services/types';
 import type {
   Rule,
   RuleCondition,
+  RuleConditionNode,
   RuleAction,
+  RuleTrigger,
   ConditionType,
   ActionType,
   Account,
@@ -27,8 +30,9 @@ interface RuleFormProps {
     description?: string;
     is_active?: boolean;
     priority?: number;
-    conditions?: RuleCondition[];
+    conditions?: RuleConditionNode[];
     actions?: RuleAction[];
+    triggers?: RuleTrigger[];
   };
   isEditMode: boolean;
   onSubmit: (rule: {
@@ -36,8 +40,9 @@ interface RuleFormProps {
     description?: string;
     is_active: boolean;
     priority?: number;
-    conditions: RuleCondition[];
+    conditions: RuleConditionNode[];
     actions: RuleAction[];
+    triggers: RuleTrigger[];
   }) => Promise<void>;
 }
 
@@ -49,14 +54,16 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
   const [description, setDescription] = useState('');
   const [isActive, setIsActive] = useState(true);
   const [priority, setPriority] = useState('100');
-  const [conditions, setConditions] = useState<RuleCondition[]>([]);
+  const [conditions, setConditions] = useState<RuleConditionNode[]>([]);
   const [actions, setActions] = useState<RuleAction[]>([]);
+  const [triggers, setTriggers] = useState<RuleTrigger[]>(['create', 'update', 'import']);
 
   // New condition/action state
   const [newConditionType, setNewConditionType] = useState<ConditionType>('description_contains');
   const [newConditionValue, setNewConditionValue] = useState('');
   const [newActionType, setNewActionType] = useState<ActionType>('set_category');
   const [newActionValue, setNewActionValue] = useState('');
+  const [newActionPattern, setNewActionPattern] = useState('');
 
   // Reference data for dropdowns
   const [accounts, setAccounts] = useState<Account[]>([]);
@@ -90,6 +97,7 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
       setDescription(initialRule.description || '');
       setIsActive(initialRule.is_active);
       setPriority(initialRule.priority.toString());
+      setTriggers(initialRule.triggers ?? ['create', 'update', 'import']);
       setConditions(initialRule.conditions);
       setActions(initialRule.actions);
     }
@@ -102,6 +110,7 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
       if (initialCreateData.description !== undefined) setDescription(initialCreateData.description);
       if (initialCreateData.is_active !== undefined) setIsActive(initialCreateData.is_active);
       if (initialCreateData.priority !== undefined) setPriority(String(initialCreateData.priority));
+      if (initialCreateData.triggers !== undefined) setTriggers(initialCreateData.triggers);
       if (initialCreateData.conditions !== undefined) setConditions(initialCreateData.conditions);
       if (initialCreateData.actions !== undefined) setActions(initialCreateData.actions);
     }
@@ -133,7 +142,8 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
 
   // Add a new condition
   const handleAddCondition = () => {
-    if (!newConditionValue.trim()) {
+    const needsValue = newConditionType !== 'category_is_empty' && newConditionType !== 'budget_is_empty';
+    if (needsValue && !newConditionValue.trim()) {
       setError('Condition value cannot be empty');
       return;
     }
@@ -185,18 +195,29 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
 
   // Add a new action
   const handleAddAction = () => {
-    if (!newActionValue.trim()) {
+    if (newActionType === 'replace_description') {
+      if (!newActionPattern.trim()) {
+        setError('Pattern cannot be empty');
+        return;
+      }
+    } else if (newActionType !== 'stop_processing' && !newActionValue.trim()) {
       setError('Action value cannot be empty');
       return;
     }
 
+    // Text added to or replacing part of the description keeps its spaces
+    const keepsSpaces = ['prepend_description', 'append_description', 'replace_description'].includes(newActionType);
     const newAction: RuleAction = {
       action_type: newActionType,
-      value: newActionValue.trim()
+      value: keepsSpaces ? newActionValue : newActionValue.trim()
     };
+    if (newActionType === 'replace_description') {
+      newAction.pattern = newActionPattern;
+    }
 
     setActions([...actions, newAction]);
     setNewActionValue('');
+    setNewActionPattern('');
     setError(null);
   };
 
@@ -236,7 +257,8 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
         is_active: isActive,
         priority: parseInt(priority, 10),
         conditions,
-        actions
+        actions,
+        triggers
       });
 
       // Navigate back to rules list on success
@@ -271,11 +293,48 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
         return 'Amount less than';
       case 'amount_equals':
         return 'Amount equals';
+      case 'description_not_contains':
+        return 'Description does not contain';
+      case 'description_not_equals':
+        return 'Description does not equal';
+      case 'description_matches_regex':
+        return 'Description matches pattern';
+      case 'source_account_not_equals':
+        return 'Source account is not';
+      case 'destination_account_not_equals':
+        return 'Destination account is not';
+      case 'destination_name_not_contains':
+        return 'Destination name does not contain';
+      case 'destination_name_not_equals':
+        return 'Destination name does not equal';
+      case 'destination_name_matches_regex':
+        return 'Destination name matches pattern';
+      case 'date_before':
+        return 'Date before';
+      case 'date_after':
+        return 'Date after';
+      case 'date_equals':
+        return 'Date is';
+      case 'weekday_is':
+        return 'Weekday is one of';
+      case 'category_is_empty':
+        return 'Category is empty';
+      case 'budget_is_empty':
+        return 'Budget is empty';
       default:
         return type;
     }
   };
 
+  // Helper function to summarise a condition or a nested group of conditions
+  const describeCondition = (node: RuleConditionNode): string => {
+    if (isRuleConditionGroup(node)) {
+      const [joiner, children] = 'all' in node ? [' and ', node.all] : [' or ', node.any];
+      return `(${children.map(describeCondition).join(joiner)})`;
+    }
+    return `${getConditionTypeLabel(node.condition_type)} ${node.value}`.trim();
+  };
+
   // Helper function to get human-readable action type
   const getActionTypeLabel = (type: ActionType): string => {
     switch (type) {
@@ -287,6 +346,22 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
         return 'Set description to';
       case 'set_destination_name':
         return 'Set destination name to';
+      case 'add_tag':
+        return 'Add tag';
+      case 'set_destination_account':
+        return 'Move to account';
+      case 'prepend_description':
+        return 'Prepend to description';
+      case 'append_description':
+        return 'Append to description';
+      case 'replace_description':
+        return 'Replace in description';
+      case 'set_memo':
+        return 'Set memo to';
+      case 'split_by_percentage':
+        return 'Split by percentage';
+      case 'stop_processing':
+        return 'Stop processing further rules';
       default:
         return type;
     }
@@ -299,6 +374,14 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
         const b = budgets.find(b => b.id === action.value);
         return b ? b.name : action.value;
       }
+      case 'set_destination_account': {
+        const a = accounts.find(a => a.id === action.value);
+        return a ? a.name : action.value;
+      }
+      case 'replace_description':
+        return `/${action.pattern ?? ''}/ with "${action.value}"`;
+      case 'split_by_percentage':
+        return (action.splits ?? []).map(share => `${share.percentage}%`).join(' / ');
       default:
         return action.value;
     }
@@ -309,6 +392,8 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
     switch (newConditionType) {
       case 'source_account_equals':
       case 'destination_account_equals':
+      case 'source_account_not_equals':
+      case 'destination_account_not_equals':
         return (
           <select
             value={newConditionValue}
@@ -336,6 +421,21 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
           />
         );
 
+      case 'date_before':
+      case 'date_after':
+      case 'date_equals':
+        return (
+          <input
+            type="date"
+            value={newConditionValue}
+            onChange={(e) => setNewConditionValue(e.target.value)}
+          />
+        );
+
+      case 'category_is_empty':
+      case 'budget_is_empty':
+        return null;
+
       default:
         return (
           <input
@@ -351,6 +451,42 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
   // Render value input based on action type
   const renderActionValueInput = () => {
     switch (newActionType) {
+      case 'set_destination_account':
+        return (
+          <select
+            value={newActionValue}
+            onChange={(e) => setNewActionValue(e.target.value)}
+          >
+            <option value="">Select Account</option>
+            {accounts.map(account => (
+              <option key={account.id} value={account.id}>
+                {account.name}
+              </option>
+            ))}
+          </select>
+        );
+
+      case 'replace_description':
+        return (
+          <>
+            <input
+              type="text"
+              value={newActionPattern}
+              onChange={(e) => setNewActionPattern(e.target.value)}
+              placeholder="Pattern, e.g. ^POS \d+"
+            />
+            <input
+              type="text"
+              value={newActionValue}
+              onChange={(e) => setNewActionValue(e.target.value)}
+              placeholder="Replace with"
+            />
+          </>
+        );
+
+      case 'stop_processing':
+        return null;
+
       case 'set_category':
         return (
           <select
@@ -486,6 +622,23 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
           </label>
         </div>
 
+        <div className="form-group">
+          <label>Apply automatically when a transaction is</label>
+          {([['create', 'Created'], ['update', 'Updated'], ['import', 'Imported']] as [RuleTrigger, string][]).map(([trigger, label]) => (
+            <label key={trigger}>
+              <input
+                type="checkbox"
+                checked={triggers.includes(trigger)}
+                onChange={(e) => setTriggers(e.target.checked
+                  ? [...triggers, trigger]
+                  : triggers.filter(t => t !== trigger))}
+              />
+              {label}
+            </label>
+          ))}
+          <small>Without any, the rule only runs when run by hand</small>
+        </div>
+
         <div className="rule-section">
           <h2>Conditions</h2>
           <p className="section-description">
@@ -499,8 +652,17 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
               <ul>
                 {conditions.map((condition, index) => (
                   <li key={index} className="condition-item">
-                    <span className="condition-type">{getConditionTypeLabel(condition.condition_type)}</span>
-                    <span className="condition-value">{condition.value}</span>
+                    {isRuleConditionGroup(condition) ? (
+                      <>
+                        <span className="condition-type">{'all' in condition ? 'All of' : 'Any of'}</span>
+                        <span className="condition-value">{describeCondition(condition)}</span>
+                      </>
+                    ) : (
+                      <>
+                        <span className="condition-type">{getConditionTypeLabel(condition.condition_type)}</span>
+                        <span className="condition-value">{condition.value}</span>
+                      </>
+                    )}
                     <button
                       type="button"
                       className="button small danger"
@@ -531,6 +693,20 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
                 <option value="amount_greater_than">Amount greater than</option>
                 <option value="amount_less_than">Amount less than</option>
                 <option value="amount_equals">Amount equals</option>
+                <option value="description_not_contains">Description does not contain</option>
+                <option value="description_not_equals">Description does not equal</option>
+                <option value="description_matches_regex">Description matches pattern</option>
+                <option value="source_account_not_equals">Source account is not</option>
+                <option value="destination_account_not_equals">Destination account is not</option>
+                <option value="destination_name_not_contains">Destination name does not contain</option>
+                <option value="destination_name_not_equals">Destination name does not equal</option>
+                <option value="destination_name_matches_regex">Destination name matches pattern</option>
+                <option value="date_before">Date before</option>
+                <option value="date_after">Date after</option>
+                <option value="date_equals">Date is</option>
+                <option value="weekday_is">Weekday is one of</option>
+                <option value="category_is_empty">Category is empty</option>
+                <option value="budget_is_empty">Budget is empty</option>
               </select>
 
               {renderConditionValueInput()}
@@ -585,6 +761,13 @@ const RuleForm: React.FC<RuleFormProps> = ({ initialRule, initialCreateData, isE
                 <option value="set_budget">Set budget to</option>
                 <option value="set_description">Set description to</option>
                 <option value="set_destination_name">Set destination name to</option>
+                <option value="add_tag">Add tag</option>
+                <option value="set_destination_account">Move to account</option>
+                <option value="prepend_description">Prepend to description</option>
+                <option value="append_description">Append to description</option>
+                <option value="replace_description">Replace in description</option>
+                <option value="set_memo">Set memo to</option>
+                <option value="stop_processing">Stop processing further rules</option>
               </select>
 
               {renderActionValueInput()}
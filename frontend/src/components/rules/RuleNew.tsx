@@ -1,7 +1,7 @@
 import { useState } from 'react';
 import { useLocation } from 'react-router-dom';
 import { rulesApi } from '../../services/api';
-import type { RuleCondition, RuleAction, ConditionType, ActionType, Transaction } from '../../services/api';
+import type { RuleCondition, RuleConditionNode, RuleAction, RuleTrigger, ActionType, Transaction } from '../../services/api';
 import RuleForm from './RuleForm';
 
 const RuleNew = () => {
@@ -45,25 +45,18 @@ const RuleNew = () => {
     description?: string;
     is_active: boolean;
     priority?: number;
-    conditions: Array<{
-      condition_type: string;
-      value: string;
-    }>;
+    conditions: RuleConditionNode[];
     actions: Array<{
       action_type: string;
       value: string;
     }>;
+    triggers: RuleTrigger[];
   }) => {
     try {
       // Convert string types to proper enum types
-      const typedConditions: RuleCondition[] = rule.conditions.map(c => ({
-        condition_type: c.condition_type as ConditionType,
-        value: c.value
-      }));
-
       const typedActions: RuleAction[] = rule.actions.map(a => ({
-        action_type: a.action_type as ActionType,
-        value: a.value
+        ...a,
+        action_type: a.action_type as ActionType
       }));
 
       // Extract only the fields that createRule expects
@@ -72,8 +65,9 @@ const RuleNew = () => {
         description: rule.description,
         is_active: rule.is_active,
         priority: rule.priority,
-        conditions: typedConditions,
-        actions: typedActions
+        conditions: rule.conditions,
+        actions: typedActions,
+        triggers: rule.triggers
       };
 
       await rulesApi.createRule(createData);
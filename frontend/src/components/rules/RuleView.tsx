@@ -1,7 +1,8 @@
 import { useState, useEffect } from 'react';
 import { useParams, Link, useNavigate } from 'react-router-dom';
 import { rulesApi } from '../../services/api';
-import type { Rule, ConditionType, ActionType } from '../../services/api';
+import { isRuleConditionGroup } from '../../services/types';
+import type { Rule, RuleAction, RuleConditionNode, ConditionType, ActionType } from '../../services/api';
 
 const RuleView = () => {
   const { id } = useParams<{ id: string }>();
@@ -80,11 +81,60 @@ const RuleView = () => {
         return 'Amount less than';
       case 'amount_equals':
         return 'Amount equals';
+      case 'description_not_contains':
+        return 'Description does not contain';
+      case 'description_not_equals':
+        return 'Description does not equal';
+      case 'description_matches_regex':
+        return 'Description matches pattern';
+      case 'source_account_not_equals':
+        return 'Source account is not';
+      case 'destination_account_not_equals':
+        return 'Destination account is not';
+      case 'destination_name_not_contains':
+        return 'Destination name does not contain';
+      case 'destination_name_not_equals':
+        return 'Destination name does not equal';
+      case 'destination_name_matches_regex':
+        return 'Destination name matches pattern';
+      case 'date_before':
+        return 'Date before';
+      case 'date_after':
+        return 'Date after';
+      case 'date_equals':
+        return 'Date is';
+      case 'weekday_is':
+        return 'Weekday is one of';
+      case 'category_is_empty':
+        return 'Category is empty';
+      case 'budget_is_empty':
+        return 'Budget is empty';
       default:
         return type;
     }
   };
 
+  // Helper function to summarise a condition or a nested group of conditions
+  const describeCondition = (node: RuleConditionNode): string => {
+    if (isRuleConditionGroup(node)) {
+      const [joiner, children] = 'all' in node ? [' and ', node.all] : [' or ', node.any];
+      return `(${children.map(describeCondition).join(joiner)})`;
+    }
+    return `${getConditionTypeLabel(node.condition_type)} ${node.value}`.trim();
+  };
+
+  // Helper function to describe the value of an action
+  const getActionValueLabel = (action: RuleAction): string => {
+    switch (action.action_type) {
+      case 'replace_description':
+        return `/${action.pattern ?? ''}/ with "${action.value}"`;
+      case 'split_by_percentage':
+        return (action.splits ?? []).map(share => `${share.percentage}%`).join(' / ');
+      default:
+        return action.value;
+    }
+  };
+
   // Helper function to get human-readable action type
   const getActionTypeLabel = (type: ActionType): string => {
     switch (type) {
@@ -96,6 +146,22 @@ const RuleView = () => {
         return 'Set description to';
       case 'set_destination_name':
         return 'Set destination name to';
+      case 'add_tag':
+        return 'Add tag';
+      case 'set_destination_account':
+        return 'Move to account';
+      case 'prepend_description':
+        return 'Prepend to description';
+      case 'append_description':
+        return 'Append to description';
+      case 'replace_description':
+        return 'Replace in description';
+      case 'set_memo':
+        return 'Set memo to';
+      case 'split_by_percentage':
+        return 'Split by percentage';
+      case 'stop_processing':
+        return 'Stop processing further rules';
       default:
         return type;
     }
@@ -144,6 +210,13 @@ const RuleView = () => {
           <div className="detail-value">{rule.priority}</div>
         </div>
 
+        <div className="detail-row">
+          <div className="detail-label">Applies on</div>
+          <div className="detail-value">
+            {rule.triggers.length > 0 ? rule.triggers.join(', ') : 'Manual runs only'}
+          </div>
+        </div>
+
         <div className="detail-row">
           <div className="detail-label">Status</div>
           <div className="detail-value">
@@ -177,8 +250,17 @@ const RuleView = () => {
             <ul className="conditions-list">
               {rule.conditions.map((condition, index) => (
                 <li key={index} className="condition-item">
-                  <span className="condition-type">{getConditionTypeLabel(condition.condition_type)}</span>
-                  <span className="condition-value">{condition.value}</span>
+                  {isRuleConditionGroup(condition) ? (
+                    <>
+                      <span className="condition-type">{'all' in condition ? 'All of' : 'Any of'}</span>
+                      <span className="condition-value">{describeCondition(condition)}</span>
+                    </>
+                  ) : (
+                    <>
+                      <span className="condition-type">{getConditionTypeLabel(condition.condition_type)}</span>
+                      <span className="condition-value">{condition.value}</span>
+                    </>
+                  )}
                 </li>
               ))}
             </ul>
@@ -198,7 +280,7 @@ const RuleView = () => {
               {rule.actions.map((action, index) => (
                 <li key={index} className="action-item">
                   <span className="action-type">{getActionTypeLabel(action.action_type)}</span>
-                  <span className="action-value">{action.value}</span>
+                  <span className="action-value">{getActionValueLabel(action)}</span>
                 </li>
               ))}
             </ul>
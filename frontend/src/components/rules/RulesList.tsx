@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import type React from 'react';
 import { Link, useNavigate } from 'react-router-dom';
 import { rulesApi, ruleGroupsApi } from '../../services/api';
-import type { Rule, RuleGroup } from '../../services/api';
+import type { Rule, RuleGroup, RuleSuggestion } from '../../services/api';
 import './Rules.css';
 
 const RulesList = () => {
@@ -24,6 +24,9 @@ const RulesList = () => {
   const [editingGroupId, setEditingGroupId] = useState<string | null>(null);
   const [editGroupName, setEditGroupName] = useState('');
   const [editGroupDescription, setEditGroupDescription] = useState('');
+  const [editGroupRunOrder, setEditGroupRunOrder] = useState(100);
+  const [editGroupActive, setEditGroupActive] = useState(true);
+  const [editGroupFirstMatch, setEditGroupFirstMatch] = useState(false);
   const [savingGroup, setSavingGroup] = useState(false);
   const [editGroupError, setEditGroupError] = useState<string | null>(null);
 
@@ -33,6 +36,10 @@ const RulesList = () => {
   const [isUpdatingGroup, setIsUpdatingGroup] = useState(false);
   const [collapsedGroups, setCollapsedGroups] = useState<Set<string>>(new Set());
 
+  // Rules suggested from categorised transactions
+  const [suggestions, setSuggestions] = useState<RuleSuggestion[] | null>(null);
+  const [loadingSuggestions, setLoadingSuggestions] = useState(false);
+
   useEffect(() => {
     const fetchRules = async () => {
       try {
@@ -129,6 +136,33 @@ const RulesList = () => {
     }
   };
 
+  const handleLoadSuggestions = async () => {
+    try {
+      setLoadingSuggestions(true);
+      setError(null);
+      setSuggestions(await rulesApi.getRuleSuggestions());
+    } catch (err) {
+      console.error('Error fetching rule suggestions:', err);
+      setError('Failed to load rule suggestions. Please try again later.');
+    } finally {
+      setLoadingSuggestions(false);
+    }
+  };
+
+  const handleAcceptSuggestion = async (suggestion: RuleSuggestion) => {
+    try {
+      setError(null);
+      setSuccessMessage(null);
+      const rule = await rulesApi.acceptRuleSuggestion(suggestion.id);
+      setRules(prev => [...prev, rule]);
+      setSuggestions(prev => (prev ?? []).filter(s => s.id !== suggestion.id));
+      setSuccessMessage(`Created rule "${rule.name}"`);
+    } catch (err) {
+      console.error(`Error accepting rule suggestion ${suggestion.id}:`, err);
+      setError('Failed to create the suggested rule. Please try again later.');
+    }
+  };
+
   // Run all active rules within a specific group (groupId or null for Ungrouped)
   const handleRunRuleGroup = async (groupId: string | null, groupName: string) => {
     try {
@@ -143,14 +177,20 @@ const RulesList = () => {
       }
 
       let totalAffected = 0;
-      for (const r of groupRules) {
-        if (!r.is_active) continue; // Only run active rules, consistent with UI
-        try {
-          const res = await rulesApi.runRule(r.id);
-          totalAffected += res.affected_transactions || 0;
-        } catch (e) {
-          console.error(`Failed running rule ${r.id} in group ${groupName}:`, e);
-          // Continue running other rules but surface a generic error; success will still display aggregate
+      if (groupId) {
+        // The server applies the group's rules in order and honours its first-match setting
+        const res = await ruleGroupsApi.runRuleGroup(groupId);
+        totalAffected = res.affected_transactions || 0;
+      } else {
+        for (const r of groupRules) {
+          if (!r.is_active) continue; // Only run active rules, consistent with UI
+          try {
+            const res = await rulesApi.runRule(r.id);
+            totalAffected += res.affected_transactions || 0;
+          } catch (e) {
+            console.error(`Failed running rule ${r.id} in group ${groupName}:`, e);
+            // Continue running other rules but surface a generic error; success will still display aggregate
+          }
         }
       }
 
@@ -286,6 +326,9 @@ const RulesList = () => {
                             const updated = await ruleGroupsApi.updateRuleGroup(group.id, {
                               name: trimmedName,
                               description: editGroupDescription.trim() || undefined,
+                              run_order: editGroupRunOrder,
+                              is_active: editGroupActive,
+                              stop_after_first_match: editGroupFirstMatch,
                             });
                             setRuleGroups(prev => prev.map(g => g.id === group.id ? updated : g));
                             setEditingGroupId(null);
@@ -312,6 +355,28 @@ const RulesList = () => {
                           value={editGroupDescription}
                           onChange={(e) => setEditGroupDescription(e.target.value)}
                         />
+                        <input
+                          type="number"
+                          title="Run order (lower runs first)"
+                          value={editGroupRunOrder}
+                          onChange={(e) => setEditGroupRunOrder(parseInt(e.target.value, 10) || 0)}
+                        />
+                        <label>
+                          <input
+                            type="checkbox"
+                            checked={editGroupActive}
+                            onChange={(e) => setEditGroupActive(e.target.checked)}
+                          />
+                          Active
+                        </label>
+                        <label>
+                          <input
+                            type="checkbox"
+                            checked={editGroupFirstMatch}
+                            onChange={(e) => setEditGroupFirstMatch(e.target.checked)}
+                          />
+                          Stop after first matching rule
+                        </label>
                         {editGroupError && <div className="error">{editGroupError}</div>}
                         <div className="button-group">
                           <button className="button small" disabled={savingGroup} type="submit">
@@ -336,6 +401,11 @@ const RulesList = () => {
                         <div className="list-item-content">
                           <div className="title">{group.name}</div>
                           <div className="subtitle">{group.description || ''}</div>
+                          <div className="subtitle">
+                            Run order {group.run_order}
+                            {!group.is_active && ' · Inactive'}
+                            {group.stop_after_first_match && ' · First match only'}
+                          </div>
                         </div>
                         <div className="actions">
                           <button
@@ -344,6 +414,9 @@ const RulesList = () => {
                               setEditingGroupId(group.id);
                               setEditGroupName(group.name);
                               setEditGroupDescription(group.description || '');
+                              setEditGroupRunOrder(group.run_order);
+                              setEditGroupActive(group.is_active);
+                              setEditGroupFirstMatch(group.stop_after_first_match);
                               setEditGroupError(null);
                             }}
                             title="Edit group"
@@ -386,7 +459,7 @@ const RulesList = () => {
                 try {
                   setCreatingGroup(true);
                   const created = await ruleGroupsApi.createRuleGroup({ name: newGroupName.trim(), description: newGroupDescription.trim() || undefined });
-                  setRuleGroups(prev => [...prev, created]);
+                  setRuleGroups(prev => [...prev, created].sort((a, b) => a.run_order - b.run_order || a.name.localeCompare(b.name)));
                   setNewGroupName('');
                   setNewGroupDescription('');
                 } catch (err) {
@@ -549,7 +622,7 @@ const RulesList = () => {
                   <button
                     className="button small secondary"
                     onClick={() => handleRunRuleGroup(group.id, group.name)}
-                    disabled={runningRules || rulesForGroup(group.id).every(r => !r.is_active)}
+                    disabled={runningRules || !group.is_active || rulesForGroup(group.id).every(r => !r.is_active)}
                     title="Run all rules in this group"
                   >
                     {runningRules ? 'Running…' : 'Run Group'}
@@ -716,6 +789,45 @@ const RulesList = () => {
         </div>
       )}
 
+      <div className="panel">
+        <div className="header-with-button">
+          <h2>Suggested Rules</h2>
+          <button className="button small secondary" onClick={handleLoadSuggestions} disabled={loadingSuggestions}>
+            {loadingSuggestions ? 'Looking...' : 'Suggest Rules'}
+          </button>
+        </div>
+        {suggestions !== null && (suggestions.length === 0 ? (
+          <div className="empty-state">
+            <p>No suggestions: categorise a few more transactions to the same payee first.</p>
+          </div>
+        ) : (
+          <table className="rules-table">
+            <thead>
+              <tr>
+                <th>Name</th>
+                <th>Matches</th>
+                <th>Confidence</th>
+                <th>Actions</th>
+              </tr>
+            </thead>
+            <tbody>
+              {suggestions.map(suggestion => (
+                <tr key={suggestion.id}>
+                  <td>{suggestion.rule.name}</td>
+                  <td>{suggestion.support}</td>
+                  <td>{Math.round(suggestion.confidence * 100)}%</td>
+                  <td>
+                    <button className="button small" onClick={() => handleAcceptSuggestion(suggestion)}>
+                      Accept
+                    </button>
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        ))}
+      </div>
+
       <div className="rules-help">
         <h2>About Transaction Rules</h2>
         <p>
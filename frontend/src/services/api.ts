@@ -8,11 +8,21 @@ import type {
   CategoryGroup,
   Transaction,
   Budget,
+  BudgetGoalType,
+  BudgetAmountSource,
+  CopiedBudgetMonth,
   MonthlyBudgetStatus,
+  BudgetMonth,
+  BudgetMonthLine,
+  BudgetTransfer,
+  ReadyToAssign,
   CategorySpending,
   Rule,
   RuleCondition,
+  RuleConditionGroup,
+  RuleConditionNode,
   RuleAction,
+  RuleTrigger,
   ConditionType,
   ActionType,
   FireflyImportOptions,
@@ -20,9 +30,25 @@ import type {
   ForecastedMonthlyIncomeResponse,
   SpendingReportRow,
   InflowOutflowReportRow,
+  BalanceHistoryPoint,
+  NetWorthPoint,
+  TransactionSearchParams,
+  TransactionSearchResult,
+  BulkEditTransactionsRequest,
+  BulkEditResult,
+  TransactionChange,
+  UndoRunResult,
   Features,
   RuleTestResponse,
-  RuleGroup
+  RuleRunPreview,
+  RuleChangePreview,
+  CommitRuleRunResult,
+  RuleSuggestion,
+  RuleGroup,
+  User,
+  Ledger,
+  CurrentUser,
+  AuthStatus
 } from './types.ts';
 
 // Re-export types for convenience
@@ -33,11 +59,23 @@ export type {
   RuleGroup,
   Transaction,
   Budget,
+  BudgetGoalType,
+  BudgetGoalProgress,
+  BudgetGroupGoals,
+  BudgetAmountSource,
+  CopiedBudgetMonth,
   MonthlyBudgetStatus,
+  BudgetMonth,
+  BudgetMonthLine,
+  BudgetTransfer,
+  ReadyToAssign,
   CategorySpending,
   Rule,
   RuleCondition,
+  RuleConditionGroup,
+  RuleConditionNode,
   RuleAction,
+  RuleTrigger,
   ConditionType,
   ActionType,
   FireflyImportOptions,
@@ -45,7 +83,92 @@ export type {
   ForecastedMonthlyIncomeResponse,
   SpendingReportRow,
   InflowOutflowReportRow,
-  RuleTestResponse
+  BalanceHistoryPoint,
+  NetWorthPoint,
+  TransactionSearchParams,
+  TransactionSearchResult,
+  BulkEditTransactionsRequest,
+  BulkEditResult,
+  TransactionChange,
+  UndoRunResult,
+  RuleTestResponse,
+  RuleRunPreview,
+  RuleChangePreview,
+  CommitRuleRunResult,
+  RuleSuggestion,
+  User,
+  Ledger,
+  CurrentUser,
+  AuthStatus
+};
+
+// Event fired when the server rejects a request because the session is missing or expired
+export const UNAUTHORIZED_EVENT = 'rustler:unauthorized';
+
+// fetch wrapper that sends the session cookie and reports expired sessions to the app
+const apiFetch = async (input: string, init?: RequestInit): Promise<Response> => {
+  const response = await fetch(input, { credentials: 'same-origin', ...init });
+  if (response.status === 401) {
+    window.dispatchEvent(new Event(UNAUTHORIZED_EVENT));
+  }
+  return response;
+};
+
+// Authentication API
+export const authApi = {
+  // Whether a new user may register
+  getStatus: async (): Promise<AuthStatus> => {
+    const response = await fetch(`${API_BASE_URL}/auth/status`);
+    if (!response.ok) {
+      throw new Error('Failed to fetch authentication status');
+    }
+    return response.json();
+  },
+
+  // Get the logged in user, or null when there is no valid session
+  getCurrentUser: async (): Promise<CurrentUser | null> => {
+    const response = await fetch(`${API_BASE_URL}/auth/me`);
+    if (response.status === 401) {
+      return null;
+    }
+    if (!response.ok) {
+      throw new Error('Failed to fetch current user');
+    }
+    return response.json();
+  },
+
+  login: async (username: string, password: string): Promise<CurrentUser> => {
+    const response = await fetch(`${API_BASE_URL}/auth/login`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ username, password }),
+    });
+    if (!response.ok) {
+      const message = await response.json().catch(() => null);
+      throw new Error(typeof message === 'string' ? message : 'Failed to log in');
+    }
+    return response.json();
+  },
+
+  register: async (username: string, password: string): Promise<CurrentUser> => {
+    const response = await fetch(`${API_BASE_URL}/auth/register`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ username, password }),
+    });
+    if (!response.ok) {
+      const message = await response.json().catch(() => null);
+      throw new Error(typeof message === 'string' ? message : 'Failed to register');
+    }
+    return response.json();
+  },
+
+  logout: async (): Promise<void> => {
+    const response = await fetch(`${API_BASE_URL}/auth/logout`, { method: 'POST' });
+    if (!response.ok) {
+      throw new Error('Failed to log out');
+    }
+  },
 };
 
 // Reports API
@@ -69,7 +192,7 @@ export const reportsApi = {
     // cache-buster to avoid caching in dev
     query.set('_t', String(Date.now()));
 
-    const res = await fetch(`${API_BASE_URL}/reports/spending?${query.toString()}`);
+    const res = await apiFetch(`${API_BASE_URL}/reports/spending?${query.toString()}`);
     if (!res.ok) {
       throw new Error('Failed to fetch spending report');
     }
@@ -92,12 +215,54 @@ export const reportsApi = {
     if (params.period) query.set('period', params.period);
     query.set('_t', String(Date.now()));
 
-    const res = await fetch(`${API_BASE_URL}/reports/inflow-outflow?${query.toString()}`);
+    const res = await apiFetch(`${API_BASE_URL}/reports/inflow-outflow?${query.toString()}`);
     if (!res.ok) {
       throw new Error('Failed to fetch inflow/outflow report');
     }
     return res.json();
   },
+
+  // Get end-of-period balances of one account, computed from its transactions
+  getBalanceHistory: async (accountId: string, params: {
+    start_date?: string;
+    end_date?: string;
+    period?: 'day' | 'week' | 'month';
+  }): Promise<BalanceHistoryPoint[]> => {
+    const query = new URLSearchParams();
+    if (params.start_date) query.set('start_date', params.start_date);
+    if (params.end_date) query.set('end_date', params.end_date);
+    if (params.period) query.set('period', params.period);
+    query.set('_t', String(Date.now()));
+
+    const res = await apiFetch(`${API_BASE_URL}/accounts/${accountId}/balance-history?${query.toString()}`);
+    if (!res.ok) {
+      throw new Error('Failed to fetch balance history');
+    }
+    return res.json();
+  },
+
+  // Get net worth over time, grouped by account type and subtype
+  getNetWorth: async (params: {
+    start_date?: string;
+    end_date?: string;
+    account_ids?: string[];
+    period?: 'day' | 'week' | 'month';
+  }): Promise<NetWorthPoint[]> => {
+    const query = new URLSearchParams();
+    if (params.start_date) query.set('start_date', params.start_date);
+    if (params.end_date) query.set('end_date', params.end_date);
+    if (params.account_ids && params.account_ids.length > 0) {
+      query.set('account_ids', params.account_ids.join(','));
+    }
+    if (params.period) query.set('period', params.period);
+    query.set('_t', String(Date.now()));
+
+    const res = await apiFetch(`${API_BASE_URL}/reports/net-worth?${query.toString()}`);
+    if (!res.ok) {
+      throw new Error('Failed to fetch net worth report');
+    }
+    return res.json();
+  },
 };
 
 // API functions for accounts
@@ -106,7 +271,7 @@ export const accountsApi = {
   getAccounts: async (): Promise<Account[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/accounts?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/accounts?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch accounts');
     }
@@ -117,7 +282,7 @@ export const accountsApi = {
   getAccount: async (id: string): Promise<Account> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/accounts/${id}?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/accounts/${id}?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch account with ID ${id}`);
     }
@@ -133,7 +298,7 @@ export const accountsApi = {
     currency: string;
     is_default?: boolean;
   }): Promise<Account> => {
-    const response = await fetch(`${API_BASE_URL}/accounts`, {
+    const response = await apiFetch(`${API_BASE_URL}/accounts`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -148,7 +313,7 @@ export const accountsApi = {
 
   // Update an existing account
   updateAccount: async (id: string, account: Partial<Account>): Promise<Account> => {
-    const response = await fetch(`${API_BASE_URL}/accounts/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/accounts/${id}`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
@@ -163,7 +328,7 @@ export const accountsApi = {
 
   // Delete an account
   deleteAccount: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/accounts/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/accounts/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -177,7 +342,7 @@ export const transactionsApi = {
   // Get monthly incoming transactions (consistent with budget monthly incoming funds)
   getMonthlyIncomingTransactions: async (year: number, month: number): Promise<Transaction[]> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/transactions/monthly-incoming?year=${year}&month=${month}&${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/transactions/monthly-incoming?year=${year}&month=${month}&${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch monthly incoming transactions');
     }
@@ -189,7 +354,7 @@ export const transactionsApi = {
     if (startDate) params.set('start_date', startDate);
     if (endDate) params.set('end_date', endDate);
     params.set('_t', String(Date.now()));
-    const response = await fetch(`${API_BASE_URL}/transactions/unbudgeted?${params.toString()}`);
+    const response = await apiFetch(`${API_BASE_URL}/transactions/unbudgeted?${params.toString()}`);
     if (!response.ok) {
       throw new Error('Failed to fetch unbudgeted transactions');
     }
@@ -201,13 +366,78 @@ export const transactionsApi = {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
     const offset = (page - 1) * limit;
-    const response = await fetch(`${API_BASE_URL}/transactions?limit=${limit}&offset=${offset}&${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/transactions?limit=${limit}&offset=${offset}&${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch transactions');
     }
     return response.json();
   },
 
+  // Search transactions by text and filters; returns one page plus the total match count
+  searchTransactions: async (search: TransactionSearchParams): Promise<TransactionSearchResult> => {
+    const params = new URLSearchParams();
+    Object.entries(search).forEach(([key, value]) => {
+      if (value !== undefined && value !== null && value !== '') params.set(key, String(value));
+    });
+    params.set('_t', String(Date.now()));
+    const response = await apiFetch(`${API_BASE_URL}/transactions/search?${params.toString()}`);
+    if (!response.ok) {
+      throw new Error('Failed to search transactions');
+    }
+    return response.json();
+  },
+
+  // Edit or delete many transactions at once; a rejected batch (400) still returns its per-item report
+  bulkEditTransactions: async (request: BulkEditTransactionsRequest): Promise<BulkEditResult> => {
+    const response = await apiFetch(`${API_BASE_URL}/transactions/bulk`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify(request),
+    });
+    if (!response.ok && response.status !== 400) {
+      throw new Error('Failed to bulk edit transactions');
+    }
+    // Validation errors (e.g. an empty patch) come back as 400 without a body
+    const body = await response.text();
+    if (!body) {
+      throw new Error('Invalid bulk edit');
+    }
+    return JSON.parse(body);
+  },
+
+  // Get the change history of a transaction, oldest first
+  getTransactionHistory: async (id: string): Promise<TransactionChange[]> => {
+    const response = await apiFetch(`${API_BASE_URL}/transactions/${id}/history`);
+    if (!response.ok) {
+      throw new Error(`Failed to fetch history of transaction ${id}`);
+    }
+    return response.json();
+  },
+
+  // Undo one change; fails when the transaction was changed again since
+  undoTransactionChange: async (changeId: string): Promise<Transaction> => {
+    const response = await apiFetch(`${API_BASE_URL}/transaction-changes/${changeId}/undo`, {
+      method: 'POST',
+    });
+    if (!response.ok) {
+      throw new Error(`Failed to undo change ${changeId}`);
+    }
+    return response.json();
+  },
+
+  // Undo all changes of a rule run or bulk edit
+  undoChangeRun: async (runId: string): Promise<UndoRunResult> => {
+    const response = await apiFetch(`${API_BASE_URL}/transaction-changes/runs/${runId}/undo`, {
+      method: 'POST',
+    });
+    if (!response.ok) {
+      throw new Error(`Failed to undo run ${runId}`);
+    }
+    return response.json();
+  },
+
   // Get transactions within a date range (inclusive)
   getTransactionsByDateRange: async (
     startDate: string,
@@ -216,7 +446,7 @@ export const transactionsApi = {
     offset: number = 0
   ): Promise<Transaction[]> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(
+    const response = await apiFetch(
       `${API_BASE_URL}/transactions?start_date=${encodeURIComponent(startDate)}&end_date=${encodeURIComponent(endDate)}&limit=${limit}&offset=${offset}&${cacheBuster}`
     );
     if (!response.ok) {
@@ -230,7 +460,7 @@ export const transactionsApi = {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
     const offset = (page - 1) * limit;
-    const response = await fetch(`${API_BASE_URL}/accounts/${accountId}/transactions?limit=${limit}&offset=${offset}&${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/accounts/${accountId}/transactions?limit=${limit}&offset=${offset}&${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch transactions for account with ID ${accountId}`);
     }
@@ -241,7 +471,7 @@ export const transactionsApi = {
   getTransaction: async (id: string): Promise<Transaction> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/transactions/${id}?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/transactions/${id}?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch transaction with ID ${id}`);
     }
@@ -250,7 +480,7 @@ export const transactionsApi = {
 
   // Create a new transaction
   createTransaction: async (transaction: Omit<Transaction, 'id' | 'created_at' | 'updated_at'>): Promise<Transaction> => {
-    const response = await fetch(`${API_BASE_URL}/transactions`, {
+    const response = await apiFetch(`${API_BASE_URL}/transactions`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -265,7 +495,7 @@ export const transactionsApi = {
 
   // Update an existing transaction
   updateTransaction: async (id: string, transaction: Partial<Transaction>): Promise<Transaction> => {
-    const response = await fetch(`${API_BASE_URL}/transactions/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/transactions/${id}`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
@@ -280,7 +510,7 @@ export const transactionsApi = {
 
   // Delete a transaction
   deleteTransaction: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/transactions/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/transactions/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -300,8 +530,8 @@ export const transactionsApi = {
       budget_id: number | null;
     };
     data: string[][];
-  }): Promise<{ success: number; failed: number }> => {
-    const response = await fetch(`${API_BASE_URL}/accounts/${importData.account_id}/import-csv`, {
+  }): Promise<{ batch_id: string; success: number; failed: number }> => {
+    const response = await apiFetch(`${API_BASE_URL}/accounts/${importData.account_id}/import-csv`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -321,7 +551,7 @@ export const categoriesApi = {
   getCategories: async (): Promise<Category[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/categories?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/categories?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch categories');
     }
@@ -343,7 +573,7 @@ export const categoriesApi = {
     const queryString = params.toString();
     url += `?${queryString}`;
 
-    const response = await fetch(url);
+    const response = await apiFetch(url);
     if (!response.ok) {
       throw new Error('Failed to fetch category spending');
     }
@@ -354,7 +584,7 @@ export const categoriesApi = {
   getCategory: async (id: string): Promise<Category> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/categories/${id}?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/categories/${id}?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch category with ID ${id}`);
     }
@@ -363,7 +593,7 @@ export const categoriesApi = {
 
   // Create a new category
   createCategory: async (category: { name: string; description?: string; group_id?: string }): Promise<Category> => {
-    const response = await fetch(`${API_BASE_URL}/categories`, {
+    const response = await apiFetch(`${API_BASE_URL}/categories`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -378,7 +608,7 @@ export const categoriesApi = {
 
   // Update an existing category
   updateCategory: async (id: string, category: { name?: string; description?: string; group_id?: string }): Promise<Category> => {
-    const response = await fetch(`${API_BASE_URL}/categories/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/categories/${id}`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
@@ -393,7 +623,7 @@ export const categoriesApi = {
 
   // Delete a category
   deleteCategory: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/categories/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/categories/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -408,7 +638,7 @@ export const categoryGroupsApi = {
   getCategoryGroups: async (): Promise<CategoryGroup[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/category-groups?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/category-groups?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch category groups');
     }
@@ -419,7 +649,7 @@ export const categoryGroupsApi = {
   getCategoryGroup: async (id: string): Promise<CategoryGroup> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/category-groups/${id}?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/category-groups/${id}?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch category group with ID ${id}`);
     }
@@ -428,7 +658,7 @@ export const categoryGroupsApi = {
 
   // Create a new category group
   createCategoryGroup: async (categoryGroup: { name: string; description?: string }): Promise<CategoryGroup> => {
-    const response = await fetch(`${API_BASE_URL}/category-groups`, {
+    const response = await apiFetch(`${API_BASE_URL}/category-groups`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -443,7 +673,7 @@ export const categoryGroupsApi = {
 
   // Update an existing category group
   updateCategoryGroup: async (id: string, categoryGroup: { name?: string; description?: string }): Promise<CategoryGroup> => {
-    const response = await fetch(`${API_BASE_URL}/category-groups/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/category-groups/${id}`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
@@ -458,7 +688,7 @@ export const categoryGroupsApi = {
 
   // Delete a category group
   deleteCategoryGroup: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/category-groups/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/category-groups/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -470,7 +700,7 @@ export const categoryGroupsApi = {
   getCategoriesByGroup: async (groupId: string): Promise<Category[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/category-groups/${groupId}/categories?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/category-groups/${groupId}/categories?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch categories for group with ID ${groupId}`);
     }
@@ -482,7 +712,7 @@ export const budgetGroupsApi = {
   // Get all budget groups
   getBudgetGroups: async (): Promise<CategoryGroup[]> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budget-groups?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budget-groups?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch budget groups');
     }
@@ -491,7 +721,7 @@ export const budgetGroupsApi = {
 
   // Create a new budget group
   createBudgetGroup: async (budgetGroup: { name: string; description?: string }): Promise<CategoryGroup> => {
-    const response = await fetch(`${API_BASE_URL}/budget-groups`, {
+    const response = await apiFetch(`${API_BASE_URL}/budget-groups`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(budgetGroup),
@@ -504,7 +734,7 @@ export const budgetGroupsApi = {
 
   // Update an existing budget group
   updateBudgetGroup: async (id: string, budgetGroup: { name?: string; description?: string }): Promise<CategoryGroup> => {
-    const response = await fetch(`${API_BASE_URL}/budget-groups/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/budget-groups/${id}`, {
       method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(budgetGroup),
@@ -517,7 +747,7 @@ export const budgetGroupsApi = {
 
   // Delete a budget group
   deleteBudgetGroup: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/budget-groups/${id}`, { method: 'DELETE' });
+    const response = await apiFetch(`${API_BASE_URL}/budget-groups/${id}`, { method: 'DELETE' });
     if (!response.ok) {
       throw new Error(`Failed to delete budget group with ID ${id}`);
     }
@@ -529,7 +759,7 @@ export const budgetsApi = {
   getBudgets: async (): Promise<Budget[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budgets?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch budgets');
     }
@@ -540,7 +770,7 @@ export const budgetsApi = {
   getActiveBudgets: async (): Promise<Budget[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budgets/active?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/active?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch active budgets');
     }
@@ -551,7 +781,7 @@ export const budgetsApi = {
   getMonthlyBudgetStatus: async (year: number, month: number): Promise<MonthlyBudgetStatus> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budgets/monthly-status?year=${year}&month=${month}&${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/monthly-status?year=${year}&month=${month}&${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch monthly budget status for ${year}-${month}`);
     }
@@ -562,7 +792,7 @@ export const budgetsApi = {
   getBudget: async (id: string): Promise<Budget> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budgets/${id}?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch budget with ID ${id}`);
     }
@@ -571,7 +801,7 @@ export const budgetsApi = {
 
   // Create a new budget
   createBudget: async (budget: Omit<Budget, 'id' | 'created_at' | 'updated_at'>): Promise<Budget> => {
-    const response = await fetch(`${API_BASE_URL}/budgets`, {
+    const response = await apiFetch(`${API_BASE_URL}/budgets`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -585,8 +815,9 @@ export const budgetsApi = {
   },
 
   // Update an existing budget
-  updateBudget: async (id: string, budget: Partial<Budget>): Promise<Budget> => {
-    const response = await fetch(`${API_BASE_URL}/budgets/${id}`, {
+  // An empty goal_type removes the budget's goal
+  updateBudget: async (id: string, budget: Partial<Omit<Budget, 'goal_type'>> & { goal_type?: BudgetGoalType | '' }): Promise<Budget> => {
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
@@ -601,7 +832,7 @@ export const budgetsApi = {
 
   // Delete a budget
   deleteBudget: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/budgets/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -612,7 +843,7 @@ export const budgetsApi = {
   // Get transactions for a budget's month
   getBudgetTransactionsForMonth: async (id: string): Promise<Transaction[]> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budgets/${id}/transactions?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}/transactions?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch transactions for budget with ID ${id}`);
     }
@@ -626,18 +857,104 @@ export const budgetsApi = {
     if (typeof year === 'number') params.set('year', String(year));
     if (typeof month === 'number') params.set('month', String(month));
     params.set('_t', String(Date.now()));
-    const response = await fetch(`${API_BASE_URL}/budgets/${id}/spent?${params.toString()}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}/spent?${params.toString()}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch spent amount for budget with ID ${id}`);
     }
     return response.json();
   },
 
-  // Get the remaining amount for a budget
+  // Get assigned, activity and available amounts of all budgets in a month
+  getBudgetMonth: async (year: number, month: number): Promise<BudgetMonth> => {
+    const cacheBuster = `_t=${Date.now()}`;
+    const response = await apiFetch(`${API_BASE_URL}/budgets/month/${year}-${String(month).padStart(2, '0')}?${cacheBuster}`);
+    if (!response.ok) {
+      throw new Error(`Failed to fetch budgets for ${year}-${month}`);
+    }
+    return response.json();
+  },
+
+  // Create a month's budgets (YYYY-MM) from the budgets of another month, the month before by default
+  copyBudgetMonth: async (month: string, options: {
+    source_month?: string;
+    amount_source?: BudgetAmountSource;
+    group_amount_sources?: Record<string, BudgetAmountSource>;
+  }): Promise<CopiedBudgetMonth> => {
+    const response = await apiFetch(`${API_BASE_URL}/budgets/month/${month}/copy`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify(options),
+    });
+    if (!response.ok) {
+      throw new Error(`Failed to create budgets for ${month}`);
+    }
+    return response.json();
+  },
+
+  // Get the money in On Budget accounts not given to a budget yet
+  getReadyToAssign: async (): Promise<ReadyToAssign> => {
+    const cacheBuster = `_t=${Date.now()}`;
+    const response = await apiFetch(`${API_BASE_URL}/budgets/ready-to-assign?${cacheBuster}`);
+    if (!response.ok) {
+      throw new Error('Failed to fetch the amount ready to assign');
+    }
+    return response.json();
+  },
+
+  // Move money from one budget to another in a month (YYYY-MM)
+  createBudgetTransfer: async (transfer: {
+    from_budget_id: string;
+    to_budget_id: string;
+    month: string;
+    amount: number;
+    note?: string;
+  }): Promise<BudgetTransfer> => {
+    const response = await apiFetch(`${API_BASE_URL}/budgets/transfers`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify(transfer),
+    });
+    if (!response.ok) {
+      throw new Error('Failed to move money between budgets');
+    }
+    return response.json();
+  },
+
+  // Get the money moved between budgets, optionally for one budget
+  getBudgetTransfers: async (budgetId?: string): Promise<BudgetTransfer[]> => {
+    const params = new URLSearchParams();
+    if (budgetId) params.set('budget_id', budgetId);
+    params.set('_t', String(Date.now()));
+    const response = await apiFetch(`${API_BASE_URL}/budgets/transfers?${params.toString()}`);
+    if (!response.ok) {
+      throw new Error('Failed to fetch budget transfers');
+    }
+    return response.json();
+  },
+
+  // Assign an amount to a budget for a month
+  setBudgetAllocation: async (id: string, year: number, month: number, amount: number): Promise<void> => {
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}/allocations/${year}-${String(month).padStart(2, '0')}`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify({ amount }),
+    });
+    if (!response.ok) {
+      throw new Error(`Failed to assign an amount to budget with ID ${id}`);
+    }
+  },
+
+  // Get the amount available in a budget this month
   getBudgetRemaining: async (id: string): Promise<number> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/budgets/${id}/remaining?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/${id}/remaining?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch remaining amount for budget with ID ${id}`);
     }
@@ -651,7 +968,7 @@ export const budgetsApi = {
     if (typeof year === 'number') params.set('year', String(year));
     if (typeof month === 'number') params.set('month', String(month));
     params.set('_t', String(Date.now()));
-    const response = await fetch(`${API_BASE_URL}/budgets/unbudgeted-spent?${params.toString()}`);
+    const response = await apiFetch(`${API_BASE_URL}/budgets/unbudgeted-spent?${params.toString()}`);
     if (!response.ok) {
       throw new Error('Failed to fetch unbudgeted spent amount');
     }
@@ -664,13 +981,13 @@ export const ruleGroupsApi = {
   // Get all rule groups
   getRuleGroups: async (): Promise<RuleGroup[]> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/rule-groups?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups?${cacheBuster}`);
     if (!response.ok) throw new Error('Failed to fetch rule groups');
     return response.json();
   },
   // Create a new rule group
-  createRuleGroup: async (group: { name: string; description?: string }): Promise<RuleGroup> => {
-    const response = await fetch(`${API_BASE_URL}/rule-groups`, {
+  createRuleGroup: async (group: { name: string; description?: string; run_order?: number; is_active?: boolean; stop_after_first_match?: boolean }): Promise<RuleGroup> => {
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(group),
@@ -679,8 +996,8 @@ export const ruleGroupsApi = {
     return response.json();
   },
   // Update an existing rule group
-  updateRuleGroup: async (id: string, group: { name?: string; description?: string }): Promise<RuleGroup> => {
-    const response = await fetch(`${API_BASE_URL}/rule-groups/${id}`, {
+  updateRuleGroup: async (id: string, group: { name?: string; description?: string; run_order?: number; is_active?: boolean; stop_after_first_match?: boolean }): Promise<RuleGroup> => {
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups/${id}`, {
       method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify(group),
@@ -690,16 +1007,28 @@ export const ruleGroupsApi = {
   },
   // Delete a rule group
   deleteRuleGroup: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/rule-groups/${id}`, { method: 'DELETE' });
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups/${id}`, { method: 'DELETE' });
     if (!response.ok) throw new Error(`Failed to delete rule group with ID ${id}`);
   },
   // Get rules in a specific group
   getRulesByGroup: async (groupId: string): Promise<Rule[]> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/rule-groups/${groupId}/rules?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups/${groupId}/rules?${cacheBuster}`);
     if (!response.ok) throw new Error(`Failed to fetch rules for group with ID ${groupId}`);
     return response.json();
   },
+  // Run the active rules of a group on all transactions
+  runRuleGroup: async (id: string): Promise<{ affected_transactions: number; run_id: string; message: string }> => {
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups/${id}/run`, { method: 'POST' });
+    if (!response.ok) throw new Error(`Failed to run rule group with ID ${id}`);
+    return response.json();
+  },
+  // Preview what running the rules of a group would change
+  testRuleGroup: async (id: string): Promise<RuleRunPreview> => {
+    const response = await apiFetch(`${API_BASE_URL}/rule-groups/${id}/test`, { method: 'POST' });
+    if (!response.ok) throw new Error(`Failed to test rule group with ID ${id}`);
+    return response.json();
+  },
 };
 
 // API functions for rules
@@ -708,7 +1037,7 @@ export const rulesApi = {
   getRules: async (): Promise<Rule[]> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/rules?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/rules?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch rules');
     }
@@ -719,7 +1048,7 @@ export const rulesApi = {
   getRule: async (id: string): Promise<Rule> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/rules/${id}?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/rules/${id}?${cacheBuster}`);
     if (!response.ok) {
       throw new Error(`Failed to fetch rule with ID ${id}`);
     }
@@ -733,10 +1062,11 @@ export const rulesApi = {
     is_active: boolean;
     priority?: number;
     group_id?: string | null;
-    conditions: RuleCondition[];
+    conditions: RuleConditionNode[];
     actions: RuleAction[];
+    triggers?: RuleTrigger[];
   }): Promise<Rule> => {
-    const response = await fetch(`${API_BASE_URL}/rules`, {
+    const response = await apiFetch(`${API_BASE_URL}/rules`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -756,10 +1086,11 @@ export const rulesApi = {
     is_active?: boolean;
     priority?: number;
     group_id?: string | null;
-    conditions?: RuleCondition[];
+    conditions?: RuleConditionNode[];
     actions?: RuleAction[];
+    triggers?: RuleTrigger[];
   }): Promise<Rule> => {
-    const response = await fetch(`${API_BASE_URL}/rules/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/rules/${id}`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
@@ -774,7 +1105,7 @@ export const rulesApi = {
 
   // Delete a rule
   deleteRule: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE_URL}/rules/${id}`, {
+    const response = await apiFetch(`${API_BASE_URL}/rules/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -783,8 +1114,8 @@ export const rulesApi = {
   },
 
   // Run all active rules on all transactions
-  runAllRules: async (): Promise<{ affected_transactions: number; message: string }> => {
-    const response = await fetch(`${API_BASE_URL}/rules/run`, {
+  runAllRules: async (): Promise<{ affected_transactions: number; run_id: string; message: string }> => {
+    const response = await apiFetch(`${API_BASE_URL}/rules/run`, {
       method: 'POST',
     });
     if (!response.ok) {
@@ -794,8 +1125,8 @@ export const rulesApi = {
   },
 
   // Run a specific rule on all transactions
-  runRule: async (id: string): Promise<{ affected_transactions: number; message: string }> => {
-    const response = await fetch(`${API_BASE_URL}/rules/${id}/run`, {
+  runRule: async (id: string): Promise<{ affected_transactions: number; run_id: string; message: string }> => {
+    const response = await apiFetch(`${API_BASE_URL}/rules/${id}/run`, {
       method: 'POST',
     });
     if (!response.ok) {
@@ -804,9 +1135,40 @@ export const rulesApi = {
     return response.json();
   },
 
+  // Preview the changes of running all active rules, or one rule, without applying them
+  previewRuleRun: async (ruleId?: string): Promise<RuleRunPreview> => {
+    const path = ruleId ? `rules/${ruleId}/run/preview` : 'rules/run/preview';
+    const response = await apiFetch(`${API_BASE_URL}/${path}`, {
+      method: 'POST',
+    });
+    if (!response.ok) {
+      throw new Error('Failed to preview rule run');
+    }
+    return response.json();
+  },
+
+  // Apply exactly the previewed changes; a stale preview (400) returns its conflicts
+  commitRuleRun: async (changes: RuleChangePreview[], ruleId?: string): Promise<CommitRuleRunResult> => {
+    const path = ruleId ? `rules/${ruleId}/run/commit` : 'rules/run/commit';
+    const response = await apiFetch(`${API_BASE_URL}/${path}`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({ changes }),
+    });
+    if (!response.ok && response.status !== 400) {
+      throw new Error('Failed to commit rule run');
+    }
+    // Invalid previews (e.g. a transaction listed twice) come back as 400 without a body
+    const body = await response.text();
+    if (!body) {
+      throw new Error('Invalid rule run preview');
+    }
+    return JSON.parse(body);
+  },
+
   // Test conditions (without saving a rule)
-  testConditions: async (conditions: RuleCondition[]): Promise<RuleTestResponse> => {
-    const response = await fetch(`${API_BASE_URL}/rules/test`, {
+  testConditions: async (conditions: RuleConditionNode[]): Promise<RuleTestResponse> => {
+    const response = await apiFetch(`${API_BASE_URL}/rules/test`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({ conditions }),
@@ -819,19 +1181,37 @@ export const rulesApi = {
 
   // Test an existing rule's conditions by ID
   testRule: async (id: string): Promise<RuleTestResponse> => {
-    const response = await fetch(`${API_BASE_URL}/rules/${id}/test`, { method: 'POST' });
+    const response = await apiFetch(`${API_BASE_URL}/rules/${id}/test`, { method: 'POST' });
     if (!response.ok) {
       throw new Error(`Failed to test rule with ID ${id}`);
     }
     return response.json();
   },
+
+  // Rules suggested from categorised transactions
+  getRuleSuggestions: async (): Promise<RuleSuggestion[]> => {
+    const response = await apiFetch(`${API_BASE_URL}/rules/suggestions`);
+    if (!response.ok) {
+      throw new Error('Failed to fetch rule suggestions');
+    }
+    return response.json();
+  },
+
+  // Create the rule of a suggestion
+  acceptRuleSuggestion: async (id: string): Promise<Rule> => {
+    const response = await apiFetch(`${API_BASE_URL}/rules/suggestions/${id}/accept`, { method: 'POST' });
+    if (!response.ok) {
+      throw new Error(`Failed to accept rule suggestion ${id}`);
+    }
+    return response.json();
+  },
 };
 
 // API functions for Firefly III import
 export const fireflyImportApi = {
   // Import data from Firefly III
   importFromFirefly: async (options: FireflyImportOptions): Promise<ImportResult> => {
-    const response = await fetch(`${API_BASE_URL}/imports/firefly`, {
+    const response = await apiFetch(`${API_BASE_URL}/imports/firefly`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -850,7 +1230,7 @@ export const fireflyImportApi = {
     formData.append('accounts', accountsFile);
     formData.append('transactions', transactionsFile);
 
-    const response = await fetch(`${API_BASE_URL}/imports/firefly/upload`, {
+    const response = await apiFetch(`${API_BASE_URL}/imports/firefly/upload`, {
       method: 'POST',
       body: formData,
     });
@@ -868,7 +1248,7 @@ export const fireflyImportApi = {
 export const featuresApi = {
   getFeatures: async (): Promise<Features> => {
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/features?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/features?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch features');
     }
@@ -882,7 +1262,7 @@ export const settingsApi = {
   getForecastedMonthlyIncome: async (): Promise<ForecastedMonthlyIncomeResponse> => {
     // Add a cache-busting parameter to prevent browser caching
     const cacheBuster = `_t=${Date.now()}`;
-    const response = await fetch(`${API_BASE_URL}/settings/forecasted-monthly-income?${cacheBuster}`);
+    const response = await apiFetch(`${API_BASE_URL}/settings/forecasted-monthly-income?${cacheBuster}`);
     if (!response.ok) {
       throw new Error('Failed to fetch forecasted monthly income');
     }
@@ -891,7 +1271,7 @@ export const settingsApi = {
 
   // Update forecasted monthly income
   updateForecastedMonthlyIncome: async (amount: number): Promise<ForecastedMonthlyIncomeResponse> => {
-    const response = await fetch(`${API_BASE_URL}/settings/forecasted-monthly-income`, {
+    const response = await apiFetch(`${API_BASE_URL}/settings/forecasted-monthly-income`, {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
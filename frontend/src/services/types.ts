@@ -5,12 +5,24 @@ export interface CategoryGroup {
   description?: string;
   created_at: string;
   updated_at: string;
+  goals?: BudgetGroupGoals | null; // budget groups only
+}
+
+// Goal progress of the budgets in a budget group this month, summed up
+export interface BudgetGroupGoals {
+  budgets_with_goals: number;
+  underfunded_budgets: number;
+  monthly_needed: number;
+  still_needed: number;
 }
 
 export interface RuleGroup {
   id: string;
   name: string;
   description?: string;
+  run_order: number; // lower runs first; rules without a group run last
+  is_active: boolean;
+  stop_after_first_match: boolean; // only the first matching rule of the group applies
   created_at: string;
   updated_at: string;
 }
@@ -35,7 +47,21 @@ export type ConditionType =
   | 'destination_name_equals'
   | 'amount_greater_than'
   | 'amount_less_than'
-  | 'amount_equals';
+  | 'amount_equals'
+  | 'description_not_contains'
+  | 'description_not_equals'
+  | 'description_matches_regex'
+  | 'source_account_not_equals'
+  | 'destination_account_not_equals'
+  | 'destination_name_not_contains'
+  | 'destination_name_not_equals'
+  | 'destination_name_matches_regex'
+  | 'date_before'
+  | 'date_after'
+  | 'date_equals'
+  | 'weekday_is'
+  | 'category_is_empty'
+  | 'budget_is_empty';
 
 // Constant values for condition types (for reference)
 export const ConditionTypes = {
@@ -49,6 +75,20 @@ export const ConditionTypes = {
   AmountGreaterThan: 'amount_greater_than' as ConditionType,
   AmountLessThan: 'amount_less_than' as ConditionType,
   AmountEquals: 'amount_equals' as ConditionType,
+  DescriptionNotContains: 'description_not_contains' as ConditionType,
+  DescriptionNotEquals: 'description_not_equals' as ConditionType,
+  DescriptionMatchesRegex: 'description_matches_regex' as ConditionType,
+  SourceAccountNotEquals: 'source_account_not_equals' as ConditionType,
+  DestinationAccountNotEquals: 'destination_account_not_equals' as ConditionType,
+  DestinationNameNotContains: 'destination_name_not_contains' as ConditionType,
+  DestinationNameNotEquals: 'destination_name_not_equals' as ConditionType,
+  DestinationNameMatchesRegex: 'destination_name_matches_regex' as ConditionType,
+  DateBefore: 'date_before' as ConditionType,
+  DateAfter: 'date_after' as ConditionType,
+  DateEquals: 'date_equals' as ConditionType,
+  WeekdayIs: 'weekday_is' as ConditionType,
+  CategoryIsEmpty: 'category_is_empty' as ConditionType,
+  BudgetIsEmpty: 'budget_is_empty' as ConditionType,
 };
 
 // String literal types for rule action types
@@ -56,7 +96,15 @@ export type ActionType =
   | 'set_category'
   | 'set_budget'
   | 'set_description'
-  | 'set_destination_name';
+  | 'set_destination_name'
+  | 'add_tag'
+  | 'set_destination_account'
+  | 'prepend_description'
+  | 'append_description'
+  | 'replace_description'
+  | 'set_memo'
+  | 'split_by_percentage'
+  | 'stop_processing';
 
 // Constant values for action types (for reference)
 export const ActionTypes = {
@@ -64,6 +112,14 @@ export const ActionTypes = {
   SetBudget: 'set_budget' as ActionType,
   SetDescription: 'set_description' as ActionType,
   SetDestinationName: 'set_destination_name' as ActionType,
+  AddTag: 'add_tag' as ActionType,
+  SetDestinationAccount: 'set_destination_account' as ActionType,
+  PrependDescription: 'prepend_description' as ActionType,
+  AppendDescription: 'append_description' as ActionType,
+  ReplaceDescription: 'replace_description' as ActionType,
+  SetMemo: 'set_memo' as ActionType,
+  SplitByPercentage: 'split_by_percentage' as ActionType,
+  StopProcessing: 'stop_processing' as ActionType,
 };
 
 // Represents a condition for a rule
@@ -72,10 +128,30 @@ export interface RuleCondition {
   value: string;
 }
 
+// Represents a group of conditions of which all or any must match
+export type RuleConditionGroup = { all: RuleConditionNode[] } | { any: RuleConditionNode[] };
+
+// Represents a single condition or a nested group of conditions
+export type RuleConditionNode = RuleCondition | RuleConditionGroup;
+
+// Whether a condition node is a group rather than a single condition
+export const isRuleConditionGroup = (node: RuleConditionNode): node is RuleConditionGroup =>
+  !('condition_type' in node);
+
 // Represents an action for a rule
 export interface RuleAction {
   action_type: ActionType;
   value: string;
+  pattern?: string; // regular expression replaced by replace_description
+  splits?: RuleSplitShare[]; // lines created by split_by_percentage
+}
+
+// One line of a split_by_percentage action; the percentages of an action add up to 100
+export interface RuleSplitShare {
+  percentage: number;
+  category_id?: string | null;
+  budget_id?: string | null;
+  memo?: string | null;
 }
 
 // Represents a rule in the system
@@ -86,12 +162,16 @@ export interface Rule {
   is_active: boolean;
   priority: number;
   group_id?: string;
-  conditions: RuleCondition[];
+  conditions: RuleConditionNode[];
   actions: RuleAction[];
+  triggers: RuleTrigger[]; // empty when the rule only runs by hand
   created_at: string;
   updated_at: string;
 }
 
+// Events on which a rule applies automatically
+export type RuleTrigger = 'create' | 'update' | 'import';
+
 export interface Account {
   id: string;
   name: string;
@@ -117,6 +197,12 @@ export interface Transaction {
   budget_id?: string;
   created_at: string;
   updated_at: string;
+  status?: 'uncleared' | 'cleared' | 'reconciled'; // reconciled transactions are locked until unlocked
+  reconciliation_id?: string | null;
+  foreign_amount?: number | null; // amount received by a destination in another currency
+  foreign_currency?: string | null;
+  tags?: string[];
+  memo?: string | null;
 }
 
 // Response for testing rule conditions
@@ -125,6 +211,62 @@ export interface RuleTestResponse {
   sample: Transaction[];
 }
 
+// Values of the transaction fields rules can change
+export interface RuleFieldValues {
+  category: string;
+  budget_id: string | null;
+  description: string;
+  destination_account_id: string;
+  destination_name: string | null; // null when the rules move the transaction to another account
+  memo: string | null;
+  splits: RuleSplitLine[];
+  tags: string[];
+}
+
+// Values of a split line in a rule preview
+export interface RuleSplitLine {
+  amount: number;
+  category_id: string | null;
+  budget_id: string | null;
+  memo: string | null;
+}
+
+// Change a rule run would make to one transaction
+export interface RuleChangePreview {
+  transaction_id: string;
+  rule_ids: string[];
+  before: RuleFieldValues;
+  after: RuleFieldValues;
+}
+
+export interface RuleRunPreview {
+  affected_transactions: number;
+  changes: RuleChangePreview[];
+}
+
+// Rule proposed from how similar transactions were categorised
+export interface RuleSuggestion {
+  id: string;
+  rule: {
+    name: string;
+    description?: string;
+    is_active: boolean;
+    conditions: RuleConditionNode[];
+    actions: RuleAction[];
+  };
+  support: number; // transactions the rule would match
+  confidence: number; // share of them already in the suggested category (0-1)
+  sample_transaction_ids: string[];
+}
+
+// Outcome of committing a preview; nothing is applied when there are conflicts
+export interface CommitRuleRunResult {
+  applied: boolean;
+  run_id: string | null;
+  affected_transactions: number;
+  conflicts: string[];
+}
+
 export interface Budget {
   id: string;
   name: string;
@@ -133,8 +275,78 @@ export interface Budget {
   start_date: string;
   end_date?: string;
   group_id?: string;
+  goal_type?: BudgetGoalType | null;
+  goal_amount?: number | null;
+  goal_target_date?: string | null; // YYYY-MM-DD
   created_at: string;
   updated_at: string;
+  goal_progress?: BudgetGoalProgress | null;
+}
+
+export type BudgetGoalType = 'monthly_funding' | 'target_balance' | 'spending_cap';
+
+// Progress of a budget towards its goal in the current month
+export interface BudgetGoalProgress {
+  month: string; // YYYY-MM
+  funded: number; // assigned plus moved in
+  spent: number;
+  available: number;
+  progress: number; // 0 to 1; share of the cap spent for a spending cap, above 1 when exceeded
+  monthly_needed: number;
+  still_needed: number;
+  underfunded: boolean;
+  over_cap: boolean;
+}
+
+// One budget in the envelope view of a month
+export interface BudgetMonthLine {
+  budget_id: string;
+  name: string;
+  group_id?: string | null;
+  carried_over: number; // available at the end of the previous month; negative when overspent
+  assigned: number;
+  transferred: number; // moved in from other budgets minus moved out
+  activity: number; // spent during the month
+  available: number;
+}
+
+// Money moved from one budget to another in a month
+export interface BudgetTransfer {
+  id: string;
+  from_budget_id: string;
+  to_budget_id: string;
+  month: string; // first day of the month
+  amount: number;
+  note?: string | null;
+  created_at: string;
+}
+
+export type BudgetAmountSource = 'source_month' | 'last_month_spending' | 'three_month_average';
+
+// Budgets created for a month from the budgets of another month
+export interface CopiedBudgetMonth {
+  month: string; // YYYY-MM
+  source_month: string; // YYYY-MM
+  created: Budget[];
+  skipped: string[]; // names the month already had
+}
+
+export interface BudgetMonth {
+  month: string; // YYYY-MM
+  assigned: number;
+  activity: number;
+  available: number;
+  budgets: BudgetMonthLine[];
+}
+
+// Money in On Budget accounts not given to a budget yet, with how it was worked out
+export interface ReadyToAssign {
+  on_budget_balance: number;
+  assigned: number; // over all months
+  activity: number; // over all months
+  available_in_budgets: number; // assigned - activity, overspent budgets included
+  overspent: number; // uncovered overspending from earlier months (zero or negative)
+  ready_to_assign: number;
 }
 
 export interface MonthlyBudgetStatus {
@@ -183,6 +395,7 @@ export interface FireflyImportOptions {
 
 // Firefly III import result
 export interface ImportResult {
+  batch_id: string; // groups the imported transactions in their change history
   accounts_imported: number;
   transactions_imported: number;
   errors: string[];
@@ -211,3 +424,146 @@ export interface InflowOutflowReportRow {
   inflow: number;
   outflow: number;
 }
+
+// End-of-period account balance from /api/accounts/{id}/balance-history
+export interface BalanceHistoryPoint {
+  period: string;
+  account_id: string;
+  balance: number;
+}
+
+// Net worth per period from /api/reports/net-worth, grouped by account type and subtype
+export interface NetWorthGroup {
+  account_type: string;
+  account_sub_type: string | null;
+  balance: number;
+}
+
+export interface NetWorthPoint {
+  period: string;
+  net_worth: number;
+  currency: string; // base currency the balances were converted into
+  groups: NetWorthGroup[];
+}
+
+// Filters for /api/transactions/search; all optional and combined with AND
+export interface TransactionSearchParams {
+  q?: string;
+  min_amount?: number;
+  max_amount?: number;
+  source_account_id?: string;
+  destination_account_id?: string;
+  account_id?: string;
+  category?: string;
+  category_group_id?: string;
+  budget_id?: string;
+  uncategorized?: boolean;
+  tag?: string;
+  status?: 'uncleared' | 'cleared' | 'reconciled';
+  start_date?: string;
+  end_date?: string;
+  sort?: 'date' | 'amount' | 'description' | 'created';
+  order?: 'asc' | 'desc';
+  limit?: number;
+  offset?: number;
+}
+
+export interface TransactionSearchResult {
+  total: number; // all matches, regardless of the page
+  limit: number;
+  offset: number;
+  transactions: Transaction[];
+}
+
+// Selection and patch for /api/transactions/bulk; give either transaction_ids or filter
+export interface BulkEditTransactionsRequest {
+  transaction_ids?: string[];
+  filter?: Omit<TransactionSearchParams, 'sort' | 'order' | 'limit' | 'offset'>;
+  patch: {
+    category?: string;
+    budget_id?: string;
+    destination_name?: string;
+    shift_days?: number;
+    delete?: boolean; // cannot be combined with other changes
+  };
+}
+
+export interface BulkEditItemResult {
+  transaction_id: string;
+  status: 'updated' | 'deleted' | 'not_found' | 'failed';
+  error?: string | null;
+}
+
+export interface BulkEditResult {
+  applied: boolean; // false when any item failed; nothing was changed then
+  run_id?: string | null; // to undo the whole edit
+  succeeded: number;
+  failed: number;
+  items: BulkEditItemResult[];
+}
+
+// One entry of a transaction's change history
+export interface TransactionChange {
+  id: string;
+  transaction_id: string;
+  change_type: 'create' | 'update';
+  source: 'user' | 'rule' | 'import' | 'recurring' | 'undo';
+  source_id?: string | null; // user, rule, recurring transaction or undone change
+  run_id?: string | null; // rule run, bulk edit or import batch
+  changes: Record<string, { old: unknown; new: unknown }>;
+  undone_at?: string | null;
+  created_at: string;
+}
+
+export interface UndoRunResult {
+  run_id: string;
+  undo_run_id: string;
+  undone: number;
+}
+
+export interface Tag {
+  id: string;
+  name: string;
+  description?: string | null;
+  transaction_count: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ExchangeRate {
+  id: string;
+  from_currency: string;
+  to_currency: string;
+  rate: number; // units of to_currency per unit of from_currency
+  rate_date: string;
+  created_at: string;
+  updated_at: string;
+}
+
+// Logged in user
+export interface User {
+  id: string;
+  username: string;
+  created_at: string;
+  updated_at: string;
+}
+
+// The books (accounts, transactions, budgets, ...) owned by a user
+export interface Ledger {
+  id: string;
+  owner_id: string | null;
+  name: string;
+  created_at: string;
+  updated_at: string;
+}
+
+// Response from /api/auth/me
+export interface CurrentUser {
+  user: User;
+  ledger: Ledger;
+}
+
+// Response from /api/auth/status
+export interface AuthStatus {
+  registration_open: boolean;
+}
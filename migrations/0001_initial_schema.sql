@@ -0,0 +1,152 @@
+-- Core schema: accounts, transactions, categories, budgets and rules.
+-- Written to be idempotent so databases created by the previous startup migration chain adopt it cleanly.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    account_type VARCHAR(50) NOT NULL,
+    balance NUMERIC(19, 4) NOT NULL DEFAULT 0.00,
+    currency VARCHAR(10) NOT NULL DEFAULT 'USD',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+ALTER TABLE accounts ADD COLUMN IF NOT EXISTS is_default BOOLEAN NOT NULL DEFAULT false;
+
+-- Only one account can be the default
+CREATE UNIQUE INDEX IF NOT EXISTS idx_accounts_is_default ON accounts (is_default) WHERE is_default = true;
+
+CREATE TABLE IF NOT EXISTS transactions (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    description VARCHAR(255) NOT NULL,
+    amount NUMERIC(19, 4) NOT NULL,
+    category VARCHAR(100) NOT NULL,
+    transaction_date TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+-- Double entry: source and destination accounts
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'transactions' AND column_name = 'source_account_id'
+    ) THEN
+        ALTER TABLE transactions
+            ADD COLUMN source_account_id UUID,
+            ADD COLUMN destination_account_id UUID NULL,
+            ADD COLUMN payee_name VARCHAR(255) NULL;
+
+        UPDATE transactions SET source_account_id = account_id;
+
+        ALTER TABLE transactions ALTER COLUMN source_account_id SET NOT NULL;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_source_account') THEN
+        ALTER TABLE transactions
+            ADD CONSTRAINT fk_source_account FOREIGN KEY (source_account_id) REFERENCES accounts(id) ON DELETE CASCADE;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_destination_account') THEN
+        ALTER TABLE transactions
+            ADD CONSTRAINT fk_destination_account FOREIGN KEY (destination_account_id) REFERENCES accounts(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_source_account_id ON transactions(source_account_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_destination_account_id ON transactions(destination_account_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_account_id ON transactions(account_id);
+CREATE INDEX IF NOT EXISTS idx_transactions_date ON transactions(transaction_date);
+
+-- Categories, seeded from the category names already used by transactions
+DO $$
+BEGIN
+    IF to_regclass('public.categories') IS NULL THEN
+        CREATE TABLE categories (
+            id UUID PRIMARY KEY,
+            name VARCHAR(255) NOT NULL,
+            description TEXT,
+            created_at TIMESTAMPTZ NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_name ON categories(name);
+
+        INSERT INTO categories (id, name, description, created_at, updated_at)
+        SELECT gen_random_uuid(), d.category, NULL, now(), now()
+        FROM (SELECT DISTINCT category FROM transactions) d
+        ON CONFLICT (name) DO NOTHING;
+    END IF;
+END $$;
+
+-- Stable category reference on transactions, backfilled from category names
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'transactions' AND column_name = 'category_id'
+    ) THEN
+        ALTER TABLE transactions ADD COLUMN category_id UUID NULL;
+
+        ALTER TABLE transactions
+            ADD CONSTRAINT fk_category FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL;
+
+        INSERT INTO categories (id, name, description, created_at, updated_at)
+        SELECT gen_random_uuid(), d.category, NULL, now(), now()
+        FROM (SELECT DISTINCT category FROM transactions) d
+        ON CONFLICT (name) DO NOTHING;
+
+        UPDATE transactions t
+        SET category_id = c.id
+        FROM categories c
+        WHERE t.category_id IS NULL AND t.category = c.name;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_category_id ON transactions(category_id);
+
+CREATE TABLE IF NOT EXISTS budgets (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    amount NUMERIC(19, 4) NOT NULL DEFAULT 0.00,
+    start_date TIMESTAMPTZ NOT NULL,
+    end_date TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_budgets_name ON budgets(name);
+CREATE INDEX IF NOT EXISTS idx_budgets_dates ON budgets(start_date, end_date);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'transactions' AND column_name = 'budget_id'
+    ) THEN
+        ALTER TABLE transactions ADD COLUMN budget_id UUID NULL;
+
+        ALTER TABLE transactions
+            ADD CONSTRAINT fk_budget FOREIGN KEY (budget_id) REFERENCES budgets(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_budget_id ON transactions(budget_id);
+
+CREATE TABLE IF NOT EXISTS rules (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    priority INTEGER NOT NULL DEFAULT 100,
+    conditions_json TEXT NOT NULL,
+    actions_json TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rules_name ON rules(name);
+CREATE INDEX IF NOT EXISTS idx_rules_active_priority ON rules(is_active, priority);
@@ -0,0 +1,30 @@
+-- Point transactions without a destination at a shared 'Unknown Destination' external account.
+
+DO $$
+DECLARE
+    unknown_destination_id UUID;
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM transactions WHERE destination_account_id IS NULL) THEN
+        RETURN;
+    END IF;
+
+    SELECT id INTO unknown_destination_id
+    FROM accounts
+    WHERE name = 'Unknown Destination' AND account_type IN ('DESTINATION', 'External')
+    LIMIT 1;
+
+    IF unknown_destination_id IS NULL THEN
+        unknown_destination_id := gen_random_uuid();
+        INSERT INTO accounts (id, name, account_type, balance, currency, created_at, updated_at)
+        VALUES (unknown_destination_id, 'Unknown Destination', 'External', 0.00, 'USD', now(), now());
+    END IF;
+
+    UPDATE transactions
+    SET destination_account_id = unknown_destination_id
+    WHERE destination_account_id IS NULL;
+
+    UPDATE accounts
+    SET balance = COALESCE((SELECT SUM(amount) FROM transactions WHERE destination_account_id = unknown_destination_id), 0),
+        updated_at = now()
+    WHERE id = unknown_destination_id;
+END $$;
@@ -0,0 +1,16 @@
+-- Display name of the destination, populated from the destination account when first added.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'transactions' AND column_name = 'destination_name'
+    ) THEN
+        ALTER TABLE transactions ADD COLUMN destination_name VARCHAR(255) NULL;
+
+        UPDATE transactions t
+        SET destination_name = a.name
+        FROM accounts a
+        WHERE t.destination_account_id = a.id;
+    END IF;
+END $$;
@@ -0,0 +1,3 @@
+-- Rename the legacy 'DESTINATION' account type to 'External'.
+
+UPDATE accounts SET account_type = 'External' WHERE account_type = 'DESTINATION';
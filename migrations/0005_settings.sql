@@ -0,0 +1,15 @@
+-- Key/value settings with the default forecasted monthly income.
+
+CREATE TABLE IF NOT EXISTS settings (
+    id SERIAL PRIMARY KEY,
+    key VARCHAR(255) NOT NULL UNIQUE,
+    value TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_settings_key ON settings(key);
+
+INSERT INTO settings (key, value, created_at, updated_at)
+VALUES ('forecasted_monthly_income', '0.0', now(), now())
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,23 @@
+-- Category groups.
+
+CREATE TABLE IF NOT EXISTS category_groups (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_category_groups_name ON category_groups(name);
+
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS group_id UUID NULL;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_category_group') THEN
+        ALTER TABLE categories
+            ADD CONSTRAINT fk_category_group FOREIGN KEY (group_id) REFERENCES category_groups(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_categories_group_id ON categories(group_id);
@@ -0,0 +1,23 @@
+-- Budget groups.
+
+CREATE TABLE IF NOT EXISTS budget_groups (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_budget_groups_name ON budget_groups(name);
+
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS group_id UUID NULL;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_budget_group') THEN
+        ALTER TABLE budgets
+            ADD CONSTRAINT fk_budget_group FOREIGN KEY (group_id) REFERENCES budget_groups(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_budgets_group_id ON budgets(group_id);
@@ -0,0 +1,23 @@
+-- Rule groups.
+
+CREATE TABLE IF NOT EXISTS rule_groups (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_rule_groups_name ON rule_groups(name);
+
+ALTER TABLE rules ADD COLUMN IF NOT EXISTS group_id UUID NULL;
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'fk_rule_group') THEN
+        ALTER TABLE rules
+            ADD CONSTRAINT fk_rule_group FOREIGN KEY (group_id) REFERENCES rule_groups(id) ON DELETE SET NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_rules_group_id ON rules(group_id);
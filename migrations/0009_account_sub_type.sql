@@ -0,0 +1,19 @@
+-- Account sub-types: split legacy "Type - SubType" values into account_type and account_sub_type.
+
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'accounts' AND column_name = 'account_sub_type'
+    ) THEN
+        ALTER TABLE accounts ADD COLUMN account_sub_type VARCHAR(50) NULL;
+
+        UPDATE accounts
+        SET account_type = btrim(split_part(btrim(account_type), ' - ', 1)),
+            account_sub_type = NULLIF(btrim(split_part(btrim(account_type), ' - ', 2)), '')
+        WHERE position(' - ' IN btrim(account_type)) > 0
+          AND btrim(split_part(btrim(account_type), ' - ', 1)) <> '';
+
+        UPDATE accounts SET account_type = btrim(account_type) WHERE account_type <> btrim(account_type);
+    END IF;
+END $$;
@@ -0,0 +1,15 @@
+-- Split lines dividing a transaction across categories and budgets.
+
+CREATE TABLE IF NOT EXISTS transaction_splits (
+    id UUID PRIMARY KEY,
+    transaction_id UUID NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    amount NUMERIC(19, 4) NOT NULL,
+    category_id UUID NULL REFERENCES categories(id) ON DELETE SET NULL,
+    budget_id UUID NULL REFERENCES budgets(id) ON DELETE SET NULL,
+    memo TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_transaction_splits_transaction_id ON transaction_splits(transaction_id);
+CREATE INDEX IF NOT EXISTS idx_transaction_splits_budget_id ON transaction_splits(budget_id);
@@ -0,0 +1,23 @@
+-- Store money as exact NUMERIC values; databases created before this used FLOAT8.
+
+DO $$
+DECLARE
+    money_column RECORD;
+BEGIN
+    FOR money_column IN
+        SELECT table_name, column_name
+        FROM information_schema.columns
+        WHERE (table_name, column_name) IN (
+            ('accounts', 'balance'),
+            ('transactions', 'amount'),
+            ('budgets', 'amount'),
+            ('transaction_splits', 'amount')
+        )
+        AND data_type <> 'numeric'
+    LOOP
+        EXECUTE format(
+            'ALTER TABLE %I ALTER COLUMN %I TYPE NUMERIC(19, 4) USING ROUND(%I::NUMERIC, 4)',
+            money_column.table_name, money_column.column_name, money_column.column_name
+        );
+    END LOOP;
+END $$;
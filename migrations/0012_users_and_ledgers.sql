@@ -0,0 +1,80 @@
+-- Users, login sessions and per-user ledgers.
+-- Every book-keeping table gets a ledger_id; existing data moves into a shared ledger
+-- without an owner, which is claimed by the first user to register.
+
+CREATE TABLE users (
+    id UUID PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_users_username ON users (lower(username));
+
+CREATE TABLE ledgers (
+    id UUID PRIMARY KEY,
+    owner_id UUID NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_ledgers_owner_id ON ledgers(owner_id);
+
+-- Only a hash of the session token is stored
+CREATE TABLE user_sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    token_hash VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE UNIQUE INDEX idx_user_sessions_token_hash ON user_sessions(token_hash);
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
+
+-- Unowned ledger holding the data created before users existed
+INSERT INTO ledgers (id, owner_id, name, created_at, updated_at)
+VALUES ('00000000-0000-0000-0000-000000000001', NULL, 'Household', now(), now());
+
+DO $$
+DECLARE
+    ledger_table TEXT;
+BEGIN
+    FOREACH ledger_table IN ARRAY ARRAY[
+        'accounts', 'transactions', 'categories', 'category_groups',
+        'budgets', 'budget_groups', 'rules', 'rule_groups', 'settings'
+    ]
+    LOOP
+        EXECUTE format('ALTER TABLE %I ADD COLUMN ledger_id UUID', ledger_table);
+        EXECUTE format('UPDATE %I SET ledger_id = %L', ledger_table, '00000000-0000-0000-0000-000000000001');
+        EXECUTE format('ALTER TABLE %I ALTER COLUMN ledger_id SET NOT NULL', ledger_table);
+        EXECUTE format(
+            'ALTER TABLE %I ADD CONSTRAINT %I FOREIGN KEY (ledger_id) REFERENCES ledgers(id) ON DELETE CASCADE',
+            ledger_table, 'fk_' || ledger_table || '_ledger'
+        );
+        EXECUTE format('CREATE INDEX %I ON %I (ledger_id)', 'idx_' || ledger_table || '_ledger_id', ledger_table);
+    END LOOP;
+END $$;
+
+-- Names, keys and the default account are unique per ledger rather than globally
+DROP INDEX IF EXISTS idx_accounts_is_default;
+CREATE UNIQUE INDEX idx_accounts_is_default ON accounts (ledger_id) WHERE is_default = true;
+
+DROP INDEX IF EXISTS idx_categories_name;
+CREATE UNIQUE INDEX idx_categories_name ON categories (ledger_id, name);
+
+DROP INDEX IF EXISTS idx_category_groups_name;
+CREATE UNIQUE INDEX idx_category_groups_name ON category_groups (ledger_id, name);
+
+DROP INDEX IF EXISTS idx_budget_groups_name;
+CREATE UNIQUE INDEX idx_budget_groups_name ON budget_groups (ledger_id, name);
+
+DROP INDEX IF EXISTS idx_rule_groups_name;
+CREATE UNIQUE INDEX idx_rule_groups_name ON rule_groups (ledger_id, name);
+
+ALTER TABLE settings DROP CONSTRAINT IF EXISTS settings_key_key;
+DROP INDEX IF EXISTS idx_settings_key;
+CREATE UNIQUE INDEX idx_settings_key ON settings (ledger_id, key);
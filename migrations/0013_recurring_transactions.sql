@@ -0,0 +1,29 @@
+-- Recurring transactions: a transaction template plus a recurrence schedule.
+-- next_occurrence is the first date that has not been posted or skipped yet (NULL once the schedule ends).
+
+CREATE TABLE recurring_transactions (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    template_json TEXT NOT NULL,
+    recurrence_json TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NULL,
+    next_occurrence DATE NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_recurring_transactions_ledger_id ON recurring_transactions(ledger_id);
+CREATE INDEX idx_recurring_transactions_next_occurrence ON recurring_transactions(next_occurrence) WHERE is_active = true;
+
+-- Occurrences that were posted or skipped; the primary key guarantees an occurrence is only posted once
+CREATE TABLE recurring_transaction_occurrences (
+    recurring_transaction_id UUID NOT NULL REFERENCES recurring_transactions(id) ON DELETE CASCADE,
+    occurrence_date DATE NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    transaction_id UUID NULL REFERENCES transactions(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (recurring_transaction_id, occurrence_date)
+);
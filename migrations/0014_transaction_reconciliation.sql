@@ -0,0 +1,26 @@
+-- Cleared/reconciled status on transactions and a record of each account reconciliation.
+-- Reconciled transactions are locked against edits until they are explicitly unlocked.
+
+CREATE TABLE reconciliations (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    account_id UUID NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+    statement_date DATE NOT NULL,
+    statement_balance NUMERIC(19, 4) NOT NULL,
+    cleared_balance NUMERIC(19, 4) NOT NULL,
+    transaction_count INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_reconciliations_account_id ON reconciliations(account_id);
+
+ALTER TABLE transactions
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'uncleared'
+        CHECK (status IN ('uncleared', 'cleared', 'reconciled')),
+    ADD COLUMN reconciliation_id UUID NULL REFERENCES reconciliations(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_transactions_status ON transactions(status);
+
+-- Opening balances are part of every statement
+UPDATE transactions SET status = 'cleared'
+WHERE description = 'Initial Balance' AND category = 'Initial Balance';
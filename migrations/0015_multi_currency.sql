@@ -0,0 +1,22 @@
+-- Exchange rates per ledger and foreign amounts on transactions between accounts of different currencies.
+-- A transaction's amount is in the source account's currency; foreign_amount is what the destination
+-- receives in its own currency and is NULL when both accounts share a currency.
+
+CREATE TABLE exchange_rates (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    from_currency VARCHAR(3) NOT NULL,
+    to_currency VARCHAR(3) NOT NULL,
+    -- One unit of from_currency is worth `rate` units of to_currency
+    rate NUMERIC(19, 8) NOT NULL CHECK (rate > 0),
+    rate_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (ledger_id, from_currency, to_currency, rate_date)
+);
+
+CREATE INDEX idx_exchange_rates_lookup ON exchange_rates(ledger_id, from_currency, to_currency, rate_date);
+
+ALTER TABLE transactions
+    ADD COLUMN foreign_amount NUMERIC(19, 4) NULL,
+    ADD COLUMN foreign_currency VARCHAR(3) NULL;
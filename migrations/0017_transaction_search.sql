@@ -0,0 +1,5 @@
+-- Full-text index for the transaction search endpoint. The expression must match the one in
+-- TransactionService::search_transactions for the planner to use it.
+
+CREATE INDEX idx_transactions_search_text ON transactions
+    USING GIN (to_tsvector('simple', description || ' ' || COALESCE(destination_name, '')));
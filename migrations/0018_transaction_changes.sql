@@ -0,0 +1,18 @@
+-- Append-only history of transaction changes: who or what changed which fields from what to what.
+-- Changes made by one operation (a rule run, bulk edit or import) share a run_id so they can be undone together.
+
+CREATE TABLE transaction_changes (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    transaction_id UUID NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+    change_type VARCHAR(20) NOT NULL,
+    source VARCHAR(20) NOT NULL,
+    source_id UUID NULL,
+    run_id UUID NULL,
+    changes_json TEXT NOT NULL,
+    undone_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_transaction_changes_transaction_id ON transaction_changes(transaction_id, created_at);
+CREATE INDEX idx_transaction_changes_run_id ON transaction_changes(run_id) WHERE run_id IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Free-form memo on transactions, e.g. the notes of an imported transaction or a note set by a rule.
+
+ALTER TABLE transactions ADD COLUMN memo TEXT NULL;
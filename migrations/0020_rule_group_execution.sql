@@ -0,0 +1,5 @@
+-- Rule groups run in order, can be switched off and can stop after their first matching rule.
+
+ALTER TABLE rule_groups ADD COLUMN IF NOT EXISTS run_order INTEGER NOT NULL DEFAULT 100;
+ALTER TABLE rule_groups ADD COLUMN IF NOT EXISTS is_active BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE rule_groups ADD COLUMN IF NOT EXISTS stop_after_first_match BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- Events on which a rule applies automatically; existing rules keep applying on all of them.
+
+ALTER TABLE rules ADD COLUMN IF NOT EXISTS triggers_json TEXT NOT NULL DEFAULT '["create","update","import"]';
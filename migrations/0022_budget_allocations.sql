@@ -0,0 +1,15 @@
+-- Amounts assigned to budgets per month (envelope budgeting). Months are stored as their first day.
+-- A budget without an allocation for the month of its start date keeps its amount there.
+
+CREATE TABLE budget_allocations (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    budget_id UUID NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    month DATE NOT NULL,
+    amount NUMERIC(19, 4) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (budget_id, month)
+);
+
+CREATE INDEX idx_budget_allocations_ledger_month ON budget_allocations(ledger_id, month);
@@ -0,0 +1,16 @@
+-- Money moved between budgets within a month, kept as a history of why budgets grew or shrank.
+
+CREATE TABLE budget_transfers (
+    id UUID PRIMARY KEY,
+    ledger_id UUID NOT NULL REFERENCES ledgers(id) ON DELETE CASCADE,
+    from_budget_id UUID NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    to_budget_id UUID NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    month DATE NOT NULL,
+    amount NUMERIC(19, 4) NOT NULL,
+    note TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_budget_transfers_ledger_month ON budget_transfers(ledger_id, month);
+CREATE INDEX idx_budget_transfers_from_budget_id ON budget_transfers(from_budget_id);
+CREATE INDEX idx_budget_transfers_to_budget_id ON budget_transfers(to_budget_id);
@@ -0,0 +1,5 @@
+-- Optional goal of a budget: a monthly funding target, a target balance by a date or a spending cap.
+
+ALTER TABLE budgets ADD COLUMN goal_type TEXT NULL;
+ALTER TABLE budgets ADD COLUMN goal_amount NUMERIC(19, 4) NULL;
+ALTER TABLE budgets ADD COLUMN goal_target_date DATE NULL;
@@ -0,0 +1,13 @@
+-- Converted totals fail instead of silently treating an amount without a known exchange rate as 1:1.
+-- Raised with SQLSTATE RL001 so the API can report it as a client error.
+
+CREATE OR REPLACE FUNCTION require_exchange_rate(rate NUMERIC, from_currency TEXT, to_currency TEXT, rate_date DATE)
+RETURNS NUMERIC AS $$
+BEGIN
+    IF rate IS NULL THEN
+        RAISE EXCEPTION 'No exchange rate from % to % on %; add a rate', from_currency, to_currency, rate_date
+            USING ERRCODE = 'RL001';
+    END IF;
+    RETURN rate;
+END;
+$$ LANGUAGE plpgsql STABLE;
@@ -12,6 +12,18 @@ pub struct Config {
     pub host: String,
     /// Enable Firefly import features (default: false)
     pub firefly_import: bool,
+    /// List pending database migrations and exit without applying them (default: false)
+    pub migrations_dry_run: bool,
+    /// Allow new users to register after the first one (default: false)
+    pub allow_registration: bool,
+    /// How long a login session stays valid, in hours (default: 720)
+    pub session_ttl_hours: i64,
+    /// Mark session cookies as Secure so they are only sent over HTTPS (default: false)
+    pub secure_cookies: bool,
+    /// Origins allowed to make cross-origin API requests (default: none)
+    pub cors_allowed_origins: Vec<String>,
+    /// How often due recurring transactions are posted, in seconds (default: 3600)
+    pub recurring_interval_secs: u64,
 }
 
 impl Config {
@@ -38,11 +50,58 @@ impl Config {
             .map(|v| v.eq_ignore_ascii_case("true") || v == "1" || v.eq_ignore_ascii_case("yes"))
             .unwrap_or(false);
 
+        // Dry-run flag for migrations (MIGRATIONS_DRY_RUN=true to only list pending migrations)
+        let migrations_dry_run = env::var("MIGRATIONS_DRY_RUN")
+            .ok()
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1" || v.eq_ignore_ascii_case("yes"))
+            .unwrap_or(false);
+
+        // Open registration flag (ALLOW_REGISTRATION=true lets anyone register after the first user)
+        let allow_registration = env::var("ALLOW_REGISTRATION")
+            .ok()
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1" || v.eq_ignore_ascii_case("yes"))
+            .unwrap_or(false);
+
+        // Get session lifetime from environment, or use default of 30 days
+        let session_ttl_hours = env::var("SESSION_TTL_HOURS")
+            .unwrap_or_else(|_| "720".to_string())
+            .parse::<i64>()
+            .unwrap_or(720);
+
+        // Secure cookie flag (SECURE_COOKIES=true when served over HTTPS)
+        let secure_cookies = env::var("SECURE_COOKIES")
+            .ok()
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1" || v.eq_ignore_ascii_case("yes"))
+            .unwrap_or(false);
+
+        // Comma-separated list of origins allowed to call the API from another site
+        let cors_allowed_origins = env::var("CORS_ALLOWED_ORIGINS")
+            .map(|v| {
+                v.split(',')
+                    .map(|origin| origin.trim().to_string())
+                    .filter(|origin| !origin.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Get recurring transaction scheduler period from environment, or use default of one hour
+        let recurring_interval_secs = env::var("RECURRING_INTERVAL_SECS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<u64>()
+            .unwrap_or(3600)
+            .max(1);
+
         Ok(Self {
             database_url,
             port,
             host,
             firefly_import,
+            migrations_dry_run,
+            allow_registration,
+            session_ttl_hours,
+            secure_cookies,
+            cors_allowed_origins,
+            recurring_interval_secs,
         })
     }
 }
@@ -2,26 +2,8 @@ use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
 use std::time::Duration;
 
 mod migrations;
-mod double_entry_migration;
-mod fix_null_destination_migration;
-mod add_destination_name_migration;
-mod update_destination_account_type_migration;
-mod settings_migration;
-mod category_groups_migration;
-mod budget_groups_migration;
-mod account_sub_type_migration;
-mod rule_groups_migration;
 
-pub use migrations::run_migrations;
-pub use double_entry_migration::migrate_to_double_entry;
-pub use fix_null_destination_migration::fix_null_destination_accounts;
-pub use add_destination_name_migration::add_destination_name_column;
-pub use update_destination_account_type_migration::update_destination_account_type;
-pub use settings_migration::add_settings_table;
-pub use category_groups_migration::add_category_groups;
-pub use budget_groups_migration::add_budget_groups as add_budget_groups_migration;
-pub use account_sub_type_migration::add_account_sub_type;
-pub use rule_groups_migration::add_rule_groups;
+pub use migrations::{migration_status, run_migrations, MigrationState};
 
 /// Initialize a connection pool to the database
 pub async fn init_db_pool(database_url: &str) -> Result<Pool<Postgres>, sqlx::Error> {
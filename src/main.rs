@@ -14,7 +14,7 @@ use axum::routing::get;
 use std::path::PathBuf;
 use tokio::fs;
 use tokio::net::TcpListener;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 use tracing::info;
@@ -75,18 +75,47 @@ async fn api_root_handler() -> Html<String> {
     <p>A personal finance application built with Rust, providing a web-based interface for managing accounts and tracking financial transactions.</p>
 
     <h2>API Endpoints</h2>
+    <p>All endpoints except <code>/api/auth/*</code> and <code>/api/features</code> require a session cookie or an <code>Authorization: Bearer &lt;token&gt;</code> header.</p>
     <ul>
+        <li><code>POST /api/auth/register</code> - Register a user (first user, or when registration is open)</li>
+        <li><code>POST /api/auth/login</code> - Log in and start a session</li>
+        <li><code>POST /api/auth/logout</code> - End the current session</li>
+        <li><code>GET /api/auth/me</code> - Get the logged in user and ledger</li>
         <li><code>GET /api/accounts</code> - List all accounts</li>
         <li><code>GET /api/accounts/{{id}}</code> - Get a specific account</li>
         <li><code>POST /api/accounts</code> - Create a new account</li>
         <li><code>PUT /api/accounts/{{id}}</code> - Update an account</li>
         <li><code>DELETE /api/accounts/{{id}}</code> - Delete an account</li>
         <li><code>GET /api/transactions</code> - List all transactions</li>
+        <li><code>GET /api/transactions/search?q=text</code> - Search transactions with filters, sorting and a total count</li>
+        <li><code>POST /api/transactions/bulk</code> - Edit or delete many transactions atomically</li>
         <li><code>GET /api/accounts/{{id}}/transactions</code> - List transactions for a specific account</li>
         <li><code>GET /api/transactions/{{id}}</code> - Get a specific transaction</li>
         <li><code>POST /api/transactions</code> - Create a new transaction</li>
         <li><code>PUT /api/transactions/{{id}}</code> - Update a transaction</li>
         <li><code>DELETE /api/transactions/{{id}}</code> - Delete a transaction</li>
+        <li><code>POST /api/transactions/{{id}}/unlock</code> - Unlock a reconciled transaction</li>
+        <li><code>GET /api/transactions/{{id}}/history</code> - Change history of a transaction</li>
+        <li><code>POST /api/transaction-changes/{{id}}/undo</code> - Undo a single change</li>
+        <li><code>POST /api/transaction-changes/runs/{{run_id}}/undo</code> - Undo all changes of a rule run or bulk edit</li>
+        <li><code>POST /api/accounts/{{id}}/reconcile</code> - Reconcile an account against a statement date and ending balance</li>
+        <li><code>GET /api/accounts/{{id}}/reconciliations</code> - List past reconciliations of an account</li>
+        <li><code>GET /api/integrity</code> - Recompute account balances and report ledger inconsistencies</li>
+        <li><code>POST /api/integrity/repair</code> - Reset mismatched account balances to the recomputed ones</li>
+        <li><code>GET /api/accounts/{{id}}/balance-history?period=month</code> - End-of-period balances of an account</li>
+        <li><code>GET /api/reports/net-worth?period=month</code> - Net worth over time, grouped by account type and subtype</li>
+        <li><code>GET /api/exchange-rates</code> - List exchange rates</li>
+        <li><code>POST /api/exchange-rates</code> - Create or replace an exchange rate</li>
+        <li><code>POST /api/exchange-rates/import</code> - Import exchange rates from CSV</li>
+        <li><code>GET /api/exchange-rates/rate?from_currency=EUR&to_currency=USD</code> - Look up the rate in effect on a day</li>
+        <li><code>DELETE /api/exchange-rates/{{id}}</code> - Delete an exchange rate</li>
+        <li><code>GET /api/tags</code> - List tags</li>
+        <li><code>POST /api/tags</code> - Create a tag</li>
+        <li><code>GET /api/tags/{{id}}</code> - Get a specific tag</li>
+        <li><code>PUT /api/tags/{{id}}</code> - Update a tag</li>
+        <li><code>DELETE /api/tags/{{id}}</code> - Delete a tag</li>
+        <li><code>GET /api/settings/base-currency</code> - Get the base currency for reports</li>
+        <li><code>PUT /api/settings/base-currency</code> - Set the base currency for reports</li>
         <li><code>GET /api/budgets</code> - List all budgets</li>
         <li><code>GET /api/budgets/active</code> - List active budgets</li>
         <li><code>GET /api/budgets/monthly-status?year=YYYY&month=MM</code> - Get monthly budget status</li>
@@ -95,7 +124,23 @@ async fn api_root_handler() -> Html<String> {
         <li><code>PUT /api/budgets/{{id}}</code> - Update a budget</li>
         <li><code>DELETE /api/budgets/{{id}}</code> - Delete a budget</li>
         <li><code>GET /api/budgets/{{id}}/spent</code> - Get total spent amount for a budget</li>
-        <li><code>GET /api/budgets/{{id}}/remaining</code> - Get remaining amount for a budget</li>
+        <li><code>GET /api/budgets/{{id}}/remaining</code> - Get the amount available in a budget this month</li>
+        <li><code>GET /api/budgets/month/YYYY-MM</code> - Get assigned, activity and available amounts of all budgets in a month</li>
+        <li><code>POST /api/budgets/month/YYYY-MM/copy</code> - Create a month's budgets from another month's budgets</li>
+        <li><code>PUT /api/budgets/{{id}}/allocations/YYYY-MM</code> - Assign an amount to a budget for a month</li>
+        <li><code>POST /api/budgets/transfers</code> - Move money between budgets in a month</li>
+        <li><code>GET /api/budgets/transfers</code> - List money moved between budgets</li>
+        <li><code>GET /api/budgets/ready-to-assign</code> - Get the money in on-budget accounts not given to a budget yet</li>
+        <li><code>GET /api/recurring-transactions</code> - List recurring transactions</li>
+        <li><code>POST /api/recurring-transactions</code> - Create a recurring transaction</li>
+        <li><code>GET /api/recurring-transactions/upcoming?days=30</code> - List upcoming occurrences</li>
+        <li><code>POST /api/recurring-transactions/{{id}}/skip</code> - Skip the next (or a given) occurrence</li>
+        <li><code>POST /api/recurring-transactions/{{id}}/post</code> - Post the next (or a given) occurrence early</li>
+        <li><code>POST /api/rules/run</code> - Run all active rules on all transactions</li>
+        <li><code>POST /api/rules/run/preview</code> - Preview the changes a rule run would make</li>
+        <li><code>POST /api/rules/run/commit</code> - Apply exactly the previewed changes of a rule run</li>
+        <li><code>GET /api/rules/suggestions</code> - Suggest rules learned from categorised transactions</li>
+        <li><code>POST /api/rules/suggestions/{{id}}/accept</code> - Create the rule of a suggestion</li>
     </ul>
 
     <p><a href="/">Go to Web Interface</a></p>
@@ -115,48 +160,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize database connection
     let db_pool = db::init_db_pool(&config.database_url).await?;
 
-    // Run database migrations
-    db::run_migrations(&db_pool).await?;
-
-    // Run migration to fix NULL destination_account_id values
-    db::fix_null_destination_accounts(&db_pool).await?;
-
-    // Run migration to add destination_name column
-    db::add_destination_name_column(&db_pool).await?;
-
-    // Run migration to update account types from 'DESTINATION' to 'External'
-    db::update_destination_account_type(&db_pool).await?;
-
-    // Run migration to add settings table with forecasted_monthly_income
-    db::add_settings_table(&db_pool).await?;
-
-    // Run migration to add category groups functionality
-    db::add_category_groups(&db_pool).await?;
-
-    // Run migration to add budget groups functionality
-    db::add_budget_groups_migration(&db_pool).await?;
-
-    // Run migration to add rule groups functionality
-    db::add_rule_groups(&db_pool).await?;
+    // List pending migrations without applying them when running as a dry run
+    if config.migrations_dry_run {
+        let status = db::migration_status(&db_pool).await?;
+        println!("Database migrations (dry run, nothing applied):");
+        for migration in &status {
+            println!("  {:>4}  {:<40} {:?}", migration.version, migration.description, migration.state);
+        }
+        let pending = status.iter().filter(|m| m.state == db::MigrationState::Pending).count();
+        println!("{} pending migration(s)", pending);
+        return Ok(());
+    }
 
-    // Run migration to add account_sub_type field and split account types
-    db::add_account_sub_type(&db_pool).await?;
+    // Apply pending versioned migrations; refuses to start if the database is ahead of this binary
+    db::run_migrations(&db_pool).await?;
 
     // Check database connection
     db::check_db_connection(&db_pool).await?;
 
     // Create services
     let account_service = Arc::new(services::AccountService::new(db_pool.clone()));
-    let transaction_service = Arc::new(services::TransactionService::new(db_pool.clone()));
     let category_service = Arc::new(services::CategoryService::new(db_pool.clone()));
     let category_group_service = Arc::new(services::CategoryGroupService::new(db_pool.clone()));
     let settings_service = Arc::new(services::SettingsService::new(db_pool.clone()));
+    // Reports convert amounts into the base currency configured in the settings
+    let transaction_service = Arc::new(services::TransactionService::new(db_pool.clone()).with_settings_service(settings_service.clone()));
     // Wire settings service into budget service so forecasted monthly income works on budget page
     let budget_service = Arc::new(services::BudgetService::new(db_pool.clone()).with_settings_service(settings_service.clone()));
-    let budget_group_service = Arc::new(services::BudgetGroupService::new(db_pool.clone()));
-    let rule_service = Arc::new(services::RuleService::new(db_pool.clone()));
-    let rule_group_service = Arc::new(services::RuleGroupService::new(db_pool.clone()));
+    let budget_group_service = Arc::new(services::BudgetGroupService::new(db_pool.clone()).with_budget_service(budget_service.clone()));
+    let rule_service = Arc::new(services::RuleService::new(db_pool.clone(), transaction_service.clone()));
+    let rule_group_service = Arc::new(services::RuleGroupService::new(db_pool.clone(), rule_service.clone()));
+    let rule_suggestion_service = Arc::new(services::RuleSuggestionService::new(db_pool.clone(), rule_service.clone()));
     let import_service = Arc::new(services::FireflyImportService::new(db_pool.clone()));
+    let reconciliation_service = Arc::new(services::ReconciliationService::new(db_pool.clone()));
+    let integrity_service = Arc::new(services::IntegrityService::new(db_pool.clone()));
+    let exchange_rate_service = Arc::new(services::ExchangeRateService::new(db_pool.clone()));
+    let tag_service = Arc::new(services::TagService::new(db_pool.clone()));
+    let auth_service = Arc::new(
+        services::AuthService::new(db_pool.clone())
+            .with_open_registration(config.allow_registration)
+            .with_session_ttl(chrono::Duration::hours(config.session_ttl_hours))
+            .with_secure_cookies(config.secure_cookies),
+    );
 
     // Create transaction rule service that combines transaction service and rule service
     let transaction_rule_service = Arc::new(services::TransactionRuleService::new(
@@ -164,11 +209,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         rule_service.clone()
     ));
 
-    // Set up CORS
+    // Post due recurring transactions in the background; rules apply as for any new transaction
+    let recurring_transaction_service = Arc::new(services::RecurringTransactionService::new(
+        db_pool.clone(),
+        transaction_rule_service.clone(),
+    ));
+    recurring_transaction_service
+        .clone()
+        .start_scheduler(std::time::Duration::from_secs(config.recurring_interval_secs));
+
+    // Set up CORS; the bundled frontend is same-origin, other origins must be listed explicitly
+    let allowed_origins = config
+        .cors_allowed_origins
+        .iter()
+        .map(|origin| origin.parse().expect("Invalid origin in CORS_ALLOWED_ORIGINS"))
+        .collect::<Vec<header::HeaderValue>>();
     let cors = CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers([header::CONTENT_TYPE])
-        .allow_origin(Any);
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+        .allow_credentials(true)
+        .allow_origin(AllowOrigin::list(allowed_origins));
 
     // Create API router
     let api_router = routes::create_router(
@@ -181,8 +241,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         budget_group_service.clone(),
         rule_service.clone(),
         rule_group_service.clone(),
+        rule_suggestion_service.clone(),
         import_service.clone(),
         settings_service.clone(),
+        recurring_transaction_service.clone(),
+        reconciliation_service.clone(),
+        integrity_service.clone(),
+        exchange_rate_service.clone(),
+        tag_service.clone(),
+        auth_service.clone(),
         config.firefly_import,
     );
 
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use rust_decimal::Decimal;
 
 /// Represents a financial account in the system
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -15,7 +16,7 @@ pub struct Account {
     /// Subtype of account (e.g., "Checking", "Savings", "Credit Card")
     pub account_sub_type: Option<String>,
     /// Current balance of the account
-    pub balance: f64,
+    pub balance: Decimal,
     /// Currency of the account (e.g., "USD", "EUR")
     pub currency: String,
     /// Whether this is the default account
@@ -32,7 +33,7 @@ pub struct CreateAccountRequest {
     pub name: String,
     pub account_type: String,
     pub account_sub_type: Option<String>,
-    pub balance: f64,
+    pub balance: Decimal,
     pub currency: String,
     #[serde(default)]
     pub is_default: bool,
@@ -44,7 +45,7 @@ pub struct UpdateAccountRequest {
     pub name: Option<String>,
     pub account_type: Option<String>,
     pub account_sub_type: Option<String>,
-    pub balance: Option<f64>,
+    pub balance: Option<Decimal>,
     pub currency: Option<String>,
     pub is_default: Option<bool>,
 }
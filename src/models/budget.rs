@@ -1,7 +1,15 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use rust_decimal::Decimal;
+
+/// Assign a fixed amount to the budget every month
+pub const GOAL_TYPE_MONTHLY_FUNDING: &str = "monthly_funding";
+/// Have the goal amount available in the budget by the target date
+pub const GOAL_TYPE_TARGET_BALANCE: &str = "target_balance";
+/// Spend no more than the goal amount per month
+pub const GOAL_TYPE_SPENDING_CAP: &str = "spending_cap";
 
 /// Represents a budget in the system
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -13,17 +21,51 @@ pub struct Budget {
     /// Description of the budget
     pub description: Option<String>,
     /// Total amount allocated to this budget
-    pub amount: f64,
+    pub amount: Decimal,
     /// Start date of the budget period
     pub start_date: DateTime<Utc>,
     /// End date of the budget period
     pub end_date: Option<DateTime<Utc>>,
     /// Group this budget belongs to (optional)
     pub group_id: Option<Uuid>,
+    /// Kind of goal the budget works towards (monthly_funding, target_balance or spending_cap)
+    pub goal_type: Option<String>,
+    /// Amount of the goal: funded per month, balance to reach or monthly spending limit
+    pub goal_amount: Option<Decimal>,
+    /// Date a target balance should be reached by
+    pub goal_target_date: Option<NaiveDate>,
     /// When the budget was created
     pub created_at: DateTime<Utc>,
     /// When the budget was last updated
     pub updated_at: DateTime<Utc>,
+    /// How far the budget is towards its goal in the current month
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub goal_progress: Option<BudgetGoalProgress>,
+}
+
+/// Progress of a budget towards its goal in a month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetGoalProgress {
+    /// The month (YYYY-MM)
+    pub month: String,
+    /// Assigned to the budget this month, plus what was moved to it
+    pub funded: Decimal,
+    /// Spent from the budget this month
+    pub spent: Decimal,
+    /// Available at the end of the month
+    pub available: Decimal,
+    /// Share of the goal reached, from 0 to 1. For a spending cap this is the share of the cap
+    /// spent and goes above 1 when the cap is exceeded.
+    pub progress: Decimal,
+    /// Amount to fund this month to reach the goal on time; zero for a spending cap
+    pub monthly_needed: Decimal,
+    /// Part of the monthly amount not funded yet
+    pub still_needed: Decimal,
+    /// The budget was funded less than its goal needs this month
+    pub underfunded: bool,
+    /// More was spent this month than the spending cap allows
+    pub over_cap: bool,
 }
 
 /// Data required to create a new budget
@@ -31,19 +73,26 @@ pub struct Budget {
 pub struct CreateBudgetRequest {
     pub name: String,
     pub description: Option<String>,
-    pub amount: f64,
+    pub amount: Decimal,
     pub start_date: DateTime<Utc>,
     pub end_date: Option<DateTime<Utc>>,
     pub group_id: Option<Uuid>,
+    pub goal_type: Option<String>,
+    pub goal_amount: Option<Decimal>,
+    pub goal_target_date: Option<NaiveDate>,
 }
 
-/// Data required to update an existing budget
+/// Data required to update an existing budget. Goal fields left out keep their value; an empty
+/// goal type removes the goal.
 #[derive(Debug, Deserialize)]
 pub struct UpdateBudgetRequest {
     pub name: Option<String>,
     pub description: Option<String>,
-    pub amount: Option<f64>,
+    pub amount: Option<Decimal>,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
     pub group_id: Option<Uuid>,
+    pub goal_type: Option<String>,
+    pub goal_amount: Option<Decimal>,
+    pub goal_target_date: Option<NaiveDate>,
 }
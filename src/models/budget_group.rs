@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -16,6 +17,23 @@ pub struct BudgetGroup {
     pub created_at: DateTime<Utc>,
     /// When the budget group was last updated
     pub updated_at: DateTime<Utc>,
+    /// Goal progress of the budgets in the group for the current month
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub goals: Option<BudgetGroupGoals>,
+}
+
+/// Goal progress of the budgets in a group, summed up
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetGroupGoals {
+    /// Budgets in the group that have a goal
+    pub budgets_with_goals: usize,
+    /// Budgets funded less than their goal needs this month
+    pub underfunded_budgets: usize,
+    /// Amount to fund this month to meet all goals on time
+    pub monthly_needed: Decimal,
+    /// Part of that amount not funded yet
+    pub still_needed: Decimal,
 }
 
 /// Data required to create a new budget group
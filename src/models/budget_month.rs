@@ -0,0 +1,78 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Amount assigned to a budget for one month
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BudgetAllocation {
+    pub id: Uuid,
+    pub budget_id: Uuid,
+    /// First day of the month
+    pub month: NaiveDate,
+    pub amount: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to assign an amount to a budget for a month
+#[derive(Debug, Deserialize)]
+pub struct SetBudgetAllocationRequest {
+    pub amount: Decimal,
+}
+
+/// One budget in a month: what was carried over, assigned, moved and spent, and what is left
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetMonthLine {
+    pub budget_id: Uuid,
+    pub name: String,
+    pub group_id: Option<Uuid>,
+    /// Available at the end of the previous month; negative when the budget was overspent
+    pub carried_over: Decimal,
+    /// Assigned to the budget for this month
+    pub assigned: Decimal,
+    /// Moved to the budget from other budgets this month, minus what was moved away from it
+    pub transferred: Decimal,
+    /// Spent from the budget during this month
+    pub activity: Decimal,
+    /// Carried over plus assigned and transferred minus activity, carried into the next month
+    pub available: Decimal,
+}
+
+/// Envelope view of all budgets in a month
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetMonth {
+    /// The month (YYYY-MM)
+    pub month: String,
+    pub assigned: Decimal,
+    pub activity: Decimal,
+    pub available: Decimal,
+    pub budgets: Vec<BudgetMonthLine>,
+}
+
+/// Money in On Budget accounts that has not been given to a budget yet (zero-based budgeting)
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadyToAssign {
+    /// Current balance of all On Budget accounts, in the base currency
+    pub on_budget_balance: Decimal,
+    /// Assigned to budgets over all months, later months included
+    pub assigned: Decimal,
+    /// Spent from budgets over all months
+    pub activity: Decimal,
+    /// Assigned minus activity: the money still in budgets. Overspent budgets count with their
+    /// negative balance, so the money to cover them is part of what is ready to assign.
+    pub available_in_budgets: Decimal,
+    /// Overspending carried into this month from earlier months that is not covered yet (zero or negative)
+    pub overspent: Decimal,
+    /// On Budget balance minus the money still in budgets, minus the uncovered overspending
+    pub ready_to_assign: Decimal,
+}
+
+/// Parse a month written as YYYY-MM into its first day. Years outside 1900-9999 are rejected,
+/// so the months around it can always be computed.
+pub fn parse_budget_month(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d")
+        .ok()
+        .filter(|month| (1900..=9999).contains(&month.year()))
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::Budget;
+
+/// New budgets get the amount assigned to the budget they copy in the source month
+pub const AMOUNT_SOURCE_SOURCE_MONTH: &str = "source_month";
+/// New budgets get what was spent under the same budget name in the month before
+pub const AMOUNT_SOURCE_LAST_MONTH_SPENDING: &str = "last_month_spending";
+/// New budgets get the average spent under the same budget name over the three months before
+pub const AMOUNT_SOURCE_THREE_MONTH_AVERAGE: &str = "three_month_average";
+
+/// Data required to create a month's budgets from the budgets of another month
+#[derive(Debug, Default, Deserialize)]
+pub struct CopyBudgetMonthRequest {
+    /// Month to copy the budgets of (YYYY-MM); the month before the new month by default
+    pub source_month: Option<String>,
+    /// Where the amounts of the new budgets come from; source_month by default
+    pub amount_source: Option<String>,
+    /// Amount source for the budgets of particular groups, overriding amount_source
+    #[serde(default)]
+    pub group_amount_sources: HashMap<Uuid, String>,
+}
+
+/// Budgets created for a month from the budgets of another month
+#[derive(Debug, Clone, Serialize)]
+pub struct CopiedBudgetMonth {
+    /// The new month (YYYY-MM)
+    pub month: String,
+    /// The month copied from (YYYY-MM)
+    pub source_month: String,
+    pub created: Vec<Budget>,
+    /// Names of the budgets left out because the month already has a budget with that name
+    pub skipped: Vec<String>,
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Money moved from one budget to another in a month
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BudgetTransfer {
+    pub id: Uuid,
+    pub from_budget_id: Uuid,
+    pub to_budget_id: Uuid,
+    /// First day of the month the money moves in
+    pub month: NaiveDate,
+    /// Amount moved, always positive
+    pub amount: Decimal,
+    /// Why the money was moved
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Data required to move money between budgets
+#[derive(Debug, Deserialize)]
+pub struct CreateBudgetTransferRequest {
+    pub from_budget_id: Uuid,
+    pub to_budget_id: Uuid,
+    /// Month written as YYYY-MM
+    pub month: String,
+    pub amount: Decimal,
+    pub note: Option<String>,
+}
+
+/// Filters of the transfer history
+#[derive(Debug, Default, Deserialize)]
+pub struct BudgetTransferQuery {
+    /// Transfers from or to this budget
+    pub budget_id: Option<Uuid>,
+    /// Transfers in this month (YYYY-MM)
+    pub month: Option<String>,
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Default base currency used for reports until one is configured
+pub const DEFAULT_BASE_CURRENCY: &str = "USD";
+
+/// Represents the value of one currency in another on a given day
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExchangeRate {
+    /// Unique identifier for the exchange rate
+    pub id: Uuid,
+    /// Currency being converted (e.g., "EUR")
+    pub from_currency: String,
+    /// Currency converted into (e.g., "USD")
+    pub to_currency: String,
+    /// Units of to_currency one unit of from_currency is worth
+    pub rate: Decimal,
+    /// Day the rate applies to; it stays in effect until a later rate
+    pub rate_date: NaiveDate,
+    /// When the exchange rate was created
+    pub created_at: DateTime<Utc>,
+    /// When the exchange rate was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create (or replace) the exchange rate of a currency pair on a day
+#[derive(Debug, Deserialize)]
+pub struct CreateExchangeRateRequest {
+    pub from_currency: String,
+    pub to_currency: String,
+    pub rate: Decimal,
+    pub rate_date: NaiveDate,
+}
+
+/// Optional filters when listing exchange rates
+#[derive(Debug, Deserialize)]
+pub struct ExchangeRateQuery {
+    pub from_currency: Option<String>,
+    pub to_currency: Option<String>,
+}
+
+/// Outcome of importing exchange rates from a CSV file
+#[derive(Debug, Serialize)]
+pub struct ExchangeRateImportResult {
+    /// Number of rates created or replaced
+    pub imported: usize,
+    /// One message per line that could not be imported
+    pub errors: Vec<String>,
+}
+
+/// Request to change the base currency reports are converted into
+#[derive(Debug, Deserialize)]
+pub struct UpdateBaseCurrencyRequest {
+    pub base_currency: String,
+}
+
+/// Response for the base currency setting
+#[derive(Debug, Serialize)]
+pub struct BaseCurrencyResponse {
+    pub base_currency: String,
+}
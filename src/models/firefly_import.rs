@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use rust_decimal::Decimal;
 
 // Account type mapping
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -53,7 +54,7 @@ pub struct FailedTransactionDetails {
     pub destination_account_id: Option<Uuid>,
     pub destination_name: Option<String>,
     pub description: String,
-    pub amount: f64,
+    pub amount: Decimal,
     pub category: String,
     pub budget_id: Option<Uuid>,
     pub transaction_date: Option<DateTime<Utc>>,
@@ -63,6 +64,8 @@ pub struct FailedTransactionDetails {
 // Import result
 #[derive(Debug, Serialize)]
 pub struct ImportResult {
+    /// Groups the transactions created by this import in their change history
+    pub batch_id: Uuid,
     pub accounts_imported: usize,
     pub transactions_imported: usize,
     pub errors: Vec<String>,
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An account whose stored balance differs from the balance computed from its transactions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BalanceMismatch {
+    pub account_id: Uuid,
+    pub account_name: String,
+    /// Balance currently stored on the account
+    pub stored_balance: Decimal,
+    /// Incoming minus outgoing transaction amounts
+    pub computed_balance: Decimal,
+    /// computed_balance - stored_balance
+    pub difference: Decimal,
+}
+
+/// A transaction whose destination account is missing or belongs to another ledger
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrphanedTransaction {
+    pub transaction_id: Uuid,
+    pub source_account_id: Uuid,
+    /// The dangling destination, or None when it was cleared by an account deletion
+    pub destination_account_id: Option<Uuid>,
+    pub destination_name: Option<String>,
+    pub description: String,
+    pub amount: Decimal,
+    pub transaction_date: DateTime<Utc>,
+}
+
+/// A transaction that moves money from an account to itself
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SelfTransferTransaction {
+    pub transaction_id: Uuid,
+    pub account_id: Uuid,
+    pub description: String,
+    pub amount: Decimal,
+    pub transaction_date: DateTime<Utc>,
+}
+
+/// Result of checking a ledger's balances and transactions
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    /// Number of accounts whose balance was recomputed
+    pub accounts_checked: i64,
+    pub balance_mismatches: Vec<BalanceMismatch>,
+    pub orphaned_transactions: Vec<OrphanedTransaction>,
+    pub self_transfers: Vec<SelfTransferTransaction>,
+    /// Whether the mismatched balances were overwritten with the computed ones
+    pub repaired: bool,
+    /// True when no problems were found (before any repair)
+    pub is_consistent: bool,
+    pub checked_at: DateTime<Utc>,
+}
@@ -4,10 +4,22 @@ mod category;
 mod category_group;
 mod budget;
 mod budget_group;
+mod budget_month;
+mod budget_template;
+mod budget_transfer;
 mod rule;
 mod settings;
 pub mod firefly_import;
 mod rule_group;
+mod rule_suggestion;
+mod user;
+mod recurring_transaction;
+mod reconciliation;
+mod integrity;
+mod report;
+mod exchange_rate;
+mod tag;
+mod transaction_change;
 
 pub use account::*;
 pub use transaction::*;
@@ -15,7 +27,19 @@ pub use category::*;
 pub use category_group::*;
 pub use budget::*;
 pub use budget_group::*;
+pub use budget_month::*;
+pub use budget_template::*;
+pub use budget_transfer::*;
 pub use rule::*;
 pub use settings::*;
 pub use firefly_import::*;
 pub use rule_group::*;
+pub use rule_suggestion::*;
+pub use user::*;
+pub use recurring_transaction::*;
+pub use reconciliation::*;
+pub use integrity::*;
+pub use report::*;
+pub use exchange_rate::*;
+pub use tag::*;
+pub use transaction_change::*;
@@ -0,0 +1,53 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Represents a completed reconciliation of an account against a bank statement
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Reconciliation {
+    /// Unique identifier for the reconciliation
+    pub id: Uuid,
+    /// ID of the reconciled account
+    pub account_id: Uuid,
+    /// Closing date of the statement
+    pub statement_date: NaiveDate,
+    /// Ending balance printed on the statement
+    pub statement_balance: Decimal,
+    /// Balance of the cleared transactions up to the statement date
+    pub cleared_balance: Decimal,
+    /// Number of transactions locked by this reconciliation
+    pub transaction_count: i32,
+    /// When the reconciliation was completed
+    pub created_at: DateTime<Utc>,
+}
+
+/// Data required to reconcile an account
+#[derive(Debug, Deserialize)]
+pub struct ReconcileAccountRequest {
+    /// Closing date of the statement; cleared transactions on or before this date are included
+    pub statement_date: NaiveDate,
+    /// Ending balance printed on the statement
+    pub statement_balance: Decimal,
+}
+
+/// Outcome of a reconciliation attempt
+#[derive(Debug, Serialize)]
+pub struct ReconciliationResult {
+    pub account_id: Uuid,
+    pub statement_date: NaiveDate,
+    pub statement_balance: Decimal,
+    /// Balance of the cleared (and previously reconciled) transactions up to the statement date
+    pub cleared_balance: Decimal,
+    /// statement_balance - cleared_balance; the account is only reconciled when this is zero
+    pub difference: Decimal,
+    /// Whether the cleared transactions were reconciled and locked
+    pub reconciled: bool,
+    /// Number of transactions locked by this reconciliation
+    pub transactions_reconciled: i64,
+    /// Number of uncleared transactions up to the statement date, which may explain a difference
+    pub uncleared_count: i64,
+    /// The stored reconciliation when successful
+    pub reconciliation: Option<Reconciliation>,
+}
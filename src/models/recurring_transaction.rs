@@ -0,0 +1,239 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::CreateTransactionRequest;
+
+/// How often a recurring transaction repeats
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+/// Schedule of a recurring transaction, anchored on its start date.
+/// `{"frequency":"monthly","day_of_month":1}` is the first of every month,
+/// `{"frequency":"weekly","interval":2}` is every two weeks on the start date's weekday.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recurrence {
+    /// Unit of the schedule
+    pub frequency: RecurrenceFrequency,
+    /// Repeat every N units (default: 1)
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    /// Day of the month for monthly schedules, clamped to the last day of shorter months
+    /// (default: the start date's day)
+    pub day_of_month: Option<u32>,
+}
+
+impl Recurrence {
+    /// Check the schedule is usable
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval == 0 {
+            return Err("Recurrence interval must be at least 1".to_string());
+        }
+        if let Some(day) = self.day_of_month {
+            if self.frequency != RecurrenceFrequency::Monthly {
+                return Err("day_of_month is only valid for monthly recurrences".to_string());
+            }
+            if !(1..=31).contains(&day) {
+                return Err("day_of_month must be between 1 and 31".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// The n-th date of the schedule counting from the start date (may fall before the start
+    /// date for monthly schedules on an earlier day of the month)
+    fn nth(&self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        let steps = n.checked_mul(self.interval)?;
+        match self.frequency {
+            RecurrenceFrequency::Daily => start.checked_add_signed(Duration::days(steps as i64)),
+            RecurrenceFrequency::Weekly => start.checked_add_signed(Duration::weeks(steps as i64)),
+            RecurrenceFrequency::Monthly => {
+                let months = start.year() as i64 * 12 + start.month0() as i64 + steps as i64;
+                let day = self.day_of_month.unwrap_or(start.day());
+                clamped_date(i32::try_from(months / 12).ok()?, (months % 12) as u32 + 1, day)
+            }
+            RecurrenceFrequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(steps).ok()?)?;
+                clamped_date(year, start.month(), start.day())
+            }
+        }
+    }
+
+    /// First scheduled date on or after `from`, not before `start` and not after `end`
+    pub fn next_on_or_after(&self, start: NaiveDate, end: Option<NaiveDate>, from: NaiveDate) -> Option<NaiveDate> {
+        let from = from.max(start);
+        let mut n = self.first_index_near(start, from);
+        loop {
+            let date = self.nth(start, n)?;
+            if end.is_some_and(|end| date > end) {
+                return None;
+            }
+            if date >= from {
+                return Some(date);
+            }
+            n = n.checked_add(1)?;
+        }
+    }
+
+    /// Index of a scheduled date at or shortly before `from`, so long-running schedules
+    /// do not have to be walked from the start
+    fn first_index_near(&self, start: NaiveDate, from: NaiveDate) -> u32 {
+        let units = match self.frequency {
+            RecurrenceFrequency::Daily => (from - start).num_days(),
+            RecurrenceFrequency::Weekly => (from - start).num_weeks(),
+            RecurrenceFrequency::Monthly => {
+                (from.year() - start.year()) as i64 * 12 + from.month0() as i64 - start.month0() as i64
+            }
+            RecurrenceFrequency::Yearly => (from.year() - start.year()) as i64,
+        };
+        // Step back one period to stay on the safe side of month-end clamping
+        u32::try_from(units / self.interval as i64 - 1).unwrap_or(0)
+    }
+}
+
+/// Build a date, clamping the day to the last day of the month
+fn clamped_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    (1..=day.min(31))
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+/// Represents a recurring transaction in the system
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RecurringTransaction {
+    /// Unique identifier for the recurring transaction
+    pub id: Uuid,
+    /// Name of the recurring transaction (e.g. "Rent")
+    pub name: String,
+    /// Transaction to create on every occurrence (serialized as JSON)
+    pub template_json: String,
+    /// Schedule of the occurrences (serialized as JSON)
+    pub recurrence_json: String,
+    /// Date of the first occurrence
+    pub start_date: NaiveDate,
+    /// Optional date after which no more occurrences are created
+    pub end_date: Option<NaiveDate>,
+    /// Next occurrence that has not been posted or skipped yet (None once the schedule has ended)
+    pub next_occurrence: Option<NaiveDate>,
+    /// Whether occurrences are posted automatically
+    pub is_active: bool,
+    /// When the recurring transaction was created
+    pub created_at: DateTime<Utc>,
+    /// When the recurring transaction was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a new recurring transaction
+#[derive(Debug, Deserialize)]
+pub struct CreateRecurringTransactionRequest {
+    /// Name of the recurring transaction
+    pub name: String,
+    /// Transaction to create on every occurrence; its `transaction_date` is ignored
+    pub template: CreateTransactionRequest,
+    /// Schedule of the occurrences
+    pub recurrence: Recurrence,
+    /// Date of the first occurrence
+    pub start_date: NaiveDate,
+    /// Optional date after which no more occurrences are created
+    pub end_date: Option<NaiveDate>,
+    /// Whether occurrences are posted automatically (default: true)
+    pub is_active: Option<bool>,
+}
+
+/// Data required to update an existing recurring transaction
+#[derive(Debug, Deserialize)]
+pub struct UpdateRecurringTransactionRequest {
+    /// Name of the recurring transaction
+    pub name: Option<String>,
+    /// Replacement transaction template
+    pub template: Option<CreateTransactionRequest>,
+    /// Replacement schedule
+    pub recurrence: Option<Recurrence>,
+    /// Date of the first occurrence
+    pub start_date: Option<NaiveDate>,
+    /// Date after which no more occurrences are created
+    pub end_date: Option<NaiveDate>,
+    /// Remove the end date so the schedule repeats indefinitely; ignored when `end_date` is given
+    #[serde(default)]
+    pub clear_end_date: bool,
+    /// Whether occurrences are posted automatically
+    pub is_active: Option<bool>,
+}
+
+/// Response for a recurring transaction with deserialized template and schedule
+#[derive(Debug, Serialize)]
+pub struct RecurringTransactionResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub template: CreateTransactionRequest,
+    pub recurrence: Recurrence,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub next_occurrence: Option<NaiveDate>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RecurringTransaction {
+    /// Convert a RecurringTransaction to a RecurringTransactionResponse by deserializing template and schedule
+    pub fn to_response(&self) -> Result<RecurringTransactionResponse, serde_json::Error> {
+        Ok(RecurringTransactionResponse {
+            id: self.id,
+            name: self.name.clone(),
+            template: serde_json::from_str(&self.template_json)?,
+            recurrence: serde_json::from_str(&self.recurrence_json)?,
+            start_date: self.start_date,
+            end_date: self.end_date,
+            next_occurrence: self.next_occurrence,
+            is_active: self.is_active,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+/// State of a single occurrence of a recurring transaction
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OccurrenceStatus {
+    /// Will be posted automatically when due
+    Scheduled,
+    /// Already posted (automatically or early)
+    Posted,
+    /// Skipped; no transaction will be created
+    Skipped,
+}
+
+/// A single upcoming occurrence of a recurring transaction
+#[derive(Debug, Serialize)]
+pub struct UpcomingOccurrence {
+    pub recurring_transaction_id: Uuid,
+    pub name: String,
+    pub occurrence_date: NaiveDate,
+    pub status: OccurrenceStatus,
+    /// Transaction created for this occurrence, if it was posted early
+    pub transaction_id: Option<Uuid>,
+    pub description: String,
+    pub amount: rust_decimal::Decimal,
+    pub source_account_id: Uuid,
+    pub destination_name: Option<String>,
+}
+
+/// Request to skip or post a specific occurrence
+#[derive(Debug, Deserialize, Default)]
+pub struct OccurrenceRequest {
+    /// Scheduled date of the occurrence (default: the next occurrence)
+    pub occurrence_date: Option<NaiveDate>,
+}
@@ -0,0 +1,33 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Balance of one account at the end of a period
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceHistoryPoint {
+    /// Start of the period (YYYY-MM-DD), like the other over-time reports
+    pub period: String,
+    pub account_id: Uuid,
+    /// Balance in the account's currency after all transactions up to the end of the period (or the requested end date)
+    pub balance: Decimal,
+}
+
+/// Combined end-of-period balance of the accounts sharing a type and subtype, in the base currency
+#[derive(Debug, Clone, Serialize)]
+pub struct NetWorthGroup {
+    pub account_type: String,
+    pub account_sub_type: Option<String>,
+    pub balance: Decimal,
+}
+
+/// Net worth at the end of a period, broken down by account type and subtype
+#[derive(Debug, Clone, Serialize)]
+pub struct NetWorthPoint {
+    /// Start of the period (YYYY-MM-DD)
+    pub period: String,
+    /// Sum of all group balances
+    pub net_worth: Decimal,
+    /// Base currency all balances were converted into
+    pub currency: String,
+    pub groups: Vec<NetWorthGroup>,
+}
@@ -1,8 +1,11 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::{Transaction, TransactionSplit};
+
 /// Represents a condition type for a rule
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -27,6 +30,34 @@ pub enum ConditionType {
     AmountLessThan,
     /// Check if amount equals a specific value
     AmountEquals,
+    /// Check if description does not contain a specific string
+    DescriptionNotContains,
+    /// Check if description does not match a specific string exactly
+    DescriptionNotEquals,
+    /// Check if description matches a regular expression (case-insensitive unless the pattern says otherwise)
+    DescriptionMatchesRegex,
+    /// Check if source account ID does not match
+    SourceAccountNotEquals,
+    /// Check if destination account ID does not match
+    DestinationAccountNotEquals,
+    /// Check if destination name does not contain a specific string
+    DestinationNameNotContains,
+    /// Check if destination name does not match a specific string exactly
+    DestinationNameNotEquals,
+    /// Check if destination name matches a regular expression (case-insensitive unless the pattern says otherwise)
+    DestinationNameMatchesRegex,
+    /// Check if the transaction date is before a day (YYYY-MM-DD)
+    DateBefore,
+    /// Check if the transaction date is after a day (YYYY-MM-DD)
+    DateAfter,
+    /// Check if the transaction date is a specific day (YYYY-MM-DD)
+    DateEquals,
+    /// Check if the transaction falls on one of the given weekdays (e.g. "saturday,sunday")
+    WeekdayIs,
+    /// Check if the transaction has no category (empty or "Uncategorized"); the value is ignored
+    CategoryIsEmpty,
+    /// Check if the transaction has no budget; the value is ignored
+    BudgetIsEmpty,
 }
 
 /// Represents an action type for a rule
@@ -41,6 +72,43 @@ pub enum ActionType {
     SetDescription,
     /// Set the destination name of the transaction
     SetDestinationName,
+    /// Attach a tag (by name) to the transaction, creating the tag if needed
+    AddTag,
+    /// Set the destination account by ID, e.g. to turn a payment to an external payee into a transfer;
+    /// the destination name follows the account
+    SetDestinationAccount,
+    /// Put text in front of the description
+    PrependDescription,
+    /// Add text to the end of the description
+    AppendDescription,
+    /// Replace the matches of `pattern` in the description with the value (`$1` refers to a capture group)
+    ReplaceDescription,
+    /// Set the memo of the transaction
+    SetMemo,
+    /// Split the transaction into the lines given in `splits` by percentage of its amount
+    SplitByPercentage,
+    /// Do not apply any rules after this one to the transactions it matches
+    StopProcessing,
+}
+
+/// Event on which a rule applies automatically. Every rule can also be run by hand; a rule
+/// without triggers only runs by hand.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleTrigger {
+    /// A transaction is created through the API or by a recurring transaction
+    Create,
+    /// A transaction is updated through the API
+    Update,
+    /// A transaction is created by a CSV import
+    Import,
+}
+
+impl RuleTrigger {
+    /// Triggers of rules that do not name any: all of them
+    pub fn all() -> Vec<Self> {
+        vec![Self::Create, Self::Update, Self::Import]
+    }
 }
 
 /// Represents a condition for a rule
@@ -48,17 +116,56 @@ pub enum ActionType {
 pub struct RuleCondition {
     /// Type of condition
     pub condition_type: ConditionType,
-    /// Value to compare against
+    /// Value to compare against (unused by the "is empty" conditions)
+    #[serde(default)]
     pub value: String,
 }
 
+/// A node of a rule's condition tree: a single condition, or a group of nodes of which all or any
+/// must match. The top level of a rule is an implicit all-of list, so rules stored as a plain
+/// list of conditions read back unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleConditionNode {
+    /// Every node must match, e.g. `{"all": [...]}`
+    All { all: Vec<RuleConditionNode> },
+    /// At least one node must match, e.g. `{"any": [...]}`
+    Any { any: Vec<RuleConditionNode> },
+    /// A single condition, e.g. `{"condition_type": "description_contains", "value": "coffee"}`
+    Condition(RuleCondition),
+}
+
+impl From<RuleCondition> for RuleConditionNode {
+    fn from(condition: RuleCondition) -> Self {
+        Self::Condition(condition)
+    }
+}
+
 /// Represents an action for a rule
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleAction {
     /// Type of action
     pub action_type: ActionType,
-    /// Value to set
+    /// Value to set (the replacement text for `replace_description`; unused by `split_by_percentage`
+    /// and `stop_processing`)
+    #[serde(default)]
     pub value: String,
+    /// Regular expression to replace, for `replace_description`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// Split lines to create, for `split_by_percentage`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub splits: Option<Vec<RuleSplitShare>>,
+}
+
+/// One line of a `split_by_percentage` action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleSplitShare {
+    /// Share of the transaction amount, in percent; the shares of an action add up to 100
+    pub percentage: Decimal,
+    pub category_id: Option<Uuid>,
+    pub budget_id: Option<Uuid>,
+    pub memo: Option<String>,
 }
 
 /// Represents a rule in the system
@@ -80,6 +187,8 @@ pub struct Rule {
     pub conditions_json: String,
     /// Actions for the rule (serialized as JSON)
     pub actions_json: String,
+    /// Triggers of the rule (serialized as JSON)
+    pub triggers_json: String,
     /// When the rule was created
     pub created_at: DateTime<Utc>,
     /// When the rule was last updated
@@ -87,7 +196,7 @@ pub struct Rule {
 }
 
 /// Data required to create a new rule
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateRuleRequest {
     /// Name of the rule
     pub name: String,
@@ -99,10 +208,13 @@ pub struct CreateRuleRequest {
     pub priority: Option<i32>,
     /// Optional rule group this rule belongs to
     pub group_id: Option<Uuid>,
-    /// Conditions for the rule
-    pub conditions: Vec<RuleCondition>,
+    /// Conditions for the rule, all of which must match
+    pub conditions: Vec<RuleConditionNode>,
     /// Actions for the rule
     pub actions: Vec<RuleAction>,
+    /// Events on which the rule applies automatically; all of them when omitted, none for a rule
+    /// that only runs by hand
+    pub triggers: Option<Vec<RuleTrigger>>,
 }
 
 /// Data required to update an existing rule
@@ -118,10 +230,12 @@ pub struct UpdateRuleRequest {
     pub priority: Option<i32>,
     /// Optional rule group this rule belongs to
     pub group_id: Option<Uuid>,
-    /// Conditions for the rule
-    pub conditions: Option<Vec<RuleCondition>>,
+    /// Conditions for the rule, all of which must match
+    pub conditions: Option<Vec<RuleConditionNode>>,
     /// Actions for the rule
     pub actions: Option<Vec<RuleAction>>,
+    /// Events on which the rule applies automatically
+    pub triggers: Option<Vec<RuleTrigger>>,
 }
 
 /// Response for a rule with deserialized conditions and actions
@@ -139,10 +253,12 @@ pub struct RuleResponse {
     pub priority: i32,
     /// Optional rule group this rule belongs to
     pub group_id: Option<Uuid>,
-    /// Conditions for the rule
-    pub conditions: Vec<RuleCondition>,
+    /// Conditions for the rule, all of which must match
+    pub conditions: Vec<RuleConditionNode>,
     /// Actions for the rule
     pub actions: Vec<RuleAction>,
+    /// Events on which the rule applies automatically
+    pub triggers: Vec<RuleTrigger>,
     /// When the rule was created
     pub created_at: DateTime<Utc>,
     /// When the rule was last updated
@@ -152,8 +268,9 @@ pub struct RuleResponse {
 impl Rule {
     /// Convert a Rule to a RuleResponse by deserializing conditions and actions
     pub fn to_response(&self) -> Result<RuleResponse, serde_json::Error> {
-        let conditions: Vec<RuleCondition> = serde_json::from_str(&self.conditions_json)?;
+        let conditions: Vec<RuleConditionNode> = serde_json::from_str(&self.conditions_json)?;
         let actions: Vec<RuleAction> = serde_json::from_str(&self.actions_json)?;
+        let triggers: Vec<RuleTrigger> = serde_json::from_str(&self.triggers_json)?;
 
         Ok(RuleResponse {
             id: self.id,
@@ -164,8 +281,124 @@ impl Rule {
             group_id: self.group_id,
             conditions,
             actions,
+            triggers,
             created_at: self.created_at,
             updated_at: self.updated_at,
         })
     }
 }
+
+/// Values of the transaction fields rules can change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleFieldValues {
+    pub category: String,
+    pub budget_id: Option<Uuid>,
+    pub description: String,
+    pub destination_account_id: Uuid,
+    /// Destination name; empty after the rules move the transaction to another account, whose
+    /// name it then takes
+    pub destination_name: Option<String>,
+    pub memo: Option<String>,
+    /// Split lines, sorted
+    pub splits: Vec<RuleSplitLine>,
+    /// Tag names, sorted
+    pub tags: Vec<String>,
+}
+
+impl From<&Transaction> for RuleFieldValues {
+    fn from(transaction: &Transaction) -> Self {
+        Self {
+            category: transaction.category.clone(),
+            budget_id: transaction.budget_id,
+            description: transaction.description.clone(),
+            destination_account_id: transaction.destination_account_id,
+            destination_name: transaction.destination_name.clone(),
+            memo: transaction.memo.clone(),
+            splits: RuleSplitLine::from_splits(&transaction.splits),
+            tags: transaction.tags.clone(),
+        }
+    }
+}
+
+/// Values of a split line, compared regardless of the line's ID and order
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RuleSplitLine {
+    pub amount: Decimal,
+    pub category_id: Option<Uuid>,
+    pub budget_id: Option<Uuid>,
+    pub memo: Option<String>,
+}
+
+impl RuleSplitLine {
+    /// Values of the split lines of a transaction, sorted
+    pub fn from_splits(splits: &[TransactionSplit]) -> Vec<Self> {
+        let mut lines: Vec<Self> = splits
+            .iter()
+            .map(|split| Self {
+                amount: split.amount,
+                category_id: split.category_id,
+                budget_id: split.budget_id,
+                memo: split.memo.clone(),
+            })
+            .collect();
+        lines.sort();
+        lines
+    }
+}
+
+/// Rules a run applies
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleRunScope {
+    /// All active rules, in active groups or without a group
+    All,
+    /// One rule, when it is active
+    Rule(Uuid),
+    /// The active rules of a group, when the group is active
+    Group(Uuid),
+}
+
+/// Transactions a rule run is restricted to; reconciled transactions are always left alone
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuleRunFilter {
+    /// First day to include (YYYY-MM-DD)
+    pub start_date: Option<NaiveDate>,
+    /// Last day to include (YYYY-MM-DD)
+    pub end_date: Option<NaiveDate>,
+    /// Transactions on either side of this account
+    pub account_id: Option<Uuid>,
+}
+
+/// Change a rule run would make to one transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleChangePreview {
+    pub transaction_id: Uuid,
+    /// Rules that matched the transaction, in the order they were applied
+    pub rule_ids: Vec<Uuid>,
+    pub before: RuleFieldValues,
+    pub after: RuleFieldValues,
+}
+
+/// Changes a rule run would make, without applying them
+#[derive(Debug, Serialize)]
+pub struct RuleRunPreview {
+    pub affected_transactions: usize,
+    pub changes: Vec<RuleChangePreview>,
+}
+
+/// Previewed changes to apply exactly as they were shown
+#[derive(Debug, Deserialize)]
+pub struct CommitRuleRunRequest {
+    pub changes: Vec<RuleChangePreview>,
+}
+
+/// Outcome of committing a previewed rule run. Nothing is applied when any transaction
+/// changed since the preview or the rules would now change it differently.
+#[derive(Debug, Serialize)]
+pub struct CommitRuleRunResult {
+    pub applied: bool,
+    /// Groups the applied changes in the transaction history, to undo them together
+    pub run_id: Option<Uuid>,
+    pub affected_transactions: usize,
+    /// Transactions whose current values or rule outcome no longer match the preview
+    pub conflicts: Vec<Uuid>,
+}
@@ -12,6 +12,12 @@ pub struct RuleGroup {
     pub name: String,
     /// Description of the rule group (optional)
     pub description: Option<String>,
+    /// Position of the group when rules run (lower numbers run first); rules without a group run last
+    pub run_order: i32,
+    /// Whether the rules of the group run
+    pub is_active: bool,
+    /// Whether only the first matching rule of the group applies to a transaction
+    pub stop_after_first_match: bool,
     /// When the rule group was created
     pub created_at: DateTime<Utc>,
     /// When the rule group was last updated
@@ -23,6 +29,12 @@ pub struct RuleGroup {
 pub struct CreateRuleGroupRequest {
     pub name: String,
     pub description: Option<String>,
+    /// Defaults to 100, like the priority of a rule
+    pub run_order: Option<i32>,
+    /// Defaults to active
+    pub is_active: Option<bool>,
+    #[serde(default)]
+    pub stop_after_first_match: bool,
 }
 
 /// Data required to update an existing rule group
@@ -30,4 +42,7 @@ pub struct CreateRuleGroupRequest {
 pub struct UpdateRuleGroupRequest {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub run_order: Option<i32>,
+    pub is_active: Option<bool>,
+    pub stop_after_first_match: Option<bool>,
 }
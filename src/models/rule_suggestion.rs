@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::CreateRuleRequest;
+
+/// A rule proposed from the categorised history of a ledger
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleSuggestion {
+    /// Stable ID derived from what the rule matches and sets, to accept the suggestion with
+    pub id: String,
+    /// The rule as it would be created
+    pub rule: CreateRuleRequest,
+    /// Number of transactions the rule's condition matches
+    pub support: usize,
+    /// Share of those transactions already in the suggested category, from 0 to 1
+    pub confidence: f64,
+    /// Some of the matching transactions, newest first
+    pub sample_transaction_ids: Vec<Uuid>,
+}
+
+/// Thresholds a group of similar transactions must meet to be suggested as a rule
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuleSuggestionQuery {
+    /// Minimum number of matching transactions (default 3)
+    pub min_support: Option<usize>,
+    /// Minimum share of them in the dominant category (default 0.8)
+    pub min_confidence: Option<f64>,
+}
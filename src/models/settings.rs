@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use rust_decimal::Decimal;
 
 /// Represents a setting in the system
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -26,5 +27,5 @@ pub struct UpdateSettingRequest {
 /// Response for forecasted monthly income
 #[derive(Debug, Serialize)]
 pub struct ForecastedMonthlyIncomeResponse {
-    pub forecasted_monthly_income: f64,
+    pub forecasted_monthly_income: Decimal,
 }
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Represents a tag that can be attached to any number of transactions
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    /// Unique identifier for the tag
+    pub id: Uuid,
+    /// Name of the tag (e.g., "vacation-2026"), unique within a ledger
+    pub name: String,
+    /// Description of the tag (optional)
+    pub description: Option<String>,
+    /// Number of transactions carrying the tag
+    pub transaction_count: i64,
+    /// When the tag was created
+    pub created_at: DateTime<Utc>,
+    /// When the tag was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data required to create a new tag
+#[derive(Debug, Deserialize)]
+pub struct CreateTagRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Data required to update an existing tag
+#[derive(Debug, Deserialize)]
+pub struct UpdateTagRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
@@ -1,7 +1,15 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
+use rust_decimal::Decimal;
+
+/// Transaction has not been matched against a bank statement yet
+pub const TRANSACTION_STATUS_UNCLEARED: &str = "uncleared";
+/// Transaction has shown up on the bank statement
+pub const TRANSACTION_STATUS_CLEARED: &str = "cleared";
+/// Transaction is part of a completed reconciliation and locked against edits
+pub const TRANSACTION_STATUS_RECONCILED: &str = "reconciled";
 
 /// Represents a financial transaction in the system
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -17,7 +25,7 @@ pub struct Transaction {
     /// Description of the transaction
     pub description: String,
     /// Amount of the transaction (always positive for transfers)
-    pub amount: f64,
+    pub amount: Decimal,
     /// Legacy category name stored on the transaction (kept for backward compatibility)
     pub category: String,
     /// Stable category ID reference; used for linking to categories so renames do not break associations
@@ -30,10 +38,64 @@ pub struct Transaction {
     pub created_at: DateTime<Utc>,
     /// When the transaction record was last updated
     pub updated_at: DateTime<Utc>,
+    /// Statement status: "uncleared", "cleared" or "reconciled"
+    pub status: String,
+    /// Reconciliation that locked this transaction, if any
+    pub reconciliation_id: Option<Uuid>,
+    /// Amount received by the destination in its own currency, when it differs from the source's
+    pub foreign_amount: Option<Decimal>,
+    /// Currency of foreign_amount (the destination account's currency)
+    pub foreign_currency: Option<String>,
+    /// Optional free-form note
+    pub memo: Option<String>,
+    /// Split lines dividing the amount across categories and budgets (empty for unsplit transactions)
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub splits: Vec<TransactionSplit>,
+    /// Names of the tags attached to the transaction, sorted by name
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Represents one split line of a transaction, carrying part of the parent amount
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TransactionSplit {
+    /// Unique identifier for the split line
+    pub id: Uuid,
+    /// ID of the parent transaction
+    pub transaction_id: Uuid,
+    /// Portion of the parent amount assigned to this line
+    pub amount: Decimal,
+    /// Optional category ID for this line
+    pub category_id: Option<Uuid>,
+    /// Optional budget ID for this line
+    pub budget_id: Option<Uuid>,
+    /// Optional note describing this line
+    pub memo: Option<String>,
+    /// When the split line was created
+    pub created_at: DateTime<Utc>,
+    /// When the split line was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Data for one split line when creating or updating a transaction
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TransactionSplitRequest {
+    /// Portion of the parent amount assigned to this line
+    pub amount: Decimal,
+    /// Category ID to assign; takes precedence over `category`
+    pub category_id: Option<Uuid>,
+    /// Category name to assign; the backend will resolve it to a category_id
+    pub category: Option<String>,
+    /// Optional budget ID for this line
+    pub budget_id: Option<Uuid>,
+    /// Optional note describing this line
+    pub memo: Option<String>,
 }
 
 /// Data required to create a new transaction
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateTransactionRequest {
     /// ID of the source account for this transaction
     pub source_account_id: Uuid,
@@ -42,12 +104,28 @@ pub struct CreateTransactionRequest {
     /// Name of the destination (used when destination_account_id is not provided)
     pub destination_name: Option<String>,
     pub description: String,
-    pub amount: f64,
+    pub amount: Decimal,
     /// Category name to assign; the backend will resolve and store category_id
     pub category: String,
     /// Optional budget ID this transaction is assigned to
     pub budget_id: Option<Uuid>,
     pub transaction_date: Option<DateTime<Utc>>,
+    /// Optional split lines; when present their amounts must sum to `amount`
+    #[serde(default)]
+    pub splits: Option<Vec<TransactionSplitRequest>>,
+    /// Statement status, "uncleared" (default) or "cleared"
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Amount received by the destination when its currency differs from the source's;
+    /// converted at the transaction date's exchange rate when omitted
+    #[serde(default)]
+    pub foreign_amount: Option<Decimal>,
+    /// Names of tags to attach; missing tags are created
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Optional free-form note
+    #[serde(default)]
+    pub memo: Option<String>,
 }
 
 /// Data required to update an existing transaction
@@ -58,10 +136,120 @@ pub struct UpdateTransactionRequest {
     /// Name of the destination (used when destination_account_id is not provided)
     pub destination_name: Option<String>,
     pub description: Option<String>,
-    pub amount: Option<f64>,
+    pub amount: Option<Decimal>,
     /// Category name to assign; the backend will resolve and store category_id
     pub category: Option<String>,
     /// Optional budget ID this transaction is assigned to
     pub budget_id: Option<Uuid>,
     pub transaction_date: Option<DateTime<Utc>>,
+    /// Replacement split lines; an empty list removes all splits, omitting it keeps the existing ones
+    #[serde(default)]
+    pub splits: Option<Vec<TransactionSplitRequest>>,
+    /// New statement status, "uncleared" or "cleared"; reconciled transactions must be unlocked first
+    #[serde(default)]
+    pub status: Option<String>,
+    /// New amount received by the destination when its currency differs from the source's
+    #[serde(default)]
+    pub foreign_amount: Option<Decimal>,
+    /// Replacement tag names; an empty list removes all tags, omitting it keeps the existing ones
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// New free-form note
+    #[serde(default)]
+    pub memo: Option<String>,
+}
+
+/// Filters, sort order and page for searching transactions; all filters are optional and combined with AND
+#[derive(Debug, Default, Deserialize)]
+pub struct TransactionSearchQuery {
+    /// Free text matched against description and destination name; every word must match the start of a word
+    pub q: Option<String>,
+    /// Minimum amount (inclusive)
+    pub min_amount: Option<Decimal>,
+    /// Maximum amount (inclusive)
+    pub max_amount: Option<Decimal>,
+    pub source_account_id: Option<Uuid>,
+    pub destination_account_id: Option<Uuid>,
+    /// Transactions on either side of this account
+    pub account_id: Option<Uuid>,
+    /// Exact category name, on the transaction or one of its split lines
+    pub category: Option<String>,
+    /// Category group, on the transaction or one of its split lines
+    pub category_group_id: Option<Uuid>,
+    /// Budget, on the transaction or one of its split lines
+    pub budget_id: Option<Uuid>,
+    /// Only transactions without any category
+    #[serde(default)]
+    pub uncategorized: bool,
+    /// Tag name
+    pub tag: Option<String>,
+    /// Statement status ("uncleared", "cleared" or "reconciled")
+    pub status: Option<String>,
+    /// First day to include (YYYY-MM-DD)
+    pub start_date: Option<NaiveDate>,
+    /// Last day to include (YYYY-MM-DD)
+    pub end_date: Option<NaiveDate>,
+    /// Sort field: "date" (default), "amount", "description" or "created"
+    pub sort: Option<String>,
+    /// Sort direction: "desc" (default) or "asc"
+    pub order: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// One page of search results together with the number of all matching transactions
+#[derive(Debug, Serialize)]
+pub struct TransactionSearchResult {
+    /// Number of transactions matching the filters, regardless of the page
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub transactions: Vec<Transaction>,
+}
+
+/// Changes a bulk edit applies to every selected transaction
+#[derive(Debug, Default, Deserialize)]
+pub struct BulkTransactionPatch {
+    /// Category name to assign
+    pub category: Option<String>,
+    /// Budget ID to assign
+    pub budget_id: Option<Uuid>,
+    /// Destination name to assign; the destination account is matched or created as on update
+    pub destination_name: Option<String>,
+    /// Number of days to move the transaction date by (negative moves it earlier)
+    pub shift_days: Option<i64>,
+    /// Delete the transactions instead of changing them
+    #[serde(default)]
+    pub delete: bool,
+}
+
+/// Transactions to edit in bulk, selected by ID or by a search filter, and the patch to apply
+#[derive(Debug, Deserialize)]
+pub struct BulkEditTransactionsRequest {
+    pub transaction_ids: Option<Vec<Uuid>>,
+    /// Search filter as for the search endpoint; paging and sorting are ignored
+    pub filter: Option<TransactionSearchQuery>,
+    pub patch: BulkTransactionPatch,
+}
+
+/// Outcome of one transaction in a bulk edit
+#[derive(Debug, Serialize)]
+pub struct BulkEditItemResult {
+    pub transaction_id: Uuid,
+    /// "updated", "deleted", "not_found" or "failed"
+    pub status: String,
+    /// Why the transaction could not be changed
+    pub error: Option<String>,
+}
+
+/// Outcome of a bulk edit; changes are only committed when every item succeeds
+#[derive(Debug, Serialize)]
+pub struct BulkEditResult {
+    /// Whether the changes were committed
+    pub applied: bool,
+    /// Run grouping the committed changes in the transaction history, to undo them together
+    pub run_id: Option<Uuid>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BulkEditItemResult>,
 }
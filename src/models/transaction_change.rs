@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// The transaction was created
+pub const CHANGE_TYPE_CREATE: &str = "create";
+/// Fields of the transaction were changed
+pub const CHANGE_TYPE_UPDATE: &str = "update";
+
+/// Change made by a user through the API
+pub const CHANGE_SOURCE_USER: &str = "user";
+/// Change made by a rule, automatically or in a rule run
+pub const CHANGE_SOURCE_RULE: &str = "rule";
+/// Transaction created by an import
+pub const CHANGE_SOURCE_IMPORT: &str = "import";
+/// Transaction posted by a recurring schedule
+pub const CHANGE_SOURCE_RECURRING: &str = "recurring";
+/// Change reverting an earlier change
+pub const CHANGE_SOURCE_UNDO: &str = "undo";
+
+/// Who or what is changing transactions, recorded with every change
+#[derive(Debug, Clone, Copy)]
+pub struct ChangeActor {
+    /// One of the CHANGE_SOURCE_* values
+    pub source: &'static str,
+    /// User ID, rule ID, recurring transaction ID or undone change ID, depending on the source
+    pub source_id: Option<Uuid>,
+    /// Groups the changes of one operation (rule run, bulk edit, import batch)
+    pub run_id: Option<Uuid>,
+}
+
+impl ChangeActor {
+    /// A user editing through the API
+    pub fn user(user_id: Uuid) -> Self {
+        Self { source: CHANGE_SOURCE_USER, source_id: Some(user_id), run_id: None }
+    }
+
+    /// The rules that matched a transaction; the rule ID is recorded when a single rule made the change
+    pub fn rules(rule_ids: &[Uuid], run_id: Option<Uuid>) -> Self {
+        let source_id = match rule_ids {
+            [rule_id] => Some(*rule_id),
+            _ => None,
+        };
+        Self { source: CHANGE_SOURCE_RULE, source_id, run_id }
+    }
+
+    /// An import batch
+    pub fn import(batch_id: Uuid) -> Self {
+        Self { source: CHANGE_SOURCE_IMPORT, source_id: None, run_id: Some(batch_id) }
+    }
+
+    /// A recurring transaction posting an occurrence
+    pub fn recurring(recurring_id: Uuid) -> Self {
+        Self { source: CHANGE_SOURCE_RECURRING, source_id: Some(recurring_id), run_id: None }
+    }
+
+    /// Undoing the change with the given ID
+    pub fn undo(change_id: Uuid, run_id: Option<Uuid>) -> Self {
+        Self { source: CHANGE_SOURCE_UNDO, source_id: Some(change_id), run_id }
+    }
+
+    /// The same actor, grouping its changes under one run
+    pub fn in_run(self, run_id: Uuid) -> Self {
+        Self { run_id: Some(run_id), ..self }
+    }
+}
+
+/// Previous and new value of one field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub old: serde_json::Value,
+    pub new: serde_json::Value,
+}
+
+/// One entry of a transaction's change history
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TransactionChange {
+    /// Unique identifier for the change
+    pub id: Uuid,
+    /// ID of the changed transaction
+    pub transaction_id: Uuid,
+    /// "create" or "update"
+    pub change_type: String,
+    /// "user", "rule", "import", "recurring" or "undo"
+    pub source: String,
+    /// User ID, rule ID, recurring transaction ID or undone change ID, depending on the source
+    pub source_id: Option<Uuid>,
+    /// Operation (rule run, bulk edit, import batch) the change was part of
+    pub run_id: Option<Uuid>,
+    /// Changed fields as stored (JSON); see `changes`
+    #[serde(skip)]
+    pub changes_json: String,
+    /// Changed fields with their previous and new values (initial values for "create")
+    #[sqlx(skip)]
+    pub changes: BTreeMap<String, FieldChange>,
+    /// When the change was undone, if it was
+    pub undone_at: Option<DateTime<Utc>>,
+    /// When the change was made
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of undoing a whole run of changes
+#[derive(Debug, Serialize)]
+pub struct UndoRunResult {
+    pub run_id: Uuid,
+    /// Run grouping the reverting changes; undoing it restores the undone values
+    pub undo_run_id: Uuid,
+    /// Number of changes reverted
+    pub undone: usize,
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Represents a user who can log in to the application
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct User {
+    /// Unique identifier for the user
+    pub id: Uuid,
+    /// Login name of the user
+    pub username: String,
+    /// Argon2 hash of the user's password
+    #[serde(skip)]
+    pub password_hash: String,
+    /// When the user was created
+    pub created_at: DateTime<Utc>,
+    /// When the user was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Represents a ledger: the set of books (accounts, transactions, budgets, ...) owned by a user
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Ledger {
+    /// Unique identifier for the ledger
+    pub id: Uuid,
+    /// ID of the user who owns the ledger (None for books created before users existed)
+    pub owner_id: Option<Uuid>,
+    /// Name of the ledger
+    pub name: String,
+    /// When the ledger was created
+    pub created_at: DateTime<Utc>,
+    /// When the ledger was last updated
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The authenticated user and the ledger the request operates on.
+/// Inserted into request extensions by the authentication middleware.
+#[derive(Debug, Clone, Copy)]
+pub struct AuthUser {
+    pub user_id: Uuid,
+    pub ledger_id: Uuid,
+}
+
+/// Credentials used to register or log in
+#[derive(Debug, Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+/// Response for a successful login or registration
+#[derive(Debug, Serialize)]
+pub struct LoginResponse {
+    pub user: User,
+    pub ledger: Ledger,
+    /// Session token, also set as a cookie; API clients send it as `Authorization: Bearer <token>`
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Response describing the current user
+#[derive(Debug, Serialize)]
+pub struct CurrentUserResponse {
+    pub user: User,
+    pub ledger: Ledger,
+}
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -8,7 +9,7 @@ use axum::{
 use uuid::Uuid;
 use std::sync::Arc;
 
-use crate::models::{Account, CreateAccountRequest, UpdateAccountRequest};
+use crate::models::{Account, CreateAccountRequest, UpdateAccountRequest, AuthUser};
 use crate::services::AccountService;
 
 pub fn router(account_service: Arc<AccountService>) -> Router {
@@ -25,9 +26,10 @@ pub fn router(account_service: Arc<AccountService>) -> Router {
 // Handler to get all accounts
 async fn get_accounts(
     State(state): State<Arc<AccountService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Account>>, StatusCode> {
     // Call the account service to get all accounts
-    match state.get_accounts().await {
+    match state.get_accounts(auth.ledger_id).await {
         Ok(accounts) => Ok(Json(accounts)),
         Err(err) => {
             eprintln!("Error getting accounts: {:?}", err);
@@ -39,10 +41,11 @@ async fn get_accounts(
 // Handler to create a new account
 async fn create_account(
     State(state): State<Arc<AccountService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateAccountRequest>,
 ) -> Result<(StatusCode, Json<Account>), StatusCode> {
     // Call the account service to create a new account
-    match state.create_account(payload).await {
+    match state.create_account(auth.ledger_id, payload).await {
         Ok(account) => Ok((StatusCode::CREATED, Json(account))),
         Err(err) => {
             eprintln!("Error creating account: {:?}", err);
@@ -55,9 +58,10 @@ async fn create_account(
 async fn get_account(
     Path(id): Path<Uuid>,
     State(state): State<Arc<AccountService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Account>, StatusCode> {
     // Call the account service to get the account by ID
-    match state.get_account(id).await {
+    match state.get_account(auth.ledger_id, id).await {
         Ok(Some(account)) => Ok(Json(account)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -71,10 +75,11 @@ async fn get_account(
 async fn update_account(
     Path(id): Path<Uuid>,
     State(state): State<Arc<AccountService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateAccountRequest>,
 ) -> Result<Json<Account>, StatusCode> {
     // Call the account service to update the account
-    match state.update_account(id, payload).await {
+    match state.update_account(auth.ledger_id, id, payload).await {
         Ok(Some(account)) => Ok(Json(account)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -88,9 +93,10 @@ async fn update_account(
 async fn delete_account(
     Path(id): Path<Uuid>,
     State(state): State<Arc<AccountService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
     // Call the account service to delete the account
-    match state.delete_account(id).await {
+    match state.delete_account(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Request, State},
+    Extension,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+    Router,
+    routing::{get, post},
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::models::{AuthUser, CurrentUserResponse, LoginRequest, LoginResponse};
+use crate::services::{AuthError, AuthService};
+
+/// Name of the cookie carrying the session token
+const SESSION_COOKIE: &str = "rustler_session";
+
+#[derive(Serialize)]
+struct AuthStatus {
+    registration_open: bool,
+}
+
+/// Routes that can be reached without being logged in
+pub fn public_router(auth_service: Arc<AuthService>) -> Router {
+    Router::new()
+        .route("/auth/status", get(get_status))
+        .route("/auth/register", post(register))
+        .route("/auth/login", post(login))
+        .route("/auth/logout", post(logout))
+        .with_state(auth_service)
+}
+
+/// Routes that require a valid session
+pub fn router(auth_service: Arc<AuthService>) -> Router {
+    Router::new()
+        .route("/auth/me", get(get_current_user))
+        .with_state(auth_service)
+}
+
+/// Middleware rejecting requests without a valid session.
+/// Accepts `Authorization: Bearer <token>` or the session cookie set by login.
+pub async fn require_auth(
+    State(state): State<Arc<AuthService>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let token = session_token(request.headers()).ok_or(StatusCode::UNAUTHORIZED)?;
+
+    match state.authenticate(&token).await {
+        Ok(Some(auth)) => {
+            request.extensions_mut().insert(auth);
+            Ok(next.run(request).await)
+        }
+        Ok(None) => Err(StatusCode::UNAUTHORIZED),
+        Err(err) => {
+            eprintln!("Error authenticating request: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to tell the login page whether it should offer registration
+async fn get_status(
+    State(state): State<Arc<AuthService>>,
+) -> Result<Json<AuthStatus>, StatusCode> {
+    match state.registration_open().await {
+        Ok(registration_open) => Ok(Json(AuthStatus { registration_open })),
+        Err(err) => {
+            eprintln!("Error getting registration status: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to register a new user
+async fn register(
+    State(state): State<Arc<AuthService>>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Response, (StatusCode, Json<String>)> {
+    match state.register(payload).await {
+        Ok(session) => Ok(session_response(&state, StatusCode::CREATED, session)),
+        Err(err) => Err(auth_error(err)),
+    }
+}
+
+// Handler to log in with a username and password
+async fn login(
+    State(state): State<Arc<AuthService>>,
+    Json(payload): Json<LoginRequest>,
+) -> Result<Response, (StatusCode, Json<String>)> {
+    match state.login(payload).await {
+        Ok(session) => Ok(session_response(&state, StatusCode::OK, session)),
+        Err(err) => Err(auth_error(err)),
+    }
+}
+
+// Handler to end the current session and clear the cookie
+async fn logout(
+    State(state): State<Arc<AuthService>>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    if let Some(token) = session_token(&headers)
+        && let Err(err) = state.logout(&token).await
+    {
+        eprintln!("Error logging out: {:?}", err);
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    let cookie = session_cookie(&state, "", 0);
+    Ok(([(header::SET_COOKIE, cookie)], StatusCode::NO_CONTENT).into_response())
+}
+
+// Handler to get the logged in user and their ledger
+async fn get_current_user(
+    State(state): State<Arc<AuthService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<CurrentUserResponse>, StatusCode> {
+    match state.get_current_user(auth).await {
+        Ok(Some(current)) => Ok(Json(current)),
+        Ok(None) => Err(StatusCode::UNAUTHORIZED),
+        Err(err) => {
+            eprintln!("Error getting current user: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Build the JSON response for a new session and set the session cookie
+fn session_response(state: &AuthService, status: StatusCode, session: LoginResponse) -> Response {
+    let cookie = session_cookie(state, &session.token, state.session_ttl_seconds());
+    (status, [(header::SET_COOKIE, cookie)], Json(session)).into_response()
+}
+
+fn session_cookie(state: &AuthService, token: &str, max_age: i64) -> HeaderValue {
+    let mut cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        SESSION_COOKIE, token, max_age
+    );
+    if state.secure_cookies() {
+        cookie.push_str("; Secure");
+    }
+    HeaderValue::from_str(&cookie).expect("session cookie is a valid header value")
+}
+
+/// Read the session token from the `Authorization` header or the session cookie
+fn session_token(headers: &HeaderMap) -> Option<String> {
+    if let Some(value) = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok())
+        && let Some(token) = value.strip_prefix("Bearer ")
+    {
+        return Some(token.trim().to_string());
+    }
+
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn auth_error(err: AuthError) -> (StatusCode, Json<String>) {
+    let status = match &err {
+        AuthError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+        AuthError::RegistrationClosed => StatusCode::FORBIDDEN,
+        AuthError::UsernameTaken => StatusCode::CONFLICT,
+        AuthError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        AuthError::PasswordHash(_) | AuthError::Database(_) => {
+            eprintln!("Error during authentication: {:?}", err);
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json("Internal server error".to_string()));
+        }
+    };
+    (status, Json(err.to_string()))
+}
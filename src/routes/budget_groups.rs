@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -8,8 +9,9 @@ use axum::{
 use uuid::Uuid;
 use std::sync::Arc;
 
-use crate::models::{BudgetGroup, CreateBudgetGroupRequest, UpdateBudgetGroupRequest, Budget};
+use crate::models::{BudgetGroup, CreateBudgetGroupRequest, UpdateBudgetGroupRequest, Budget, AuthUser};
 use crate::services::BudgetGroupService;
+use crate::routes::error_status;
 
 pub fn router(budget_group_service: Arc<BudgetGroupService>) -> Router {
     Router::new()
@@ -26,12 +28,13 @@ pub fn router(budget_group_service: Arc<BudgetGroupService>) -> Router {
 // Handler to get all budget groups
 async fn get_budget_groups(
     State(state): State<Arc<BudgetGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<BudgetGroup>>, StatusCode> {
-    match state.get_budget_groups().await {
+    match state.get_budget_groups(auth.ledger_id).await {
         Ok(groups) => Ok(Json(groups)),
         Err(err) => {
             eprintln!("Error getting budget groups: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -39,9 +42,10 @@ async fn get_budget_groups(
 // Handler to create a new budget group
 async fn create_budget_group(
     State(state): State<Arc<BudgetGroupService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateBudgetGroupRequest>,
 ) -> Result<(StatusCode, Json<BudgetGroup>), StatusCode> {
-    match state.create_budget_group(payload).await {
+    match state.create_budget_group(auth.ledger_id, payload).await {
         Ok(group) => Ok((StatusCode::CREATED, Json(group))),
         Err(err) => {
             eprintln!("Error creating budget group: {:?}", err);
@@ -54,13 +58,14 @@ async fn create_budget_group(
 async fn get_budget_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<BudgetGroup>, StatusCode> {
-    match state.get_budget_group(id).await {
+    match state.get_budget_group(auth.ledger_id, id).await {
         Ok(Some(group)) => Ok(Json(group)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
             eprintln!("Error getting budget group: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -69,9 +74,10 @@ async fn get_budget_group(
 async fn update_budget_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetGroupService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateBudgetGroupRequest>,
 ) -> Result<Json<BudgetGroup>, StatusCode> {
-    match state.update_budget_group(id, payload).await {
+    match state.update_budget_group(auth.ledger_id, id, payload).await {
         Ok(Some(group)) => Ok(Json(group)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -85,8 +91,9 @@ async fn update_budget_group(
 async fn delete_budget_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
-    match state.delete_budget_group(id).await {
+    match state.delete_budget_group(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -100,12 +107,13 @@ async fn delete_budget_group(
 async fn get_budgets_by_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Budget>>, StatusCode> {
-    match state.get_budgets_by_group(id).await {
+    match state.get_budgets_by_group(auth.ledger_id, id).await {
         Ok(budgets) => Ok(Json(budgets)),
         Err(err) => {
             eprintln!("Error getting budgets by group: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, State, Query},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -9,8 +10,10 @@ use uuid::Uuid;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{Budget, CreateBudgetRequest, UpdateBudgetRequest, Transaction};
+use crate::models::{parse_budget_month, Budget, BudgetAllocation, BudgetMonth, CopiedBudgetMonth, CopyBudgetMonthRequest, BudgetTransfer, BudgetTransferQuery, CreateBudgetTransferRequest, ReadyToAssign, CreateBudgetRequest, SetBudgetAllocationRequest, UpdateBudgetRequest, Transaction, AuthUser};
 use crate::services::BudgetService;
+use crate::routes::error_status;
+use rust_decimal::Decimal;
 
 // Query parameters for monthly budget status
 #[derive(Debug, Deserialize)]
@@ -22,10 +25,10 @@ struct MonthlyBudgetQuery {
 // Response structure for monthly budget status
 #[derive(Debug, Serialize)]
 struct MonthlyBudgetStatus {
-    incoming_funds: f64,
-    budgeted_amount: f64,
-    remaining_to_budget: f64,
-    forecasted_monthly_income: f64,
+    incoming_funds: Decimal,
+    budgeted_amount: Decimal,
+    remaining_to_budget: Decimal,
+    forecasted_monthly_income: Decimal,
 }
 
 pub fn router(budget_service: Arc<BudgetService>) -> Router {
@@ -34,6 +37,11 @@ pub fn router(budget_service: Arc<BudgetService>) -> Router {
         .route("/budgets/active", get(get_active_budgets))
         .route("/budgets/monthly-status", get(get_monthly_budget_status))
         .route("/budgets/unbudgeted-spent", get(get_unbudgeted_spent))
+        .route("/budgets/month/{month}", get(get_budget_month))
+        .route("/budgets/month/{month}/copy", post(copy_budget_month))
+        .route("/budgets/ready-to-assign", get(get_ready_to_assign))
+        .route("/budgets/transfers", get(get_budget_transfers))
+        .route("/budgets/transfers", post(create_budget_transfer))
         .route("/budgets", post(create_budget))
         .route("/budgets/{id}", get(get_budget))
         .route("/budgets/{id}", put(update_budget))
@@ -42,19 +50,26 @@ pub fn router(budget_service: Arc<BudgetService>) -> Router {
         .route("/budgets/{id}/spent", get(get_budget_spent))
         .route("/budgets/{id}/remaining", get(get_budget_remaining))
         .route("/budgets/{id}/transactions", get(get_budget_transactions_for_month))
+        .route("/budgets/{id}/allocations", get(get_budget_allocations))
+        .route("/budgets/{id}/allocations/{month}", put(set_budget_allocation))
         .with_state(budget_service)
 }
 
 // Handler to get all budgets
 async fn get_budgets(
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Budget>>, StatusCode> {
     // Call the budget service to get all budgets
-    match state.get_budgets().await {
+    let budgets = match state.get_budgets(auth.ledger_id).await {
+        Ok(budgets) => state.with_goal_progress(auth.ledger_id, budgets).await,
+        Err(err) => Err(err),
+    };
+    match budgets {
         Ok(budgets) => Ok(Json(budgets)),
         Err(err) => {
             eprintln!("Error getting budgets: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -62,13 +77,18 @@ async fn get_budgets(
 // Handler to get active budgets
 async fn get_active_budgets(
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Budget>>, StatusCode> {
     // Call the budget service to get active budgets
-    match state.get_active_budgets().await {
+    let budgets = match state.get_active_budgets(auth.ledger_id).await {
+        Ok(budgets) => state.with_goal_progress(auth.ledger_id, budgets).await,
+        Err(err) => Err(err),
+    };
+    match budgets {
         Ok(budgets) => Ok(Json(budgets)),
         Err(err) => {
             eprintln!("Error getting active budgets: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -76,14 +96,15 @@ async fn get_active_budgets(
 // Handler to create a new budget
 async fn create_budget(
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateBudgetRequest>,
 ) -> Result<(StatusCode, Json<Budget>), StatusCode> {
     // Call the budget service to create a new budget
-    match state.create_budget(payload).await {
+    match state.create_budget(auth.ledger_id, payload).await {
         Ok(budget) => Ok((StatusCode::CREATED, Json(budget))),
         Err(err) => {
             eprintln!("Error creating budget: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -92,14 +113,19 @@ async fn create_budget(
 async fn get_budget(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Budget>, StatusCode> {
     // Call the budget service to get the budget by ID
-    match state.get_budget(id).await {
+    let budget = match state.get_budget(auth.ledger_id, id).await {
+        Ok(Some(budget)) => state.with_goal_progress(auth.ledger_id, vec![budget]).await.map(|mut budgets| budgets.pop()),
+        other => other,
+    };
+    match budget {
         Ok(Some(budget)) => Ok(Json(budget)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
             eprintln!("Error getting budget: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -108,15 +134,16 @@ async fn get_budget(
 async fn update_budget(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateBudgetRequest>,
 ) -> Result<Json<Budget>, StatusCode> {
     // Call the budget service to update the budget
-    match state.update_budget(id, payload).await {
+    match state.update_budget(auth.ledger_id, id, payload).await {
         Ok(Some(budget)) => Ok(Json(budget)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
             eprintln!("Error updating budget: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -125,9 +152,10 @@ async fn update_budget(
 async fn delete_budget(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
     // Call the budget service to delete the budget
-    match state.delete_budget(id).await {
+    match state.delete_budget(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -142,7 +170,8 @@ async fn get_budget_spent(
     Path(id): Path<Uuid>,
     Query(query): Query<std::collections::HashMap<String, String>>,
     State(state): State<Arc<BudgetService>>,
-) -> Result<Json<f64>, StatusCode> {
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Decimal>, StatusCode> {
     // If year and month are provided, compute for that month; otherwise, return all-time
     if let (Some(year_str), Some(month_str)) = (query.get("year"), query.get("month")) {
         // Parse query params
@@ -155,20 +184,20 @@ async fn get_budget_spent(
             _ => return Err(StatusCode::BAD_REQUEST),
         };
 
-        match state.get_budget_spent_for_month(id, year, month).await {
+        match state.get_budget_spent_for_month(auth.ledger_id, id, year, month).await {
             Ok(spent) => Ok(Json(spent)),
             Err(err) => {
                 eprintln!("Error getting monthly budget spent: {:?}", err);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+                Err(error_status(&err))
             }
         }
     } else {
         // Call the budget service to get the spent amount (all-time)
-        match state.get_budget_spent(id).await {
+        match state.get_budget_spent(auth.ledger_id, id).await {
             Ok(spent) => Ok(Json(spent)),
             Err(err) => {
                 eprintln!("Error getting budget spent: {:?}", err);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+                Err(error_status(&err))
             }
         }
     }
@@ -178,13 +207,14 @@ async fn get_budget_spent(
 async fn get_budget_remaining(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetService>>,
-) -> Result<Json<f64>, StatusCode> {
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Decimal>, StatusCode> {
     // Call the budget service to get the remaining amount
-    match state.get_budget_remaining(id).await {
+    match state.get_budget_remaining(auth.ledger_id, id).await {
         Ok(remaining) => Ok(Json(remaining)),
         Err(err) => {
             eprintln!("Error getting budget remaining: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -193,6 +223,7 @@ async fn get_budget_remaining(
 async fn get_monthly_budget_status(
     Query(query): Query<MonthlyBudgetQuery>,
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<MonthlyBudgetStatus>, StatusCode> {
     // Validate month value (1-12)
     if query.month < 1 || query.month > 12 {
@@ -200,7 +231,7 @@ async fn get_monthly_budget_status(
     }
 
     // Call the budget service to get the monthly budget status
-    match state.get_monthly_budget_status(query.year, query.month).await {
+    match state.get_monthly_budget_status(auth.ledger_id, query.year, query.month).await {
         Ok((incoming_funds, budgeted_amount, remaining_to_budget, forecasted_monthly_income)) => {
             Ok(Json(MonthlyBudgetStatus {
                 incoming_funds,
@@ -211,7 +242,7 @@ async fn get_monthly_budget_status(
         },
         Err(err) => {
             eprintln!("Error getting monthly budget status: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -220,7 +251,8 @@ async fn get_monthly_budget_status(
 async fn get_unbudgeted_spent(
     Query(query): Query<std::collections::HashMap<String, String>>,
     State(state): State<Arc<BudgetService>>,
-) -> Result<Json<f64>, StatusCode> {
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Decimal>, StatusCode> {
     // If year and month are provided, compute for that month; otherwise, return all-time
     if let (Some(year_str), Some(month_str)) = (query.get("year"), query.get("month")) {
         // Parse query params
@@ -233,20 +265,20 @@ async fn get_unbudgeted_spent(
             _ => return Err(StatusCode::BAD_REQUEST),
         };
 
-        match state.get_unbudgeted_spent_for_month(year, month).await {
+        match state.get_unbudgeted_spent_for_month(auth.ledger_id, year, month).await {
             Ok(spent) => Ok(Json(spent)),
             Err(err) => {
                 eprintln!("Error getting monthly unbudgeted spent: {:?}", err);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+                Err(error_status(&err))
             }
         }
     } else {
         // Call the budget service to get the unbudgeted spent amount (all-time)
-        match state.get_unbudgeted_spent().await {
+        match state.get_unbudgeted_spent(auth.ledger_id).await {
             Ok(spent) => Ok(Json(spent)),
             Err(err) => {
                 eprintln!("Error getting unbudgeted spent: {:?}", err);
-                Err(StatusCode::INTERNAL_SERVER_ERROR)
+                Err(error_status(&err))
             }
         }
     }
@@ -257,12 +289,132 @@ async fn get_unbudgeted_spent(
 async fn get_budget_transactions_for_month(
     Path(id): Path<Uuid>,
     State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Transaction>>, StatusCode> {
-    match state.get_budget_transactions_for_month(id).await {
+    match state.get_budget_transactions_for_month(auth.ledger_id, id).await {
         Ok(txs) => Ok(Json(txs)),
         Err(err) => {
             eprintln!("Error getting budget transactions for month: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to get the envelope view of a month (YYYY-MM)
+async fn get_budget_month(
+    Path(month): Path<String>,
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<BudgetMonth>, StatusCode> {
+    let month = parse_budget_month(&month).ok_or(StatusCode::BAD_REQUEST)?;
+    match state.get_budget_month(auth.ledger_id, month).await {
+        Ok(view) => Ok(Json(view)),
+        Err(err) => {
+            eprintln!("Error getting budget month: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to create a month's budgets from the budgets of another month
+async fn copy_budget_month(
+    Path(month): Path<String>,
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CopyBudgetMonthRequest>,
+) -> Result<(StatusCode, Json<CopiedBudgetMonth>), StatusCode> {
+    let month = parse_budget_month(&month).ok_or(StatusCode::BAD_REQUEST)?;
+    match state.copy_budget_month(auth.ledger_id, month, payload).await {
+        Ok(copied) => Ok((StatusCode::CREATED, Json(copied))),
+        Err(err) => {
+            eprintln!("Error copying budget month: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to get the money in On Budget accounts that is not in any budget yet
+async fn get_ready_to_assign(
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<ReadyToAssign>, StatusCode> {
+    match state.get_ready_to_assign(auth.ledger_id).await {
+        Ok(ready) => Ok(Json(ready)),
+        Err(err) => {
+            eprintln!("Error getting ready to assign: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to move money between budgets
+async fn create_budget_transfer(
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CreateBudgetTransferRequest>,
+) -> Result<(StatusCode, Json<BudgetTransfer>), StatusCode> {
+    match state.create_budget_transfer(auth.ledger_id, payload).await {
+        Ok(transfer) => Ok((StatusCode::CREATED, Json(transfer))),
+        Err(err) => {
+            eprintln!("Error creating budget transfer: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to get the history of money moved between budgets
+async fn get_budget_transfers(
+    Query(query): Query<BudgetTransferQuery>,
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<BudgetTransfer>>, StatusCode> {
+    match state.get_budget_transfers(auth.ledger_id, &query).await {
+        Ok(transfers) => Ok(Json(transfers)),
+        Err(err) => {
+            eprintln!("Error getting budget transfers: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to get the amounts assigned to a budget per month
+async fn get_budget_allocations(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<BudgetAllocation>>, StatusCode> {
+    match state.get_budget(auth.ledger_id, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting budget: {:?}", err);
+            return Err(error_status(&err));
+        }
+    }
+
+    match state.get_budget_allocations(auth.ledger_id, id).await {
+        Ok(allocations) => Ok(Json(allocations)),
+        Err(err) => {
+            eprintln!("Error getting budget allocations: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to assign an amount to a budget for a month (YYYY-MM)
+async fn set_budget_allocation(
+    Path((id, month)): Path<(Uuid, String)>,
+    State(state): State<Arc<BudgetService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<SetBudgetAllocationRequest>,
+) -> Result<Json<BudgetAllocation>, StatusCode> {
+    let month = parse_budget_month(&month).ok_or(StatusCode::BAD_REQUEST)?;
+    match state.set_budget_allocation(auth.ledger_id, id, month, payload.amount).await {
+        Ok(Some(allocation)) => Ok(Json(allocation)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error setting budget allocation: {:?}", err);
+            Err(error_status(&err))
         }
     }
 }
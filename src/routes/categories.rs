@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, State, Query},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -10,7 +11,7 @@ use std::sync::Arc;
 use serde::Deserialize;
 use chrono::Utc;
 
-use crate::models::{Category, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::models::{Category, CreateCategoryRequest, UpdateCategoryRequest, AuthUser};
 use crate::services::{CategoryService, TransactionService};
 
 pub fn router(category_service: Arc<CategoryService>) -> Router {
@@ -27,9 +28,10 @@ pub fn router(category_service: Arc<CategoryService>) -> Router {
 // Handler to get all categories
 async fn get_categories(
     State(state): State<Arc<CategoryService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Category>>, StatusCode> {
     // Call the category service to get all categories
-    match state.get_categories().await {
+    match state.get_categories(auth.ledger_id).await {
         Ok(categories) => Ok(Json(categories)),
         Err(err) => {
             eprintln!("Error getting categories: {:?}", err);
@@ -41,10 +43,11 @@ async fn get_categories(
 // Handler to create a new category
 async fn create_category(
     State(state): State<Arc<CategoryService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateCategoryRequest>,
 ) -> Result<(StatusCode, Json<Category>), StatusCode> {
     // Call the category service to create a new category
-    match state.create_category(payload).await {
+    match state.create_category(auth.ledger_id, payload).await {
         Ok(category) => Ok((StatusCode::CREATED, Json(category))),
         Err(err) => {
             eprintln!("Error creating category: {:?}", err);
@@ -57,9 +60,10 @@ async fn create_category(
 async fn get_category(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Category>, StatusCode> {
     // Call the category service to get the category by ID
-    match state.get_category(id).await {
+    match state.get_category(auth.ledger_id, id).await {
         Ok(Some(category)) => Ok(Json(category)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -73,10 +77,11 @@ async fn get_category(
 async fn update_category(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateCategoryRequest>,
 ) -> Result<Json<Category>, StatusCode> {
     // Call the category service to update the category
-    match state.update_category(id, payload).await {
+    match state.update_category(auth.ledger_id, id, payload).await {
         Ok(Some(category)) => Ok(Json(category)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -90,9 +95,10 @@ async fn update_category(
 async fn delete_category(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
     // Call the category service to delete the category
-    match state.delete_category(id).await {
+    match state.delete_category(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -8,7 +9,7 @@ use axum::{
 use uuid::Uuid;
 use std::sync::Arc;
 
-use crate::models::{CategoryGroup, CreateCategoryGroupRequest, UpdateCategoryGroupRequest, Category};
+use crate::models::{CategoryGroup, CreateCategoryGroupRequest, UpdateCategoryGroupRequest, Category, AuthUser};
 use crate::services::CategoryGroupService;
 
 pub fn router(category_group_service: Arc<CategoryGroupService>) -> Router {
@@ -26,9 +27,10 @@ pub fn router(category_group_service: Arc<CategoryGroupService>) -> Router {
 // Handler to get all category groups
 async fn get_category_groups(
     State(state): State<Arc<CategoryGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<CategoryGroup>>, StatusCode> {
     // Call the category group service to get all category groups
-    match state.get_category_groups().await {
+    match state.get_category_groups(auth.ledger_id).await {
         Ok(category_groups) => Ok(Json(category_groups)),
         Err(err) => {
             eprintln!("Error getting category groups: {:?}", err);
@@ -40,10 +42,11 @@ async fn get_category_groups(
 // Handler to create a new category group
 async fn create_category_group(
     State(state): State<Arc<CategoryGroupService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateCategoryGroupRequest>,
 ) -> Result<(StatusCode, Json<CategoryGroup>), StatusCode> {
     // Call the category group service to create a new category group
-    match state.create_category_group(payload).await {
+    match state.create_category_group(auth.ledger_id, payload).await {
         Ok(category_group) => Ok((StatusCode::CREATED, Json(category_group))),
         Err(err) => {
             eprintln!("Error creating category group: {:?}", err);
@@ -56,9 +59,10 @@ async fn create_category_group(
 async fn get_category_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<CategoryGroup>, StatusCode> {
     // Call the category group service to get the category group by ID
-    match state.get_category_group(id).await {
+    match state.get_category_group(auth.ledger_id, id).await {
         Ok(Some(category_group)) => Ok(Json(category_group)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -72,10 +76,11 @@ async fn get_category_group(
 async fn update_category_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryGroupService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateCategoryGroupRequest>,
 ) -> Result<Json<CategoryGroup>, StatusCode> {
     // Call the category group service to update the category group
-    match state.update_category_group(id, payload).await {
+    match state.update_category_group(auth.ledger_id, id, payload).await {
         Ok(Some(category_group)) => Ok(Json(category_group)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -89,9 +94,10 @@ async fn update_category_group(
 async fn delete_category_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
     // Call the category group service to delete the category group
-    match state.delete_category_group(id).await {
+    match state.delete_category_group(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -105,9 +111,10 @@ async fn delete_category_group(
 async fn get_categories_by_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<CategoryGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Category>>, StatusCode> {
     // Call the category group service to get all categories in the group
-    match state.get_categories_by_group(id).await {
+    match state.get_categories_by_group(auth.ledger_id, id).await {
         Ok(categories) => Ok(Json(categories)),
         Err(err) => {
             eprintln!("Error getting categories by group: {:?}", err);
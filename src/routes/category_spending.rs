@@ -1,5 +1,6 @@
 use axum::{
     extract::{Query, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -9,7 +10,10 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
 
+use crate::models::AuthUser;
 use crate::services::TransactionService;
+use crate::routes::error_status;
+use rust_decimal::Decimal;
 
 #[derive(Debug, Deserialize)]
 pub struct DateRangeQuery {
@@ -20,7 +24,7 @@ pub struct DateRangeQuery {
 #[derive(Debug, Serialize)]
 pub struct CategorySpending {
     pub category: String,
-    pub amount: f64,
+    pub amount: Decimal,
 }
 
 pub fn router(transaction_service: Arc<TransactionService>) -> Router {
@@ -33,6 +37,7 @@ pub fn router(transaction_service: Arc<TransactionService>) -> Router {
 async fn get_spending_by_category(
     Query(query): Query<DateRangeQuery>,
     State(state): State<Arc<TransactionService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<CategorySpending>>, StatusCode> {
     // Parse dates if provided
     let start_date = query.start_date.as_ref().and_then(|date_str| {
@@ -60,7 +65,7 @@ async fn get_spending_by_category(
     });
 
     // Call the transaction service to get spending by category
-    match state.get_spending_by_category(start_date, end_date).await {
+    match state.get_spending_by_category(auth.ledger_id, start_date, end_date).await {
         Ok(spending) => {
             // Convert the result to the expected format
             let result = spending
@@ -71,7 +76,7 @@ async fn get_spending_by_category(
         },
         Err(err) => {
             eprintln!("Error getting spending by category: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
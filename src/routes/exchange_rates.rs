@@ -0,0 +1,126 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    http::StatusCode,
+    Json,
+    Router,
+    routing::{get, post, delete},
+};
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use std::sync::Arc;
+
+use crate::models::{AuthUser, CreateExchangeRateRequest, ExchangeRate, ExchangeRateImportResult, ExchangeRateQuery};
+use crate::services::ExchangeRateService;
+use crate::routes::error_status;
+
+#[derive(Debug, Deserialize)]
+struct RateLookupQuery {
+    from_currency: String,
+    to_currency: String,
+    /// Day to look up (YYYY-MM-DD); defaults to today
+    date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize)]
+struct RateLookupResponse {
+    from_currency: String,
+    to_currency: String,
+    date: NaiveDate,
+    rate: Decimal,
+}
+
+pub fn router(exchange_rate_service: Arc<ExchangeRateService>) -> Router {
+    Router::new()
+        .route("/exchange-rates", get(get_exchange_rates))
+        .route("/exchange-rates", post(create_exchange_rate))
+        .route("/exchange-rates/import", post(import_exchange_rates))
+        .route("/exchange-rates/rate", get(get_rate))
+        .route("/exchange-rates/{id}", delete(delete_exchange_rate))
+        .with_state(exchange_rate_service)
+}
+
+// Handler to list exchange rates (?from_currency=EUR&to_currency=USD)
+async fn get_exchange_rates(
+    Query(query): Query<ExchangeRateQuery>,
+    State(state): State<Arc<ExchangeRateService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<ExchangeRate>>, StatusCode> {
+    match state.get_exchange_rates(auth.ledger_id, query).await {
+        Ok(rates) => Ok(Json(rates)),
+        Err(err) => {
+            eprintln!("Error getting exchange rates: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to create or replace the exchange rate of a currency pair on a day
+async fn create_exchange_rate(
+    State(state): State<Arc<ExchangeRateService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CreateExchangeRateRequest>,
+) -> Result<(StatusCode, Json<ExchangeRate>), StatusCode> {
+    match state.create_exchange_rate(auth.ledger_id, payload).await {
+        Ok(rate) => Ok((StatusCode::CREATED, Json(rate))),
+        Err(err) => {
+            eprintln!("Error creating exchange rate: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to import exchange rates from a CSV file sent as the request body
+async fn import_exchange_rates(
+    State(state): State<Arc<ExchangeRateService>>,
+    Extension(auth): Extension<AuthUser>,
+    body: String,
+) -> Result<Json<ExchangeRateImportResult>, StatusCode> {
+    match state.import_exchange_rates(auth.ledger_id, &body).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            eprintln!("Error importing exchange rates: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to look up the rate in effect for a currency pair on a day
+async fn get_rate(
+    Query(query): Query<RateLookupQuery>,
+    State(state): State<Arc<ExchangeRateService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RateLookupResponse>, StatusCode> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+    match state.get_rate(auth.ledger_id, &query.from_currency, &query.to_currency, date).await {
+        Ok(Some(rate)) => Ok(Json(RateLookupResponse {
+            from_currency: query.from_currency.to_ascii_uppercase(),
+            to_currency: query.to_currency.to_ascii_uppercase(),
+            date,
+            rate,
+        })),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error looking up exchange rate: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to delete an exchange rate
+async fn delete_exchange_rate(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<ExchangeRateService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> StatusCode {
+    match state.delete_exchange_rate(auth.ledger_id, id).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(err) => {
+            eprintln!("Error deleting exchange rate: {:?}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
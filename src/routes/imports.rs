@@ -1,5 +1,6 @@
 use axum::{
     extract::{Multipart, State},
+    Extension,
     http::{StatusCode, HeaderMap},
     Json,
     Router,
@@ -13,6 +14,7 @@ use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 use tracing::{info, error, debug};
 
+use crate::models::AuthUser;
 use crate::services::FireflyImportService;
 use crate::models::firefly_import::{FireflyImportOptions, ImportResult};
 
@@ -27,10 +29,11 @@ pub fn router(import_service: Arc<FireflyImportService>) -> Router {
 // Handler to import data from Firefly III
 async fn import_from_firefly(
     State(state): State<Arc<FireflyImportService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(options): Json<FireflyImportOptions>,
 ) -> Result<Json<ImportResult>, (StatusCode, Json<String>)> {
     // Call the import service to import data from Firefly III
-    match state.import(options).await {
+    match state.import(auth.ledger_id, options).await {
         Ok(result) => Ok(Json(result)),
         Err(err) => {
             eprintln!("Error importing from Firefly III: {}", err);
@@ -45,6 +48,7 @@ async fn import_from_firefly(
 // Handler to upload CSV files for Firefly import
 async fn upload_firefly_csv(
     State(state): State<Arc<FireflyImportService>>,
+    Extension(auth): Extension<AuthUser>,
     headers: HeaderMap,
     mut multipart: Multipart,
 ) -> Result<Json<ImportResult>, (StatusCode, Json<String>)> {
@@ -195,7 +199,7 @@ async fn upload_firefly_csv(
     };
 
     // Call the import service
-    match state.import(options).await {
+    match state.import(auth.ledger_id, options).await {
         Ok(result) => {
             // Clean up temporary files
             let _ = fs::remove_dir_all(&temp_dir).await;
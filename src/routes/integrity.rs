@@ -0,0 +1,47 @@
+use axum::{
+    extract::State,
+    Extension,
+    http::StatusCode,
+    Json,
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+use crate::models::{AuthUser, IntegrityReport};
+use crate::services::IntegrityService;
+
+pub fn router(integrity_service: Arc<IntegrityService>) -> Router {
+    Router::new()
+        .route("/integrity", get(check_integrity))
+        .route("/integrity/repair", post(repair_integrity))
+        .with_state(integrity_service)
+}
+
+// Handler to report balance mismatches and inconsistent transactions without changing anything
+async fn check_integrity(
+    State(state): State<Arc<IntegrityService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<IntegrityReport>, StatusCode> {
+    match state.check_integrity(auth.ledger_id, false).await {
+        Ok(report) => Ok(Json(report)),
+        Err(err) => {
+            eprintln!("Error checking ledger integrity: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to reset mismatched account balances to the ones computed from the transactions
+async fn repair_integrity(
+    State(state): State<Arc<IntegrityService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<IntegrityReport>, StatusCode> {
+    match state.check_integrity(auth.ledger_id, true).await {
+        Ok(report) => Ok(Json(report)),
+        Err(err) => {
+            eprintln!("Error repairing ledger integrity: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
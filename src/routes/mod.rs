@@ -9,10 +9,19 @@ mod category_spending;
 mod reports;
 mod rules;
 mod rule_groups;
+mod rule_suggestions;
 mod imports;
 mod settings;
+mod auth;
+mod recurring_transactions;
+mod reconciliations;
+mod integrity;
+mod exchange_rates;
+mod tags;
 
 use axum::{
+    http::StatusCode,
+    middleware,
     Router,
     routing::{get, post, put, delete},
 };
@@ -29,11 +38,20 @@ pub fn create_router(
     budget_group_service: Arc<BudgetGroupService>,
     rule_service: Arc<RuleService>,
     rule_group_service: Arc<RuleGroupService>,
+    rule_suggestion_service: Arc<RuleSuggestionService>,
     import_service: Arc<FireflyImportService>,
     settings_service: Arc<SettingsService>,
+    recurring_transaction_service: Arc<RecurringTransactionService>,
+    reconciliation_service: Arc<ReconciliationService>,
+    integrity_service: Arc<IntegrityService>,
+    exchange_rate_service: Arc<ExchangeRateService>,
+    tag_service: Arc<TagService>,
+    auth_service: Arc<AuthService>,
     firefly_import_enabled: bool,
 ) -> Router {
+    // Everything except login/registration and the feature flags requires a session
     let mut router = Router::new()
+        .merge(auth::router(auth_service.clone()))
         .merge(accounts::router(account_service))
         .merge(transactions::router(transaction_rule_service.clone()))
         .merge(categories::router(category_service))
@@ -44,20 +62,38 @@ pub fn create_router(
         .merge(reports::router(transaction_service.clone()))
         .merge(rules::router(rule_service))
         .merge(rule_groups::router(rule_group_service))
+        .merge(rule_suggestions::router(rule_suggestion_service))
         .merge(settings::router(settings_service))
-        .merge(features::router(firefly_import_enabled));
+        .merge(recurring_transactions::router(recurring_transaction_service))
+        .merge(reconciliations::router(reconciliation_service))
+        .merge(integrity::router(integrity_service))
+        .merge(exchange_rates::router(exchange_rate_service))
+        .merge(tags::router(tag_service));
 
     if firefly_import_enabled {
         router = router.merge(imports::router(import_service));
     }
 
     router
+        .route_layer(middleware::from_fn_with_state(auth_service.clone(), auth::require_auth))
+        .merge(auth::public_router(auth_service))
+        .merge(features::router(firefly_import_enabled))
 }
 
 pub use web::router as web_router_impl;
 
+/// Validation errors from the services (`sqlx::Error::Protocol`) and amounts without an
+/// exchange rate into the requested currency are the client's fault
+pub(crate) fn error_status(err: &sqlx::Error) -> StatusCode {
+    match err {
+        sqlx::Error::Protocol(_) => StatusCode::BAD_REQUEST,
+        err if is_missing_exchange_rate(err) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
 use std::sync::Arc;
-use crate::services::{AccountService, TransactionService, TransactionRuleService, CategoryService, CategoryGroupService, BudgetService, BudgetGroupService, RuleService, RuleGroupService, FireflyImportService, SettingsService};
+use crate::services::{AccountService, TransactionService, TransactionRuleService, CategoryService, CategoryGroupService, BudgetService, BudgetGroupService, RuleService, RuleGroupService, RuleSuggestionService, FireflyImportService, SettingsService, RecurringTransactionService, ReconciliationService, IntegrityService, ExchangeRateService, TagService, AuthService, is_missing_exchange_rate};
 
 pub fn web_router(
     account_service: Arc<AccountService>,
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, State},
+    Extension,
+    http::StatusCode,
+    Json,
+    Router,
+    routing::{get, post},
+};
+use uuid::Uuid;
+use std::sync::Arc;
+
+use crate::models::{AuthUser, ReconcileAccountRequest, Reconciliation, ReconciliationResult};
+use crate::services::ReconciliationService;
+
+pub fn router(reconciliation_service: Arc<ReconciliationService>) -> Router {
+    Router::new()
+        .route("/accounts/{id}/reconcile", post(reconcile_account))
+        .route("/accounts/{id}/reconciliations", get(get_reconciliations))
+        .with_state(reconciliation_service)
+}
+
+// Handler to reconcile an account against a statement date and ending balance
+async fn reconcile_account(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<ReconciliationService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<ReconcileAccountRequest>,
+) -> Result<Json<ReconciliationResult>, StatusCode> {
+    match state.reconcile_account(auth.ledger_id, id, payload).await {
+        Ok(Some(result)) => Ok(Json(result)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error reconciling account: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to list past reconciliations of an account
+async fn get_reconciliations(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<ReconciliationService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<Reconciliation>>, StatusCode> {
+    match state.get_reconciliations(auth.ledger_id, id).await {
+        Ok(reconciliations) => Ok(Json(reconciliations)),
+        Err(err) => {
+            eprintln!("Error getting reconciliations: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    http::StatusCode,
+    Json,
+    Router,
+    routing::{get, post, put, delete},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+use std::sync::Arc;
+
+use crate::models::{
+    AuthUser, CreateRecurringTransactionRequest, OccurrenceRequest, RecurringTransactionResponse,
+    Transaction, UpcomingOccurrence, UpdateRecurringTransactionRequest,
+};
+use crate::services::RecurringTransactionService;
+use crate::routes::error_status;
+
+#[derive(Debug, Deserialize)]
+struct UpcomingQuery {
+    /// Last date to include (YYYY-MM-DD); takes precedence over `days`
+    until: Option<NaiveDate>,
+    /// Number of days ahead to include (default: 30)
+    days: Option<i64>,
+}
+
+impl UpcomingQuery {
+    fn until(&self) -> NaiveDate {
+        self.until.unwrap_or_else(|| {
+            Utc::now().date_naive() + Duration::days(self.days.unwrap_or(30).clamp(0, 3660))
+        })
+    }
+}
+
+pub fn router(recurring_transaction_service: Arc<RecurringTransactionService>) -> Router {
+    Router::new()
+        .route("/recurring-transactions", get(get_recurring_transactions))
+        .route("/recurring-transactions", post(create_recurring_transaction))
+        .route("/recurring-transactions/upcoming", get(get_upcoming))
+        .route("/recurring-transactions/{id}", get(get_recurring_transaction))
+        .route("/recurring-transactions/{id}", put(update_recurring_transaction))
+        .route("/recurring-transactions/{id}", delete(delete_recurring_transaction))
+        .route("/recurring-transactions/{id}/upcoming", get(get_upcoming_for))
+        .route("/recurring-transactions/{id}/skip", post(skip_occurrence))
+        .route("/recurring-transactions/{id}/post", post(post_occurrence))
+        .with_state(recurring_transaction_service)
+}
+
+// Handler to get all recurring transactions
+async fn get_recurring_transactions(
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<RecurringTransactionResponse>>, StatusCode> {
+    match state.get_recurring_transactions(auth.ledger_id).await {
+        Ok(recurring) => Ok(Json(recurring)),
+        Err(err) => {
+            eprintln!("Error getting recurring transactions: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to create a new recurring transaction
+async fn create_recurring_transaction(
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CreateRecurringTransactionRequest>,
+) -> Result<(StatusCode, Json<RecurringTransactionResponse>), StatusCode> {
+    match state.create_recurring_transaction(auth.ledger_id, payload).await {
+        Ok(recurring) => Ok((StatusCode::CREATED, Json(recurring))),
+        Err(err) => {
+            eprintln!("Error creating recurring transaction: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to list upcoming occurrences of all recurring transactions
+async fn get_upcoming(
+    Query(query): Query<UpcomingQuery>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<UpcomingOccurrence>>, StatusCode> {
+    match state.get_upcoming(auth.ledger_id, query.until()).await {
+        Ok(upcoming) => Ok(Json(upcoming)),
+        Err(err) => {
+            eprintln!("Error getting upcoming recurring transactions: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to get a specific recurring transaction by ID
+async fn get_recurring_transaction(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RecurringTransactionResponse>, StatusCode> {
+    match state.get_recurring_transaction(auth.ledger_id, id).await {
+        Ok(Some(recurring)) => Ok(Json(recurring)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting recurring transaction: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to update a recurring transaction
+async fn update_recurring_transaction(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<UpdateRecurringTransactionRequest>,
+) -> Result<Json<RecurringTransactionResponse>, StatusCode> {
+    match state.update_recurring_transaction(auth.ledger_id, id, payload).await {
+        Ok(Some(recurring)) => Ok(Json(recurring)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error updating recurring transaction: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to delete a recurring transaction
+async fn delete_recurring_transaction(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> StatusCode {
+    match state.delete_recurring_transaction(auth.ledger_id, id).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(err) => {
+            eprintln!("Error deleting recurring transaction: {:?}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+// Handler to list upcoming occurrences of one recurring transaction
+async fn get_upcoming_for(
+    Path(id): Path<Uuid>,
+    Query(query): Query<UpcomingQuery>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<UpcomingOccurrence>>, StatusCode> {
+    match state.get_upcoming_for(auth.ledger_id, id, query.until()).await {
+        Ok(Some(upcoming)) => Ok(Json(upcoming)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting upcoming occurrences: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to skip an occurrence (?occurrence_date=YYYY-MM-DD, default: the next one)
+async fn skip_occurrence(
+    Path(id): Path<Uuid>,
+    Query(query): Query<OccurrenceRequest>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RecurringTransactionResponse>, StatusCode> {
+    match state.skip_occurrence(auth.ledger_id, id, query.occurrence_date).await {
+        Ok(Some(recurring)) => Ok(Json(recurring)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error skipping occurrence: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to post an occurrence early (?occurrence_date=YYYY-MM-DD, default: the next one)
+async fn post_occurrence(
+    Path(id): Path<Uuid>,
+    Query(query): Query<OccurrenceRequest>,
+    State(state): State<Arc<RecurringTransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<(StatusCode, Json<Transaction>), StatusCode> {
+    match state.post_occurrence(auth.ledger_id, id, query.occurrence_date).await {
+        Ok(Some(transaction)) => Ok((StatusCode::CREATED, Json(transaction))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error posting occurrence: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
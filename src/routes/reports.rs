@@ -1,5 +1,6 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
+    Extension,
     http::StatusCode,
     Json, Router,
     routing::get,
@@ -8,7 +9,10 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::models::{AuthUser, BalanceHistoryPoint, NetWorthPoint};
 use crate::services::TransactionService;
+use crate::routes::error_status;
+use rust_decimal::Decimal;
 
 #[derive(Debug, Deserialize)]
 pub struct SpendingReportQuery {
@@ -19,6 +23,8 @@ pub struct SpendingReportQuery {
     /// If true, group by category group name; if false, group by category name
     #[serde(default = "default_true")]
     pub group: bool,
+    /// Grouping mode: "group", "category" or "tag"; takes precedence over `group`
+    pub group_by: Option<String>,
     /// Period granularity: month (default), week, or day
     pub period: Option<String>,
 }
@@ -29,26 +35,49 @@ fn default_true() -> bool { true }
 pub struct SpendingReportRow {
     pub period: String,
     pub name: String,
-    pub amount: f64,
+    pub amount: Decimal,
 }
 
 #[derive(Debug, Serialize)]
 pub struct InflowOutflowReportRow {
     pub period: String,
-    pub inflow: f64,
-    pub outflow: f64,
+    pub inflow: Decimal,
+    pub outflow: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BalanceReportQuery {
+    /// First day to report (YYYY-MM-DD); defaults to one year before end_date
+    pub start_date: Option<chrono::NaiveDate>,
+    /// Last day to report (YYYY-MM-DD); defaults to today
+    pub end_date: Option<chrono::NaiveDate>,
+    /// Comma-separated list of account UUIDs to include (net worth only); if omitted, include all non-external accounts
+    pub account_ids: Option<String>,
+    /// Period granularity: month (default), week, or day
+    pub period: Option<String>,
+}
+
+impl BalanceReportQuery {
+    fn date_range(&self) -> (chrono::NaiveDate, chrono::NaiveDate) {
+        let end_date = self.end_date.unwrap_or_else(|| chrono::Utc::now().date_naive());
+        let start_date = self.start_date.unwrap_or_else(|| end_date - chrono::Duration::days(365));
+        (start_date, end_date)
+    }
 }
 
 pub fn router(transaction_service: Arc<TransactionService>) -> Router {
     Router::new()
         .route("/reports/spending", get(spending_by_group_over_time))
         .route("/reports/inflow-outflow", get(inflow_outflow_over_time))
+        .route("/reports/net-worth", get(net_worth_over_time))
+        .route("/accounts/{id}/balance-history", get(balance_history))
         .with_state(transaction_service)
 }
 
 async fn spending_by_group_over_time(
     Query(query): Query<SpendingReportQuery>,
     State(state): State<Arc<TransactionService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<SpendingReportRow>>, StatusCode> {
     // Parse dates if provided
     let start_date = query.start_date.as_ref().and_then(|date_str| {
@@ -82,11 +111,16 @@ async fn spending_by_group_over_time(
             .collect::<Vec<_>>()
     }).filter(|v| !v.is_empty());
 
-    let group_flag = query.group;
+    let group_by = match query.group_by.as_deref() {
+        Some(mode @ ("group" | "category" | "tag")) => mode,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+        None if query.group => "group",
+        None => "category",
+    };
     let period = query.period.as_deref().unwrap_or("month");
 
     match state
-        .get_spending_over_time(account_ids, start_date, end_date, group_flag, period)
+        .get_spending_over_time(auth.ledger_id, account_ids, start_date, end_date, group_by, period)
         .await
     {
         Ok(rows) => {
@@ -98,7 +132,7 @@ async fn spending_by_group_over_time(
         }
         Err(err) => {
             eprintln!("Error generating spending report: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -106,6 +140,7 @@ async fn spending_by_group_over_time(
 async fn inflow_outflow_over_time(
     Query(query): Query<SpendingReportQuery>,
     State(state): State<Arc<TransactionService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<InflowOutflowReportRow>>, StatusCode> {
     // Parse dates
     let start_date = query.start_date.as_ref().and_then(|date_str| {
@@ -142,7 +177,7 @@ async fn inflow_outflow_over_time(
     let period = query.period.as_deref().unwrap_or("month");
 
     match state
-        .get_inflow_outflow_over_time(account_ids, start_date, end_date, period)
+        .get_inflow_outflow_over_time(auth.ledger_id, account_ids, start_date, end_date, period)
         .await
     {
         Ok(rows) => {
@@ -154,7 +189,57 @@ async fn inflow_outflow_over_time(
         }
         Err(err) => {
             eprintln!("Error generating inflow/outflow report: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
+        }
+    }
+}
+
+async fn balance_history(
+    Path(id): Path<Uuid>,
+    Query(query): Query<BalanceReportQuery>,
+    State(state): State<Arc<TransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<BalanceHistoryPoint>>, StatusCode> {
+    let (start_date, end_date) = query.date_range();
+    let period = query.period.as_deref().unwrap_or("month");
+
+    match state
+        .get_balance_history(auth.ledger_id, id, start_date, end_date, period)
+        .await
+    {
+        Ok(Some(points)) => Ok(Json(points)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error generating balance history: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+async fn net_worth_over_time(
+    Query(query): Query<BalanceReportQuery>,
+    State(state): State<Arc<TransactionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<NetWorthPoint>>, StatusCode> {
+    let (start_date, end_date) = query.date_range();
+
+    // Parse account IDs if provided
+    let account_ids: Option<Vec<Uuid>> = query.account_ids.as_ref().map(|s| {
+        s.split(',')
+            .filter_map(|part| Uuid::parse_str(part.trim()).ok())
+            .collect::<Vec<_>>()
+    }).filter(|v| !v.is_empty());
+
+    let period = query.period.as_deref().unwrap_or("month");
+
+    match state
+        .get_net_worth_over_time(auth.ledger_id, account_ids, start_date, end_date, period)
+        .await
+    {
+        Ok(points) => Ok(Json(points)),
+        Err(err) => {
+            eprintln!("Error generating net worth report: {:?}", err);
+            Err(error_status(&err))
         }
     }
 }
@@ -1,5 +1,6 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -7,8 +8,9 @@ use axum::{
 };
 use uuid::Uuid;
 use std::sync::Arc;
+use serde::Serialize;
 
-use crate::models::{RuleGroup, CreateRuleGroupRequest, UpdateRuleGroupRequest, RuleResponse, Rule};
+use crate::models::{RuleGroup, CreateRuleGroupRequest, UpdateRuleGroupRequest, Rule, RuleRunFilter, RuleRunPreview, AuthUser};
 use crate::services::RuleGroupService;
 
 pub fn router(rule_group_service: Arc<RuleGroupService>) -> Router {
@@ -20,14 +22,17 @@ pub fn router(rule_group_service: Arc<RuleGroupService>) -> Router {
         .route("/rule-groups/{id}", post(update_rule_group)) // POST handler for updates (compat)
         .route("/rule-groups/{id}", delete(delete_rule_group))
         .route("/rule-groups/{id}/rules", get(get_rules_by_group))
+        .route("/rule-groups/{id}/run", post(run_rule_group))
+        .route("/rule-groups/{id}/test", post(test_rule_group))
         .with_state(rule_group_service)
 }
 
 // Handler to get all rule groups
 async fn get_rule_groups(
     State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<RuleGroup>>, StatusCode> {
-    match state.get_rule_groups().await {
+    match state.get_rule_groups(auth.ledger_id).await {
         Ok(groups) => Ok(Json(groups)),
         Err(err) => {
             eprintln!("Error getting rule groups: {:?}", err);
@@ -39,9 +44,10 @@ async fn get_rule_groups(
 // Handler to create a new rule group
 async fn create_rule_group(
     State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateRuleGroupRequest>,
 ) -> Result<(StatusCode, Json<RuleGroup>), StatusCode> {
-    match state.create_rule_group(payload).await {
+    match state.create_rule_group(auth.ledger_id, payload).await {
         Ok(group) => Ok((StatusCode::CREATED, Json(group))),
         Err(err) => {
             eprintln!("Error creating rule group: {:?}", err);
@@ -54,8 +60,9 @@ async fn create_rule_group(
 async fn get_rule_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<RuleGroup>, StatusCode> {
-    match state.get_rule_group(id).await {
+    match state.get_rule_group(auth.ledger_id, id).await {
         Ok(Some(group)) => Ok(Json(group)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -69,9 +76,10 @@ async fn get_rule_group(
 async fn update_rule_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateRuleGroupRequest>,
 ) -> Result<Json<RuleGroup>, StatusCode> {
-    match state.update_rule_group(id, payload).await {
+    match state.update_rule_group(auth.ledger_id, id, payload).await {
         Ok(Some(group)) => Ok(Json(group)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -85,8 +93,9 @@ async fn update_rule_group(
 async fn delete_rule_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
-    match state.delete_rule_group(id).await {
+    match state.delete_rule_group(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -100,8 +109,9 @@ async fn delete_rule_group(
 async fn get_rules_by_group(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Rule>>, StatusCode> {
-    match state.get_rules_by_group(id).await {
+    match state.get_rules_by_group(auth.ledger_id, id).await {
         Ok(rules) => Ok(Json(rules)),
         Err(err) => {
             eprintln!("Error getting rules by group: {:?}", err);
@@ -109,3 +119,78 @@ async fn get_rules_by_group(
         }
     }
 }
+
+// Response structure for running the rules of a group
+#[derive(Serialize)]
+struct RuleGroupExecutionResponse {
+    affected_transactions: usize,
+    /// Groups the changes of this run in the transaction history, to undo them together
+    run_id: Uuid,
+    message: String,
+}
+
+/// Handler to run the active rules of a group on all transactions
+///
+/// The group's rules apply in priority order, and only the first matching one when the group
+/// stops after its first match. An inactive group changes nothing. Like other rule runs, the run can
+/// be restricted with `start_date`, `end_date` and `account_id` query parameters.
+async fn run_rule_group(
+    Path(id): Path<Uuid>,
+    Query(filter): Query<RuleRunFilter>,
+    State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RuleGroupExecutionResponse>, StatusCode> {
+    match state.get_rule_group(auth.ledger_id, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting rule group {}: {:?}", id, err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match state.run_rule_group(auth.ledger_id, id, &filter).await {
+        Ok((count, run_id)) => {
+            let message = if count > 0 {
+                format!("Successfully applied rule group to {} transactions", count)
+            } else {
+                "No transactions were affected by the rule group".to_string()
+            };
+
+            Ok(Json(RuleGroupExecutionResponse {
+                affected_transactions: count,
+                run_id,
+                message,
+            }))
+        }
+        Err(err) => {
+            eprintln!("Error running rule group {}: {:?}", id, err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Handler to preview what running the rules of a group would change, without writing anything
+async fn test_rule_group(
+    Path(id): Path<Uuid>,
+    Query(filter): Query<RuleRunFilter>,
+    State(state): State<Arc<RuleGroupService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RuleRunPreview>, StatusCode> {
+    match state.get_rule_group(auth.ledger_id, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting rule group {}: {:?}", id, err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match state.test_rule_group(auth.ledger_id, id, &filter).await {
+        Ok(preview) => Ok(Json(preview)),
+        Err(err) => {
+            eprintln!("Error testing rule group {}: {:?}", id, err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension,
+    http::StatusCode,
+    Json,
+    Router,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+use crate::models::{AuthUser, RuleResponse, RuleSuggestion, RuleSuggestionQuery};
+use crate::services::RuleSuggestionService;
+use crate::routes::error_status;
+
+pub fn router(rule_suggestion_service: Arc<RuleSuggestionService>) -> Router {
+    Router::new()
+        .route("/rules/suggestions", get(get_rule_suggestions))
+        .route("/rules/suggestions/{id}/accept", post(accept_rule_suggestion))
+        .with_state(rule_suggestion_service)
+}
+
+// Handler to suggest rules learned from categorised transactions
+async fn get_rule_suggestions(
+    Query(query): Query<RuleSuggestionQuery>,
+    State(state): State<Arc<RuleSuggestionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<RuleSuggestion>>, StatusCode> {
+    match state.suggest_rules(auth.ledger_id, &query).await {
+        Ok(suggestions) => Ok(Json(suggestions)),
+        Err(err) => {
+            eprintln!("Error suggesting rules: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to create the rule of a suggestion; the thresholds must match the ones it was listed with
+async fn accept_rule_suggestion(
+    Path(id): Path<String>,
+    Query(query): Query<RuleSuggestionQuery>,
+    State(state): State<Arc<RuleSuggestionService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<(StatusCode, Json<RuleResponse>), StatusCode> {
+    match state.accept_suggestion(auth.ledger_id, &id, &query).await {
+        Ok(Some(rule)) => Ok((StatusCode::CREATED, Json(rule))),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error accepting rule suggestion: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -9,8 +10,10 @@ use uuid::Uuid;
 use std::sync::Arc;
 use serde::{Serialize, Deserialize};
 
-use crate::models::{CreateRuleRequest, UpdateRuleRequest, RuleResponse, RuleCondition, Transaction};
+use crate::models::{CreateRuleRequest, UpdateRuleRequest, RuleResponse, RuleConditionNode, Transaction, AuthUser};
+use crate::models::{CommitRuleRunRequest, CommitRuleRunResult, RuleRunFilter, RuleRunPreview, RuleRunScope};
 use crate::services::RuleService;
+use crate::routes::error_status;
 
 
 pub fn router(rule_service: Arc<RuleService>) -> Router {
@@ -19,6 +22,10 @@ pub fn router(rule_service: Arc<RuleService>) -> Router {
         .route("/rules", post(create_rule))
         .route("/rules/run", post(run_all_rules))
         .route("/rules/{id}/run", post(run_rule))
+        .route("/rules/run/preview", post(preview_all_rules))
+        .route("/rules/{id}/run/preview", post(preview_rule))
+        .route("/rules/run/commit", post(commit_all_rules))
+        .route("/rules/{id}/run/commit", post(commit_rule))
         .route("/rules/test", post(test_rule_conditions))
         .route("/rules/{id}/test", post(test_rule_by_id))
         .route("/rules/{id}", get(get_rule))
@@ -30,8 +37,9 @@ pub fn router(rule_service: Arc<RuleService>) -> Router {
 // Handler to get all rules
 async fn get_rules(
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<RuleResponse>>, StatusCode> {
-    match state.get_rules().await {
+    match state.get_rules(auth.ledger_id).await {
         Ok(rules) => Ok(Json(rules)),
         Err(err) => {
             eprintln!("Error getting rules: {:?}", err);
@@ -44,8 +52,9 @@ async fn get_rules(
 async fn get_rule(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<RuleResponse>, StatusCode> {
-    match state.get_rule(id).await {
+    match state.get_rule(auth.ledger_id, id).await {
         Ok(Some(rule)) => Ok(Json(rule)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -58,6 +67,7 @@ async fn get_rule(
 // Handler to create a new rule
 async fn create_rule(
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateRuleRequest>,
 ) -> Result<(StatusCode, Json<RuleResponse>), StatusCode> {
     // Validate the request
@@ -73,11 +83,11 @@ async fn create_rule(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    match state.create_rule(payload).await {
+    match state.create_rule(auth.ledger_id, payload).await {
         Ok(rule) => Ok((StatusCode::CREATED, Json(rule))),
         Err(err) => {
             eprintln!("Error creating rule: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -86,14 +96,15 @@ async fn create_rule(
 async fn update_rule(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateRuleRequest>,
 ) -> Result<Json<RuleResponse>, StatusCode> {
-    match state.update_rule(id, payload).await {
+    match state.update_rule(auth.ledger_id, id, payload).await {
         Ok(Some(rule)) => Ok(Json(rule)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
             eprintln!("Error updating rule: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -102,8 +113,9 @@ async fn update_rule(
 async fn delete_rule(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
-    match state.delete_rule(id).await {
+    match state.delete_rule(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
@@ -117,13 +129,15 @@ async fn delete_rule(
 #[derive(Serialize)]
 struct RuleExecutionResponse {
     affected_transactions: usize,
+    /// Groups the changes of this run in the transaction history, to undo them together
+    run_id: Uuid,
     message: String,
 }
 
 // Request payload to test rule conditions
 #[derive(Deserialize)]
 struct RuleTestRequest {
-    conditions: Vec<RuleCondition>,
+    conditions: Vec<RuleConditionNode>,
 }
 
 // Response for testing rule conditions
@@ -140,12 +154,16 @@ struct RuleTestResponse {
 /// but this endpoint provides a way to apply rules to existing transactions that
 /// may have been created before the rules were defined or when rules have been updated.
 ///
-/// Returns the number of transactions that were affected by the rules.
+/// The run can be restricted with `start_date`, `end_date` (YYYY-MM-DD) and `account_id` query parameters.
+///
+/// Returns the number of transactions that were affected by the rules and the run ID to undo them with.
 async fn run_all_rules(
+    Query(filter): Query<RuleRunFilter>,
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<RuleExecutionResponse>, StatusCode> {
-    match state.apply_all_rules_to_all_transactions().await {
-        Ok(count) => {
+    match state.apply_all_rules_to_all_transactions(auth.ledger_id, &filter).await {
+        Ok((count, run_id)) => {
             let message = if count > 0 {
                 format!("Successfully applied rules to {} transactions", count)
             } else {
@@ -154,6 +172,7 @@ async fn run_all_rules(
 
             Ok(Json(RuleExecutionResponse {
                 affected_transactions: count,
+                run_id,
                 message,
             }))
         },
@@ -175,14 +194,15 @@ async fn run_all_rules(
 /// Handler to test rule conditions against transactions (payload-based)
 async fn test_rule_conditions(
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<RuleTestRequest>,
 ) -> Result<Json<RuleTestResponse>, StatusCode> {
     // Accept empty conditions as matching none
-    let (total, sample) = match state.test_conditions(payload.conditions).await {
+    let (total, sample) = match state.test_conditions(auth.ledger_id, payload.conditions).await {
         Ok(res) => res,
         Err(err) => {
             eprintln!("Error testing rule conditions: {:?}", err);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            return Err(error_status(&err));
         }
     };
     Ok(Json(RuleTestResponse { total_matches: total, sample }))
@@ -192,8 +212,9 @@ async fn test_rule_conditions(
 async fn test_rule_by_id(
     Path(id): Path<Uuid>,
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<RuleTestResponse>, StatusCode> {
-    let rule = match state.get_rule(id).await {
+    let rule = match state.get_rule(auth.ledger_id, id).await {
         Ok(Some(rule)) => rule,
         Ok(None) => return Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -202,7 +223,7 @@ async fn test_rule_by_id(
         }
     };
 
-    let (total, sample) = match state.test_conditions(rule.conditions).await {
+    let (total, sample) = match state.test_conditions(auth.ledger_id, rule.conditions).await {
         Ok(res) => res,
         Err(err) => {
             eprintln!("Error testing rule {} conditions: {:?}", id, err);
@@ -215,14 +236,16 @@ async fn test_rule_by_id(
 
 async fn run_rule(
     Path(id): Path<Uuid>,
+    Query(filter): Query<RuleRunFilter>,
     State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<RuleExecutionResponse>, StatusCode> {
     // First check if the rule exists
-    match state.get_rule(id).await {
+    match state.get_rule(auth.ledger_id, id).await {
         Ok(Some(_)) => {
             // Rule exists, apply it to all transactions
-            match state.apply_rule_to_all_transactions(id).await {
-                Ok(count) => {
+            match state.apply_rule_to_all_transactions(auth.ledger_id, id, &filter).await {
+                Ok((count, run_id)) => {
                     let message = if count > 0 {
                         format!("Successfully applied rule to {} transactions", count)
                     } else {
@@ -231,6 +254,7 @@ async fn run_rule(
 
                     Ok(Json(RuleExecutionResponse {
                         affected_transactions: count,
+                        run_id,
                         message,
                     }))
                 },
@@ -247,3 +271,92 @@ async fn run_rule(
         }
     }
 }
+
+/// Handler to preview what running all active rules would change
+///
+/// Returns, per transaction that would change, its values before and after the run. Nothing is
+/// written; the returned changes can be committed as they are with `/rules/run/commit`.
+async fn preview_all_rules(
+    Query(filter): Query<RuleRunFilter>,
+    State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RuleRunPreview>, StatusCode> {
+    match state.preview_rule_run(auth.ledger_id, RuleRunScope::All, &filter).await {
+        Ok(preview) => Ok(Json(preview)),
+        Err(err) => {
+            eprintln!("Error previewing all rules: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Handler to preview what running a specific rule would change
+async fn preview_rule(
+    Path(id): Path<Uuid>,
+    Query(filter): Query<RuleRunFilter>,
+    State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<RuleRunPreview>, StatusCode> {
+    match state.get_rule(auth.ledger_id, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting rule {}: {:?}", id, err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    match state.preview_rule_run(auth.ledger_id, RuleRunScope::Rule(id), &filter).await {
+        Ok(preview) => Ok(Json(preview)),
+        Err(err) => {
+            eprintln!("Error previewing rule {}: {:?}", id, err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Map the outcome of committing a preview: 200 when applied, 400 with the conflicts otherwise
+fn commit_response(result: Result<CommitRuleRunResult, sqlx::Error>) -> Result<(StatusCode, Json<CommitRuleRunResult>), StatusCode> {
+    match result {
+        Ok(result) if result.applied => Ok((StatusCode::OK, Json(result))),
+        Ok(result) => Ok((StatusCode::BAD_REQUEST, Json(result))),
+        Err(sqlx::Error::Protocol(message)) => {
+            eprintln!("Invalid rule run preview: {}", message);
+            Err(StatusCode::BAD_REQUEST)
+        }
+        Err(err) => {
+            eprintln!("Error committing rule run preview: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Handler to apply exactly the changes previewed for all active rules, with the filter of the preview
+async fn commit_all_rules(
+    Query(filter): Query<RuleRunFilter>,
+    State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CommitRuleRunRequest>,
+) -> Result<(StatusCode, Json<CommitRuleRunResult>), StatusCode> {
+    commit_response(state.commit_rule_run(auth.ledger_id, RuleRunScope::All, &filter, payload.changes).await)
+}
+
+/// Handler to apply exactly the changes previewed for a specific rule
+async fn commit_rule(
+    Path(id): Path<Uuid>,
+    Query(filter): Query<RuleRunFilter>,
+    State(state): State<Arc<RuleService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CommitRuleRunRequest>,
+) -> Result<(StatusCode, Json<CommitRuleRunResult>), StatusCode> {
+    match state.get_rule(auth.ledger_id, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting rule {}: {:?}", id, err);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    commit_response(state.commit_rule_run(auth.ledger_id, RuleRunScope::Rule(id), &filter, payload.changes).await)
+}
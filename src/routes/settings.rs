@@ -1,5 +1,6 @@
 use axum::{
     extract::{State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -8,7 +9,9 @@ use axum::{
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
+use crate::models::{AuthUser, BaseCurrencyResponse, UpdateBaseCurrencyRequest};
 use crate::services::SettingsService;
+use rust_decimal::Decimal;
 
 // Request structure for updating forecasted monthly income
 #[derive(Debug, Deserialize)]
@@ -19,22 +22,25 @@ struct UpdateForecastedMonthlyIncomeRequest {
 // Response structure for forecasted monthly income
 #[derive(Debug, Serialize)]
 struct ForecastedMonthlyIncomeResponse {
-    forecasted_monthly_income: f64,
+    forecasted_monthly_income: Decimal,
 }
 
 pub fn router(settings_service: Arc<SettingsService>) -> Router {
     Router::new()
         .route("/settings/forecasted-monthly-income", get(get_forecasted_monthly_income))
         .route("/settings/forecasted-monthly-income", put(update_forecasted_monthly_income))
+        .route("/settings/base-currency", get(get_base_currency))
+        .route("/settings/base-currency", put(update_base_currency))
         .with_state(settings_service)
 }
 
 // Handler to get the forecasted monthly income
 async fn get_forecasted_monthly_income(
     State(state): State<Arc<SettingsService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<ForecastedMonthlyIncomeResponse>, StatusCode> {
     // Call the settings service to get the forecasted monthly income
-    match state.get_forecasted_monthly_income().await {
+    match state.get_forecasted_monthly_income(auth.ledger_id).await {
         Ok(income) => Ok(Json(ForecastedMonthlyIncomeResponse {
             forecasted_monthly_income: income,
         })),
@@ -48,10 +54,11 @@ async fn get_forecasted_monthly_income(
 // Handler to update the forecasted monthly income
 async fn update_forecasted_monthly_income(
     State(state): State<Arc<SettingsService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateForecastedMonthlyIncomeRequest>,
 ) -> Result<Json<ForecastedMonthlyIncomeResponse>, StatusCode> {
-    // Parse the value as f64
-    let amount = match payload.value.parse::<f64>() {
+    // Parse the value as a decimal
+    let amount = match payload.value.trim().parse::<Decimal>() {
         Ok(value) => value,
         Err(_) => {
             eprintln!("Error parsing forecasted monthly income value: {}", payload.value);
@@ -60,7 +67,7 @@ async fn update_forecasted_monthly_income(
     };
 
     // Call the settings service to update the forecasted monthly income
-    match state.update_forecasted_monthly_income(amount).await {
+    match state.update_forecasted_monthly_income(auth.ledger_id, amount).await {
         Ok(income) => Ok(Json(ForecastedMonthlyIncomeResponse {
             forecasted_monthly_income: income,
         })),
@@ -70,3 +77,36 @@ async fn update_forecasted_monthly_income(
         }
     }
 }
+
+// Handler to get the base currency reports are converted into
+async fn get_base_currency(
+    State(state): State<Arc<SettingsService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<BaseCurrencyResponse>, StatusCode> {
+    match state.get_base_currency(auth.ledger_id).await {
+        Ok(base_currency) => Ok(Json(BaseCurrencyResponse { base_currency })),
+        Err(err) => {
+            eprintln!("Error getting base currency: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to update the base currency
+async fn update_base_currency(
+    State(state): State<Arc<SettingsService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<UpdateBaseCurrencyRequest>,
+) -> Result<Json<BaseCurrencyResponse>, StatusCode> {
+    match state.update_base_currency(auth.ledger_id, &payload.base_currency).await {
+        Ok(base_currency) => Ok(Json(BaseCurrencyResponse { base_currency })),
+        Err(sqlx::Error::Protocol(message)) => {
+            eprintln!("Invalid base currency: {}", message);
+            Err(StatusCode::BAD_REQUEST)
+        }
+        Err(err) => {
+            eprintln!("Error updating base currency: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
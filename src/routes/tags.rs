@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, State},
+    Extension,
+    http::StatusCode,
+    Json,
+    Router,
+    routing::{get, post, put, delete},
+};
+use uuid::Uuid;
+use std::sync::Arc;
+
+use crate::models::{AuthUser, CreateTagRequest, Tag, UpdateTagRequest};
+use crate::services::TagService;
+use crate::routes::error_status;
+
+pub fn router(tag_service: Arc<TagService>) -> Router {
+    Router::new()
+        .route("/tags", get(get_tags))
+        .route("/tags", post(create_tag))
+        .route("/tags/{id}", get(get_tag))
+        .route("/tags/{id}", put(update_tag))
+        .route("/tags/{id}", delete(delete_tag))
+        .with_state(tag_service)
+}
+
+// Handler to get all tags
+async fn get_tags(
+    State(state): State<Arc<TagService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<Tag>>, StatusCode> {
+    match state.get_tags(auth.ledger_id).await {
+        Ok(tags) => Ok(Json(tags)),
+        Err(err) => {
+            eprintln!("Error getting tags: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to create a new tag
+async fn create_tag(
+    State(state): State<Arc<TagService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<CreateTagRequest>,
+) -> Result<(StatusCode, Json<Tag>), StatusCode> {
+    match state.create_tag(auth.ledger_id, payload).await {
+        Ok(tag) => Ok((StatusCode::CREATED, Json(tag))),
+        Err(err) => {
+            eprintln!("Error creating tag: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to get a specific tag by ID
+async fn get_tag(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<TagService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Tag>, StatusCode> {
+    match state.get_tag(auth.ledger_id, id).await {
+        Ok(Some(tag)) => Ok(Json(tag)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting tag: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to update (or rename) a tag
+async fn update_tag(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<TagService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<UpdateTagRequest>,
+) -> Result<Json<Tag>, StatusCode> {
+    match state.update_tag(auth.ledger_id, id, payload).await {
+        Ok(Some(tag)) => Ok(Json(tag)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error updating tag: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to delete a tag (transactions keep everything but the tag)
+async fn delete_tag(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<TagService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> StatusCode {
+    match state.delete_tag(auth.ledger_id, id).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(err) => {
+            eprintln!("Error deleting tag: {:?}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
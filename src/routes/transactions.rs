@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
+    Extension,
     http::StatusCode,
     Json,
     Router,
@@ -10,18 +11,26 @@ use uuid::Uuid;
 use std::sync::Arc;
 use chrono::Utc;
 
-use crate::models::{Transaction, CreateTransactionRequest, UpdateTransactionRequest};
+use crate::models::{Transaction, CreateTransactionRequest, UpdateTransactionRequest, TransactionSearchQuery, TransactionSearchResult, BulkEditTransactionsRequest, BulkEditResult, ChangeActor, TransactionChange, UndoRunResult, AuthUser};
 use crate::services::TransactionRuleService;
+use crate::routes::error_status;
+use rust_decimal::Decimal;
 
 pub fn router(transaction_service: Arc<TransactionRuleService>) -> Router {
     Router::new()
         .route("/transactions", get(get_transactions))
         .route("/transactions/monthly-incoming", get(get_monthly_incoming_transactions))
         .route("/transactions/unbudgeted", get(get_unbudgeted_transactions))
+        .route("/transactions/search", get(search_transactions))
+        .route("/transactions/bulk", post(bulk_edit_transactions))
         .route("/transactions", post(create_transaction))
         .route("/transactions/{id}", get(get_transaction))
         .route("/transactions/{id}", put(update_transaction))
         .route("/transactions/{id}", delete(delete_transaction))
+        .route("/transactions/{id}/unlock", post(unlock_transaction))
+        .route("/transactions/{id}/history", get(get_transaction_history))
+        .route("/transaction-changes/{id}/undo", post(undo_transaction_change))
+        .route("/transaction-changes/runs/{run_id}/undo", post(undo_change_run))
         .route("/accounts/{source_account_id}/transactions", get(get_account_transactions))
         .route("/accounts/{source_account_id}/import-csv", post(import_csv_transactions))
         .with_state(transaction_service)
@@ -31,6 +40,8 @@ pub fn router(transaction_service: Arc<TransactionRuleService>) -> Router {
 pub struct TransactionQuery {
     pub source_account_id: Option<Uuid>,
     pub category: Option<String>,
+    /// Only transactions carrying the tag with this name
+    pub tag: Option<String>,
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub limit: Option<i64>,
@@ -47,6 +58,7 @@ struct MonthlyIncomingQuery {
 async fn get_transactions(
     Query(query): Query<TransactionQuery>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Transaction>>, StatusCode> {
     // Parse dates if provided
     let start_date = query.start_date.as_ref().and_then(|date_str| {
@@ -78,8 +90,10 @@ async fn get_transactions(
 
     // Call the transaction service to get transactions with filters and pagination
     match state.get_transactions(
+        auth.ledger_id,
         query.source_account_id,
         query.category.as_deref(),
+        query.tag.as_deref(),
         start_date,
         end_date,
         limit,
@@ -93,12 +107,45 @@ async fn get_transactions(
     }
 }
 
+// Handler to search transactions by text and filters, returning a page and the total match count
+async fn search_transactions(
+    Query(query): Query<TransactionSearchQuery>,
+    State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<TransactionSearchResult>, StatusCode> {
+    match state.search_transactions(auth.ledger_id, &query).await {
+        Ok(result) => Ok(Json(result)),
+        Err(err) => {
+            eprintln!("Error searching transactions: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to edit or delete many transactions at once; nothing changes unless every item succeeds
+async fn bulk_edit_transactions(
+    State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
+    Json(payload): Json<BulkEditTransactionsRequest>,
+) -> Result<(StatusCode, Json<BulkEditResult>), StatusCode> {
+    match state.bulk_edit_transactions(auth.ledger_id, payload, ChangeActor::user(auth.user_id)).await {
+        Ok(result) if result.applied => Ok((StatusCode::OK, Json(result))),
+        // Report which items failed alongside the rejection
+        Ok(result) => Ok((StatusCode::BAD_REQUEST, Json(result))),
+        Err(err) => {
+            eprintln!("Error bulk editing transactions: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
 // Handler to get monthly incoming transactions (mirrors budget_service selection)
 async fn get_monthly_incoming_transactions(
     Query(params): Query<MonthlyIncomingQuery>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Transaction>>, StatusCode> {
-    match state.get_monthly_incoming_transactions(params.year, params.month).await {
+    match state.get_monthly_incoming_transactions(auth.ledger_id, params.year, params.month).await {
         Ok(transactions) => Ok(Json(transactions)),
         Err(err) => {
             eprintln!("Error getting monthly incoming transactions: {:?}", err);
@@ -111,6 +158,7 @@ async fn get_monthly_incoming_transactions(
 async fn get_unbudgeted_transactions(
     Query(query): Query<TransactionQuery>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Transaction>>, StatusCode> {
     // Parse dates if provided
     let start_date = query.start_date.as_ref().and_then(|date_str| {
@@ -137,7 +185,7 @@ async fn get_unbudgeted_transactions(
         })
     });
 
-    match state.get_unbudgeted_transactions(start_date, end_date).await {
+    match state.get_unbudgeted_transactions(auth.ledger_id, start_date, end_date).await {
         Ok(txs) => Ok(Json(txs)),
         Err(err) => {
             eprintln!("Error getting unbudgeted transactions: {:?}", err);
@@ -151,12 +199,13 @@ async fn get_account_transactions(
     Path(source_account_id): Path<Uuid>,
     Query(query): Query<TransactionQuery>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Vec<Transaction>>, StatusCode> {
     // Set default limit to 100 if not provided
     let limit = query.limit.or(Some(100));
 
     // Call the transaction service to get transactions for the account
-    match state.get_account_transactions(source_account_id, limit, query.offset).await {
+    match state.get_account_transactions(auth.ledger_id, source_account_id, limit, query.offset).await {
         Ok(transactions) => Ok(Json(transactions)),
         Err(err) => {
             eprintln!("Error getting account transactions: {:?}", err);
@@ -168,14 +217,15 @@ async fn get_account_transactions(
 // Handler to create a new transaction
 async fn create_transaction(
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<CreateTransactionRequest>,
 ) -> Result<(StatusCode, Json<Transaction>), StatusCode> {
     // Call the transaction service to create a new transaction
-    match state.create_transaction(payload).await {
+    match state.create_transaction(auth.ledger_id, payload, ChangeActor::user(auth.user_id)).await {
         Ok(transaction) => Ok((StatusCode::CREATED, Json(transaction))),
         Err(err) => {
             eprintln!("Error creating transaction: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -184,9 +234,10 @@ async fn create_transaction(
 async fn get_transaction(
     Path(id): Path<Uuid>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> Result<Json<Transaction>, StatusCode> {
     // Call the transaction service to get the transaction by ID
-    match state.get_transaction(id).await {
+    match state.get_transaction(auth.ledger_id, id).await {
         Ok(Some(transaction)) => Ok(Json(transaction)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
@@ -200,15 +251,16 @@ async fn get_transaction(
 async fn update_transaction(
     Path(id): Path<Uuid>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<UpdateTransactionRequest>,
 ) -> Result<Json<Transaction>, StatusCode> {
     // Call the transaction service to update the transaction
-    match state.update_transaction(id, payload).await {
+    match state.update_transaction(auth.ledger_id, id, payload, ChangeActor::user(auth.user_id)).await {
         Ok(Some(transaction)) => Ok(Json(transaction)),
         Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(err) => {
             eprintln!("Error updating transaction: {:?}", err);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_status(&err))
         }
     }
 }
@@ -217,14 +269,79 @@ async fn update_transaction(
 async fn delete_transaction(
     Path(id): Path<Uuid>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
 ) -> StatusCode {
     // Call the transaction service to delete the transaction
-    match state.delete_transaction(id).await {
+    match state.delete_transaction(auth.ledger_id, id).await {
         Ok(true) => StatusCode::NO_CONTENT,
         Ok(false) => StatusCode::NOT_FOUND,
         Err(err) => {
             eprintln!("Error deleting transaction: {:?}", err);
-            StatusCode::INTERNAL_SERVER_ERROR
+            error_status(&err)
+        }
+    }
+}
+
+// Handler to unlock a reconciled transaction so it can be edited or deleted
+async fn unlock_transaction(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Transaction>, StatusCode> {
+    match state.unlock_transaction(auth.ledger_id, id, ChangeActor::user(auth.user_id)).await {
+        Ok(Some(transaction)) => Ok(Json(transaction)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error unlocking transaction: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to get the change history of a transaction, oldest first
+async fn get_transaction_history(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Vec<TransactionChange>>, StatusCode> {
+    match state.get_transaction_history(auth.ledger_id, id).await {
+        Ok(Some(changes)) => Ok(Json(changes)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error getting transaction history: {:?}", err);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+// Handler to undo a single change, returning the restored transaction
+async fn undo_transaction_change(
+    Path(id): Path<Uuid>,
+    State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<Transaction>, StatusCode> {
+    match state.undo_transaction_change(auth.ledger_id, id).await {
+        Ok(Some(transaction)) => Ok(Json(transaction)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error undoing transaction change: {:?}", err);
+            Err(error_status(&err))
+        }
+    }
+}
+
+// Handler to undo every change of a rule run or bulk edit
+async fn undo_change_run(
+    Path(run_id): Path<Uuid>,
+    State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
+) -> Result<Json<UndoRunResult>, StatusCode> {
+    match state.undo_change_run(auth.ledger_id, run_id).await {
+        Ok(Some(result)) => Ok(Json(result)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(err) => {
+            eprintln!("Error undoing change run: {:?}", err);
+            Err(error_status(&err))
         }
     }
 }
@@ -248,6 +365,8 @@ struct ImportCsvRequest {
 
 #[derive(Debug, Serialize)]
 struct ImportCsvResponse {
+    /// Groups the imported transactions in their change history
+    batch_id: Uuid,
     success: usize,
     failed: usize,
 }
@@ -256,6 +375,7 @@ struct ImportCsvResponse {
 async fn import_csv_transactions(
     Path(source_account_id): Path<Uuid>,
     State(state): State<Arc<TransactionRuleService>>,
+    Extension(auth): Extension<AuthUser>,
     Json(payload): Json<ImportCsvRequest>,
 ) -> Result<Json<ImportCsvResponse>, StatusCode> {
     // Validate required mappings
@@ -263,6 +383,7 @@ async fn import_csv_transactions(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    let batch_id = Uuid::new_v4();
     let mut success_count = 0;
     let mut failed_count = 0;
 
@@ -292,7 +413,7 @@ async fn import_csv_transactions(
         };
 
         // Clean and parse amount
-        let amount = match amount_str.trim().replace('$', "").replace(',', "").parse::<f64>() {
+        let amount = match amount_str.trim().replace(['$', ','], "").parse::<Decimal>() {
             Ok(val) => val,
             Err(_) => {
                 failed_count += 1;
@@ -355,10 +476,15 @@ async fn import_csv_transactions(
             category,
             budget_id,
             transaction_date,
+            splits: None,
+            status: None,
+            foreign_amount: None,
+            tags: None,
+            memo: None,
         };
 
         // Create the transaction
-        match state.create_transaction(transaction_request).await {
+        match state.create_transaction(auth.ledger_id, transaction_request, ChangeActor::import(batch_id)).await {
             Ok(_) => success_count += 1,
             Err(err) => {
                 eprintln!("Error creating transaction from CSV: {:?}", err);
@@ -369,6 +495,7 @@ async fn import_csv_transactions(
 
     // Return the import results
     Ok(Json(ImportCsvResponse {
+        batch_id,
         success: success_count,
         failed: failed_count,
     }))
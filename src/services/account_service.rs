@@ -1,7 +1,8 @@
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::models::{Account, CreateAccountRequest, UpdateAccountRequest};
+use rust_decimal::Decimal;
 
 /// Service for handling account-related operations
 pub struct AccountService {
@@ -14,23 +15,25 @@ impl AccountService {
         Self { db }
     }
 
-    /// Get all accounts, with default account first
-    pub async fn get_accounts(&self) -> Result<Vec<Account>, sqlx::Error> {
-        sqlx::query_as::<_, Account>("SELECT * FROM accounts ORDER BY is_default DESC, name")
+    /// Get all accounts in a ledger, with default account first
+    pub async fn get_accounts(&self, ledger_id: Uuid) -> Result<Vec<Account>, sqlx::Error> {
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE ledger_id = $1 ORDER BY is_default DESC, name")
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await
     }
 
     /// Get an account by ID
-    pub async fn get_account(&self, id: Uuid) -> Result<Option<Account>, sqlx::Error> {
-        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = $1")
+    pub async fn get_account(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Account>, sqlx::Error> {
+        sqlx::query_as::<_, Account>("SELECT * FROM accounts WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
             .await
     }
 
     /// Create a new account
-    pub async fn create_account(&self, req: CreateAccountRequest) -> Result<Account, sqlx::Error> {
+    pub async fn create_account(&self, ledger_id: Uuid, req: CreateAccountRequest) -> Result<Account, sqlx::Error> {
         let now = chrono::Utc::now();
         let account_id = Uuid::new_v4();
 
@@ -39,7 +42,8 @@ impl AccountService {
 
         // If this account should be default, unset others first to satisfy unique partial index
         if req.is_default {
-            sqlx::query("UPDATE accounts SET is_default = false WHERE is_default = true")
+            sqlx::query("UPDATE accounts SET is_default = false WHERE is_default = true AND ledger_id = $1")
+                .bind(ledger_id)
                 .execute(&mut *tx)
                 .await?;
         }
@@ -47,12 +51,13 @@ impl AccountService {
         // Create the account including is_default
         let account = sqlx::query_as::<_, Account>(
             r#"
-            INSERT INTO accounts (id, name, account_type, account_sub_type, balance, currency, is_default, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO accounts (id, ledger_id, name, account_type, account_sub_type, balance, currency, is_default, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING *
             "#,
         )
         .bind(account_id)
+        .bind(ledger_id)
         .bind(&req.name)
         .bind(&req.account_type)
         .bind(&req.account_sub_type)
@@ -64,19 +69,20 @@ impl AccountService {
         .fetch_one(&mut *tx)
         .await?;
 
-        // If the initial balance is not zero, create an 'Initial Balance' transaction
-        if req.balance != 0.0 {
+        // If the initial balance is not zero, create an 'Initial Balance' transaction (cleared, as it opens every statement)
+        if !req.balance.is_zero() {
             // Create an external account for the initial balance source/destination
             let external_account_id = Uuid::new_v4();
             sqlx::query(
                 r#"
-                INSERT INTO accounts (id, name, account_type, account_sub_type, balance, currency, created_at, updated_at)
-                VALUES ($1, $2, 'External', NULL, $3, $4, $5, $6)
+                INSERT INTO accounts (id, ledger_id, name, account_type, account_sub_type, balance, currency, created_at, updated_at)
+                VALUES ($1, $2, $3, 'External', NULL, $4, $5, $6, $7)
                 "#,
             )
             .bind(external_account_id)
+            .bind(ledger_id)
             .bind("Initial Balance")
-            .bind(0.0)
+            .bind(Decimal::ZERO)
             .bind(&req.currency)
             .bind(now)
             .bind(now)
@@ -84,7 +90,7 @@ impl AccountService {
             .await?;
 
             // Determine if this is an initial deposit (positive balance) or initial debt (negative balance)
-            let (source_id, destination_id, amount) = if req.balance > 0.0 {
+            let (source_id, destination_id, amount) = if req.balance > Decimal::ZERO {
                 // For positive balance, money comes from external account to the new account
                 (external_account_id, account_id, req.balance)
             } else {
@@ -95,11 +101,12 @@ impl AccountService {
             // Create the transaction
             sqlx::query(
                 r#"
-                INSERT INTO transactions (id, account_id, source_account_id, destination_account_id, destination_name, description, amount, category, transaction_date, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                INSERT INTO transactions (id, ledger_id, account_id, source_account_id, destination_account_id, destination_name, description, amount, category, transaction_date, created_at, updated_at, status)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, 'cleared')
                 "#,
             )
             .bind(Uuid::new_v4())
+            .bind(ledger_id)
             .bind(account_id)
             .bind(source_id)
             .bind(destination_id)
@@ -121,9 +128,9 @@ impl AccountService {
     }
 
     /// Update an existing account
-    pub async fn update_account(&self, id: Uuid, req: UpdateAccountRequest) -> Result<Option<Account>, sqlx::Error> {
+    pub async fn update_account(&self, ledger_id: Uuid, id: Uuid, req: UpdateAccountRequest) -> Result<Option<Account>, sqlx::Error> {
         // First, check if the account exists
-        let account = self.get_account(id).await?;
+        let account = self.get_account(ledger_id, id).await?;
 
         if account.is_none() {
             return Ok(None);
@@ -136,49 +143,46 @@ impl AccountService {
 
         // If setting this account as default, unset others first to satisfy unique partial index
         if let Some(true) = req.is_default {
-            sqlx::query("UPDATE accounts SET is_default = false WHERE is_default = true")
+            sqlx::query("UPDATE accounts SET is_default = false WHERE is_default = true AND ledger_id = $1")
+                .bind(ledger_id)
                 .execute(&mut *tx)
                 .await?;
         }
 
         // Build the update query dynamically based on which fields are provided
-        let mut query = String::from("UPDATE accounts SET updated_at = $1");
-        let mut params: Vec<String> = vec![];
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE accounts SET updated_at = ");
+        query.push_bind(now);
 
         if let Some(name) = &req.name {
-            params.push(format!("name = '{}'", name));
+            query.push(", name = ").push_bind(name.clone());
         }
 
         if let Some(account_type) = &req.account_type {
-            params.push(format!("account_type = '{}'", account_type));
+            query.push(", account_type = ").push_bind(account_type.clone());
         }
 
         if let Some(account_sub_type) = &req.account_sub_type {
-            params.push(format!("account_sub_type = '{}'", account_sub_type));
+            query.push(", account_sub_type = ").push_bind(account_sub_type.clone());
         }
 
         if let Some(balance) = req.balance {
-            params.push(format!("balance = {}", balance));
+            query.push(", balance = ").push_bind(balance);
         }
 
         if let Some(currency) = &req.currency {
-            params.push(format!("currency = '{}'", currency));
+            query.push(", currency = ").push_bind(currency.clone());
         }
 
         if let Some(is_default) = req.is_default {
-            params.push(format!("is_default = {}", if is_default { "true" } else { "false" }));
+            query.push(", is_default = ").push_bind(is_default);
         }
 
-        if !params.is_empty() {
-            query.push_str(", ");
-            query.push_str(&params.join(", "));
-        }
-
-        query.push_str(" WHERE id = $2 RETURNING *");
+        query.push(" WHERE id = ").push_bind(id);
+        query.push(" AND ledger_id = ").push_bind(ledger_id);
+        query.push(" RETURNING *");
 
-        let updated = sqlx::query_as::<_, Account>(&query)
-            .bind(now)
-            .bind(id)
+        let updated = query
+            .build_query_as::<Account>()
             .fetch_optional(&mut *tx)
             .await?;
 
@@ -188,9 +192,9 @@ impl AccountService {
     }
 
     /// Delete an account
-    pub async fn delete_account(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    pub async fn delete_account(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
         // First, check if the account exists
-        let account = self.get_account(id).await?;
+        let account = self.get_account(ledger_id, id).await?;
 
         if account.is_none() {
             println!("Account with id {} not found, cannot delete", id);
@@ -227,8 +231,9 @@ impl AccountService {
         let mut tx = self.db.begin().await?;
 
         // Delete the account
-        let result = sqlx::query("DELETE FROM accounts WHERE id = $1")
+        let result = sqlx::query("DELETE FROM accounts WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
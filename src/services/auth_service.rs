@@ -0,0 +1,297 @@
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::models::{AuthUser, CurrentUserResponse, Ledger, LoginRequest, LoginResponse, User};
+
+/// Minimum accepted password length
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Errors that can occur while registering or logging in
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("invalid username or password")]
+    InvalidCredentials,
+    #[error("registration is closed")]
+    RegistrationClosed,
+    #[error("username is already taken")]
+    UsernameTaken,
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("failed to hash password: {0}")]
+    PasswordHash(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Service for handling users, login sessions and ledger ownership
+pub struct AuthService {
+    db: Pool<Postgres>,
+    open_registration: bool,
+    session_ttl: Duration,
+    secure_cookies: bool,
+}
+
+impl AuthService {
+    /// Create a new AuthService with the given database pool.
+    /// Only the first user may register unless open registration is enabled.
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self {
+            db,
+            open_registration: false,
+            session_ttl: Duration::days(30),
+            secure_cookies: false,
+        }
+    }
+
+    /// Allow anyone who can reach the server to register once the first user exists
+    pub fn with_open_registration(mut self, open_registration: bool) -> Self {
+        self.open_registration = open_registration;
+        self
+    }
+
+    /// Set how long a login session stays valid
+    pub fn with_session_ttl(mut self, session_ttl: Duration) -> Self {
+        self.session_ttl = session_ttl;
+        self
+    }
+
+    /// Mark session cookies as `Secure` (only sent over HTTPS)
+    pub fn with_secure_cookies(mut self, secure_cookies: bool) -> Self {
+        self.secure_cookies = secure_cookies;
+        self
+    }
+
+    /// Whether session cookies should carry the `Secure` attribute
+    pub fn secure_cookies(&self) -> bool {
+        self.secure_cookies
+    }
+
+    /// Session lifetime in seconds, used for the cookie `Max-Age`
+    pub fn session_ttl_seconds(&self) -> i64 {
+        self.session_ttl.num_seconds()
+    }
+
+    /// Whether a new user may register right now
+    pub async fn registration_open(&self) -> Result<bool, sqlx::Error> {
+        if self.open_registration {
+            return Ok(true);
+        }
+
+        let users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+            .fetch_one(&self.db)
+            .await?;
+
+        Ok(users == 0)
+    }
+
+    /// Register a new user with their own ledger and start a session.
+    /// The first user to register takes over the books created before users existed.
+    pub async fn register(&self, req: LoginRequest) -> Result<LoginResponse, AuthError> {
+        let username = req.username.trim();
+        if username.is_empty() {
+            return Err(AuthError::InvalidInput("Username must not be empty".into()));
+        }
+        if req.password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(AuthError::InvalidInput(format!(
+                "Password must be at least {} characters long",
+                MIN_PASSWORD_LENGTH
+            )));
+        }
+
+        let password_hash = Argon2::default()
+            .hash_password(req.password.as_bytes(), &SaltString::generate(&mut OsRng))
+            .map_err(|e| AuthError::PasswordHash(e.to_string()))?
+            .to_string();
+
+        let now = Utc::now();
+        let mut tx = self.db.begin().await?;
+
+        // Serialize registrations so only one request can be "the first user"
+        sqlx::query("LOCK TABLE users IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+
+        let user_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+            .fetch_one(&mut *tx)
+            .await?;
+        if user_count > 0 && !self.open_registration {
+            return Err(AuthError::RegistrationClosed);
+        }
+
+        let taken = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM users WHERE lower(username) = lower($1))")
+            .bind(username)
+            .fetch_one(&mut *tx)
+            .await?;
+        if taken {
+            return Err(AuthError::UsernameTaken);
+        }
+
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (id, username, password_hash, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(username)
+        .bind(&password_hash)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Claim the unowned ledger if there is one, otherwise start empty books
+        let claimed = sqlx::query_as::<_, Ledger>(
+            r#"
+            UPDATE ledgers SET owner_id = $1, updated_at = $2
+            WHERE id = (SELECT id FROM ledgers WHERE owner_id IS NULL ORDER BY created_at LIMIT 1)
+            RETURNING *
+            "#,
+        )
+        .bind(user.id)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let ledger = match claimed {
+            Some(ledger) => {
+                info!("User '{}' claimed existing ledger {}", user.username, ledger.id);
+                ledger
+            }
+            None => {
+                sqlx::query_as::<_, Ledger>(
+                    r#"
+                    INSERT INTO ledgers (id, owner_id, name, created_at, updated_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    RETURNING *
+                    "#,
+                )
+                .bind(Uuid::new_v4())
+                .bind(user.id)
+                .bind(format!("{}'s ledger", user.username))
+                .bind(now)
+                .bind(now)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+        };
+
+        tx.commit().await?;
+
+        info!("Registered user '{}'", user.username);
+        self.start_session(user, ledger).await
+    }
+
+    /// Verify a username and password and start a session
+    pub async fn login(&self, req: LoginRequest) -> Result<LoginResponse, AuthError> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE lower(username) = lower($1)")
+            .bind(req.username.trim())
+            .fetch_optional(&self.db)
+            .await?
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        let parsed_hash = PasswordHash::new(&user.password_hash).map_err(|e| AuthError::PasswordHash(e.to_string()))?;
+        if Argon2::default().verify_password(req.password.as_bytes(), &parsed_hash).is_err() {
+            return Err(AuthError::InvalidCredentials);
+        }
+
+        let ledger = self.get_owned_ledger(user.id).await?.ok_or(AuthError::InvalidCredentials)?;
+
+        self.start_session(user, ledger).await
+    }
+
+    /// End the session identified by the given token
+    pub async fn logout(&self, token: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM user_sessions WHERE token_hash = $1")
+            .bind(Self::hash_token(token))
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Resolve a session token to the authenticated user and ledger
+    pub async fn authenticate(&self, token: &str) -> Result<Option<AuthUser>, sqlx::Error> {
+        let session = sqlx::query_as::<_, (Uuid, Uuid)>(
+            "SELECT user_id, ledger_id FROM user_sessions WHERE token_hash = $1 AND expires_at > $2",
+        )
+        .bind(Self::hash_token(token))
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(session.map(|(user_id, ledger_id)| AuthUser { user_id, ledger_id }))
+    }
+
+    /// Get the authenticated user together with the ledger they are working in
+    pub async fn get_current_user(&self, auth: AuthUser) -> Result<Option<CurrentUserResponse>, sqlx::Error> {
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(auth.user_id)
+            .fetch_optional(&self.db)
+            .await?;
+        let ledger = sqlx::query_as::<_, Ledger>("SELECT * FROM ledgers WHERE id = $1")
+            .bind(auth.ledger_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(user.zip(ledger).map(|(user, ledger)| CurrentUserResponse { user, ledger }))
+    }
+
+    /// Get the oldest ledger owned by a user
+    async fn get_owned_ledger(&self, user_id: Uuid) -> Result<Option<Ledger>, sqlx::Error> {
+        sqlx::query_as::<_, Ledger>("SELECT * FROM ledgers WHERE owner_id = $1 ORDER BY created_at LIMIT 1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// Create a session for the user in the given ledger; only the token hash is stored
+    async fn start_session(&self, user: User, ledger: Ledger) -> Result<LoginResponse, AuthError> {
+        let now = Utc::now();
+        let expires_at = now + self.session_ttl;
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+
+        // Drop expired sessions while we are here
+        sqlx::query("DELETE FROM user_sessions WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.db)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_sessions (id, user_id, ledger_id, token_hash, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user.id)
+        .bind(ledger.id)
+        .bind(Self::hash_token(&token))
+        .bind(now)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+
+        Ok(LoginResponse {
+            user,
+            ledger,
+            token,
+            expires_at,
+        })
+    }
+
+    /// Hex-encoded SHA-256 of a session token
+    fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+}
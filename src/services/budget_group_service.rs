@@ -1,45 +1,97 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
-use crate::models::{BudgetGroup, CreateBudgetGroupRequest, UpdateBudgetGroupRequest};
+use crate::models::{Budget, BudgetGroup, BudgetGroupGoals, CreateBudgetGroupRequest, UpdateBudgetGroupRequest};
+use crate::services::BudgetService;
 
 /// Service for handling budget group-related operations
 pub struct BudgetGroupService {
     db: Pool<Postgres>,
+    budget_service: Option<Arc<BudgetService>>,
 }
 
 impl BudgetGroupService {
     /// Create a new BudgetGroupService with the given database pool
     pub fn new(db: Pool<Postgres>) -> Self {
-        Self { db }
+        Self { db, budget_service: None }
+    }
+
+    /// Set the budget service, which computes the goal progress of budgets
+    pub fn with_budget_service(mut self, budget_service: Arc<BudgetService>) -> Self {
+        self.budget_service = Some(budget_service);
+        self
     }
 
-    /// Get all budget groups
-    pub async fn get_budget_groups(&self) -> Result<Vec<BudgetGroup>, sqlx::Error> {
-        sqlx::query_as::<_, BudgetGroup>("SELECT * FROM budget_groups ORDER BY name")
+    /// Add the current month's goal progress to budgets when a budget service is set
+    async fn with_goal_progress(&self, ledger_id: Uuid, budgets: Vec<Budget>) -> Result<Vec<Budget>, sqlx::Error> {
+        match &self.budget_service {
+            Some(budget_service) => budget_service.with_goal_progress(ledger_id, budgets).await,
+            None => Ok(budgets),
+        }
+    }
+
+    /// Sum up the goal progress of the budgets in each group
+    async fn with_goals(&self, ledger_id: Uuid, mut groups: Vec<BudgetGroup>) -> Result<Vec<BudgetGroup>, sqlx::Error> {
+        let budgets = sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE ledger_id = $1 AND group_id IS NOT NULL AND goal_type IS NOT NULL")
+            .bind(ledger_id)
             .fetch_all(&self.db)
-            .await
+            .await?;
+        let budgets = self.with_goal_progress(ledger_id, budgets).await?;
+
+        let mut goals: HashMap<Uuid, BudgetGroupGoals> = HashMap::new();
+        for budget in &budgets {
+            let (Some(group_id), Some(progress)) = (budget.group_id, &budget.goal_progress) else {
+                continue;
+            };
+            let group_goals = goals.entry(group_id).or_default();
+            group_goals.budgets_with_goals += 1;
+            group_goals.underfunded_budgets += usize::from(progress.underfunded);
+            group_goals.monthly_needed += progress.monthly_needed;
+            group_goals.still_needed += progress.still_needed;
+        }
+        for group in groups.iter_mut() {
+            group.goals = Some(goals.remove(&group.id).unwrap_or_default());
+        }
+        Ok(groups)
+    }
+
+    /// Get all budget groups with the goal progress of their budgets
+    pub async fn get_budget_groups(&self, ledger_id: Uuid) -> Result<Vec<BudgetGroup>, sqlx::Error> {
+        let groups = sqlx::query_as::<_, BudgetGroup>("SELECT * FROM budget_groups WHERE ledger_id = $1 ORDER BY name")
+            .bind(ledger_id)
+            .fetch_all(&self.db)
+            .await?;
+        self.with_goals(ledger_id, groups).await
     }
 
-    /// Get a budget group by ID
-    pub async fn get_budget_group(&self, id: Uuid) -> Result<Option<BudgetGroup>, sqlx::Error> {
-        sqlx::query_as::<_, BudgetGroup>("SELECT * FROM budget_groups WHERE id = $1")
+    /// Get a budget group by ID with the goal progress of its budgets
+    pub async fn get_budget_group(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<BudgetGroup>, sqlx::Error> {
+        let group = sqlx::query_as::<_, BudgetGroup>("SELECT * FROM budget_groups WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
-            .await
+            .await?;
+        match group {
+            Some(group) => Ok(self.with_goals(ledger_id, vec![group]).await?.pop()),
+            None => Ok(None),
+        }
     }
 
     /// Create a new budget group
-    pub async fn create_budget_group(&self, req: CreateBudgetGroupRequest) -> Result<BudgetGroup, sqlx::Error> {
+    pub async fn create_budget_group(&self, ledger_id: Uuid, req: CreateBudgetGroupRequest) -> Result<BudgetGroup, sqlx::Error> {
         let now = chrono::Utc::now();
         sqlx::query_as::<_, BudgetGroup>(
             r#"
-            INSERT INTO budget_groups (id, name, description, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO budget_groups (id, ledger_id, name, description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
             RETURNING *
             "#,
         )
         .bind(Uuid::new_v4())
+        .bind(ledger_id)
         .bind(&req.name)
         .bind(&req.description)
         .bind(now)
@@ -49,9 +101,9 @@ impl BudgetGroupService {
     }
 
     /// Update an existing budget group
-    pub async fn update_budget_group(&self, id: Uuid, req: UpdateBudgetGroupRequest) -> Result<Option<BudgetGroup>, sqlx::Error> {
+    pub async fn update_budget_group(&self, ledger_id: Uuid, id: Uuid, req: UpdateBudgetGroupRequest) -> Result<Option<BudgetGroup>, sqlx::Error> {
         // First, check if the budget group exists
-        let group = self.get_budget_group(id).await?;
+        let group = self.get_budget_group(ledger_id, id).await?;
         if group.is_none() {
             return Ok(None);
         }
@@ -65,7 +117,7 @@ impl BudgetGroupService {
                 name = COALESCE($1, name),
                 description = COALESCE($2, description),
                 updated_at = $3
-            WHERE id = $4
+            WHERE id = $4 AND ledger_id = $5
             RETURNING *
             "#,
         )
@@ -73,6 +125,7 @@ impl BudgetGroupService {
         .bind(req.description)
         .bind(now)
         .bind(id)
+        .bind(ledger_id)
         .fetch_optional(&self.db)
         .await?;
 
@@ -80,17 +133,19 @@ impl BudgetGroupService {
     }
 
     /// Delete a budget group
-    pub async fn delete_budget_group(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    pub async fn delete_budget_group(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
         // Use a transaction: clear budgets.group_id then delete group
         let mut tx = self.db.begin().await?;
 
-        sqlx::query("UPDATE budgets SET group_id = NULL WHERE group_id = $1")
+        sqlx::query("UPDATE budgets SET group_id = NULL WHERE group_id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
-        let result = sqlx::query("DELETE FROM budget_groups WHERE id = $1")
+        let result = sqlx::query("DELETE FROM budget_groups WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
@@ -98,11 +153,13 @@ impl BudgetGroupService {
         Ok(result.rows_affected() > 0)
     }
 
-    /// Get all budgets in a specific group
-    pub async fn get_budgets_by_group(&self, group_id: Uuid) -> Result<Vec<crate::models::Budget>, sqlx::Error> {
-        sqlx::query_as::<_, crate::models::Budget>("SELECT * FROM budgets WHERE group_id = $1 ORDER BY name")
+    /// Get all budgets in a specific group, with their goal progress
+    pub async fn get_budgets_by_group(&self, ledger_id: Uuid, group_id: Uuid) -> Result<Vec<Budget>, sqlx::Error> {
+        let budgets = sqlx::query_as::<_, Budget>("SELECT * FROM budgets WHERE group_id = $1 AND ledger_id = $2 ORDER BY name")
             .bind(group_id)
+            .bind(ledger_id)
             .fetch_all(&self.db)
-            .await
+            .await?;
+        self.with_goal_progress(ledger_id, budgets).await
     }
 }
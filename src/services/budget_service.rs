@@ -1,10 +1,103 @@
-use chrono::{Utc, Datelike};
+use chrono::{DateTime, Utc, Datelike, Months, NaiveDate};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{debug, info};
-use crate::models::{Budget, CreateBudgetRequest, UpdateBudgetRequest, Transaction};
+use crate::models::{parse_budget_month, Budget, BudgetAllocation, BudgetGoalProgress, CopiedBudgetMonth, CopyBudgetMonthRequest, BudgetMonth, BudgetMonthLine, BudgetTransfer, BudgetTransferQuery, CreateBudgetTransferRequest, ReadyToAssign, CreateBudgetRequest, UpdateBudgetRequest, Transaction, DEFAULT_BASE_CURRENCY, GOAL_TYPE_MONTHLY_FUNDING, GOAL_TYPE_SPENDING_CAP, GOAL_TYPE_TARGET_BALANCE, AMOUNT_SOURCE_LAST_MONTH_SPENDING, AMOUNT_SOURCE_SOURCE_MONTH, AMOUNT_SOURCE_THREE_MONTH_AVERAGE};
 use crate::services::SettingsService;
+use crate::services::exchange_rate_service::converted_amount_sql;
+use crate::services::transaction_service::TRANSACTION_LINES_SQL;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Amounts per budget and month (keyed by the first day of the month)
+type MonthlyAmounts = HashMap<Uuid, BTreeMap<NaiveDate, Decimal>>;
+
+/// First day of the month of a date
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap()
+}
+
+/// Start of a month as a timestamp, to compare with transaction dates
+fn month_start(month: NaiveDate) -> DateTime<Utc> {
+    DateTime::<Utc>::from_naive_utc_and_offset(month.and_hms_opt(0, 0, 0).unwrap(), Utc)
+}
+
+/// A budget's line in a month, from its amounts per month up to that month
+fn month_line(
+    budget: &Budget,
+    month: NaiveDate,
+    assigned: &BTreeMap<NaiveDate, Decimal>,
+    transferred: &BTreeMap<NaiveDate, Decimal>,
+    activity: &BTreeMap<NaiveDate, Decimal>,
+) -> BudgetMonthLine {
+    let before = |amounts: &BTreeMap<NaiveDate, Decimal>| amounts.range(..month).map(|(_, amount)| *amount).sum::<Decimal>();
+    let in_month = |amounts: &BTreeMap<NaiveDate, Decimal>| amounts.get(&month).copied().unwrap_or_default();
+    let carried_over = before(assigned) + before(transferred) - before(activity);
+    BudgetMonthLine {
+        budget_id: budget.id,
+        name: budget.name.clone(),
+        group_id: budget.group_id,
+        carried_over,
+        assigned: in_month(assigned),
+        transferred: in_month(transferred),
+        activity: in_month(activity),
+        available: carried_over + in_month(assigned) + in_month(transferred) - in_month(activity),
+    }
+}
+
+/// Check that a goal has a known type, a positive amount and, for a target balance, a target date
+fn validate_goal(goal_type: Option<&str>, goal_amount: Option<Decimal>, goal_target_date: Option<NaiveDate>) -> Result<(), sqlx::Error> {
+    let Some(goal_type) = goal_type else {
+        return Ok(());
+    };
+    if ![GOAL_TYPE_MONTHLY_FUNDING, GOAL_TYPE_TARGET_BALANCE, GOAL_TYPE_SPENDING_CAP].contains(&goal_type) {
+        return Err(sqlx::Error::Protocol(format!("Unknown goal type: {}", goal_type)));
+    }
+    if goal_amount.is_none_or(|amount| amount <= Decimal::ZERO) {
+        return Err(sqlx::Error::Protocol("Goal amount must be positive".to_string()));
+    }
+    if goal_type == GOAL_TYPE_TARGET_BALANCE && goal_target_date.is_none() {
+        return Err(sqlx::Error::Protocol("A target balance goal needs a target date".to_string()));
+    }
+    Ok(())
+}
+
+/// Progress of a budget towards its goal, from its line in a month
+fn goal_progress(budget: &Budget, line: &BudgetMonthLine, month: NaiveDate) -> Option<BudgetGoalProgress> {
+    let goal_type = budget.goal_type.as_deref()?;
+    let goal_amount = budget.goal_amount.filter(|amount| *amount > Decimal::ZERO)?;
+    let funded = line.assigned + line.transferred;
+    let share = |amount: Decimal| (amount / goal_amount).clamp(Decimal::ZERO, Decimal::ONE);
+
+    let (progress, monthly_needed) = match goal_type {
+        GOAL_TYPE_MONTHLY_FUNDING => (share(funded), goal_amount),
+        GOAL_TYPE_TARGET_BALANCE => {
+            // Spread what is missing at the start of the month over the months left, this one included
+            let target_month = first_of_month(budget.goal_target_date?);
+            let months_left = (target_month.year() - month.year()) * 12 + target_month.month() as i32 - month.month() as i32 + 1;
+            let missing = (goal_amount - line.carried_over).max(Decimal::ZERO);
+            let monthly_needed = (missing / Decimal::from(months_left.max(1)))
+                .round_dp_with_strategy(2, RoundingStrategy::AwayFromZero);
+            (share(line.available), monthly_needed)
+        }
+        GOAL_TYPE_SPENDING_CAP => ((line.activity / goal_amount).max(Decimal::ZERO), Decimal::ZERO),
+        _ => return None,
+    };
+    let still_needed = (monthly_needed - funded).max(Decimal::ZERO);
+
+    Some(BudgetGoalProgress {
+        month: month.format("%Y-%m").to_string(),
+        funded,
+        spent: line.activity,
+        available: line.available,
+        progress: progress.round_dp(4),
+        monthly_needed,
+        still_needed,
+        underfunded: still_needed > Decimal::ZERO,
+        over_cap: goal_type == GOAL_TYPE_SPENDING_CAP && line.activity > goal_amount,
+    })
+}
 
 pub struct BudgetService {
     db: Pool<Postgres>,
@@ -13,9 +106,9 @@ pub struct BudgetService {
 
 impl BudgetService {
     /// Get transactions assigned to the given budget within the month of the budget's start_date
-    pub async fn get_budget_transactions_for_month(&self, budget_id: Uuid) -> Result<Vec<Transaction>, sqlx::Error> {
+    pub async fn get_budget_transactions_for_month(&self, ledger_id: Uuid, budget_id: Uuid) -> Result<Vec<Transaction>, sqlx::Error> {
         // Load the budget to determine its linked month (based on start_date)
-        let budget = self.get_budget(budget_id).await?;
+        let budget = self.get_budget(ledger_id, budget_id).await?;
         let budget = match budget {
             Some(b) => b,
             None => return Ok(vec![]),
@@ -39,19 +132,22 @@ impl BudgetService {
             .unwrap();
         let end_date = chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(end_naive, chrono::Utc);
 
-        // Query transactions with this budget_id within the month
-        let transactions = sqlx::query_as::<_, Transaction>(
+        // Query transactions with this budget_id (on the transaction or on one of its split lines) within the month
+        let transactions = sqlx::query_as::<_, Transaction>(&format!(
             r#"
             SELECT * FROM transactions
-            WHERE budget_id = $1
+            WHERE id IN (SELECT l.id FROM ({}) l WHERE l.budget_id = $1)
               AND transaction_date >= $2
               AND transaction_date < $3
+              AND ledger_id = $4
             ORDER BY transaction_date DESC
             "#,
-        )
+            TRANSACTION_LINES_SQL
+        ))
         .bind(budget_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(ledger_id)
         .fetch_all(&self.db)
         .await?;
 
@@ -70,14 +166,24 @@ impl BudgetService {
         self
     }
 
-    /// Get all budgets
-    pub async fn get_budgets(&self) -> Result<Vec<Budget>, sqlx::Error> {
+    /// Get the currency budget amounts are expressed in, which spending is converted into
+    async fn base_currency(&self, ledger_id: Uuid) -> Result<String, sqlx::Error> {
+        match &self.settings_service {
+            Some(settings_service) => settings_service.get_base_currency(ledger_id).await,
+            None => Ok(DEFAULT_BASE_CURRENCY.to_string()),
+        }
+    }
+
+    /// Get all budgets in a ledger
+    pub async fn get_budgets(&self, ledger_id: Uuid) -> Result<Vec<Budget>, sqlx::Error> {
         let budgets = sqlx::query_as::<_, Budget>(
             r#"
             SELECT * FROM budgets
+            WHERE ledger_id = $1
             ORDER BY name ASC
             "#,
         )
+        .bind(ledger_id)
         .fetch_all(&self.db)
         .await?;
 
@@ -85,16 +191,18 @@ impl BudgetService {
     }
 
     /// Get active budgets (current date is between start_date and end_date, or end_date is null)
-    pub async fn get_active_budgets(&self) -> Result<Vec<Budget>, sqlx::Error> {
+    pub async fn get_active_budgets(&self, ledger_id: Uuid) -> Result<Vec<Budget>, sqlx::Error> {
         let now = Utc::now();
         let budgets = sqlx::query_as::<_, Budget>(
             r#"
             SELECT * FROM budgets
             WHERE start_date <= $1 AND (end_date IS NULL OR end_date >= $1)
+              AND ledger_id = $2
             ORDER BY name ASC
             "#,
         )
         .bind(now)
+        .bind(ledger_id)
         .fetch_all(&self.db)
         .await?;
 
@@ -102,14 +210,15 @@ impl BudgetService {
     }
 
     /// Get a budget by ID
-    pub async fn get_budget(&self, id: Uuid) -> Result<Option<Budget>, sqlx::Error> {
+    pub async fn get_budget(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Budget>, sqlx::Error> {
         let budget = sqlx::query_as::<_, Budget>(
             r#"
             SELECT * FROM budgets
-            WHERE id = $1
+            WHERE id = $1 AND ledger_id = $2
             "#,
         )
         .bind(id)
+        .bind(ledger_id)
         .fetch_optional(&self.db)
         .await?;
 
@@ -117,29 +226,43 @@ impl BudgetService {
     }
 
     /// Create a new budget
-    pub async fn create_budget(&self, req: CreateBudgetRequest) -> Result<Budget, sqlx::Error> {
+    pub async fn create_budget(&self, ledger_id: Uuid, req: CreateBudgetRequest) -> Result<Budget, sqlx::Error> {
+        Self::insert_budget(&self.db, ledger_id, req).await
+    }
+
+    /// Validate and insert a budget with the given executor, so several can share a database transaction
+    async fn insert_budget<'e, E>(executor: E, ledger_id: Uuid, req: CreateBudgetRequest) -> Result<Budget, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
         let id = Uuid::new_v4();
         let now = Utc::now();
         let start_date = req.start_date;
         let end_date = req.end_date;
+        validate_goal(req.goal_type.as_deref(), req.goal_amount, req.goal_target_date)?;
 
         let budget = sqlx::query_as::<_, Budget>(
             r#"
-            INSERT INTO budgets (id, name, description, amount, start_date, end_date, group_id, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO budgets (id, ledger_id, name, description, amount, start_date, end_date, group_id,
+                                 goal_type, goal_amount, goal_target_date, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING *
             "#,
         )
         .bind(id)
+        .bind(ledger_id)
         .bind(req.name)
         .bind(req.description)
         .bind(req.amount)
         .bind(start_date)
         .bind(end_date)
         .bind(req.group_id)
+        .bind(req.goal_type)
+        .bind(req.goal_amount)
+        .bind(req.goal_target_date)
         .bind(now)
         .bind(now)
-        .fetch_one(&self.db)
+        .fetch_one(executor)
         .await?;
 
         Ok(budget)
@@ -148,16 +271,27 @@ impl BudgetService {
     /// Update an existing budget
     pub async fn update_budget(
         &self,
+        ledger_id: Uuid,
         id: Uuid,
         req: UpdateBudgetRequest,
     ) -> Result<Option<Budget>, sqlx::Error> {
         let now = Utc::now();
 
         // First check if the budget exists
-        let budget = self.get_budget(id).await?;
-        if budget.is_none() {
+        let Some(budget) = self.get_budget(ledger_id, id).await? else {
             return Ok(None);
-        }
+        };
+
+        // Goal fields left out keep their value, an empty goal type removes the goal
+        let (goal_type, goal_amount, goal_target_date) = match req.goal_type.as_deref() {
+            Some("") => (None, None, None),
+            _ => (
+                req.goal_type.or(budget.goal_type),
+                req.goal_amount.or(budget.goal_amount),
+                req.goal_target_date.or(budget.goal_target_date),
+            ),
+        };
+        validate_goal(goal_type.as_deref(), goal_amount, goal_target_date)?;
 
         // Update the budget
         let updated_budget = sqlx::query_as::<_, Budget>(
@@ -170,8 +304,11 @@ impl BudgetService {
                 start_date = COALESCE($4, start_date),
                 end_date = $5,
                 group_id = $6,
-                updated_at = $7
-            WHERE id = $8
+                goal_type = $7,
+                goal_amount = $8,
+                goal_target_date = $9,
+                updated_at = $10
+            WHERE id = $11 AND ledger_id = $12
             RETURNING *
             "#,
         )
@@ -181,8 +318,12 @@ impl BudgetService {
         .bind(req.start_date)
         .bind(req.end_date) // We allow setting end_date to NULL
         .bind(req.group_id)
+        .bind(goal_type)
+        .bind(goal_amount)
+        .bind(goal_target_date)
         .bind(now)
         .bind(id)
+        .bind(ledger_id)
         .fetch_one(&self.db)
         .await?;
 
@@ -190,31 +331,39 @@ impl BudgetService {
     }
 
     /// Delete a budget
-    pub async fn delete_budget(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    pub async fn delete_budget(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
         let result = sqlx::query(
             r#"
             DELETE FROM budgets
-            WHERE id = $1
+            WHERE id = $1 AND ledger_id = $2
             "#,
         )
         .bind(id)
+        .bind(ledger_id)
         .execute(&self.db)
         .await?;
 
         Ok(result.rows_affected() > 0)
     }
 
-    /// Get the total spent amount for a budget (all time)
-    pub async fn get_budget_spent(&self, budget_id: Uuid) -> Result<f64, sqlx::Error> {
-        let spent = sqlx::query_scalar::<_, f64>(
+    /// Get the total spent amount for a budget (all time), counting split lines individually.
+    /// Spending from accounts in other currencies is converted into the base currency.
+    pub async fn get_budget_spent(&self, ledger_id: Uuid, budget_id: Uuid) -> Result<Decimal, sqlx::Error> {
+        let spent = sqlx::query_scalar::<_, Decimal>(&format!(
             r#"
-            SELECT COALESCE(SUM(amount), 0.0)
-            FROM transactions
-            WHERE budget_id = $1
-              AND amount > 0
+            SELECT COALESCE(SUM({}), 0.0)
+            FROM ({}) t
+            LEFT JOIN accounts src ON src.id = t.source_account_id
+            WHERE t.budget_id = $1
+              AND t.amount > 0
+              AND t.ledger_id = $2
             "#,
-        )
+            converted_amount_sql("t.amount", "src.currency", "$3", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
+        ))
         .bind(budget_id)
+        .bind(ledger_id)
+        .bind(self.base_currency(ledger_id).await?)
         .fetch_one(&self.db)
         .await?;
 
@@ -222,7 +371,7 @@ impl BudgetService {
     }
 
     /// Get the total spent amount for a budget for a specific month
-    pub async fn get_budget_spent_for_month(&self, budget_id: Uuid, year: i32, month: u32) -> Result<f64, sqlx::Error> {
+    pub async fn get_budget_spent_for_month(&self, ledger_id: Uuid, budget_id: Uuid, year: i32, month: u32) -> Result<Decimal, sqlx::Error> {
         // Calculate the start and end dates for the specified month
         let start_date = chrono::NaiveDate::from_ymd_opt(year, month, 1)
             .unwrap()
@@ -241,38 +390,490 @@ impl BudgetService {
         .unwrap();
         let end_date = chrono::DateTime::<Utc>::from_naive_utc_and_offset(end_date, Utc);
 
-        let spent = sqlx::query_scalar::<_, f64>(
+        // Split transactions contribute each split line to its own budget; amounts are converted into the base currency
+        let spent = sqlx::query_scalar::<_, Decimal>(&format!(
             r#"
-            SELECT COALESCE(SUM(amount), 0.0)
-            FROM transactions
-            WHERE budget_id = $1
-              AND amount > 0
-              AND transaction_date >= $2
-              AND transaction_date < $3
+            SELECT COALESCE(SUM({}), 0.0)
+            FROM ({}) t
+            LEFT JOIN accounts src ON src.id = t.source_account_id
+            WHERE t.budget_id = $1
+              AND t.amount > 0
+              AND t.transaction_date >= $2
+              AND t.transaction_date < $3
+              AND t.ledger_id = $4
             "#,
-        )
+            converted_amount_sql("t.amount", "src.currency", "$5", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
+        ))
         .bind(budget_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(ledger_id)
+        .bind(self.base_currency(ledger_id).await?)
         .fetch_one(&self.db)
         .await?;
 
         Ok(spent)
     }
 
-    /// Get the remaining amount for a budget
-    pub async fn get_budget_remaining(&self, budget_id: Uuid) -> Result<f64, sqlx::Error> {
-        let budget = self.get_budget(budget_id).await?;
+    /// Get the amount available in a budget this month (or in its first month if it starts later),
+    /// including what was carried over from earlier months
+    pub async fn get_budget_remaining(&self, ledger_id: Uuid, budget_id: Uuid) -> Result<Decimal, sqlx::Error> {
+        let budget = self.get_budget(ledger_id, budget_id).await?;
         if let Some(budget) = budget {
-            let spent = self.get_budget_spent(budget_id).await?;
-            Ok(budget.amount - spent)
+            let month = first_of_month(Utc::now().date_naive()).max(first_of_month(budget.start_date.date_naive()));
+            let line = self
+                .budget_month_lines(ledger_id, month)
+                .await?
+                .into_iter()
+                .find(|line| line.budget_id == budget_id);
+            Ok(line.map_or(Decimal::ZERO, |line| line.available))
         } else {
-            Ok(0.0)
+            Ok(Decimal::ZERO)
+        }
+    }
+
+    /// Get the amounts assigned to a budget per month, oldest first
+    pub async fn get_budget_allocations(&self, ledger_id: Uuid, budget_id: Uuid) -> Result<Vec<BudgetAllocation>, sqlx::Error> {
+        sqlx::query_as::<_, BudgetAllocation>(
+            "SELECT * FROM budget_allocations WHERE budget_id = $1 AND ledger_id = $2 ORDER BY month",
+        )
+        .bind(budget_id)
+        .bind(ledger_id)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Assign an amount to a budget for a month, replacing what was assigned before.
+    /// Returns `None` when the budget does not exist.
+    pub async fn set_budget_allocation(
+        &self,
+        ledger_id: Uuid,
+        budget_id: Uuid,
+        month: NaiveDate,
+        amount: Decimal,
+    ) -> Result<Option<BudgetAllocation>, sqlx::Error> {
+        if self.get_budget(ledger_id, budget_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let allocation = sqlx::query_as::<_, BudgetAllocation>(
+            r#"
+            INSERT INTO budget_allocations (id, ledger_id, budget_id, month, amount, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            ON CONFLICT (budget_id, month) DO UPDATE SET amount = EXCLUDED.amount, updated_at = EXCLUDED.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ledger_id)
+        .bind(budget_id)
+        .bind(first_of_month(month))
+        .bind(amount)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(Some(allocation))
+    }
+
+    /// Get the envelope view of a month: what every budget carried over from the previous month,
+    /// was assigned, spent and has available. Unspent and overspent amounts both carry over.
+    pub async fn get_budget_month(&self, ledger_id: Uuid, month: NaiveDate) -> Result<BudgetMonth, sqlx::Error> {
+        let month = first_of_month(month);
+        let budgets = self.budget_month_lines(ledger_id, month).await?;
+        Ok(BudgetMonth {
+            month: month.format("%Y-%m").to_string(),
+            assigned: budgets.iter().map(|line| line.assigned).sum(),
+            activity: budgets.iter().map(|line| line.activity).sum(),
+            available: budgets.iter().map(|line| line.available).sum(),
+            budgets,
+        })
+    }
+
+    /// Add the current month's goal progress to the budgets that have a goal
+    pub async fn with_goal_progress(&self, ledger_id: Uuid, mut budgets: Vec<Budget>) -> Result<Vec<Budget>, sqlx::Error> {
+        if budgets.iter().all(|budget| budget.goal_type.is_none()) {
+            return Ok(budgets);
+        }
+
+        let month = first_of_month(Utc::now().date_naive());
+        let next_month = month + Months::new(1);
+        let assigned = self.assigned_by_month(ledger_id, &budgets, next_month).await?;
+        let transferred = self.transferred_by_month(ledger_id, next_month).await?;
+        let activity = self.activity_by_month(ledger_id, next_month).await?;
+
+        let none = BTreeMap::new();
+        for budget in budgets.iter_mut().filter(|budget| budget.goal_type.is_some()) {
+            let line = month_line(
+                budget,
+                month,
+                assigned.get(&budget.id).unwrap_or(&none),
+                transferred.get(&budget.id).unwrap_or(&none),
+                activity.get(&budget.id).unwrap_or(&none),
+            );
+            budget.goal_progress = goal_progress(budget, &line, month);
+        }
+        Ok(budgets)
+    }
+
+    /// Move money from one budget to another in a month. The amount must be positive and both
+    /// budgets must exist and differ.
+    pub async fn create_budget_transfer(&self, ledger_id: Uuid, req: CreateBudgetTransferRequest) -> Result<BudgetTransfer, sqlx::Error> {
+        let month = parse_budget_month(&req.month)
+            .ok_or_else(|| sqlx::Error::Protocol(format!("Invalid month {}, expected YYYY-MM", req.month)))?;
+        if req.amount <= Decimal::ZERO {
+            return Err(sqlx::Error::Protocol("Transfer amount must be positive".into()));
+        }
+        if req.from_budget_id == req.to_budget_id {
+            return Err(sqlx::Error::Protocol("Cannot transfer money to the same budget".into()));
+        }
+        for budget_id in [req.from_budget_id, req.to_budget_id] {
+            if self.get_budget(ledger_id, budget_id).await?.is_none() {
+                return Err(sqlx::Error::Protocol(format!("Budget {} not found", budget_id)));
+            }
         }
+
+        let transfer = sqlx::query_as::<_, BudgetTransfer>(
+            r#"
+            INSERT INTO budget_transfers (id, ledger_id, from_budget_id, to_budget_id, month, amount, note, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ledger_id)
+        .bind(req.from_budget_id)
+        .bind(req.to_budget_id)
+        .bind(month)
+        .bind(req.amount)
+        .bind(req.note.filter(|note| !note.trim().is_empty()))
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(transfer)
+    }
+
+    /// Get the history of money moved between budgets, newest month first
+    pub async fn get_budget_transfers(&self, ledger_id: Uuid, query: &BudgetTransferQuery) -> Result<Vec<BudgetTransfer>, sqlx::Error> {
+        let month = match &query.month {
+            Some(month) => Some(
+                parse_budget_month(month).ok_or_else(|| sqlx::Error::Protocol(format!("Invalid month {}, expected YYYY-MM", month)))?,
+            ),
+            None => None,
+        };
+
+        sqlx::query_as::<_, BudgetTransfer>(
+            r#"
+            SELECT * FROM budget_transfers
+            WHERE ledger_id = $1
+              AND ($2::uuid IS NULL OR from_budget_id = $2 OR to_budget_id = $2)
+              AND ($3::date IS NULL OR month = $3)
+            ORDER BY month DESC, created_at DESC, id
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(query.budget_id)
+        .bind(month)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Create a month's budgets from the budgets starting in a source month. Amounts come from the
+    /// source month, last month's spending or the average spending of the three months before,
+    /// per budget group. Spending is matched by budget name, as every month has its own budgets.
+    /// Budgets whose name the month already has are skipped, so copying again adds nothing.
+    pub async fn copy_budget_month(
+        &self,
+        ledger_id: Uuid,
+        month: NaiveDate,
+        req: CopyBudgetMonthRequest,
+    ) -> Result<CopiedBudgetMonth, sqlx::Error> {
+        let month = first_of_month(month);
+        let source_month = match req.source_month.as_deref() {
+            Some(value) => parse_budget_month(value)
+                .ok_or_else(|| sqlx::Error::Protocol(format!("Invalid source month: {}", value)))?,
+            None => month - Months::new(1),
+        };
+        if source_month == month {
+            return Err(sqlx::Error::Protocol("The source month must differ from the new month".to_string()));
+        }
+        let amount_sources = [AMOUNT_SOURCE_SOURCE_MONTH, AMOUNT_SOURCE_LAST_MONTH_SPENDING, AMOUNT_SOURCE_THREE_MONTH_AVERAGE];
+        let default_source = req.amount_source.as_deref().unwrap_or(AMOUNT_SOURCE_SOURCE_MONTH);
+        for source in std::iter::once(default_source).chain(req.group_amount_sources.values().map(String::as_str)) {
+            if !amount_sources.contains(&source) {
+                return Err(sqlx::Error::Protocol(format!("Unknown amount source: {}", source)));
+            }
+        }
+
+        let budgets = self.get_budgets(ledger_id).await?;
+        let starts_in = |budget: &Budget, month: NaiveDate| first_of_month(budget.start_date.date_naive()) == month;
+        let assigned = self.assigned_by_month(ledger_id, &budgets, source_month + Months::new(1)).await?;
+
+        // Spending per budget name and month, up to the new month
+        let mut spent_by_name: HashMap<String, BTreeMap<NaiveDate, Decimal>> = HashMap::new();
+        let activity = self.activity_by_month(ledger_id, month).await?;
+        for budget in &budgets {
+            for (spent_month, amount) in activity.get(&budget.id).into_iter().flatten() {
+                *spent_by_name.entry(budget.name.to_lowercase()).or_default().entry(*spent_month).or_default() += *amount;
+            }
+        }
+        let spent = |name: &str, months: u32| {
+            spent_by_name
+                .get(name)
+                .map(|amounts| amounts.range(month - Months::new(months)..month).map(|(_, amount)| *amount).sum())
+                .unwrap_or(Decimal::ZERO)
+        };
+
+        let mut existing: HashSet<String> = budgets
+            .iter()
+            .filter(|budget| starts_in(budget, month))
+            .map(|budget| budget.name.to_lowercase())
+            .collect();
+        let end_of_month = month_start(month + Months::new(1)) - chrono::Duration::seconds(1);
+
+        // All budgets of the month are created or none are
+        let mut tx = self.db.begin().await?;
+        let mut created = Vec::new();
+        let mut skipped = Vec::new();
+        for budget in budgets.iter().filter(|budget| starts_in(budget, source_month)) {
+            let name = budget.name.to_lowercase();
+            if !existing.insert(name.clone()) {
+                skipped.push(budget.name.clone());
+                continue;
+            }
+
+            let source = budget
+                .group_id
+                .and_then(|group_id| req.group_amount_sources.get(&group_id))
+                .map(String::as_str)
+                .unwrap_or(default_source);
+            let amount = match source {
+                AMOUNT_SOURCE_LAST_MONTH_SPENDING => spent(&name, 1),
+                AMOUNT_SOURCE_THREE_MONTH_AVERAGE => (spent(&name, 3) / Decimal::from(3)).round_dp(2),
+                _ => assigned
+                    .get(&budget.id)
+                    .and_then(|amounts| amounts.get(&source_month))
+                    .copied()
+                    .unwrap_or(budget.amount),
+            };
+
+            let new_budget = Self::insert_budget(
+                &mut *tx,
+                ledger_id,
+                CreateBudgetRequest {
+                    name: budget.name.clone(),
+                    description: budget.description.clone(),
+                    amount,
+                    start_date: month_start(month),
+                    end_date: budget.end_date.map(|_| end_of_month),
+                    group_id: budget.group_id,
+                    goal_type: budget.goal_type.clone(),
+                    goal_amount: budget.goal_amount,
+                    goal_target_date: budget.goal_target_date,
+                },
+            )
+            .await?;
+            created.push(new_budget);
+        }
+        tx.commit().await?;
+        info!("Copied {} budgets from {} into {}", created.len(), source_month, month);
+
+        Ok(CopiedBudgetMonth {
+            month: month.format("%Y-%m").to_string(),
+            source_month: source_month.format("%Y-%m").to_string(),
+            created,
+            skipped,
+        })
+    }
+
+    /// Work out how much of the money in On Budget accounts has not been given to a budget yet: the
+    /// balance minus what is still in budgets, minus overspending from earlier months that this
+    /// month's assignments do not cover. Overspending in the current month only reduces the pool
+    /// once the month is over. Balances are converted into the base currency at today's rate,
+    /// spending at the rate of its date.
+    pub async fn get_ready_to_assign(&self, ledger_id: Uuid) -> Result<ReadyToAssign, sqlx::Error> {
+        let base_currency = self.base_currency(ledger_id).await?;
+
+        let on_budget_balance = sqlx::query_scalar::<_, Decimal>(&format!(
+            r#"
+            SELECT COALESCE(SUM({}), 0.0)
+            FROM accounts a
+            WHERE a.account_type = 'On Budget'
+              AND a.ledger_id = $1
+            "#,
+            converted_amount_sql("a.balance", "a.currency", "$2", "a.ledger_id", "CURRENT_DATE")
+        ))
+        .bind(ledger_id)
+        .bind(&base_currency)
+        .fetch_one(&self.db)
+        .await?;
+
+        // Allocations, plus the amounts of budgets without an allocation for their first month
+        let assigned = sqlx::query_scalar::<_, Decimal>(
+            r#"
+            SELECT (SELECT COALESCE(SUM(amount), 0.0) FROM budget_allocations WHERE ledger_id = $1)
+                 + (SELECT COALESCE(SUM(b.amount), 0.0)
+                    FROM budgets b
+                    WHERE b.ledger_id = $1
+                      AND NOT EXISTS (
+                          SELECT 1 FROM budget_allocations a
+                          WHERE a.budget_id = b.id AND a.month = date_trunc('month', b.start_date AT TIME ZONE 'UTC')::date
+                      ))
+            "#,
+        )
+        .bind(ledger_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let activity = sqlx::query_scalar::<_, Decimal>(&format!(
+            r#"
+            SELECT COALESCE(SUM({}), 0.0)
+            FROM ({}) t
+            LEFT JOIN accounts src ON src.id = t.source_account_id
+            WHERE t.budget_id IS NOT NULL
+              AND t.amount > 0
+              AND t.ledger_id = $1
+            "#,
+            converted_amount_sql("t.amount", "src.currency", "$2", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
+        ))
+        .bind(ledger_id)
+        .bind(&base_currency)
+        .fetch_one(&self.db)
+        .await?;
+
+        // Overspent budgets hold a negative balance, which alone would add the overspending back to
+        // the pool; the part carried in from earlier months and not covered yet is taken out again
+        let overspent: Decimal = self
+            .budget_month_lines(ledger_id, first_of_month(Utc::now().date_naive()))
+            .await?
+            .iter()
+            .filter(|line| line.carried_over < Decimal::ZERO)
+            .map(|line| (line.carried_over + line.assigned + line.transferred).clamp(line.carried_over, Decimal::ZERO))
+            .sum();
+
+        let available_in_budgets = assigned - activity;
+        Ok(ReadyToAssign {
+            on_budget_balance,
+            assigned,
+            activity,
+            available_in_budgets,
+            overspent,
+            ready_to_assign: on_budget_balance - available_in_budgets + overspent,
+        })
+    }
+
+    /// Lines of the month view. Budgets are listed while they run, and before or after that as long
+    /// as they have something assigned, spent or carried over.
+    async fn budget_month_lines(&self, ledger_id: Uuid, month: NaiveDate) -> Result<Vec<BudgetMonthLine>, sqlx::Error> {
+        let budgets = self.get_budgets(ledger_id).await?;
+        let next_month = month + Months::new(1);
+        let assigned = self.assigned_by_month(ledger_id, &budgets, next_month).await?;
+        let transferred = self.transferred_by_month(ledger_id, next_month).await?;
+        let activity = self.activity_by_month(ledger_id, next_month).await?;
+
+        let none = BTreeMap::new();
+        let lines = budgets
+            .iter()
+            .filter_map(|budget| {
+                let line = month_line(
+                    budget,
+                    month,
+                    assigned.get(&budget.id).unwrap_or(&none),
+                    transferred.get(&budget.id).unwrap_or(&none),
+                    activity.get(&budget.id).unwrap_or(&none),
+                );
+                let running = first_of_month(budget.start_date.date_naive()) <= month
+                    && budget.end_date.is_none_or(|end| end.date_naive() >= month);
+                let has_amounts = !(line.carried_over.is_zero()
+                    && line.assigned.is_zero()
+                    && line.transferred.is_zero()
+                    && line.activity.is_zero());
+                (running || has_amounts).then_some(line)
+            })
+            .collect();
+        Ok(lines)
+    }
+
+    /// Amounts assigned per budget and month, before the given month. A budget without an allocation
+    /// for the month it starts in keeps its amount there.
+    async fn assigned_by_month(&self, ledger_id: Uuid, budgets: &[Budget], before: NaiveDate) -> Result<MonthlyAmounts, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (Uuid, NaiveDate, Decimal)>(
+            "SELECT budget_id, month, amount FROM budget_allocations WHERE ledger_id = $1 AND month < $2",
+        )
+        .bind(ledger_id)
+        .bind(before)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut assigned = MonthlyAmounts::new();
+        for (budget_id, month, amount) in rows {
+            assigned.entry(budget_id).or_default().insert(month, amount);
+        }
+        for budget in budgets {
+            let start = first_of_month(budget.start_date.date_naive());
+            if start < before {
+                assigned.entry(budget.id).or_default().entry(start).or_insert(budget.amount);
+            }
+        }
+        Ok(assigned)
+    }
+
+    /// Net amount moved to each budget per month, before the given month
+    async fn transferred_by_month(&self, ledger_id: Uuid, before: NaiveDate) -> Result<MonthlyAmounts, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (Uuid, NaiveDate, Decimal)>(
+            r#"
+            SELECT to_budget_id, month, amount FROM budget_transfers WHERE ledger_id = $1 AND month < $2
+            UNION ALL
+            SELECT from_budget_id, month, -amount FROM budget_transfers WHERE ledger_id = $1 AND month < $2
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(before)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut transferred = MonthlyAmounts::new();
+        for (budget_id, month, amount) in rows {
+            *transferred.entry(budget_id).or_default().entry(month).or_default() += amount;
+        }
+        Ok(transferred)
+    }
+
+    /// Spending per budget and month, before the given month. Split lines count individually and
+    /// amounts are converted into the base currency, as for the spent amounts.
+    async fn activity_by_month(&self, ledger_id: Uuid, before: NaiveDate) -> Result<MonthlyAmounts, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (Uuid, NaiveDate, Decimal)>(&format!(
+            r#"
+            SELECT t.budget_id, date_trunc('month', t.transaction_date AT TIME ZONE 'UTC')::date AS month, COALESCE(SUM({}), 0.0)
+            FROM ({}) t
+            LEFT JOIN accounts src ON src.id = t.source_account_id
+            WHERE t.budget_id IS NOT NULL
+              AND t.amount > 0
+              AND t.transaction_date < $2
+              AND t.ledger_id = $1
+            GROUP BY 1, 2
+            "#,
+            converted_amount_sql("t.amount", "src.currency", "$3", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
+        ))
+        .bind(ledger_id)
+        .bind(month_start(before))
+        .bind(self.base_currency(ledger_id).await?)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut activity = MonthlyAmounts::new();
+        for (budget_id, month, amount) in rows {
+            activity.entry(budget_id).or_default().insert(month, amount);
+        }
+        Ok(activity)
     }
 
     /// Get the total monthly incoming funds to on-budget accounts
-    pub async fn get_monthly_incoming_funds(&self, year: i32, month: u32) -> Result<f64, sqlx::Error> {
+    pub async fn get_monthly_incoming_funds(&self, ledger_id: Uuid, year: i32, month: u32) -> Result<Decimal, sqlx::Error> {
         // Calculate the start and end dates for the specified month
         let start_date = chrono::NaiveDate::from_ymd_opt(year, month, 1)
             .unwrap()
@@ -297,9 +898,10 @@ impl BudgetService {
 
         // Get deposits (negative amounts) to on-budget accounts
         // Exclude internal transfers where BOTH source and destination are On Budget accounts
-        let deposits = sqlx::query_scalar::<_, f64>(
+        // Amounts are converted into the base currency
+        let deposits = sqlx::query_scalar::<_, Decimal>(&format!(
             r#"
-            SELECT COALESCE(SUM(ABS(t.amount)), 0.0)
+            SELECT COALESCE(SUM(ABS({})), 0.0)
             FROM transactions t
             JOIN accounts dst ON t.destination_account_id = dst.id
             LEFT JOIN accounts src ON t.source_account_id = src.id
@@ -308,10 +910,14 @@ impl BudgetService {
               AND t.amount > 0
               AND t.transaction_date >= $1
               AND t.transaction_date < $2
+              AND t.ledger_id = $3
             "#,
-        )
+            converted_amount_sql("t.amount", "src.currency", "$4", "t.ledger_id", "t.transaction_date")
+        ))
         .bind(start_date)
         .bind(end_date)
+        .bind(ledger_id)
+        .bind(self.base_currency(ledger_id).await?)
         .fetch_one(&self.db)
         .await?;
         info!("Monthly incoming funds (excluding on-budget↔on-budget transfers) for {}-{}: ${:.2}", start_date, end_date, deposits);
@@ -320,7 +926,7 @@ impl BudgetService {
     }
 
     /// Get the total budgeted amount for a specific month
-    pub async fn get_monthly_budgeted_amount(&self, year: i32, month: u32) -> Result<f64, sqlx::Error> {
+    pub async fn get_monthly_budgeted_amount(&self, ledger_id: Uuid, year: i32, month: u32) -> Result<Decimal, sqlx::Error> {
         // Calculate the start and end dates for the specified month
         let start_date = chrono::NaiveDate::from_ymd_opt(year, month, 1)
             .unwrap()
@@ -340,15 +946,17 @@ impl BudgetService {
         let end_date = chrono::DateTime::<Utc>::from_naive_utc_and_offset(end_date, Utc);
 
         // Query for budgets that are active during the specified month
-        let budgeted_amount = sqlx::query_scalar::<_, f64>(
+        let budgeted_amount = sqlx::query_scalar::<_, Decimal>(
             r#"
             SELECT COALESCE(SUM(amount), 0.0)
             FROM budgets
             WHERE (start_date <= $2 AND (end_date IS NULL OR end_date >= $1))
+              AND ledger_id = $3
             "#,
         )
         .bind(start_date)
         .bind(end_date)
+        .bind(ledger_id)
         .fetch_one(&self.db)
         .await?;
 
@@ -359,43 +967,48 @@ impl BudgetService {
     /// Returns a tuple with (incoming_funds, budgeted_amount, remaining_to_budget, forecasted_monthly_income)
     /// If remaining_to_budget is positive, there are funds left to budget
     /// If remaining_to_budget is negative, the budgeted amount exceeds the incoming funds
-    pub async fn get_monthly_budget_status(&self, year: i32, month: u32) -> Result<(f64, f64, f64, f64), sqlx::Error> {
-        let incoming_funds = self.get_monthly_incoming_funds(year, month).await?;
-        let budgeted_amount = self.get_monthly_budgeted_amount(year, month).await?;
+    pub async fn get_monthly_budget_status(&self, ledger_id: Uuid, year: i32, month: u32) -> Result<(Decimal, Decimal, Decimal, Decimal), sqlx::Error> {
+        let incoming_funds = self.get_monthly_incoming_funds(ledger_id, year, month).await?;
+        let budgeted_amount = self.get_monthly_budgeted_amount(ledger_id, year, month).await?;
         let remaining_to_budget = incoming_funds - budgeted_amount;
 
         // Get forecasted monthly income from settings if available
         let forecasted_monthly_income = match &self.settings_service {
             Some(settings_service) => {
-                match settings_service.get_forecasted_monthly_income().await {
+                match settings_service.get_forecasted_monthly_income(ledger_id).await {
                     Ok(income) => income,
-                    Err(_) => 0.0 // Default to 0 if there's an error
+                    Err(_) => Decimal::ZERO // Default to 0 if there's an error
                 }
             },
-            None => 0.0 // Default to 0 if settings service is not available
+            None => Decimal::ZERO // Default to 0 if settings service is not available
         };
 
         Ok((incoming_funds, budgeted_amount, remaining_to_budget, forecasted_monthly_income))
     }
 
     /// Get the total spent amount not associated with any budget (all time)
-    pub async fn get_unbudgeted_spent(&self) -> Result<f64, sqlx::Error> {
-        // Sum unbudgeted spending (outflows) from On Budget accounts, excluding transfers and initial balance
-        let spent = sqlx::query_scalar::<_, f64>(
+    pub async fn get_unbudgeted_spent(&self, ledger_id: Uuid) -> Result<Decimal, sqlx::Error> {
+        // Sum unbudgeted spending (outflows) from On Budget accounts, excluding transfers and initial balance; unbudgeted split lines count even when siblings are budgeted; amounts are converted into the base currency
+        let spent = sqlx::query_scalar::<_, Decimal>(&format!(
             r#"
-            SELECT COALESCE(SUM(t.amount), 0.0)
-            FROM transactions t
+            SELECT COALESCE(SUM({}), 0.0)
+            FROM ({}) t
             JOIN accounts src ON t.source_account_id = src.id
             LEFT JOIN accounts dst ON t.destination_account_id = dst.id
             LEFT JOIN categories c_id ON c_id.id = t.category_id
-            LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category
+            LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category AND c_name.ledger_id = t.ledger_id
             WHERE t.budget_id IS NULL
               AND src.account_type = 'On Budget'
               AND t.amount > 0
               AND NOT (dst.account_type = 'On Budget')
               AND (COALESCE(c_id.name, c_name.name, t.category) IS NULL OR COALESCE(c_id.name, c_name.name, t.category) NOT IN ('Initial Balance', 'Transfer', 'Transfers'))
+              AND t.ledger_id = $1
             "#,
-        )
+            converted_amount_sql("t.amount", "src.currency", "$2", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
+        ))
+        .bind(ledger_id)
+        .bind(self.base_currency(ledger_id).await?)
         .fetch_one(&self.db)
         .await?;
 
@@ -403,7 +1016,7 @@ impl BudgetService {
     }
 
     /// Get the total spent amount not associated with any budget for a specific month
-    pub async fn get_unbudgeted_spent_for_month(&self, year: i32, month: u32) -> Result<f64, sqlx::Error> {
+    pub async fn get_unbudgeted_spent_for_month(&self, ledger_id: Uuid, year: i32, month: u32) -> Result<Decimal, sqlx::Error> {
         // Calculate the start and end dates for the specified month
         let start_date = chrono::NaiveDate::from_ymd_opt(year, month, 1)
             .unwrap()
@@ -422,15 +1035,15 @@ impl BudgetService {
         .unwrap();
         let end_date = chrono::DateTime::<Utc>::from_naive_utc_and_offset(end_date, Utc);
 
-        // Sum unbudgeted spending (outflows) from On Budget accounts within the month, excluding transfers and initial balance
-        let spent = sqlx::query_scalar::<_, f64>(
+        // Sum unbudgeted spending (outflows) from On Budget accounts within the month, excluding transfers and initial balance; unbudgeted split lines count even when siblings are budgeted; amounts are converted into the base currency
+        let spent = sqlx::query_scalar::<_, Decimal>(&format!(
             r#"
-            SELECT COALESCE(SUM(t.amount), 0.0)
-            FROM transactions t
+            SELECT COALESCE(SUM({}), 0.0)
+            FROM ({}) t
             JOIN accounts src ON t.source_account_id = src.id
             LEFT JOIN accounts dst ON t.destination_account_id = dst.id
             LEFT JOIN categories c_id ON c_id.id = t.category_id
-            LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category
+            LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category AND c_name.ledger_id = t.ledger_id
             WHERE t.budget_id IS NULL
               AND src.account_type = 'On Budget'
               AND t.amount > 0
@@ -438,10 +1051,15 @@ impl BudgetService {
               AND (COALESCE(c_id.name, c_name.name, t.category) IS NULL OR COALESCE(c_id.name, c_name.name, t.category) NOT IN ('Initial Balance', 'Transfer', 'Transfers'))
               AND t.transaction_date >= $1
               AND t.transaction_date < $2
+              AND t.ledger_id = $3
             "#,
-        )
+            converted_amount_sql("t.amount", "src.currency", "$4", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
+        ))
         .bind(start_date)
         .bind(end_date)
+        .bind(ledger_id)
+        .bind(self.base_currency(ledger_id).await?)
         .fetch_one(&self.db)
         .await?;
 
@@ -1,4 +1,4 @@
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::models::{CategoryGroup, CreateCategoryGroupRequest, UpdateCategoryGroupRequest};
@@ -15,32 +15,35 @@ impl CategoryGroupService {
     }
 
     /// Get all category groups
-    pub async fn get_category_groups(&self) -> Result<Vec<CategoryGroup>, sqlx::Error> {
-        sqlx::query_as::<_, CategoryGroup>("SELECT * FROM category_groups ORDER BY name")
+    pub async fn get_category_groups(&self, ledger_id: Uuid) -> Result<Vec<CategoryGroup>, sqlx::Error> {
+        sqlx::query_as::<_, CategoryGroup>("SELECT * FROM category_groups WHERE ledger_id = $1 ORDER BY name")
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await
     }
 
     /// Get a category group by ID
-    pub async fn get_category_group(&self, id: Uuid) -> Result<Option<CategoryGroup>, sqlx::Error> {
-        sqlx::query_as::<_, CategoryGroup>("SELECT * FROM category_groups WHERE id = $1")
+    pub async fn get_category_group(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<CategoryGroup>, sqlx::Error> {
+        sqlx::query_as::<_, CategoryGroup>("SELECT * FROM category_groups WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
             .await
     }
 
     /// Create a new category group
-    pub async fn create_category_group(&self, req: CreateCategoryGroupRequest) -> Result<CategoryGroup, sqlx::Error> {
+    pub async fn create_category_group(&self, ledger_id: Uuid, req: CreateCategoryGroupRequest) -> Result<CategoryGroup, sqlx::Error> {
         let now = chrono::Utc::now();
 
         sqlx::query_as::<_, CategoryGroup>(
             r#"
-            INSERT INTO category_groups (id, name, description, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO category_groups (id, ledger_id, name, description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
             RETURNING *
             "#,
         )
         .bind(Uuid::new_v4())
+        .bind(ledger_id)
         .bind(&req.name)
         .bind(&req.description)
         .bind(now)
@@ -50,34 +53,30 @@ impl CategoryGroupService {
     }
 
     /// Update an existing category group
-    pub async fn update_category_group(&self, id: Uuid, req: UpdateCategoryGroupRequest) -> Result<Option<CategoryGroup>, sqlx::Error> {
+    pub async fn update_category_group(&self, ledger_id: Uuid, id: Uuid, req: UpdateCategoryGroupRequest) -> Result<Option<CategoryGroup>, sqlx::Error> {
         // First, check if the category group exists
-        let category_group = self.get_category_group(id).await?;
+        let category_group = self.get_category_group(ledger_id, id).await?;
 
         if let Some(_) = category_group {
             // Build the update query dynamically based on which fields are provided
-            let mut query = String::from("UPDATE category_groups SET updated_at = $1");
-            let mut params: Vec<String> = vec![];
             let now = chrono::Utc::now();
+            let mut query = QueryBuilder::<Postgres>::new("UPDATE category_groups SET updated_at = ");
+            query.push_bind(now);
 
             if let Some(name) = &req.name {
-                params.push(format!("name = '{}'", name));
+                query.push(", name = ").push_bind(name.clone());
             }
 
             if let Some(description) = &req.description {
-                params.push(format!("description = '{}'", description.replace("'", "''")));
+                query.push(", description = ").push_bind(description.clone());
             }
 
-            if !params.is_empty() {
-                query.push_str(", ");
-                query.push_str(&params.join(", "));
-            }
-
-            query.push_str(" WHERE id = $2 RETURNING *");
+            query.push(" WHERE id = ").push_bind(id);
+            query.push(" AND ledger_id = ").push_bind(ledger_id);
+            query.push(" RETURNING *");
 
-            sqlx::query_as::<_, CategoryGroup>(&query)
-                .bind(now)
-                .bind(id)
+            query
+                .build_query_as::<CategoryGroup>()
                 .fetch_optional(&self.db)
                 .await
         } else {
@@ -86,9 +85,9 @@ impl CategoryGroupService {
     }
 
     /// Delete a category group
-    pub async fn delete_category_group(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    pub async fn delete_category_group(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
         // First, check if the category group exists
-        let category_group = self.get_category_group(id).await?;
+        let category_group = self.get_category_group(ledger_id, id).await?;
 
         if category_group.is_none() {
             return Ok(false);
@@ -98,14 +97,16 @@ impl CategoryGroupService {
         let mut tx = self.db.begin().await?;
 
         // Update any categories that use this group to set group_id to NULL
-        sqlx::query("UPDATE categories SET group_id = NULL WHERE group_id = $1")
+        sqlx::query("UPDATE categories SET group_id = NULL WHERE group_id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
         // Now delete the category group
-        let result = sqlx::query("DELETE FROM category_groups WHERE id = $1")
+        let result = sqlx::query("DELETE FROM category_groups WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
@@ -118,9 +119,10 @@ impl CategoryGroupService {
     }
 
     /// Get all categories in a specific group
-    pub async fn get_categories_by_group(&self, group_id: Uuid) -> Result<Vec<crate::models::Category>, sqlx::Error> {
-        sqlx::query_as::<_, crate::models::Category>("SELECT * FROM categories WHERE group_id = $1 ORDER BY name")
+    pub async fn get_categories_by_group(&self, ledger_id: Uuid, group_id: Uuid) -> Result<Vec<crate::models::Category>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::Category>("SELECT * FROM categories WHERE group_id = $1 AND ledger_id = $2 ORDER BY name")
             .bind(group_id)
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await
     }
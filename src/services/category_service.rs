@@ -1,4 +1,4 @@
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::models::{Category, CreateCategoryRequest, UpdateCategoryRequest};
@@ -14,56 +14,67 @@ impl CategoryService {
         Self { db }
     }
 
-    /// Get all categories
-    pub async fn get_categories(&self) -> Result<Vec<Category>, sqlx::Error> {
-        sqlx::query_as::<_, Category>("SELECT * FROM categories ORDER BY name")
+    /// Get all categories in a ledger
+    pub async fn get_categories(&self, ledger_id: Uuid) -> Result<Vec<Category>, sqlx::Error> {
+        sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE ledger_id = $1 ORDER BY name")
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await
     }
 
-    /// Find a category by name, or create it if it doesn't exist
-    pub async fn find_or_create_category(&self, name: &str) -> Result<Category, sqlx::Error> {
-        // First, try to find the category by name
-        let existing_category = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE name = $1")
-            .bind(name)
-            .fetch_optional(&self.db)
-            .await?;
+    /// Find a category by name, or create it if it doesn't exist. Takes an executor so the category
+    /// is created within the caller's database transaction and rolled back with it.
+    pub async fn find_or_create_category<'e, E>(&self, executor: E, ledger_id: Uuid, name: &str) -> Result<Category, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Postgres>,
+    {
+        let now = chrono::Utc::now();
 
-        if let Some(category) = existing_category {
-            // Category exists, return it
-            Ok(category)
-        } else {
-            // Category doesn't exist, create it
-            let create_request = CreateCategoryRequest {
-                name: name.to_string(),
-                description: None,
-                group_id: None,
-            };
-
-            self.create_category(create_request).await
-        }
+        sqlx::query_as::<_, Category>(
+            r#"
+            WITH existing AS (
+                SELECT * FROM categories WHERE name = $1 AND ledger_id = $2
+            ), created AS (
+                INSERT INTO categories (id, ledger_id, name, description, group_id, created_at, updated_at)
+                SELECT $3, $2, $1, NULL, NULL, $4, $4
+                WHERE NOT EXISTS (SELECT 1 FROM existing)
+                RETURNING *
+            )
+            SELECT * FROM existing
+            UNION ALL
+            SELECT * FROM created
+            "#,
+        )
+        .bind(name)
+        .bind(ledger_id)
+        .bind(Uuid::new_v4())
+        .bind(now)
+        .fetch_one(executor)
+        .await
     }
 
     /// Get a category by ID
-    pub async fn get_category(&self, id: Uuid) -> Result<Option<Category>, sqlx::Error> {
-        sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = $1")
+    pub async fn get_category(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Category>, sqlx::Error> {
+        sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
             .await
     }
 
     /// Create a new category
-    pub async fn create_category(&self, req: CreateCategoryRequest) -> Result<Category, sqlx::Error> {
+    pub async fn create_category(&self, ledger_id: Uuid, req: CreateCategoryRequest) -> Result<Category, sqlx::Error> {
         let now = chrono::Utc::now();
 
         sqlx::query_as::<_, Category>(
             r#"
-            INSERT INTO categories (id, name, description, group_id, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO categories (id, ledger_id, name, description, group_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             RETURNING *
             "#,
         )
         .bind(Uuid::new_v4())
+        .bind(ledger_id)
         .bind(&req.name)
         .bind(&req.description)
         .bind(&req.group_id)
@@ -74,39 +85,35 @@ impl CategoryService {
     }
 
     /// Update an existing category
-    pub async fn update_category(&self, id: Uuid, req: UpdateCategoryRequest) -> Result<Option<Category>, sqlx::Error> {
+    pub async fn update_category(&self, ledger_id: Uuid, id: Uuid, req: UpdateCategoryRequest) -> Result<Option<Category>, sqlx::Error> {
         // First, check if the category exists and capture the original name
-        let existing = self.get_category(id).await?;
+        let existing = self.get_category(ledger_id, id).await?;
 
         if let Some(original) = existing {
             // Build the update query dynamically based on which fields are provided
-            let mut query = String::from("UPDATE categories SET updated_at = $1");
-            let mut params: Vec<String> = vec![];
             let now = chrono::Utc::now();
+            let mut query = QueryBuilder::<Postgres>::new("UPDATE categories SET updated_at = ");
+            query.push_bind(now);
 
             if let Some(name) = &req.name {
-                params.push(format!("name = '{}'", name.replace("'", "''")));
+                query.push(", name = ").push_bind(name.clone());
             }
 
             if let Some(description) = &req.description {
-                params.push(format!("description = '{}'", description.replace("'", "''")));
-            }
-
-            if let Some(group_id) = &req.group_id {
-                params.push(format!("group_id = '{}'", group_id));
+                query.push(", description = ").push_bind(description.clone());
             }
 
-            if !params.is_empty() {
-                query.push_str(", ");
-                query.push_str(&params.join(", "));
+            if let Some(group_id) = req.group_id {
+                query.push(", group_id = ").push_bind(group_id);
             }
 
-            query.push_str(" WHERE id = $2 RETURNING *");
+            query.push(" WHERE id = ").push_bind(id);
+            query.push(" AND ledger_id = ").push_bind(ledger_id);
+            query.push(" RETURNING *");
 
             // Perform the category update
-            let updated = sqlx::query_as::<_, Category>(&query)
-                .bind(now)
-                .bind(id)
+            let updated = query
+                .build_query_as::<Category>()
                 .fetch_optional(&self.db)
                 .await?;
 
@@ -118,11 +125,12 @@ impl CategoryService {
                         r#"
                         UPDATE transactions t
                         SET category_id = $1
-                        WHERE t.category_id IS NULL AND t.category = $2
+                        WHERE t.category_id IS NULL AND t.category = $2 AND t.ledger_id = $3
                         "#,
                     )
                     .bind(updated_cat.id)
                     .bind(&original.name)
+                    .bind(ledger_id)
                     .execute(&self.db)
                     .await?;
                 }
@@ -135,9 +143,9 @@ impl CategoryService {
     }
 
     /// Delete a category
-    pub async fn delete_category(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    pub async fn delete_category(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
         // First, check if the category exists
-        let category = self.get_category(id).await?;
+        let category = self.get_category(ledger_id, id).await?;
 
         if category.is_none() {
             return Ok(false);
@@ -153,8 +161,9 @@ impl CategoryService {
             .await?;
 
         // Now delete the category
-        let result = sqlx::query("DELETE FROM categories WHERE id = $1")
+        let result = sqlx::query("DELETE FROM categories WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
@@ -0,0 +1,231 @@
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::models::{CreateExchangeRateRequest, ExchangeRate, ExchangeRateImportResult, ExchangeRateQuery};
+
+/// SQL expression for the rate converting `currency` into `target` on `date` within `ledger`,
+/// or NULL when no rate is known. The latest rate on or before the date wins, falling back to the
+/// earliest later one; a rate stored for the opposite direction is inverted.
+pub(crate) fn exchange_rate_sql(ledger: &str, currency: &str, target: &str, date: &str) -> String {
+    format!(
+        "(SELECT CASE WHEN r.from_currency = {currency} THEN r.rate ELSE 1 / r.rate END
+          FROM exchange_rates r
+          WHERE r.ledger_id = {ledger}
+            AND ((r.from_currency = {currency} AND r.to_currency = {target})
+              OR (r.from_currency = {target} AND r.to_currency = {currency}))
+          ORDER BY (r.rate_date <= ({date})::date) DESC, ABS(r.rate_date - ({date})::date), (r.from_currency = {currency}) DESC
+          LIMIT 1)"
+    )
+}
+
+/// SQL expression converting `amount` in `currency` into `target` at the rate of `date`.
+/// Amounts already in the target currency, and zero amounts, are left unchanged; any other amount
+/// without a known rate fails the query with [`MISSING_EXCHANGE_RATE`] naming the currencies.
+pub(crate) fn converted_amount_sql(amount: &str, currency: &str, target: &str, ledger: &str, date: &str) -> String {
+    format!(
+        "ROUND({amount} * CASE WHEN {currency} IS NULL OR {currency} = {target} OR {amount} = 0 THEN 1 \
+         ELSE require_exchange_rate({rate}, {currency}, {target}, ({date})::date) END, 4)",
+        rate = exchange_rate_sql(ledger, currency, target, date)
+    )
+}
+
+/// SQLSTATE raised by `require_exchange_rate` (migration 0025) when an amount can't be converted
+pub(crate) const MISSING_EXCHANGE_RATE: &str = "RL001";
+
+/// Whether a query failed because an amount had no exchange rate into the requested currency
+pub fn is_missing_exchange_rate(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(db_err) => db_err.code().as_deref() == Some(MISSING_EXCHANGE_RATE),
+        _ => false,
+    }
+}
+
+/// Look up the rate converting one currency into another on a date; 1 for the same currency
+pub(crate) async fn find_exchange_rate<'e, E>(
+    executor: E,
+    ledger_id: Uuid,
+    from_currency: &str,
+    to_currency: &str,
+    date: NaiveDate,
+) -> Result<Option<Decimal>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    if from_currency == to_currency {
+        return Ok(Some(Decimal::ONE));
+    }
+
+    sqlx::query_scalar::<_, Option<Decimal>>(&format!("SELECT {}", exchange_rate_sql("$1", "$2", "$3", "$4")))
+        .bind(ledger_id)
+        .bind(from_currency)
+        .bind(to_currency)
+        .bind(date)
+        .fetch_one(executor)
+        .await
+}
+
+/// Validate an ISO 4217 style currency code and return it upper-cased
+pub(crate) fn normalize_currency(code: &str) -> Result<String, sqlx::Error> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(sqlx::Error::Protocol(format!("Invalid currency code: '{}'", code)));
+    }
+    Ok(code)
+}
+
+/// Service for managing exchange rates between currencies
+pub struct ExchangeRateService {
+    db: Pool<Postgres>,
+}
+
+impl ExchangeRateService {
+    /// Create a new ExchangeRateService with the given database pool
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self { db }
+    }
+
+    /// Get the exchange rates of a ledger, newest first, optionally for one currency pair
+    pub async fn get_exchange_rates(&self, ledger_id: Uuid, query: ExchangeRateQuery) -> Result<Vec<ExchangeRate>, sqlx::Error> {
+        let from_currency = query.from_currency.as_deref().map(normalize_currency).transpose()?;
+        let to_currency = query.to_currency.as_deref().map(normalize_currency).transpose()?;
+
+        sqlx::query_as::<_, ExchangeRate>(
+            r#"
+            SELECT * FROM exchange_rates
+            WHERE ledger_id = $1
+              AND ($2::VARCHAR IS NULL OR from_currency = $2)
+              AND ($3::VARCHAR IS NULL OR to_currency = $3)
+            ORDER BY rate_date DESC, from_currency, to_currency
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(from_currency)
+        .bind(to_currency)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Create the exchange rate of a currency pair on a day, replacing any existing one
+    pub async fn create_exchange_rate(&self, ledger_id: Uuid, req: CreateExchangeRateRequest) -> Result<ExchangeRate, sqlx::Error> {
+        let from_currency = normalize_currency(&req.from_currency)?;
+        let to_currency = normalize_currency(&req.to_currency)?;
+        if from_currency == to_currency {
+            return Err(sqlx::Error::Protocol("Invalid exchange rate: currencies must differ".into()));
+        }
+        if req.rate <= Decimal::ZERO {
+            return Err(sqlx::Error::Protocol("Invalid exchange rate: rate must be positive".into()));
+        }
+
+        let now = Utc::now();
+        sqlx::query_as::<_, ExchangeRate>(
+            r#"
+            INSERT INTO exchange_rates (id, ledger_id, from_currency, to_currency, rate, rate_date, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (ledger_id, from_currency, to_currency, rate_date)
+            DO UPDATE SET rate = EXCLUDED.rate, updated_at = EXCLUDED.updated_at
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ledger_id)
+        .bind(&from_currency)
+        .bind(&to_currency)
+        .bind(req.rate)
+        .bind(req.rate_date)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Delete an exchange rate
+    pub async fn delete_exchange_rate(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM exchange_rates WHERE id = $1 AND ledger_id = $2")
+            .bind(id)
+            .bind(ledger_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Import exchange rates from CSV lines of `date,from_currency,to_currency,rate`
+    /// (date as YYYY-MM-DD; a header line is skipped). Valid lines are imported even when others fail.
+    pub async fn import_exchange_rates(&self, ledger_id: Uuid, csv_data: &str) -> Result<ExchangeRateImportResult, sqlx::Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(csv_data.as_bytes());
+
+        let mut imported = 0;
+        let mut errors = Vec::new();
+
+        for (index, record) in reader.records().enumerate() {
+            let line = index + 1;
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    errors.push(format!("Line {}: {}", line, err));
+                    continue;
+                }
+            };
+            if record.iter().all(|field| field.is_empty()) {
+                continue;
+            }
+            if record.len() != 4 {
+                errors.push(format!("Line {}: expected 4 columns (date,from_currency,to_currency,rate)", line));
+                continue;
+            }
+
+            let rate_date = match NaiveDate::parse_from_str(&record[0], "%Y-%m-%d") {
+                Ok(date) => date,
+                // Header line
+                Err(_) if line == 1 => continue,
+                Err(_) => {
+                    errors.push(format!("Line {}: invalid date '{}'", line, &record[0]));
+                    continue;
+                }
+            };
+            let rate = match record[3].parse::<Decimal>() {
+                Ok(rate) => rate,
+                Err(_) => {
+                    errors.push(format!("Line {}: invalid rate '{}'", line, &record[3]));
+                    continue;
+                }
+            };
+
+            let req = CreateExchangeRateRequest {
+                from_currency: record[1].to_string(),
+                to_currency: record[2].to_string(),
+                rate,
+                rate_date,
+            };
+            match self.create_exchange_rate(ledger_id, req).await {
+                Ok(_) => imported += 1,
+                Err(sqlx::Error::Protocol(message)) => errors.push(format!("Line {}: {}", line, message)),
+                Err(err) => return Err(err),
+            }
+        }
+
+        info!("Imported {} exchange rate(s) into ledger {} ({} error(s))", imported, ledger_id, errors.len());
+
+        Ok(ExchangeRateImportResult { imported, errors })
+    }
+
+    /// Get the rate converting one currency into another on a date, if any is known
+    pub async fn get_rate(
+        &self,
+        ledger_id: Uuid,
+        from_currency: &str,
+        to_currency: &str,
+        date: NaiveDate,
+    ) -> Result<Option<Decimal>, sqlx::Error> {
+        let from_currency = normalize_currency(from_currency)?;
+        let to_currency = normalize_currency(to_currency)?;
+        find_exchange_rate(&self.db, ledger_id, &from_currency, &to_currency, date).await
+    }
+}
@@ -11,8 +11,10 @@ use uuid::Uuid;
 use csv::ReaderBuilder;
 use tracing::{debug, info, log};
 use crate::models::{Account, CreateAccountRequest, Transaction, CreateTransactionRequest, firefly_import::{FireflyImportOptions, ImportResult, AccountTypeMapping, FailedTransactionDetails}};
+use crate::models::ChangeActor;
 use crate::services::account_service::AccountService;
 use crate::services::transaction_service::TransactionService;
+use rust_decimal::Decimal;
 
 // Firefly III account types
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -53,7 +55,7 @@ pub struct FireflyAccount {
     pub name: String,
     pub type_: FireflyAccountType,
     pub currency_code: String,
-    pub current_balance: Option<f64>,
+    pub current_balance: Option<Decimal>,
     pub notes: Option<String>,
 }
 
@@ -64,13 +66,15 @@ pub struct FireflyTransaction {
     pub transaction_type: FireflyTransactionType,
     pub description: String,
     pub date: DateTime<Utc>,
-    pub amount: f64,
+    pub amount: Decimal,
     pub source_id: String,
     pub source_name: String,
     pub destination_id: String,
     pub destination_name: String,
     pub category_name: Option<String>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 // Firefly III API response structure for accounts
@@ -152,6 +156,7 @@ struct FireflyApiTransactionSplit {
     destination_id: Option<String>,
     destination_name: Option<String>,
     category_name: Option<String>,
+    tags: Option<Vec<String>>,
     #[serde(rename = "type")]
     transaction_type: String,
     date: String,
@@ -288,8 +293,9 @@ impl FireflyImportService {
     }
 
     // Import accounts and transactions from Firefly III
-    pub async fn import(&self, options: FireflyImportOptions) -> Result<ImportResult, String> {
+    pub async fn import(&self, ledger_id: Uuid, options: FireflyImportOptions) -> Result<ImportResult, String> {
         let mut result = ImportResult {
+            batch_id: Uuid::new_v4(),
             accounts_imported: 0,
             transactions_imported: 0,
             errors: Vec::new(),
@@ -300,14 +306,14 @@ impl FireflyImportService {
         match options.import_method.as_str() {
             "api" => {
                 if let (Some(api_url), Some(api_token)) = (&options.api_url, &options.api_token) {
-                    self.import_from_api(api_url, api_token, &options.account_type_mapping, &mut result).await?;
+                    self.import_from_api(ledger_id, api_url, api_token, &options.account_type_mapping, &mut result).await?;
                 } else {
                     return Err("API URL and token are required for API import".to_string());
                 }
             }
             "csv" => {
                 if let (Some(accounts_csv), Some(transactions_csv)) = (&options.accounts_csv_path, &options.transactions_csv_path) {
-                    self.import_from_csv(accounts_csv, transactions_csv, &options.account_type_mapping, &mut result).await?;
+                    self.import_from_csv(ledger_id, accounts_csv, transactions_csv, &options.account_type_mapping, &mut result).await?;
                 } else {
                     return Err("Accounts and transactions CSV paths are required for CSV import".to_string());
                 }
@@ -321,7 +327,7 @@ impl FireflyImportService {
     }
 
     // Import accounts and transactions from Firefly III API
-    async fn import_from_api(&self, api_url: &str, api_token: &str, account_type_mapping: &AccountTypeMapping, result: &mut ImportResult) -> Result<(), String> {
+    async fn import_from_api(&self, ledger_id: Uuid, api_url: &str, api_token: &str, account_type_mapping: &AccountTypeMapping, result: &mut ImportResult) -> Result<(), String> {
         // Create HTTP client
         let client = Client::new();
 
@@ -329,13 +335,13 @@ impl FireflyImportService {
         let accounts = self.fetch_accounts_from_api(&client, api_url, api_token).await?;
 
         // Map of Firefly III account IDs to Rustler account IDs
-        let account_id_map = self.import_accounts(accounts, account_type_mapping, result).await?;
+        let account_id_map = self.import_accounts(ledger_id, accounts, account_type_mapping, result).await?;
 
         // Fetch transactions from Firefly III API
         let transactions = self.fetch_transactions_from_api(&client, api_url, api_token).await?;
 
         // Import transactions
-        self.import_transactions(transactions, &account_id_map, result).await?;
+        self.import_transactions(ledger_id, transactions, &account_id_map, result).await?;
 
         Ok(())
     }
@@ -383,7 +389,7 @@ impl FireflyImportService {
 
                 // Parse current balance
                 let current_balance = api_account.attributes.current_balance
-                    .and_then(|b| b.parse::<f64>().ok());
+                    .and_then(|b| b.parse::<Decimal>().ok());
 
                 // Create FireflyAccount from FireflyApiAccount
                 let account = FireflyAccount {
@@ -476,7 +482,7 @@ impl FireflyImportService {
                     };
 
                     // Parse amount
-                    let amount = split.amount.parse::<f64>()
+                    let amount = split.amount.parse::<Decimal>()
                         .map_err(|_| format!("Failed to parse transaction amount: {}", split.amount))?;
 
                     // Parse date
@@ -498,6 +504,7 @@ impl FireflyImportService {
                         destination_name: split.destination_name.clone().unwrap_or_default(),
                         category_name: split.category_name.clone(),
                         notes: None, // API doesn't provide notes in this format
+                        tags: split.tags.clone().unwrap_or_default(),
                     };
 
                     transactions.push(transaction);
@@ -542,18 +549,18 @@ impl FireflyImportService {
     }
 
     // Import accounts and transactions from CSV files
-    async fn import_from_csv(&self, accounts_csv_path: &str, transactions_csv_path: &str, account_type_mapping: &AccountTypeMapping, result: &mut ImportResult) -> Result<(), String> {
+    async fn import_from_csv(&self, ledger_id: Uuid, accounts_csv_path: &str, transactions_csv_path: &str, account_type_mapping: &AccountTypeMapping, result: &mut ImportResult) -> Result<(), String> {
         // Read accounts from CSV
         let accounts = self.read_accounts_from_csv(accounts_csv_path)?;
 
         // Map of Firefly III account IDs to Rustler account IDs
-        let account_id_map = self.import_accounts(accounts, account_type_mapping, result).await?;
+        let account_id_map = self.import_accounts(ledger_id, accounts, account_type_mapping, result).await?;
 
         // Read transactions from CSV
         let transactions = self.read_transactions_from_csv(transactions_csv_path)?;
 
         // Import transactions
-        self.import_transactions(transactions, &account_id_map, result).await?;
+        self.import_transactions(ledger_id, transactions, &account_id_map, result).await?;
 
         Ok(())
     }
@@ -593,7 +600,7 @@ impl FireflyImportService {
                     //raw csv data
                     info!("Account: {:?}", csv_account);
                     let current_balance = csv_account.current_balance
-                        .and_then(|b| b.parse::<f64>().ok());
+                        .and_then(|b| b.parse::<Decimal>().ok());
 
                     accounts.push(FireflyAccount {
                         id: csv_account.id,
@@ -648,7 +655,7 @@ impl FireflyImportService {
                         .with_timezone(&Utc);
 
                     // Parse amount
-                    let amount = csv_transaction.amount.parse::<f64>()
+                    let amount = csv_transaction.amount.parse::<Decimal>()
                         .map_err(|e| format!("Failed to parse transaction amount: {}", e))?;
 
                     // Generate source_id and destination_id from source_name and destination_name
@@ -669,6 +676,14 @@ impl FireflyImportService {
                         destination_name: csv_transaction.destination_name,
                         category_name: csv_transaction.category_name,
                         notes: csv_transaction.notes,
+                        // Firefly III exports tags as one comma-separated column
+                        tags: csv_transaction.tags
+                            .as_deref()
+                            .unwrap_or_default()
+                            .split(',')
+                            .map(|tag| tag.trim().to_string())
+                            .filter(|tag| !tag.is_empty())
+                            .collect(),
                     });
                 }
                 Err(e) => {
@@ -681,12 +696,12 @@ impl FireflyImportService {
     }
 
     // Import accounts from Firefly III to Rustler
-    async fn import_accounts(&self, accounts: Vec<FireflyAccount>, account_type_mapping: &AccountTypeMapping, result: &mut ImportResult) -> Result<HashMap<String, Uuid>, String> {
+    async fn import_accounts(&self, ledger_id: Uuid, accounts: Vec<FireflyAccount>, account_type_mapping: &AccountTypeMapping, result: &mut ImportResult) -> Result<HashMap<String, Uuid>, String> {
         debug!("Importing {} accounts", accounts.len());
         let mut account_id_map = HashMap::new();
 
         // Get existing accounts to avoid duplicates
-        let existing_accounts = self.account_service.get_accounts()
+        let existing_accounts = self.account_service.get_accounts(ledger_id)
             .await
             .map_err(|e| format!("Failed to fetch existing accounts: {}", e))?;
 
@@ -734,13 +749,13 @@ impl FireflyImportService {
                 name: firefly_account.name.clone(),
                 account_type: account_type.to_string(),
                 account_sub_type: None, // No specific subtype for imported accounts
-                balance: firefly_account.current_balance.unwrap_or(0.0),
+                balance: firefly_account.current_balance.unwrap_or(Decimal::ZERO),
                 currency: firefly_account.currency_code.clone(),
                 is_default: false, // Imported accounts are not default by default
             };
 
             // Create the account
-            match self.account_service.create_account(create_request).await {
+            match self.account_service.create_account(ledger_id, create_request).await {
                 Ok(account) => {
                     debug!("Created account {} with ID {}", firefly_account.name, account.id);
                     account_id_map.insert(firefly_account.id, account.id);
@@ -757,9 +772,9 @@ impl FireflyImportService {
         Ok(account_id_map)
     }
     // Import transactions from Firefly III to Rustler
-    async fn import_transactions(&self, transactions: Vec<FireflyTransaction>, account_id_map: &HashMap<String, Uuid>, result: &mut ImportResult) -> Result<(), String> {
+    async fn import_transactions(&self, ledger_id: Uuid, transactions: Vec<FireflyTransaction>, account_id_map: &HashMap<String, Uuid>, result: &mut ImportResult) -> Result<(), String> {
         // Get existing accounts to find accounts by name if they're not in the map
-        let existing_accounts = self.account_service.get_accounts()
+        let existing_accounts = self.account_service.get_accounts(ledger_id)
             .await
             .map_err(|e| format!("Failed to fetch existing accounts: {}", e))?;
 
@@ -785,12 +800,12 @@ impl FireflyImportService {
                         name: firefly_transaction.source_name.clone(),
                         account_type: "On Budget".to_string(), // Default to On Budget for new accounts
                         account_sub_type: None, // No specific subtype for automatically created accounts
-                        balance: 0.0, // Start with zero balance
+                        balance: Decimal::ZERO, // Start with zero balance
                         currency: "USD".to_string(), // Default currency
                         is_default: false,
                     };
 
-                    match self.account_service.create_account(create_request).await {
+                    match self.account_service.create_account(ledger_id, create_request).await {
                         Ok(account) => {
                             // Add the new account to our maps for future lookups
                             existing_account_names.insert(firefly_transaction.source_name.clone(), account.id);
@@ -846,12 +861,17 @@ impl FireflyImportService {
                 category,
                 budget_id: None, // Firefly III doesn't have direct budget mapping
                 transaction_date: Some(firefly_transaction.date),
+                splits: None,
+                status: None,
+                foreign_amount: None,
+                tags: Some(firefly_transaction.tags.clone()),
+                memo: firefly_transaction.notes.clone().filter(|notes| !notes.trim().is_empty()),
             };
             info!("Transaction type: {:?}", firefly_transaction.transaction_type);
             info!("Creating transaction: {:?}", create_request);
 
             // Create the transaction
-            match self.transaction_service.create_transaction(create_request.clone()).await {
+            match self.transaction_service.create_transaction(ledger_id, create_request.clone(), ChangeActor::import(result.batch_id)).await {
                 Ok(_) => {
                     result.transactions_imported += 1;
                 }
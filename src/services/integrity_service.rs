@@ -0,0 +1,116 @@
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::models::{BalanceMismatch, IntegrityReport, OrphanedTransaction, SelfTransferTransaction};
+
+/// Service for checking that account balances agree with the transaction history
+pub struct IntegrityService {
+    db: Pool<Postgres>,
+}
+
+impl IntegrityService {
+    /// Create a new IntegrityService with the given database pool
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self { db }
+    }
+
+    /// Recompute every account balance in a ledger from its transactions and report
+    /// mismatches, orphaned destinations and transactions whose source equals their destination.
+    /// With `repair`, mismatched balances are overwritten with the computed ones; the check and
+    /// the repair run in one database transaction so no balance can change in between.
+    pub async fn check_integrity(&self, ledger_id: Uuid, repair: bool) -> Result<IntegrityReport, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        // Lock the ledger's accounts so concurrent transactions cannot move balances mid-check
+        let accounts_checked = sqlx::query_scalar::<_, Uuid>("SELECT id FROM accounts WHERE ledger_id = $1 FOR UPDATE")
+            .bind(ledger_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .len() as i64;
+
+        // Positive amounts move money from source to destination, negative ones the other way,
+        // so the balance is simply incoming minus outgoing amounts; destinations in another
+        // currency receive the foreign amount. Opening balances are posted against an External
+        // "Initial Balance" account whose balance stays at zero, so that side is left out.
+        let balance_mismatches = sqlx::query_as::<_, BalanceMismatch>(
+            r#"
+            SELECT account_id, account_name, stored_balance, computed_balance,
+                   computed_balance - stored_balance AS difference
+            FROM (
+                SELECT a.id AS account_id, a.name AS account_name, a.balance AS stored_balance,
+                       COALESCE((SELECT SUM(COALESCE(t.foreign_amount, t.amount)) FROM transactions t
+                                 WHERE t.destination_account_id = a.id AND t.ledger_id = a.ledger_id
+                                   AND NOT (a.account_type = 'External' AND t.category = 'Initial Balance')), 0)
+                     - COALESCE((SELECT SUM(t.amount) FROM transactions t
+                                 WHERE t.source_account_id = a.id AND t.ledger_id = a.ledger_id
+                                   AND NOT (a.account_type = 'External' AND t.category = 'Initial Balance')), 0) AS computed_balance
+                FROM accounts a
+                WHERE a.ledger_id = $1
+            ) balances
+            WHERE computed_balance <> stored_balance
+            ORDER BY account_name
+            "#,
+        )
+        .bind(ledger_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let orphaned_transactions = sqlx::query_as::<_, OrphanedTransaction>(
+            r#"
+            SELECT t.id AS transaction_id, t.source_account_id, t.destination_account_id, t.destination_name,
+                   t.description, t.amount, t.transaction_date
+            FROM transactions t
+            WHERE t.ledger_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM accounts a WHERE a.id = t.destination_account_id AND a.ledger_id = t.ledger_id
+              )
+            ORDER BY t.transaction_date DESC
+            "#,
+        )
+        .bind(ledger_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let self_transfers = sqlx::query_as::<_, SelfTransferTransaction>(
+            r#"
+            SELECT id AS transaction_id, source_account_id AS account_id, description, amount, transaction_date
+            FROM transactions
+            WHERE ledger_id = $1 AND source_account_id = destination_account_id
+            ORDER BY transaction_date DESC
+            "#,
+        )
+        .bind(ledger_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let is_consistent = balance_mismatches.is_empty() && orphaned_transactions.is_empty() && self_transfers.is_empty();
+        let now = Utc::now();
+
+        if repair && !balance_mismatches.is_empty() {
+            for mismatch in &balance_mismatches {
+                sqlx::query("UPDATE accounts SET balance = $1, updated_at = $2 WHERE id = $3 AND ledger_id = $4")
+                    .bind(mismatch.computed_balance)
+                    .bind(now)
+                    .bind(mismatch.account_id)
+                    .bind(ledger_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            info!("Repaired {} account balance(s) in ledger {}", balance_mismatches.len(), ledger_id);
+        }
+
+        tx.commit().await?;
+
+        Ok(IntegrityReport {
+            accounts_checked,
+            repaired: repair && !balance_mismatches.is_empty(),
+            balance_mismatches,
+            orphaned_transactions,
+            self_transfers,
+            is_consistent,
+            checked_at: now,
+        })
+    }
+}
@@ -5,10 +5,19 @@ mod category_group_service;
 mod budget_service;
 mod budget_group_service;
 mod rule_service;
+mod rule_engine;
 mod transaction_rule_service;
 mod firefly_import_service;
 mod settings_service;
 mod rule_group_service;
+mod rule_suggestion_service;
+mod auth_service;
+mod recurring_transaction_service;
+mod reconciliation_service;
+mod integrity_service;
+mod exchange_rate_service;
+mod tag_service;
+mod transaction_change_service;
 
 pub use account_service::AccountService;
 pub use transaction_service::TransactionService;
@@ -21,3 +30,10 @@ pub use transaction_rule_service::TransactionRuleService;
 pub use firefly_import_service::FireflyImportService;
 pub use settings_service::SettingsService;
 pub use rule_group_service::RuleGroupService;
+pub use rule_suggestion_service::RuleSuggestionService;
+pub use auth_service::{AuthError, AuthService};
+pub use recurring_transaction_service::RecurringTransactionService;
+pub use reconciliation_service::ReconciliationService;
+pub use integrity_service::IntegrityService;
+pub use exchange_rate_service::{is_missing_exchange_rate, ExchangeRateService};
+pub use tag_service::TagService;
@@ -0,0 +1,173 @@
+use chrono::{Duration, NaiveTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{Pool, Postgres};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::models::{
+    ReconcileAccountRequest, Reconciliation, ReconciliationResult, TRANSACTION_STATUS_CLEARED,
+    TRANSACTION_STATUS_RECONCILED, TRANSACTION_STATUS_UNCLEARED,
+};
+
+/// Service for reconciling accounts against bank statements
+pub struct ReconciliationService {
+    db: Pool<Postgres>,
+}
+
+impl ReconciliationService {
+    /// Create a new ReconciliationService with the given database pool
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self { db }
+    }
+
+    /// Reconcile an account against a statement.
+    /// Computes the balance of the cleared transactions up to the statement date; when it matches
+    /// the statement balance those transactions are marked reconciled and locked against edits.
+    /// Returns None if the account does not exist in the ledger.
+    pub async fn reconcile_account(
+        &self,
+        ledger_id: Uuid,
+        account_id: Uuid,
+        req: ReconcileAccountRequest,
+    ) -> Result<Option<ReconciliationResult>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        // Lock the account so concurrent reconciliations see a consistent set of transactions
+        let account = sqlx::query_scalar::<_, Uuid>("SELECT id FROM accounts WHERE id = $1 AND ledger_id = $2 FOR UPDATE")
+            .bind(account_id)
+            .bind(ledger_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if account.is_none() {
+            return Ok(None);
+        }
+
+        // Transactions dated on the statement day are included
+        let statement_end = (req.statement_date + Duration::days(1)).and_time(NaiveTime::MIN).and_utc();
+
+        let cleared_balance = sqlx::query_scalar::<_, Option<Decimal>>(
+            r#"
+            SELECT SUM(CASE WHEN destination_account_id = $1 THEN COALESCE(foreign_amount, amount) ELSE -amount END)
+            FROM transactions
+            WHERE ledger_id = $2
+              AND (source_account_id = $1 OR destination_account_id = $1)
+              AND status IN ($3, $4)
+              AND transaction_date < $5
+            "#,
+        )
+        .bind(account_id)
+        .bind(ledger_id)
+        .bind(TRANSACTION_STATUS_CLEARED)
+        .bind(TRANSACTION_STATUS_RECONCILED)
+        .bind(statement_end)
+        .fetch_one(&mut *tx)
+        .await?
+        .unwrap_or(Decimal::ZERO);
+
+        let uncleared_count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM transactions
+            WHERE ledger_id = $1
+              AND (source_account_id = $2 OR destination_account_id = $2)
+              AND status = $3
+              AND transaction_date < $4
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(account_id)
+        .bind(TRANSACTION_STATUS_UNCLEARED)
+        .bind(statement_end)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let difference = req.statement_balance - cleared_balance;
+        let mut result = ReconciliationResult {
+            account_id,
+            statement_date: req.statement_date,
+            statement_balance: req.statement_balance,
+            cleared_balance,
+            difference,
+            reconciled: false,
+            transactions_reconciled: 0,
+            uncleared_count,
+            reconciliation: None,
+        };
+
+        // Nothing is locked unless the statement balances
+        if !difference.is_zero() {
+            return Ok(Some(result));
+        }
+
+        let reconciliation_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        // Record the reconciliation first so the transactions can reference it
+        sqlx::query(
+            r#"
+            INSERT INTO reconciliations (id, ledger_id, account_id, statement_date, statement_balance, cleared_balance, transaction_count, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, 0, $7)
+            "#,
+        )
+        .bind(reconciliation_id)
+        .bind(ledger_id)
+        .bind(account_id)
+        .bind(req.statement_date)
+        .bind(req.statement_balance)
+        .bind(cleared_balance)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let locked = sqlx::query(
+            r#"
+            UPDATE transactions
+            SET status = $1, reconciliation_id = $2, updated_at = $3
+            WHERE ledger_id = $4
+              AND (source_account_id = $5 OR destination_account_id = $5)
+              AND status = $6
+              AND transaction_date < $7
+            "#,
+        )
+        .bind(TRANSACTION_STATUS_RECONCILED)
+        .bind(reconciliation_id)
+        .bind(now)
+        .bind(ledger_id)
+        .bind(account_id)
+        .bind(TRANSACTION_STATUS_CLEARED)
+        .bind(statement_end)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        let reconciliation = sqlx::query_as::<_, Reconciliation>(
+            "UPDATE reconciliations SET transaction_count = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(locked as i32)
+        .bind(reconciliation_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        info!(
+            "Reconciled account {} as of {}: {} transaction(s) locked",
+            account_id, req.statement_date, locked
+        );
+
+        result.reconciled = true;
+        result.transactions_reconciled = locked;
+        result.reconciliation = Some(reconciliation);
+        Ok(Some(result))
+    }
+
+    /// Get past reconciliations of an account, newest first
+    pub async fn get_reconciliations(&self, ledger_id: Uuid, account_id: Uuid) -> Result<Vec<Reconciliation>, sqlx::Error> {
+        sqlx::query_as::<_, Reconciliation>(
+            "SELECT * FROM reconciliations WHERE ledger_id = $1 AND account_id = $2 ORDER BY statement_date DESC, created_at DESC",
+        )
+        .bind(ledger_id)
+        .bind(account_id)
+        .fetch_all(&self.db)
+        .await
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveTime, Utc};
+use sqlx::{Pool, Postgres};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::models::{
+    ChangeActor, CreateRecurringTransactionRequest, CreateTransactionRequest, OccurrenceStatus, Recurrence,
+    RecurringTransaction, RecurringTransactionResponse, RuleTrigger, Transaction, UpcomingOccurrence,
+    UpdateRecurringTransactionRequest,
+};
+use crate::services::TransactionRuleService;
+
+/// Upper bound on occurrences returned when listing upcoming transactions
+const MAX_UPCOMING: usize = 500;
+
+/// Service for managing recurring transactions and posting their due occurrences
+pub struct RecurringTransactionService {
+    db: Pool<Postgres>,
+    transaction_rule_service: Arc<TransactionRuleService>,
+}
+
+impl RecurringTransactionService {
+    /// Create a new RecurringTransactionService; occurrences are posted through the
+    /// TransactionRuleService so rules apply to them like to any other new transaction
+    pub fn new(db: Pool<Postgres>, transaction_rule_service: Arc<TransactionRuleService>) -> Self {
+        Self {
+            db,
+            transaction_rule_service,
+        }
+    }
+
+    /// Get all recurring transactions
+    pub async fn get_recurring_transactions(&self, ledger_id: Uuid) -> Result<Vec<RecurringTransactionResponse>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RecurringTransaction>(
+            "SELECT * FROM recurring_transactions WHERE ledger_id = $1 ORDER BY next_occurrence NULLS LAST, name",
+        )
+        .bind(ledger_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut responses = Vec::with_capacity(rows.len());
+        for row in rows {
+            match row.to_response() {
+                Ok(response) => responses.push(response),
+                Err(e) => error!("Failed to deserialize recurring transaction {}: {}", row.id, e),
+            }
+        }
+
+        Ok(responses)
+    }
+
+    /// Get a recurring transaction by ID
+    pub async fn get_recurring_transaction(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<RecurringTransactionResponse>, sqlx::Error> {
+        match self.find(ledger_id, id).await? {
+            Some(row) => Ok(Some(Self::to_response(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Create a new recurring transaction
+    pub async fn create_recurring_transaction(
+        &self,
+        ledger_id: Uuid,
+        req: CreateRecurringTransactionRequest,
+    ) -> Result<RecurringTransactionResponse, sqlx::Error> {
+        req.recurrence.validate().map_err(sqlx::Error::Protocol)?;
+        Self::validate_dates(req.start_date, req.end_date)?;
+        self.validate_template(ledger_id, &req.template).await?;
+
+        let now = Utc::now();
+        let next_occurrence = req.recurrence.next_on_or_after(req.start_date, req.end_date, req.start_date);
+
+        let row = sqlx::query_as::<_, RecurringTransaction>(
+            r#"
+            INSERT INTO recurring_transactions
+                (id, ledger_id, name, template_json, recurrence_json, start_date, end_date, next_occurrence, is_active, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ledger_id)
+        .bind(&req.name)
+        .bind(Self::to_json(&req.template)?)
+        .bind(Self::to_json(&req.recurrence)?)
+        .bind(req.start_date)
+        .bind(req.end_date)
+        .bind(next_occurrence)
+        .bind(req.is_active.unwrap_or(true))
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.db)
+        .await?;
+
+        info!("Created recurring transaction '{}' ({}), next occurrence {:?}", row.name, row.id, row.next_occurrence);
+        Self::to_response(&row)
+    }
+
+    /// Update an existing recurring transaction.
+    /// Changing the schedule never re-posts or re-opens occurrences that were already posted or skipped.
+    pub async fn update_recurring_transaction(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        req: UpdateRecurringTransactionRequest,
+    ) -> Result<Option<RecurringTransactionResponse>, sqlx::Error> {
+        let row = match self.find(ledger_id, id).await? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let current = Self::to_response(&row)?;
+
+        let template = req.template.unwrap_or(current.template);
+        let recurrence = req.recurrence.unwrap_or(current.recurrence);
+        let start_date = req.start_date.unwrap_or(current.start_date);
+        let end_date = match req.end_date {
+            Some(end_date) => Some(end_date),
+            None if req.clear_end_date => None,
+            None => current.end_date,
+        };
+
+        recurrence.validate().map_err(sqlx::Error::Protocol)?;
+        Self::validate_dates(start_date, end_date)?;
+        self.validate_template(ledger_id, &template).await?;
+
+        // Resume after the latest occurrence that was already handled
+        let last_handled = sqlx::query_scalar::<_, Option<NaiveDate>>(
+            "SELECT MAX(occurrence_date) FROM recurring_transaction_occurrences WHERE recurring_transaction_id = $1",
+        )
+        .bind(id)
+        .fetch_one(&self.db)
+        .await?;
+        let from = match last_handled.and_then(|date| date.succ_opt()) {
+            Some(date) => date.max(start_date),
+            None => start_date,
+        };
+        let next_occurrence = recurrence.next_on_or_after(start_date, end_date, from);
+
+        let row = sqlx::query_as::<_, RecurringTransaction>(
+            r#"
+            UPDATE recurring_transactions
+            SET name = $1, template_json = $2, recurrence_json = $3, start_date = $4, end_date = $5,
+                next_occurrence = $6, is_active = $7, updated_at = $8
+            WHERE id = $9 AND ledger_id = $10
+            RETURNING *
+            "#,
+        )
+        .bind(req.name.unwrap_or(current.name))
+        .bind(Self::to_json(&template)?)
+        .bind(Self::to_json(&recurrence)?)
+        .bind(start_date)
+        .bind(end_date)
+        .bind(next_occurrence)
+        .bind(req.is_active.unwrap_or(current.is_active))
+        .bind(Utc::now())
+        .bind(id)
+        .bind(ledger_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(Self::to_response(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Delete a recurring transaction; transactions already posted are kept
+    pub async fn delete_recurring_transaction(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM recurring_transactions WHERE id = $1 AND ledger_id = $2")
+            .bind(id)
+            .bind(ledger_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// List upcoming occurrences of all active recurring transactions up to and including `until`.
+    /// Occurrences that were skipped or posted early are included with their status.
+    pub async fn get_upcoming(&self, ledger_id: Uuid, until: NaiveDate) -> Result<Vec<UpcomingOccurrence>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RecurringTransaction>(
+            r#"
+            SELECT * FROM recurring_transactions
+            WHERE ledger_id = $1 AND is_active = true AND next_occurrence IS NOT NULL AND next_occurrence <= $2
+            "#,
+        )
+        .bind(ledger_id)
+        .bind(until)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut upcoming = Vec::new();
+        for row in rows {
+            upcoming.extend(self.occurrences_until(&row, until).await?);
+        }
+        upcoming.sort_by(|a, b| a.occurrence_date.cmp(&b.occurrence_date).then_with(|| a.name.cmp(&b.name)));
+        upcoming.truncate(MAX_UPCOMING);
+
+        Ok(upcoming)
+    }
+
+    /// List upcoming occurrences of one recurring transaction up to and including `until`
+    pub async fn get_upcoming_for(&self, ledger_id: Uuid, id: Uuid, until: NaiveDate) -> Result<Option<Vec<UpcomingOccurrence>>, sqlx::Error> {
+        match self.find(ledger_id, id).await? {
+            Some(row) => Ok(Some(self.occurrences_until(&row, until).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Skip an occurrence so no transaction is created for it (default: the next occurrence)
+    pub async fn skip_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence_date: Option<NaiveDate>,
+    ) -> Result<Option<RecurringTransactionResponse>, sqlx::Error> {
+        let row = match self.find(ledger_id, id).await? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let occurrence_date = self.resolve_occurrence(&row, occurrence_date).await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO recurring_transaction_occurrences (recurring_transaction_id, occurrence_date, status, transaction_id, created_at)
+            VALUES ($1, $2, 'skipped', NULL, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(row.id)
+        .bind(occurrence_date)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::Protocol(format!(
+                "Occurrence on {} has already been posted or skipped",
+                occurrence_date
+            )));
+        }
+
+        info!("Skipped occurrence {} of recurring transaction {}", occurrence_date, row.id);
+        let row = self.advance(&row).await?;
+        Ok(Some(Self::to_response(&row)?))
+    }
+
+    /// Post an occurrence now instead of waiting for its date (default: the next occurrence).
+    /// The occurrence will not be posted again when it becomes due.
+    pub async fn post_occurrence(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        occurrence_date: Option<NaiveDate>,
+    ) -> Result<Option<Transaction>, sqlx::Error> {
+        let row = match self.find(ledger_id, id).await? {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        let occurrence_date = self.resolve_occurrence(&row, occurrence_date).await?;
+
+        let transaction = match self.post(ledger_id, &row, occurrence_date).await? {
+            Some(transaction) => transaction,
+            None => {
+                return Err(sqlx::Error::Protocol(format!(
+                    "Occurrence on {} has already been posted or skipped",
+                    occurrence_date
+                )));
+            }
+        };
+
+        self.advance(&row).await?;
+        Ok(Some(transaction))
+    }
+
+    /// Post every occurrence that is due today or earlier, across all ledgers.
+    /// Returns the number of transactions created.
+    pub async fn post_due_transactions(&self) -> Result<usize, sqlx::Error> {
+        let today = Utc::now().date_naive();
+        let due = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r#"
+            SELECT id, ledger_id FROM recurring_transactions
+            WHERE is_active = true AND next_occurrence IS NOT NULL AND next_occurrence <= $1
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut posted = 0;
+        for (id, ledger_id) in due {
+            let mut row = match self.find(ledger_id, id).await? {
+                Some(row) => row,
+                None => continue,
+            };
+
+            while let Some(occurrence_date) = row.next_occurrence.filter(|date| *date <= today) {
+                match self.post(ledger_id, &row, occurrence_date).await {
+                    Ok(Some(transaction)) => {
+                        info!(
+                            "Posted occurrence {} of recurring transaction '{}' as transaction {}",
+                            occurrence_date, row.name, transaction.id
+                        );
+                        posted += 1;
+                    }
+                    // Skipped or already posted early
+                    Ok(None) => {}
+                    Err(e) => {
+                        // Leave the occurrence pending; it is retried on the next run
+                        error!(
+                            "Failed to post occurrence {} of recurring transaction {}: {:?}",
+                            occurrence_date, row.id, e
+                        );
+                        break;
+                    }
+                }
+                row = self.advance(&row).await?;
+            }
+        }
+
+        Ok(posted)
+    }
+
+    /// Spawn a background task that posts due occurrences every `period`
+    pub fn start_scheduler(self: Arc<Self>, period: std::time::Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                match self.post_due_transactions().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("Posted {} recurring transaction(s)", count),
+                    Err(e) => error!("Error posting recurring transactions: {:?}", e),
+                }
+            }
+        })
+    }
+
+    /// Claim an occurrence and create its transaction.
+    /// Returns None if the occurrence was already posted or skipped.
+    async fn post(&self, ledger_id: Uuid, row: &RecurringTransaction, occurrence_date: NaiveDate) -> Result<Option<Transaction>, sqlx::Error> {
+        let mut template: CreateTransactionRequest = serde_json::from_str(&row.template_json)
+            .map_err(|e| sqlx::Error::Protocol(format!("Failed to deserialize recurring transaction template: {}", e)))?;
+
+        // Due occurrences are dated on their scheduled day, early ones on the day they are posted
+        let today = Utc::now().date_naive();
+        template.transaction_date = Some(if occurrence_date <= today {
+            occurrence_date.and_time(NaiveTime::MIN).and_utc()
+        } else {
+            Utc::now()
+        });
+
+        // The claim and the transaction commit together, so a failure at any point leaves the
+        // occurrence pending. A concurrent run waits on the claimed row and then finds it taken.
+        let mut tx = self.db.begin().await?;
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO recurring_transaction_occurrences (recurring_transaction_id, occurrence_date, status, transaction_id, created_at)
+            VALUES ($1, $2, 'posted', NULL, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(row.id)
+        .bind(occurrence_date)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let actor = ChangeActor::recurring(row.id);
+        let transaction = self.transaction_rule_service.create_transaction_in(&mut tx, ledger_id, template, actor).await?;
+        sqlx::query(
+            "UPDATE recurring_transaction_occurrences SET transaction_id = $1 WHERE recurring_transaction_id = $2 AND occurrence_date = $3",
+        )
+        .bind(transaction.id)
+        .bind(row.id)
+        .bind(occurrence_date)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(self.transaction_rule_service.apply_creation_rules(ledger_id, transaction, RuleTrigger::Create, actor).await))
+    }
+
+    /// Move next_occurrence past every occurrence that has been posted or skipped
+    async fn advance(&self, row: &RecurringTransaction) -> Result<RecurringTransaction, sqlx::Error> {
+        let recurrence = Self::recurrence(row)?;
+        let handled = self.handled_occurrences(row.id).await?;
+
+        let mut next = row.next_occurrence;
+        while let Some(date) = next {
+            if !handled.contains_key(&date) {
+                break;
+            }
+            next = date
+                .succ_opt()
+                .and_then(|from| recurrence.next_on_or_after(row.start_date, row.end_date, from));
+        }
+
+        sqlx::query_as::<_, RecurringTransaction>(
+            "UPDATE recurring_transactions SET next_occurrence = $1, updated_at = $2 WHERE id = $3 RETURNING *",
+        )
+        .bind(next)
+        .bind(Utc::now())
+        .bind(row.id)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Occurrences of a recurring transaction from its next occurrence up to `until`
+    async fn occurrences_until(&self, row: &RecurringTransaction, until: NaiveDate) -> Result<Vec<UpcomingOccurrence>, sqlx::Error> {
+        let recurrence = Self::recurrence(row)?;
+        let template: CreateTransactionRequest = serde_json::from_str(&row.template_json)
+            .map_err(|e| sqlx::Error::Protocol(format!("Failed to deserialize recurring transaction template: {}", e)))?;
+        let handled = self.handled_occurrences(row.id).await?;
+
+        let mut occurrences = Vec::new();
+        let mut next = row.next_occurrence;
+        while let Some(date) = next.filter(|date| *date <= until) {
+            if occurrences.len() >= MAX_UPCOMING {
+                break;
+            }
+            let (status, transaction_id) = match handled.get(&date) {
+                Some((status, transaction_id)) => (*status, *transaction_id),
+                None => (OccurrenceStatus::Scheduled, None),
+            };
+            occurrences.push(UpcomingOccurrence {
+                recurring_transaction_id: row.id,
+                name: row.name.clone(),
+                occurrence_date: date,
+                status,
+                transaction_id,
+                description: template.description.clone(),
+                amount: template.amount,
+                source_account_id: template.source_account_id,
+                destination_name: template.destination_name.clone(),
+            });
+            next = date
+                .succ_opt()
+                .and_then(|from| recurrence.next_on_or_after(row.start_date, row.end_date, from));
+        }
+
+        Ok(occurrences)
+    }
+
+    /// Default to the next occurrence, and make sure an explicit date is on the schedule and still pending
+    async fn resolve_occurrence(&self, row: &RecurringTransaction, occurrence_date: Option<NaiveDate>) -> Result<NaiveDate, sqlx::Error> {
+        let next = row
+            .next_occurrence
+            .ok_or_else(|| sqlx::Error::Protocol("Recurring transaction has no remaining occurrences".to_string()))?;
+        let date = match occurrence_date {
+            Some(date) => date,
+            None => return Ok(next),
+        };
+
+        let recurrence = Self::recurrence(row)?;
+        if date < next || recurrence.next_on_or_after(row.start_date, row.end_date, date) != Some(date) {
+            return Err(sqlx::Error::Protocol(format!(
+                "{} is not an upcoming occurrence of this recurring transaction",
+                date
+            )));
+        }
+
+        Ok(date)
+    }
+
+    /// Occurrences that were posted or skipped, keyed by date
+    async fn handled_occurrences(&self, id: Uuid) -> Result<HashMap<NaiveDate, (OccurrenceStatus, Option<Uuid>)>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (NaiveDate, String, Option<Uuid>)>(
+            "SELECT occurrence_date, status, transaction_id FROM recurring_transaction_occurrences WHERE recurring_transaction_id = $1",
+        )
+        .bind(id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(date, status, transaction_id)| {
+                let status = if status == "skipped" {
+                    OccurrenceStatus::Skipped
+                } else {
+                    OccurrenceStatus::Posted
+                };
+                (date, (status, transaction_id))
+            })
+            .collect())
+    }
+
+    /// The source account and references of the template must belong to the ledger
+    async fn validate_template(&self, ledger_id: Uuid, template: &CreateTransactionRequest) -> Result<(), sqlx::Error> {
+        let account_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM accounts WHERE id = $1 AND ledger_id = $2)")
+            .bind(template.source_account_id)
+            .bind(ledger_id)
+            .fetch_one(&self.db)
+            .await?;
+        if !account_exists {
+            return Err(sqlx::Error::Protocol(format!(
+                "Invalid reference: accounts {} not found in this ledger",
+                template.source_account_id
+            )));
+        }
+
+        if template.amount <= rust_decimal::Decimal::ZERO {
+            return Err(sqlx::Error::Protocol("Recurring transaction amount must be positive".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn validate_dates(start_date: NaiveDate, end_date: Option<NaiveDate>) -> Result<(), sqlx::Error> {
+        if end_date.is_some_and(|end| end < start_date) {
+            return Err(sqlx::Error::Protocol("end_date must not be before start_date".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn find(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<RecurringTransaction>, sqlx::Error> {
+        sqlx::query_as::<_, RecurringTransaction>("SELECT * FROM recurring_transactions WHERE id = $1 AND ledger_id = $2")
+            .bind(id)
+            .bind(ledger_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    fn recurrence(row: &RecurringTransaction) -> Result<Recurrence, sqlx::Error> {
+        serde_json::from_str(&row.recurrence_json)
+            .map_err(|e| sqlx::Error::Protocol(format!("Failed to deserialize recurrence: {}", e)))
+    }
+
+    fn to_response(row: &RecurringTransaction) -> Result<RecurringTransactionResponse, sqlx::Error> {
+        row.to_response().map_err(|e| {
+            error!("Failed to deserialize recurring transaction {}: {}", row.id, e);
+            sqlx::Error::Protocol(format!("Failed to deserialize recurring transaction: {}", e))
+        })
+    }
+
+    fn to_json<T: serde::Serialize>(value: &T) -> Result<String, sqlx::Error> {
+        serde_json::to_string(value).map_err(|e| sqlx::Error::Protocol(format!("Failed to serialize recurring transaction: {}", e)))
+    }
+}
@@ -0,0 +1,993 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use regex::{Regex, RegexBuilder};
+use rust_decimal::Decimal;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::models::{
+    ActionType, ConditionType, Rule, RuleAction, RuleCondition, RuleConditionNode, RuleSplitLine, RuleSplitShare, RuleTrigger, Transaction,
+    TransactionSplit, TransactionSplitRequest, UpdateTransactionRequest,
+};
+
+/// A condition tree with its values parsed once: text lower-cased, patterns compiled, IDs, amounts,
+/// dates and weekdays parsed. Values that do not parse never match, not even in the "not" variants.
+#[derive(Debug, Clone)]
+pub(crate) enum CompiledCondition {
+    All(Vec<CompiledCondition>),
+    Any(Vec<CompiledCondition>),
+    DescriptionContains(String),
+    DescriptionNotContains(String),
+    DescriptionStartsWith(String),
+    DescriptionEquals(String),
+    DescriptionNotEquals(String),
+    DescriptionMatchesRegex(Option<Regex>),
+    SourceAccountEquals(Option<Uuid>),
+    SourceAccountNotEquals(Option<Uuid>),
+    DestinationAccountEquals(Option<Uuid>),
+    DestinationAccountNotEquals(Option<Uuid>),
+    DestinationNameContains(String),
+    DestinationNameNotContains(String),
+    DestinationNameEquals(String),
+    DestinationNameNotEquals(String),
+    DestinationNameMatchesRegex(Option<Regex>),
+    AmountGreaterThan(Option<Decimal>),
+    AmountLessThan(Option<Decimal>),
+    AmountEquals(Option<Decimal>),
+    DateBefore(Option<NaiveDate>),
+    DateAfter(Option<NaiveDate>),
+    DateEquals(Option<NaiveDate>),
+    WeekdayIs(Option<Vec<Weekday>>),
+    CategoryIsEmpty,
+    BudgetIsEmpty,
+}
+
+/// Compile a pattern; like the other text conditions it ignores case unless it turns that off with `(?-i)`
+fn parse_regex(value: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(value).case_insensitive(true).build()
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
+/// Parse a comma-separated list of weekdays ("sat,sunday")
+fn parse_weekdays(value: &str) -> Option<Vec<Weekday>> {
+    let weekdays: Option<Vec<Weekday>> = value.split(',').map(|day| day.trim().parse::<Weekday>().ok()).collect();
+    weekdays.filter(|days| !days.is_empty())
+}
+
+impl CompiledCondition {
+    pub(crate) fn compile(node: &RuleConditionNode) -> Self {
+        match node {
+            RuleConditionNode::All { all } => Self::All(compile_conditions(all)),
+            RuleConditionNode::Any { any } => Self::Any(compile_conditions(any)),
+            RuleConditionNode::Condition(condition) => Self::compile_condition(condition),
+        }
+    }
+
+    fn compile_condition(condition: &RuleCondition) -> Self {
+        let text = || condition.value.to_lowercase();
+        let id = || Uuid::parse_str(condition.value.trim()).ok();
+        let amount = || condition.value.trim().parse::<Decimal>().ok();
+        let regex = || parse_regex(&condition.value).ok();
+        let date = || parse_date(&condition.value);
+
+        match condition.condition_type {
+            ConditionType::DescriptionContains => Self::DescriptionContains(text()),
+            ConditionType::DescriptionNotContains => Self::DescriptionNotContains(text()),
+            ConditionType::DescriptionStartsWith => Self::DescriptionStartsWith(text()),
+            ConditionType::DescriptionEquals => Self::DescriptionEquals(text()),
+            ConditionType::DescriptionNotEquals => Self::DescriptionNotEquals(text()),
+            ConditionType::DescriptionMatchesRegex => Self::DescriptionMatchesRegex(regex()),
+            ConditionType::SourceAccountEquals => Self::SourceAccountEquals(id()),
+            ConditionType::SourceAccountNotEquals => Self::SourceAccountNotEquals(id()),
+            ConditionType::DestinationAccountEquals => Self::DestinationAccountEquals(id()),
+            ConditionType::DestinationAccountNotEquals => Self::DestinationAccountNotEquals(id()),
+            ConditionType::DestinationNameContains => Self::DestinationNameContains(text()),
+            ConditionType::DestinationNameNotContains => Self::DestinationNameNotContains(text()),
+            ConditionType::DestinationNameEquals => Self::DestinationNameEquals(text()),
+            ConditionType::DestinationNameNotEquals => Self::DestinationNameNotEquals(text()),
+            ConditionType::DestinationNameMatchesRegex => Self::DestinationNameMatchesRegex(regex()),
+            ConditionType::AmountGreaterThan => Self::AmountGreaterThan(amount()),
+            ConditionType::AmountLessThan => Self::AmountLessThan(amount()),
+            ConditionType::AmountEquals => Self::AmountEquals(amount()),
+            ConditionType::DateBefore => Self::DateBefore(date()),
+            ConditionType::DateAfter => Self::DateAfter(date()),
+            ConditionType::DateEquals => Self::DateEquals(date()),
+            ConditionType::WeekdayIs => Self::WeekdayIs(parse_weekdays(&condition.value)),
+            ConditionType::CategoryIsEmpty => Self::CategoryIsEmpty,
+            ConditionType::BudgetIsEmpty => Self::BudgetIsEmpty,
+        }
+    }
+
+    /// Whether a transaction meets the condition; text comparisons ignore case and dates are UTC days
+    pub(crate) fn matches(&self, transaction: &Transaction) -> bool {
+        let description = || transaction.description.to_lowercase();
+        let destination_name = || transaction.destination_name.as_deref().map(str::to_lowercase);
+        let day = || transaction.transaction_date.date_naive();
+
+        match self {
+            Self::All(conditions) => conditions.iter().all(|condition| condition.matches(transaction)),
+            Self::Any(conditions) => conditions.iter().any(|condition| condition.matches(transaction)),
+            Self::DescriptionContains(value) => description().contains(value.as_str()),
+            Self::DescriptionNotContains(value) => !description().contains(value.as_str()),
+            Self::DescriptionStartsWith(value) => description().starts_with(value.as_str()),
+            Self::DescriptionEquals(value) => description() == *value,
+            Self::DescriptionNotEquals(value) => description() != *value,
+            Self::DescriptionMatchesRegex(regex) => regex.as_ref().is_some_and(|regex| regex.is_match(&transaction.description)),
+            Self::SourceAccountEquals(id) => *id == Some(transaction.source_account_id),
+            Self::SourceAccountNotEquals(id) => id.is_some_and(|id| id != transaction.source_account_id),
+            Self::DestinationAccountEquals(id) => *id == Some(transaction.destination_account_id),
+            Self::DestinationAccountNotEquals(id) => id.is_some_and(|id| id != transaction.destination_account_id),
+            Self::DestinationNameContains(value) => destination_name().is_some_and(|name| name.contains(value.as_str())),
+            Self::DestinationNameNotContains(value) => destination_name().is_none_or(|name| !name.contains(value.as_str())),
+            Self::DestinationNameEquals(value) => destination_name().is_some_and(|name| name == *value),
+            Self::DestinationNameNotEquals(value) => destination_name().is_none_or(|name| name != *value),
+            Self::DestinationNameMatchesRegex(regex) => match (regex, &transaction.destination_name) {
+                (Some(regex), Some(name)) => regex.is_match(name),
+                _ => false,
+            },
+            Self::AmountGreaterThan(value) => value.is_some_and(|value| transaction.amount > value),
+            Self::AmountLessThan(value) => value.is_some_and(|value| transaction.amount < value),
+            Self::AmountEquals(value) => value.is_some_and(|value| transaction.amount == value),
+            Self::DateBefore(date) => date.is_some_and(|date| day() < date),
+            Self::DateAfter(date) => date.is_some_and(|date| day() > date),
+            Self::DateEquals(date) => date.is_some_and(|date| day() == date),
+            Self::WeekdayIs(weekdays) => weekdays.as_ref().is_some_and(|weekdays| weekdays.contains(&day().weekday())),
+            Self::CategoryIsEmpty => {
+                let category = transaction.category.trim();
+                category.is_empty() || category == "Uncategorized"
+            }
+            Self::BudgetIsEmpty => transaction.budget_id.is_none(),
+        }
+    }
+}
+
+/// Check the values of a condition tree that would otherwise silently never match:
+/// patterns, dates and weekdays must parse and groups must not be empty
+pub(crate) fn validate_conditions(nodes: &[RuleConditionNode]) -> Result<(), String> {
+    for node in nodes {
+        match node {
+            RuleConditionNode::All { all: nodes } | RuleConditionNode::Any { any: nodes } => {
+                if nodes.is_empty() {
+                    return Err("Condition groups need at least one condition".to_string());
+                }
+                validate_conditions(nodes)?;
+            }
+            RuleConditionNode::Condition(condition) => {
+                let value = &condition.value;
+                let valid = match condition.condition_type {
+                    ConditionType::DescriptionMatchesRegex | ConditionType::DestinationNameMatchesRegex => {
+                        parse_regex(value).map_err(|e| format!("Invalid regular expression '{}': {}", value, e))?;
+                        true
+                    }
+                    ConditionType::DateBefore | ConditionType::DateAfter | ConditionType::DateEquals => parse_date(value).is_some(),
+                    ConditionType::WeekdayIs => parse_weekdays(value).is_some(),
+                    _ => true,
+                };
+                if !valid {
+                    return Err(format!("Invalid value '{}' for condition {:?}", value, condition.condition_type));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Split shares must each be positive and add up to 100 percent
+fn valid_split_shares(shares: &[RuleSplitShare]) -> bool {
+    !shares.is_empty()
+        && shares.iter().all(|share| share.percentage > Decimal::ZERO)
+        && shares.iter().map(|share| share.percentage).sum::<Decimal>() == Decimal::ONE_HUNDRED
+}
+
+/// An action with its value validated once
+#[derive(Debug, Clone)]
+pub(crate) enum CompiledAction {
+    SetCategory(String),
+    SetBudget(Uuid),
+    SetDescription(String),
+    SetDestinationName(String),
+    AddTag(String),
+    SetDestinationAccount(Uuid),
+    PrependDescription(String),
+    AppendDescription(String),
+    ReplaceDescription { pattern: Regex, replacement: String },
+    SetMemo(String),
+    SplitByPercentage(Vec<RuleSplitShare>),
+}
+
+impl CompiledAction {
+    /// Compile an action; an ID that does not parse, an empty tag, a missing or invalid pattern or
+    /// split shares that do not add up yield nothing. Stopping is a property of the rule, not an action.
+    pub(crate) fn compile(action: &RuleAction) -> Option<Self> {
+        match action.action_type {
+            ActionType::SetCategory => Some(Self::SetCategory(action.value.clone())),
+            ActionType::SetBudget => Uuid::parse_str(action.value.trim()).ok().map(Self::SetBudget),
+            ActionType::SetDescription => Some(Self::SetDescription(action.value.clone())),
+            ActionType::SetDestinationName => Some(Self::SetDestinationName(action.value.clone())),
+            ActionType::AddTag => {
+                let tag = action.value.trim();
+                (!tag.is_empty()).then(|| Self::AddTag(tag.to_string()))
+            }
+            ActionType::SetDestinationAccount => Uuid::parse_str(action.value.trim()).ok().map(Self::SetDestinationAccount),
+            ActionType::PrependDescription => Some(Self::PrependDescription(action.value.clone())),
+            ActionType::AppendDescription => Some(Self::AppendDescription(action.value.clone())),
+            ActionType::ReplaceDescription => {
+                let pattern = parse_regex(action.pattern.as_deref()?).ok()?;
+                Some(Self::ReplaceDescription { pattern, replacement: action.value.clone() })
+            }
+            ActionType::SetMemo => Some(Self::SetMemo(action.value.clone())),
+            ActionType::SplitByPercentage => {
+                let shares = action.splits.as_ref().filter(|shares| valid_split_shares(shares))?;
+                Some(Self::SplitByPercentage(shares.clone()))
+            }
+            ActionType::StopProcessing => None,
+        }
+    }
+
+    /// Apply the action to a transaction's values. Text is only prepended or appended when the
+    /// description does not already start or end with it, so running the rules again changes nothing.
+    pub(crate) fn apply(&self, transaction: &mut Transaction) {
+        match self {
+            Self::SetCategory(category) => transaction.category = category.clone(),
+            Self::SetBudget(budget_id) => transaction.budget_id = Some(*budget_id),
+            Self::SetDescription(description) => transaction.description = description.clone(),
+            Self::SetDestinationName(name) => transaction.destination_name = Some(name.clone()),
+            Self::AddTag(tag) => {
+                if !transaction.tags.contains(tag) {
+                    transaction.tags.push(tag.clone());
+                    transaction.tags.sort();
+                }
+            }
+            // The name is looked up from the account when the change is written
+            Self::SetDestinationAccount(account_id) => {
+                if *account_id != transaction.destination_account_id && *account_id != transaction.source_account_id {
+                    transaction.destination_account_id = *account_id;
+                    transaction.destination_name = None;
+                }
+            }
+            Self::PrependDescription(text) => {
+                if !transaction.description.starts_with(text.as_str()) {
+                    transaction.description = format!("{}{}", text, transaction.description);
+                }
+            }
+            Self::AppendDescription(text) => {
+                if !transaction.description.ends_with(text.as_str()) {
+                    transaction.description.push_str(text);
+                }
+            }
+            Self::ReplaceDescription { pattern, replacement } => {
+                transaction.description = pattern.replace_all(&transaction.description, replacement.as_str()).into_owned();
+            }
+            Self::SetMemo(memo) => transaction.memo = Some(memo.clone()),
+            Self::SplitByPercentage(shares) => {
+                // Lines are rounded to cents; the last line takes what is left so they add up exactly
+                let (amount, transaction_id, now) = (transaction.amount, transaction.id, transaction.updated_at);
+                let mut remaining = amount;
+                transaction.splits = shares
+                    .iter()
+                    .enumerate()
+                    .map(|(i, share)| {
+                        let line_amount = if i + 1 == shares.len() {
+                            remaining
+                        } else {
+                            (amount * share.percentage / Decimal::ONE_HUNDRED).round_dp(2)
+                        };
+                        remaining -= line_amount;
+                        TransactionSplit {
+                            id: Uuid::nil(), // Written as new split lines
+                            transaction_id,
+                            amount: line_amount,
+                            category_id: share.category_id,
+                            budget_id: share.budget_id,
+                            memo: share.memo.clone(),
+                            created_at: now,
+                            updated_at: now,
+                        }
+                    })
+                    .collect();
+            }
+        }
+    }
+}
+
+/// Check the values of actions that would otherwise be silently ignored: account IDs, patterns
+/// and split shares
+pub(crate) fn validate_actions(actions: &[RuleAction]) -> Result<(), String> {
+    for action in actions {
+        match action.action_type {
+            ActionType::SetDestinationAccount if Uuid::parse_str(action.value.trim()).is_err() => {
+                return Err(format!("Invalid value '{}' for action {:?}", action.value, action.action_type));
+            }
+            ActionType::ReplaceDescription => {
+                let pattern = action.pattern.as_deref().ok_or("Action ReplaceDescription needs a pattern")?;
+                parse_regex(pattern).map_err(|e| format!("Invalid regular expression '{}': {}", pattern, e))?;
+            }
+            ActionType::SplitByPercentage if !action.splits.as_deref().is_some_and(valid_split_shares) => {
+                return Err("Split shares must be positive and add up to 100 percent".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// A rule parsed from its stored JSON, ready to be evaluated against many transactions
+#[derive(Debug, Clone)]
+pub(crate) struct CompiledRule {
+    pub id: Uuid,
+    pub name: String,
+    pub conditions: Vec<CompiledCondition>,
+    pub actions: Vec<CompiledAction>,
+    /// No rules after this one apply to the transactions it matches
+    pub stop_processing: bool,
+    /// Group of the rule when that group stops after its first matching rule: once this rule
+    /// matches, the later rules of the group are skipped
+    pub first_match_group: Option<Uuid>,
+    /// Events on which the rule applies automatically
+    pub triggers: Vec<RuleTrigger>,
+}
+
+impl CompiledRule {
+    pub(crate) fn compile(rule: &Rule) -> Result<Self, serde_json::Error> {
+        let conditions: Vec<RuleConditionNode> = serde_json::from_str(&rule.conditions_json)?;
+        let actions: Vec<RuleAction> = serde_json::from_str(&rule.actions_json)?;
+        let triggers: Vec<RuleTrigger> = serde_json::from_str(&rule.triggers_json)?;
+
+        let mut compiled_actions = Vec::with_capacity(actions.len());
+        let mut stop_processing = false;
+        for action in &actions {
+            if action.action_type == ActionType::StopProcessing {
+                stop_processing = true;
+                continue;
+            }
+            match CompiledAction::compile(action) {
+                Some(compiled) => compiled_actions.push(compiled),
+                None => error!("Ignoring invalid {:?} action '{}' in rule {}", action.action_type, action.value, rule.id),
+            }
+        }
+
+        Ok(Self {
+            id: rule.id,
+            name: rule.name.clone(),
+            conditions: compile_conditions(&conditions),
+            actions: compiled_actions,
+            stop_processing,
+            first_match_group: None,
+            triggers,
+        })
+    }
+
+    /// Whether a transaction meets all conditions of the rule
+    pub(crate) fn matches(&self, transaction: &Transaction) -> bool {
+        conditions_match(&self.conditions, transaction)
+    }
+}
+
+/// Compile rules in the given order, skipping (and logging) rules whose stored JSON is invalid
+pub(crate) fn compile_rules(rules: &[Rule]) -> Vec<CompiledRule> {
+    rules
+        .iter()
+        .filter_map(|rule| match CompiledRule::compile(rule) {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                error!("Failed to deserialize rule {}: {}", rule.id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn compile_conditions(conditions: &[RuleConditionNode]) -> Vec<CompiledCondition> {
+    conditions.iter().map(CompiledCondition::compile).collect()
+}
+
+/// Whether a transaction meets all conditions; an empty list matches every transaction
+pub(crate) fn conditions_match(conditions: &[CompiledCondition], transaction: &Transaction) -> bool {
+    conditions.iter().all(|condition| condition.matches(transaction))
+}
+
+/// Result of evaluating rules against one transaction
+#[derive(Debug, Clone)]
+pub(crate) struct RuleOutcome {
+    /// The transaction with the actions of all matching rules applied
+    pub transaction: Transaction,
+    /// Rules that matched, in the order they were applied
+    pub rule_ids: Vec<Uuid>,
+}
+
+/// Update request setting the fields that differ between a transaction and its updated values
+pub(crate) fn update_request(original: &Transaction, after: &Transaction) -> UpdateTransactionRequest {
+    fn changed<T: PartialEq>(before: T, after: T) -> Option<T> {
+        (before != after).then_some(after)
+    }
+    let splits = changed(RuleSplitLine::from_splits(&original.splits), RuleSplitLine::from_splits(&after.splits));
+
+    UpdateTransactionRequest {
+        destination_account_id: changed(original.destination_account_id, after.destination_account_id),
+        destination_name: changed(original.destination_name.as_ref(), after.destination_name.as_ref()).flatten().cloned(),
+        description: changed(&original.description, &after.description).cloned(),
+        amount: None,
+        category: changed(&original.category, &after.category).cloned(),
+        budget_id: changed(original.budget_id, after.budget_id).flatten(),
+        transaction_date: None,
+        splits: splits.map(|lines| {
+            lines
+                .into_iter()
+                .map(|line| TransactionSplitRequest {
+                    amount: line.amount,
+                    category_id: line.category_id,
+                    category: None,
+                    budget_id: line.budget_id,
+                    memo: line.memo,
+                })
+                .collect()
+        }),
+        status: None,
+        foreign_amount: None,
+        tags: changed(&original.tags, &after.tags).cloned(),
+        memo: changed(original.memo.as_ref(), after.memo.as_ref()).flatten().cloned(),
+    }
+}
+
+/// Evaluate rules in order against a transaction. Conditions are checked against the
+/// transaction as it is, so one rule's actions do not affect whether a later rule matches; later
+/// rules override the values set by earlier ones, until a matching rule that stops processing.
+/// Rules of a group that already had its first match are skipped. Returns `None` when no rule matched.
+pub(crate) fn evaluate_rules(rules: &[CompiledRule], transaction: &Transaction) -> Option<RuleOutcome> {
+    let mut outcome = RuleOutcome { transaction: transaction.clone(), rule_ids: Vec::new() };
+    let mut matched_groups = Vec::new();
+
+    for rule in rules {
+        if rule.first_match_group.is_some_and(|group| matched_groups.contains(&group)) {
+            continue;
+        }
+        if !rule.matches(transaction) {
+            continue;
+        }
+        matched_groups.extend(rule.first_match_group);
+        debug!("Rule {} matched for transaction {}", rule.name, transaction.id);
+
+        for action in &rule.actions {
+            action.apply(&mut outcome.transaction);
+        }
+        outcome.rule_ids.push(rule.id);
+
+        if rule.stop_processing {
+            debug!("Rule {} stops processing for transaction {}", rule.name, transaction.id);
+            break;
+        }
+    }
+
+    (!outcome.rule_ids.is_empty()).then_some(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::models::RuleFieldValues;
+
+    /// A grocery purchase on Saturday 2026-03-14
+    fn transaction() -> Transaction {
+        let now = Utc::now();
+        Transaction {
+            id: Uuid::new_v4(),
+            source_account_id: Uuid::new_v4(),
+            destination_account_id: Uuid::new_v4(),
+            destination_name: Some("Trader Joe's".to_string()),
+            description: "Weekly Groceries".to_string(),
+            amount: dec!(42.50),
+            category: "Uncategorized".to_string(),
+            category_id: None,
+            budget_id: None,
+            transaction_date: Utc.with_ymd_and_hms(2026, 3, 14, 12, 0, 0).unwrap(),
+            created_at: now,
+            updated_at: now,
+            status: "uncleared".to_string(),
+            reconciliation_id: None,
+            foreign_amount: None,
+            foreign_currency: None,
+            memo: None,
+            splits: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn condition(condition_type: ConditionType, value: &str) -> CompiledCondition {
+        CompiledCondition::compile(&RuleCondition { condition_type, value: value.to_string() }.into())
+    }
+
+    fn action(action_type: ActionType, value: &str) -> Option<CompiledAction> {
+        CompiledAction::compile(&RuleAction { action_type, value: value.to_string(), pattern: None, splits: None })
+    }
+
+    fn share(percentage: Decimal) -> RuleSplitShare {
+        RuleSplitShare { percentage, category_id: None, budget_id: None, memo: None }
+    }
+
+    fn rule(conditions: Vec<CompiledCondition>, actions: Vec<CompiledAction>) -> CompiledRule {
+        CompiledRule { id: Uuid::new_v4(), name: "test".to_string(), conditions, actions, stop_processing: false, first_match_group: None, triggers: RuleTrigger::all() }
+    }
+
+    #[test]
+    fn text_conditions_ignore_case() {
+        let t = transaction();
+        let cases = [
+            (ConditionType::DescriptionContains, "GROCERIES", true),
+            (ConditionType::DescriptionContains, "rent", false),
+            (ConditionType::DescriptionStartsWith, "weekly", true),
+            (ConditionType::DescriptionStartsWith, "groceries", false),
+            (ConditionType::DescriptionEquals, "weekly groceries", true),
+            (ConditionType::DescriptionEquals, "weekly", false),
+            (ConditionType::DestinationNameContains, "joe's", true),
+            (ConditionType::DestinationNameContains, "aldi", false),
+            (ConditionType::DestinationNameEquals, "TRADER JOE'S", true),
+            (ConditionType::DestinationNameEquals, "trader", false),
+        ];
+        for (condition_type, value, expected) in cases {
+            assert_eq!(condition(condition_type.clone(), value).matches(&t), expected, "{:?} {}", condition_type, value);
+        }
+    }
+
+    #[test]
+    fn destination_name_conditions_never_match_without_a_name() {
+        let t = Transaction { destination_name: None, ..transaction() };
+        assert!(!condition(ConditionType::DestinationNameContains, "").matches(&t));
+        assert!(!condition(ConditionType::DestinationNameEquals, "").matches(&t));
+    }
+
+    #[test]
+    fn account_conditions_compare_ids() {
+        let t = transaction();
+        let source = t.source_account_id.to_string();
+        let destination = t.destination_account_id.to_string();
+
+        assert!(condition(ConditionType::SourceAccountEquals, &source).matches(&t));
+        assert!(condition(ConditionType::SourceAccountEquals, &source.to_uppercase()).matches(&t));
+        assert!(!condition(ConditionType::SourceAccountEquals, &destination).matches(&t));
+        assert!(condition(ConditionType::DestinationAccountEquals, &destination).matches(&t));
+        assert!(!condition(ConditionType::DestinationAccountEquals, &source).matches(&t));
+        assert!(!condition(ConditionType::DestinationAccountEquals, "not-an-id").matches(&t));
+    }
+
+    #[test]
+    fn amount_conditions_compare_decimals() {
+        let t = transaction();
+        let cases = [
+            (ConditionType::AmountGreaterThan, "40", true),
+            (ConditionType::AmountGreaterThan, "42.50", false),
+            (ConditionType::AmountLessThan, " 50 ", true),
+            (ConditionType::AmountLessThan, "42.5", false),
+            (ConditionType::AmountEquals, "42.5", true),
+            (ConditionType::AmountEquals, "42.49", false),
+            (ConditionType::AmountEquals, "lots", false),
+            (ConditionType::AmountGreaterThan, "", false),
+        ];
+        for (condition_type, value, expected) in cases {
+            assert_eq!(condition(condition_type.clone(), value).matches(&t), expected, "{:?} {}", condition_type, value);
+        }
+    }
+
+    #[test]
+    fn not_conditions_negate_their_counterparts() {
+        let t = transaction();
+        let source = t.source_account_id.to_string();
+        let cases = [
+            (ConditionType::DescriptionNotContains, "rent", true),
+            (ConditionType::DescriptionNotContains, "GROCERIES", false),
+            (ConditionType::DescriptionNotEquals, "weekly", true),
+            (ConditionType::DescriptionNotEquals, "weekly groceries", false),
+            (ConditionType::DestinationNameNotContains, "aldi", true),
+            (ConditionType::DestinationNameNotContains, "joe", false),
+            (ConditionType::DestinationNameNotEquals, "trader", true),
+            (ConditionType::DestinationNameNotEquals, "trader joe's", false),
+            (ConditionType::SourceAccountNotEquals, &source, false),
+            (ConditionType::DestinationAccountNotEquals, &source, true),
+            (ConditionType::SourceAccountNotEquals, "not-an-id", false),
+        ];
+        for (condition_type, value, expected) in cases {
+            assert_eq!(condition(condition_type.clone(), value).matches(&t), expected, "{:?} {}", condition_type, value);
+        }
+
+        let unnamed = Transaction { destination_name: None, ..transaction() };
+        assert!(condition(ConditionType::DestinationNameNotContains, "joe").matches(&unnamed));
+        assert!(condition(ConditionType::DestinationNameNotEquals, "joe").matches(&unnamed));
+    }
+
+    #[test]
+    fn regex_conditions_ignore_case_unless_told_otherwise() {
+        let t = transaction();
+        let cases = [
+            (ConditionType::DescriptionMatchesRegex, r"^weekly\s+groc", true),
+            (ConditionType::DescriptionMatchesRegex, "rent|groceries$", true),
+            (ConditionType::DescriptionMatchesRegex, "(?-i)^weekly", false),
+            (ConditionType::DescriptionMatchesRegex, "(", false),
+            (ConditionType::DestinationNameMatchesRegex, "joe'?s$", true),
+            (ConditionType::DestinationNameMatchesRegex, "^joe", false),
+        ];
+        for (condition_type, value, expected) in cases {
+            assert_eq!(condition(condition_type.clone(), value).matches(&t), expected, "{:?} {}", condition_type, value);
+        }
+
+        let unnamed = Transaction { destination_name: None, ..transaction() };
+        assert!(!condition(ConditionType::DestinationNameMatchesRegex, ".*").matches(&unnamed));
+    }
+
+    #[test]
+    fn date_and_weekday_conditions_use_the_transaction_day() {
+        let t = transaction();
+        let cases = [
+            (ConditionType::DateBefore, "2026-03-15", true),
+            (ConditionType::DateBefore, "2026-03-14", false),
+            (ConditionType::DateAfter, "2026-03-13", true),
+            (ConditionType::DateAfter, "2026-03-14", false),
+            (ConditionType::DateEquals, " 2026-03-14 ", true),
+            (ConditionType::DateEquals, "14/03/2026", false),
+            (ConditionType::WeekdayIs, "Sat, sunday", true),
+            (ConditionType::WeekdayIs, "monday", false),
+            (ConditionType::WeekdayIs, "saturday,funday", false),
+            (ConditionType::WeekdayIs, "", false),
+        ];
+        for (condition_type, value, expected) in cases {
+            assert_eq!(condition(condition_type.clone(), value).matches(&t), expected, "{:?} {}", condition_type, value);
+        }
+    }
+
+    #[test]
+    fn empty_conditions_check_category_and_budget() {
+        let uncategorized = transaction();
+        let blank = Transaction { category: " ".to_string(), ..transaction() };
+        let categorized = Transaction { category: "Food".to_string(), budget_id: Some(Uuid::new_v4()), ..transaction() };
+
+        assert!(condition(ConditionType::CategoryIsEmpty, "").matches(&uncategorized));
+        assert!(condition(ConditionType::CategoryIsEmpty, "").matches(&blank));
+        assert!(!condition(ConditionType::CategoryIsEmpty, "").matches(&categorized));
+        assert!(condition(ConditionType::BudgetIsEmpty, "").matches(&uncategorized));
+        assert!(!condition(ConditionType::BudgetIsEmpty, "").matches(&categorized));
+    }
+
+    #[test]
+    fn groups_combine_conditions() {
+        let nodes: Vec<RuleConditionNode> = serde_json::from_str(
+            r#"[
+                {"condition_type": "budget_is_empty"},
+                {"any": [
+                    {"condition_type": "description_contains", "value": "rent"},
+                    {"all": [
+                        {"condition_type": "weekday_is", "value": "saturday,sunday"},
+                        {"condition_type": "amount_greater_than", "value": "40"}
+                    ]}
+                ]}
+            ]"#,
+        )
+        .expect("valid condition tree");
+        let conditions = compile_conditions(&nodes);
+
+        assert!(conditions_match(&conditions, &transaction()));
+        assert!(!conditions_match(&conditions, &Transaction { amount: dec!(5), ..transaction() }));
+        assert!(conditions_match(&conditions, &Transaction { amount: dec!(5), description: "Rent".into(), ..transaction() }));
+        assert!(!conditions_match(&conditions, &Transaction { budget_id: Some(Uuid::new_v4()), ..transaction() }));
+
+        assert!(!CompiledCondition::Any(Vec::new()).matches(&transaction()));
+        assert!(CompiledCondition::All(Vec::new()).matches(&transaction()));
+    }
+
+    #[test]
+    fn plain_condition_lists_still_deserialize() {
+        let nodes: Vec<RuleConditionNode> = serde_json::from_str(
+            r#"[{"condition_type":"description_contains","value":"groceries"},{"condition_type":"amount_less_than","value":"50"}]"#,
+        )
+        .expect("stored conditions");
+        assert!(nodes.iter().all(|node| matches!(node, RuleConditionNode::Condition(_))));
+        assert!(conditions_match(&compile_conditions(&nodes), &transaction()));
+
+        // Serializing keeps the plain format
+        let json = serde_json::to_string(&nodes).unwrap();
+        assert_eq!(json, r#"[{"condition_type":"description_contains","value":"groceries"},{"condition_type":"amount_less_than","value":"50"}]"#);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let parse = |json: &str| serde_json::from_str::<Vec<RuleConditionNode>>(json).expect("condition tree");
+
+        assert!(validate_conditions(&parse(r#"[{"condition_type":"description_matches_regex","value":"^(coffee|tea)"}]"#)).is_ok());
+        assert!(validate_conditions(&parse(r#"[{"any":[{"condition_type":"weekday_is","value":"sat,sun"}]}]"#)).is_ok());
+        assert!(validate_conditions(&parse(r#"[{"condition_type":"amount_equals","value":"lots"}]"#)).is_ok());
+
+        for invalid in [
+            r#"[{"condition_type":"description_matches_regex","value":"(coffee"}]"#,
+            r#"[{"condition_type":"date_before","value":"yesterday"}]"#,
+            r#"[{"all":[{"condition_type":"weekday_is","value":"funday"}]}]"#,
+            r#"[{"any":[]}]"#,
+        ] {
+            assert!(validate_conditions(&parse(invalid)).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn all_conditions_must_match() {
+        let t = transaction();
+        let groceries = condition(ConditionType::DescriptionContains, "groceries");
+        let small = condition(ConditionType::AmountLessThan, "10");
+
+        assert!(conditions_match(&[], &t));
+        assert!(conditions_match(std::slice::from_ref(&groceries), &t));
+        assert!(!conditions_match(&[groceries, small], &t));
+    }
+
+    #[test]
+    fn actions_set_fields() {
+        let budget_id = Uuid::new_v4();
+        let mut t = transaction();
+        for compiled in [
+            action(ActionType::SetCategory, "Food & Drink"),
+            action(ActionType::SetBudget, &budget_id.to_string()),
+            action(ActionType::SetDescription, "Groceries at Joe's"),
+            action(ActionType::SetDestinationName, "O'Brien's Market"),
+            action(ActionType::AddTag, " weekly "),
+        ] {
+            compiled.expect("valid action").apply(&mut t);
+        }
+
+        assert_eq!(t.category, "Food & Drink");
+        assert_eq!(t.budget_id, Some(budget_id));
+        assert_eq!(t.description, "Groceries at Joe's");
+        assert_eq!(t.destination_name.as_deref(), Some("O'Brien's Market"));
+        assert_eq!(t.tags, vec!["weekly".to_string()]);
+    }
+
+    #[test]
+    fn invalid_actions_are_dropped() {
+        assert!(action(ActionType::SetBudget, "not-a-budget").is_none());
+        assert!(action(ActionType::AddTag, "  ").is_none());
+        assert!(action(ActionType::SetDestinationAccount, "savings").is_none());
+        assert!(action(ActionType::ReplaceDescription, "no pattern").is_none());
+        assert!(action(ActionType::SplitByPercentage, "").is_none());
+        assert!(action(ActionType::StopProcessing, "").is_none());
+    }
+
+    #[test]
+    fn description_text_is_added_once() {
+        let mut t = transaction();
+        for _ in 0..2 {
+            action(ActionType::PrependDescription, "[Food] ").unwrap().apply(&mut t);
+            action(ActionType::AppendDescription, " (shared)").unwrap().apply(&mut t);
+        }
+        assert_eq!(t.description, "[Food] Weekly Groceries (shared)");
+    }
+
+    #[test]
+    fn replace_description_uses_the_pattern() {
+        let replace = |pattern: &str, value: &str| {
+            let mut t = Transaction { description: "POS 1234 Trader Joe's #567".to_string(), ..transaction() };
+            let rule_action = RuleAction {
+                action_type: ActionType::ReplaceDescription,
+                value: value.to_string(),
+                pattern: Some(pattern.to_string()),
+                splits: None,
+            };
+            CompiledAction::compile(&rule_action).expect("valid action").apply(&mut t);
+            t.description
+        };
+
+        assert_eq!(replace(r"^pos \d+ ", ""), "Trader Joe's #567");
+        assert_eq!(replace(r"#(\d+)", "store $1"), "POS 1234 Trader Joe's store 567");
+        assert_eq!(replace("(?-i)^pos", "Card"), "POS 1234 Trader Joe's #567");
+    }
+
+    #[test]
+    fn set_destination_account_turns_the_transaction_into_a_transfer() {
+        let savings = Uuid::new_v4();
+        let mut t = transaction();
+        action(ActionType::SetDestinationAccount, &savings.to_string()).unwrap().apply(&mut t);
+        assert_eq!(t.destination_account_id, savings);
+        assert_eq!(t.destination_name, None);
+
+        // Moving to the source account or the current destination changes nothing
+        let original = transaction();
+        for account_id in [original.source_account_id, original.destination_account_id] {
+            let mut t = original.clone();
+            action(ActionType::SetDestinationAccount, &account_id.to_string()).unwrap().apply(&mut t);
+            assert_eq!(RuleFieldValues::from(&t), RuleFieldValues::from(&original));
+        }
+    }
+
+    #[test]
+    fn split_by_percentage_divides_the_amount() {
+        let groceries = Uuid::new_v4();
+        let shares = vec![
+            RuleSplitShare { category_id: Some(groceries), ..share(dec!(33.3)) },
+            share(dec!(33.3)),
+            RuleSplitShare { memo: Some("rest".to_string()), ..share(dec!(33.4)) },
+        ];
+        let mut t = Transaction { amount: dec!(10), ..transaction() };
+        CompiledAction::SplitByPercentage(shares).apply(&mut t);
+
+        let amounts: Vec<Decimal> = t.splits.iter().map(|split| split.amount).collect();
+        assert_eq!(amounts, vec![dec!(3.33), dec!(3.33), dec!(3.34)]);
+        assert_eq!(t.splits[0].category_id, Some(groceries));
+        assert_eq!(t.splits[2].memo.as_deref(), Some("rest"));
+
+        // Shares must add up to 100 percent
+        let invalid = |shares: Vec<RuleSplitShare>| RuleAction {
+            action_type: ActionType::SplitByPercentage,
+            value: String::new(),
+            pattern: None,
+            splits: Some(shares),
+        };
+        assert!(CompiledAction::compile(&invalid(vec![share(dec!(60)), share(dec!(40))])).is_some());
+        assert!(CompiledAction::compile(&invalid(vec![share(dec!(60)), share(dec!(30))])).is_none());
+        assert!(CompiledAction::compile(&invalid(vec![share(dec!(110)), share(dec!(-10))])).is_none());
+        assert!(CompiledAction::compile(&invalid(Vec::new())).is_none());
+    }
+
+    #[test]
+    fn invalid_action_values_are_rejected() {
+        let parse = |json: &str| serde_json::from_str::<Vec<RuleAction>>(json).expect("actions");
+
+        assert!(validate_actions(&parse(r#"[{"action_type":"replace_description","pattern":"^pos ","value":""}]"#)).is_ok());
+        assert!(validate_actions(&parse(r#"[{"action_type":"split_by_percentage","splits":[{"percentage":50},{"percentage":50}]}]"#)).is_ok());
+        assert!(validate_actions(&parse(r#"[{"action_type":"stop_processing"},{"action_type":"set_budget","value":"nope"}]"#)).is_ok());
+
+        for invalid in [
+            r#"[{"action_type":"set_destination_account","value":"savings"}]"#,
+            r#"[{"action_type":"replace_description","value":"x"}]"#,
+            r#"[{"action_type":"replace_description","pattern":"(pos","value":"x"}]"#,
+            r#"[{"action_type":"split_by_percentage","splits":[{"percentage":50}]}]"#,
+        ] {
+            assert!(validate_actions(&parse(invalid)).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn tags_are_added_once_and_kept_sorted() {
+        let mut t = Transaction { tags: vec!["b".to_string()], ..transaction() };
+        for tag in ["c", "a", "b"] {
+            action(ActionType::AddTag, tag).unwrap().apply(&mut t);
+        }
+        assert_eq!(t.tags, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn later_rules_override_earlier_ones() {
+        let t = transaction();
+        let matching = || vec![condition(ConditionType::DescriptionContains, "groceries")];
+        let first = rule(matching(), vec![CompiledAction::SetCategory("Food".into()), CompiledAction::AddTag("one".into())]);
+        let second = rule(matching(), vec![CompiledAction::SetCategory("Groceries".into()), CompiledAction::AddTag("two".into())]);
+        let other = rule(vec![condition(ConditionType::DescriptionContains, "rent")], vec![CompiledAction::SetCategory("Housing".into())]);
+
+        let outcome = evaluate_rules(&[first.clone(), other, second.clone()], &t).expect("rules matched");
+        assert_eq!(outcome.transaction.category, "Groceries");
+        assert_eq!(outcome.transaction.tags, vec!["one", "two"]);
+        assert_eq!(outcome.rule_ids, vec![first.id, second.id]);
+    }
+
+    #[test]
+    fn stop_processing_skips_later_rules() {
+        let t = transaction();
+        let matching = || vec![condition(ConditionType::DescriptionContains, "groceries")];
+        let first = CompiledRule { stop_processing: true, ..rule(matching(), vec![CompiledAction::SetCategory("Food".into())]) };
+        let second = rule(matching(), vec![CompiledAction::SetCategory("Groceries".into())]);
+        let unmatched = CompiledRule { stop_processing: true, ..rule(vec![condition(ConditionType::DescriptionContains, "rent")], Vec::new()) };
+
+        let outcome = evaluate_rules(&[unmatched, first.clone(), second], &t).expect("rules matched");
+        assert_eq!(outcome.transaction.category, "Food");
+        assert_eq!(outcome.rule_ids, vec![first.id]);
+    }
+
+    #[test]
+    fn first_match_groups_apply_one_rule_each() {
+        let t = transaction();
+        let matching = || vec![condition(ConditionType::DescriptionContains, "groceries")];
+        let cleanup = Some(Uuid::new_v4());
+        let first = CompiledRule { first_match_group: cleanup, ..rule(matching(), vec![CompiledAction::SetDescription("Groceries".into())]) };
+        let skipped = CompiledRule { first_match_group: cleanup, ..rule(matching(), vec![CompiledAction::SetDescription("Shop".into())]) };
+        let categorise = rule(matching(), vec![CompiledAction::SetCategory("Food".into())]);
+        let unmatched = CompiledRule { first_match_group: cleanup, ..rule(vec![condition(ConditionType::DescriptionContains, "rent")], Vec::new()) };
+
+        let outcome = evaluate_rules(&[unmatched, first.clone(), skipped, categorise.clone()], &t).expect("rules matched");
+        assert_eq!(outcome.transaction.description, "Groceries");
+        assert_eq!(outcome.transaction.category, "Food");
+        assert_eq!(outcome.rule_ids, vec![first.id, categorise.id]);
+    }
+
+    #[test]
+    fn conditions_are_checked_against_the_original_transaction() {
+        let t = transaction();
+        let rename = rule(vec![condition(ConditionType::DescriptionContains, "groceries")], vec![CompiledAction::SetDescription("Food".into())]);
+        let renamed = rule(vec![condition(ConditionType::DescriptionEquals, "food")], vec![CompiledAction::SetCategory("Food".into())]);
+
+        let outcome = evaluate_rules(&[rename.clone(), renamed], &t).expect("rules matched");
+        assert_eq!(outcome.transaction.description, "Food");
+        assert_eq!(outcome.transaction.category, "Uncategorized");
+        assert_eq!(outcome.rule_ids, vec![rename.id]);
+    }
+
+    #[test]
+    fn no_outcome_without_a_match() {
+        let t = transaction();
+        let other = rule(vec![condition(ConditionType::DescriptionContains, "rent")], vec![CompiledAction::SetCategory("Housing".into())]);
+        assert!(evaluate_rules(&[other], &t).is_none());
+        assert!(evaluate_rules(&[], &t).is_none());
+    }
+
+    #[test]
+    fn update_request_contains_only_changed_fields() {
+        let t = transaction();
+        let actions = vec![
+            CompiledAction::SetCategory("Groceries".into()),
+            CompiledAction::SetDescription(t.description.clone()),
+            CompiledAction::AddTag("food".into()),
+        ];
+        let outcome = evaluate_rules(&[rule(Vec::new(), actions)], &t).expect("rule matched");
+        let request = update_request(&t, &outcome.transaction);
+
+        assert_eq!(request.category.as_deref(), Some("Groceries"));
+        assert_eq!(request.description, None);
+        assert_eq!(request.destination_name, None);
+        assert_eq!(request.budget_id, None);
+        assert_eq!(request.tags, Some(vec!["food".to_string()]));
+        assert_eq!(request.destination_account_id, None);
+        assert!(request.splits.is_none());
+        assert_eq!(request.memo, None);
+    }
+
+    #[test]
+    fn update_request_moves_the_account_and_replaces_splits() {
+        let t = transaction();
+        let savings = Uuid::new_v4();
+        let actions = vec![
+            CompiledAction::SetDestinationAccount(savings),
+            CompiledAction::SetMemo("Moved to savings".into()),
+            CompiledAction::SplitByPercentage(vec![share(dec!(50)), share(dec!(50))]),
+        ];
+        let outcome = evaluate_rules(&[rule(Vec::new(), actions)], &t).expect("rule matched");
+        let request = update_request(&t, &outcome.transaction);
+
+        // The destination name is left to follow the new account
+        assert_eq!(request.destination_account_id, Some(savings));
+        assert_eq!(request.destination_name, None);
+        assert_eq!(request.memo.as_deref(), Some("Moved to savings"));
+        let amounts: Vec<Decimal> = request.splits.expect("splits").iter().map(|split| split.amount).collect();
+        assert_eq!(amounts, vec![dec!(21.25), dec!(21.25)]);
+
+        // Splits equal to the current ones are no change
+        let split = evaluate_rules(&[rule(Vec::new(), vec![CompiledAction::SplitByPercentage(vec![share(dec!(50)), share(dec!(50))])])], &t)
+            .expect("rule matched")
+            .transaction;
+        let again = evaluate_rules(&[rule(Vec::new(), vec![CompiledAction::SplitByPercentage(vec![share(dec!(50)), share(dec!(50))])])], &split)
+            .expect("rule matched");
+        assert!(update_request(&split, &again.transaction).splits.is_none());
+    }
+
+    #[test]
+    fn rules_compile_from_stored_json() {
+        let now = Utc::now();
+        let stored = Rule {
+            id: Uuid::new_v4(),
+            name: "Joe's".to_string(),
+            description: None,
+            is_active: true,
+            priority: 0,
+            group_id: None,
+            conditions_json: r#"[{"condition_type":"destination_name_contains","value":"Joe's"}]"#.to_string(),
+            actions_json: r#"[{"action_type":"set_budget","value":"nope"},{"action_type":"set_category","value":"Food"}]"#.to_string(),
+            triggers_json: r#"["import"]"#.to_string(),
+            created_at: now,
+            updated_at: now,
+        };
+        let broken = Rule { conditions_json: "not json".to_string(), ..stored.clone() };
+
+        let compiled = compile_rules(&[stored, broken]);
+        assert_eq!(compiled.len(), 1);
+        assert!(matches!(compiled[0].conditions.as_slice(), [CompiledCondition::DestinationNameContains(value)] if value == "joe's"));
+        assert!(matches!(compiled[0].actions.as_slice(), [CompiledAction::SetCategory(category)] if category == "Food"));
+        assert!(!compiled[0].stop_processing);
+        assert_eq!(compiled[0].triggers, vec![RuleTrigger::Import]);
+        assert!(compiled[0].matches(&transaction()));
+    }
+}
@@ -1,47 +1,58 @@
+use std::sync::Arc;
+
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
-use crate::models::{RuleGroup, CreateRuleGroupRequest, UpdateRuleGroupRequest};
+use crate::models::{RuleGroup, CreateRuleGroupRequest, UpdateRuleGroupRequest, RuleRunFilter, RuleRunPreview, RuleRunScope};
+use crate::services::RuleService;
 
 /// Service for handling rule group-related operations
 pub struct RuleGroupService {
     db: Pool<Postgres>,
+    /// Runs the rules of a group
+    rule_service: Arc<RuleService>,
 }
 
 impl RuleGroupService {
-    /// Create a new RuleGroupService with the given database pool
-    pub fn new(db: Pool<Postgres>) -> Self {
-        Self { db }
+    /// Create a new RuleGroupService with the given database pool and rule service
+    pub fn new(db: Pool<Postgres>, rule_service: Arc<RuleService>) -> Self {
+        Self { db, rule_service }
     }
 
-    /// Get all rule groups
-    pub async fn get_rule_groups(&self) -> Result<Vec<RuleGroup>, sqlx::Error> {
-        sqlx::query_as::<_, RuleGroup>("SELECT * FROM rule_groups ORDER BY name")
+    /// Get all rule groups in the order their rules run
+    pub async fn get_rule_groups(&self, ledger_id: Uuid) -> Result<Vec<RuleGroup>, sqlx::Error> {
+        sqlx::query_as::<_, RuleGroup>("SELECT * FROM rule_groups WHERE ledger_id = $1 ORDER BY run_order, name")
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await
     }
 
     /// Get a rule group by ID
-    pub async fn get_rule_group(&self, id: Uuid) -> Result<Option<RuleGroup>, sqlx::Error> {
-        sqlx::query_as::<_, RuleGroup>("SELECT * FROM rule_groups WHERE id = $1")
+    pub async fn get_rule_group(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<RuleGroup>, sqlx::Error> {
+        sqlx::query_as::<_, RuleGroup>("SELECT * FROM rule_groups WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
             .await
     }
 
     /// Create a new rule group
-    pub async fn create_rule_group(&self, req: CreateRuleGroupRequest) -> Result<RuleGroup, sqlx::Error> {
+    pub async fn create_rule_group(&self, ledger_id: Uuid, req: CreateRuleGroupRequest) -> Result<RuleGroup, sqlx::Error> {
         let now = chrono::Utc::now();
         sqlx::query_as::<_, RuleGroup>(
             r#"
-            INSERT INTO rule_groups (id, name, description, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO rule_groups (id, ledger_id, name, description, run_order, is_active, stop_after_first_match, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *
             "#,
         )
         .bind(Uuid::new_v4())
+        .bind(ledger_id)
         .bind(&req.name)
         .bind(&req.description)
+        .bind(req.run_order.unwrap_or(100))
+        .bind(req.is_active.unwrap_or(true))
+        .bind(req.stop_after_first_match)
         .bind(now)
         .bind(now)
         .fetch_one(&self.db)
@@ -49,9 +60,9 @@ impl RuleGroupService {
     }
 
     /// Update an existing rule group
-    pub async fn update_rule_group(&self, id: Uuid, req: UpdateRuleGroupRequest) -> Result<Option<RuleGroup>, sqlx::Error> {
+    pub async fn update_rule_group(&self, ledger_id: Uuid, id: Uuid, req: UpdateRuleGroupRequest) -> Result<Option<RuleGroup>, sqlx::Error> {
         // First, check if the rule group exists
-        let group = self.get_rule_group(id).await?;
+        let group = self.get_rule_group(ledger_id, id).await?;
         if group.is_none() {
             return Ok(None);
         }
@@ -64,15 +75,22 @@ impl RuleGroupService {
             SET
                 name = COALESCE($1, name),
                 description = COALESCE($2, description),
-                updated_at = $3
-            WHERE id = $4
+                run_order = COALESCE($3, run_order),
+                is_active = COALESCE($4, is_active),
+                stop_after_first_match = COALESCE($5, stop_after_first_match),
+                updated_at = $6
+            WHERE id = $7 AND ledger_id = $8
             RETURNING *
             "#,
         )
         .bind(req.name)
         .bind(req.description)
+        .bind(req.run_order)
+        .bind(req.is_active)
+        .bind(req.stop_after_first_match)
         .bind(now)
         .bind(id)
+        .bind(ledger_id)
         .fetch_optional(&self.db)
         .await?;
 
@@ -80,17 +98,19 @@ impl RuleGroupService {
     }
 
     /// Delete a rule group
-    pub async fn delete_rule_group(&self, id: Uuid) -> Result<bool, sqlx::Error> {
+    pub async fn delete_rule_group(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
         // Use a transaction: clear rules.group_id then delete group
         let mut tx = self.db.begin().await?;
 
-        sqlx::query("UPDATE rules SET group_id = NULL WHERE group_id = $1")
+        sqlx::query("UPDATE rules SET group_id = NULL WHERE group_id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
-        let result = sqlx::query("DELETE FROM rule_groups WHERE id = $1")
+        let result = sqlx::query("DELETE FROM rule_groups WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&mut *tx)
             .await?;
 
@@ -99,10 +119,22 @@ impl RuleGroupService {
     }
 
     /// Get all rules in a specific group
-    pub async fn get_rules_by_group(&self, group_id: Uuid) -> Result<Vec<crate::models::Rule>, sqlx::Error> {
-        sqlx::query_as::<_, crate::models::Rule>("SELECT * FROM rules WHERE group_id = $1 ORDER BY priority ASC, name ASC")
+    pub async fn get_rules_by_group(&self, ledger_id: Uuid, group_id: Uuid) -> Result<Vec<crate::models::Rule>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::Rule>("SELECT * FROM rules WHERE group_id = $1 AND ledger_id = $2 ORDER BY priority ASC, name ASC")
             .bind(group_id)
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await
     }
+
+    /// Run the active rules of a group on all transactions passing the filter; nothing changes when the
+    /// group is inactive. Returns the number of transactions changed and the run ID to undo them with.
+    pub async fn run_rule_group(&self, ledger_id: Uuid, id: Uuid, filter: &RuleRunFilter) -> Result<(usize, Uuid), sqlx::Error> {
+        self.rule_service.apply_rule_group_to_all_transactions(ledger_id, id, filter).await
+    }
+
+    /// Show what running the rules of a group would change, without changing anything
+    pub async fn test_rule_group(&self, ledger_id: Uuid, id: Uuid, filter: &RuleRunFilter) -> Result<RuleRunPreview, sqlx::Error> {
+        self.rule_service.preview_rule_run(ledger_id, RuleRunScope::Group(id), filter).await
+    }
 }
@@ -1,439 +1,259 @@
-use chrono::Utc;
-use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{NaiveTime, Utc};
+use sqlx::{PgConnection, Pool, Postgres, QueryBuilder};
 use uuid::Uuid;
 use serde_json;
-use tracing::{debug, error, info};
+use tracing::{error, info};
 
 use crate::models::{
     Rule, RuleResponse, CreateRuleRequest, UpdateRuleRequest,
-    RuleCondition, RuleAction, ConditionType, ActionType,
-    Transaction, UpdateTransactionRequest
+    RuleConditionNode, Transaction, UpdateTransactionRequest, ChangeActor,
+    RuleFieldValues, RuleChangePreview, RuleRunPreview, RuleRunScope, RuleRunFilter, RuleTrigger, CommitRuleRunResult
+};
+use crate::services::rule_engine::{
+    compile_conditions, compile_rules, conditions_match, evaluate_rules, update_request, validate_actions, validate_conditions,
+    CompiledRule,
 };
+use crate::services::TransactionService;
 
 /// Service for handling rule-related operations
 ///
-/// Rules are automatically applied in the following scenarios:
+/// Rules are automatically applied, depending on their triggers, in the following scenarios:
 /// 1. When a new transaction is created
 /// 2. When an existing transaction is updated
+/// 3. When a transaction is imported from a CSV file
 ///
 /// Rules can also be manually applied using:
 /// 1. The `/api/rules/run` endpoint to run all active rules on all transactions
 /// 2. The `/api/rules/{id}/run` endpoint to run a specific rule on all transactions
+/// 3. The `/api/rule-groups/{id}/run` endpoint to run the rules of a group on all transactions
+///
+/// Rule runs can be previewed first with `/run/preview` (`/test` for a group) and the previewed
+/// changes of a full or single rule run applied exactly with `/run/commit`.
+///
+/// Rules run group by group in the groups' run order, rules without a group last, and by priority
+/// within a group. Rules of inactive groups only run on their own. Manual runs apply rules
+/// regardless of their triggers and can be restricted to a date range and an account.
 pub struct RuleService {
     db: Pool<Postgres>,
+    /// Writes the changes of rule runs, so balances, split lines and history stay consistent
+    transaction_service: Arc<TransactionService>,
 }
 
 impl RuleService {
-    /// Create a new RuleService with the given database pool
-    pub fn new(db: Pool<Postgres>) -> Self {
-        Self { db }
+    /// Create a new RuleService with the given database pool and transaction service
+    pub fn new(db: Pool<Postgres>, transaction_service: Arc<TransactionService>) -> Self {
+        Self { db, transaction_service }
     }
 
-    /// Apply a specific rule to all transactions
-    pub async fn apply_rule_to_all_transactions(&self, rule_id: Uuid) -> Result<usize, sqlx::Error> {
-        // Get the rule by ID
-        let rule = match self.get_rule(rule_id).await? {
-            Some(rule) => rule,
-            None => {
-                error!("Rule with ID {} not found", rule_id);
-                return Err(sqlx::Error::RowNotFound);
-            },
-        };
+    /// Active rules of a run, compiled in the order they apply. Rules of equal priority run in
+    /// the order they were created.
+    async fn get_run_rules(&self, ledger_id: Uuid, scope: RuleRunScope) -> Result<Vec<CompiledRule>, sqlx::Error> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT r.* FROM rules r LEFT JOIN rule_groups g ON g.id = r.group_id WHERE r.is_active = true AND r.ledger_id = ",
+        );
+        query.push_bind(ledger_id);
+        match scope {
+            RuleRunScope::All => {
+                query.push(" AND (g.id IS NULL OR g.is_active = true)");
+            }
+            RuleRunScope::Rule(rule_id) => {
+                query.push(" AND r.id = ").push_bind(rule_id);
+            }
+            RuleRunScope::Group(group_id) => {
+                query.push(" AND g.is_active = true AND g.id = ").push_bind(group_id);
+            }
+        }
+        query.push(" ORDER BY g.run_order ASC NULLS LAST, g.name, g.id, r.priority ASC, r.created_at, r.id");
+        let rules = query.build_query_as::<Rule>().fetch_all(&self.db).await?;
+
+        let first_match_groups: HashSet<Uuid> =
+            sqlx::query_scalar("SELECT id FROM rule_groups WHERE ledger_id = $1 AND stop_after_first_match = true")
+                .bind(ledger_id)
+                .fetch_all(&self.db)
+                .await?
+                .into_iter()
+                .collect();
+
+        let rule_groups: HashMap<Uuid, Uuid> = rules.iter().filter_map(|rule| Some((rule.id, rule.group_id?))).collect();
+        let mut compiled = compile_rules(&rules);
+        for rule in compiled.iter_mut() {
+            rule.first_match_group = rule_groups.get(&rule.id).copied().filter(|group_id| first_match_groups.contains(group_id));
+        }
+        Ok(compiled)
+    }
 
-        // Check if the rule is active
-        if !rule.is_active {
-            info!("Rule '{}' is not active, no transactions will be affected", rule.name);
-            return Ok(0); // Rule is not active, no transactions affected
+    /// Active rules of the ledger, compiled in the order they apply
+    pub(crate) async fn get_active_rules(&self, ledger_id: Uuid) -> Result<Vec<CompiledRule>, sqlx::Error> {
+        self.get_run_rules(ledger_id, RuleRunScope::All).await
+    }
+
+    /// Work out what the rules of a run would change, without writing anything
+    async fn plan_rule_run(&self, ledger_id: Uuid, scope: RuleRunScope, filter: &RuleRunFilter) -> Result<Vec<PlannedRuleChange>, sqlx::Error> {
+        let rules = self.get_run_rules(ledger_id, scope).await?;
+        if rules.is_empty() {
+            return Ok(Vec::new()); // No active rules, no transactions affected
         }
 
-        // Get all transactions
-        let transactions = match sqlx::query_as::<_, Transaction>("SELECT * FROM transactions")
-            .fetch_all(&self.db)
-            .await {
-                Ok(txns) => txns,
-                Err(e) => {
-                    error!("Failed to fetch transactions for rule '{}': {}", rule.name, e);
-                    return Err(e);
-                }
-            };
+        let mut conn = self.db.acquire().await?;
+        let transactions = self.get_unlocked_transactions(&mut conn, ledger_id, None, filter).await?;
+        Ok(plan_rule_changes(&rules, transactions))
+    }
 
+    /// Apply the changes of a run one transaction at a time; failures are logged and skipped
+    async fn run_rules(&self, ledger_id: Uuid, scope: RuleRunScope, filter: &RuleRunFilter, run_id: Uuid) -> Result<usize, sqlx::Error> {
+        let planned = self.plan_rule_run(ledger_id, scope, filter).await?;
         let mut affected_count = 0;
 
-        // Process each transaction
-        for transaction in transactions {
-            // Create a rule with just this one rule
-            let single_rule = Rule {
-                id: rule.id,
-                name: rule.name.clone(),
-                description: rule.description.clone(),
-                is_active: true,
-                priority: rule.priority,
-                group_id: None,
-                conditions_json: serde_json::to_string(&rule.conditions).unwrap_or_default(),
-                actions_json: serde_json::to_string(&rule.actions).unwrap_or_default(),
-                created_at: rule.created_at,
-                updated_at: rule.updated_at,
+        for change in &planned {
+            let actor = ChangeActor::rules(&change.rule_ids, Some(run_id));
+            let mut tx = self.db.begin().await?;
+            let result = match self.write_rule_change(&mut tx, ledger_id, change, actor).await {
+                Ok(()) => tx.commit().await,
+                Err(e) => Err(e),
             };
 
-            // Initialize an empty update request
-            let mut update_request = UpdateTransactionRequest {
-                destination_account_id: None,
-                destination_name: None,
-                description: None,
-                amount: None,
-                category: None,
-                budget_id: None,
-                transaction_date: None,
-            };
+            match result {
+                Ok(()) => {
+                    affected_count += 1;
+                    info!("Applied rules to transaction {}", change.after.id);
+                },
+                Err(e) => error!("Failed to update transaction {}: {}", change.after.id, e),
+            }
+        }
 
-            // Deserialize conditions and actions
-            let conditions: Vec<RuleCondition> = match serde_json::from_str(&single_rule.conditions_json) {
-                Ok(c) => c,
-                Err(e) => {
-                    error!("Failed to deserialize conditions for rule {}: {}", single_rule.id, e);
-                    continue;
-                }
-            };
+        Ok(affected_count)
+    }
 
-            let actions: Vec<RuleAction> = match serde_json::from_str(&single_rule.actions_json) {
-                Ok(a) => a,
-                Err(e) => {
-                    error!("Failed to deserialize actions for rule {}: {}", single_rule.id, e);
-                    continue;
-                }
-            };
+    /// Apply a specific rule to all transactions passing the filter. Returns the number of transactions
+    /// changed and the run ID grouping the changes in the transaction history, to undo them together.
+    pub async fn apply_rule_to_all_transactions(&self, ledger_id: Uuid, rule_id: Uuid, filter: &RuleRunFilter) -> Result<(usize, Uuid), sqlx::Error> {
+        let run_id = Uuid::new_v4();
 
-            // Check if all conditions match
-            let all_conditions_match = conditions.iter().all(|condition| {
-                match condition.condition_type {
-                    ConditionType::DescriptionContains => {
-                        transaction.description.to_lowercase().contains(&condition.value.to_lowercase())
-                    },
-                    ConditionType::DescriptionStartsWith => {
-                        transaction.description.to_lowercase().starts_with(&condition.value.to_lowercase())
-                    },
-                    ConditionType::DescriptionEquals => {
-                        transaction.description.to_lowercase() == condition.value.to_lowercase()
-                    },
-                    ConditionType::SourceAccountEquals => {
-                        transaction.source_account_id.to_string() == condition.value
-                    },
-                    ConditionType::DestinationAccountEquals => {
-                        transaction.destination_account_id.to_string() == condition.value
-                    },
-                    ConditionType::DestinationNameContains => {
-                        match &transaction.destination_name {
-                            Some(name) => name.to_lowercase().contains(&condition.value.to_lowercase()),
-                            None => false,
-                        }
-                    },
-                    ConditionType::DestinationNameEquals => {
-                        match &transaction.destination_name {
-                            Some(name) => name.to_lowercase() == condition.value.to_lowercase(),
-                            None => false,
-                        }
-                    },
-                    ConditionType::AmountGreaterThan => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => transaction.amount > value,
-                            Err(_) => false,
-                        }
-                    },
-                    ConditionType::AmountLessThan => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => transaction.amount < value,
-                            Err(_) => false,
-                        }
-                    },
-                    ConditionType::AmountEquals => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => (transaction.amount - value).abs() < 0.001, // Use a small epsilon for float comparison
-                            Err(_) => false,
-                        }
-                    },
-                }
-            });
-
-            // If all conditions match, apply the actions
-            if all_conditions_match {
-                debug!("Rule {} matched for transaction {}", single_rule.name, transaction.id);
-
-                for action in actions {
-                    match action.action_type {
-                        ActionType::SetCategory => {
-                            update_request.category = Some(action.value);
-                        },
-                        ActionType::SetBudget => {
-                            // Try to parse the budget ID
-                            match Uuid::parse_str(&action.value) {
-                                Ok(budget_id) => {
-                                    update_request.budget_id = Some(budget_id);
-                                },
-                                Err(e) => {
-                                    error!("Invalid budget ID in rule {}: {}", single_rule.id, e);
-                                }
-                            }
-                        },
-                        ActionType::SetDescription => {
-                            update_request.description = Some(action.value);
-                        },
-                        ActionType::SetDestinationName => {
-                            update_request.destination_name = Some(action.value);
-                        },
-                    }
-                }
+        // Get the rule by ID
+        let rule = match self.get_rule(ledger_id, rule_id).await? {
+            Some(rule) => rule,
+            None => {
+                error!("Rule with ID {} not found", rule_id);
+                return Err(sqlx::Error::RowNotFound);
+            },
+        };
 
-                // If any actions were applied, update the transaction
-                if update_request.category.is_some() ||
-                   update_request.budget_id.is_some() ||
-                   update_request.description.is_some() ||
-                   update_request.destination_name.is_some() {
-
-                    // Update the transaction
-                    let now = Utc::now();
-                    let mut query = String::from("UPDATE transactions SET updated_at = $1");
-                    let mut params: Vec<String> = vec![];
-
-                    if let Some(category) = &update_request.category {
-                        params.push(format!("category = '{}'", category));
-                    }
-
-                    if let Some(budget_id) = update_request.budget_id {
-                        params.push(format!("budget_id = '{}'", budget_id));
-                    }
-
-                    if let Some(description) = &update_request.description {
-                        params.push(format!("description = '{}'", description));
-                    }
-
-                    if let Some(destination_name) = &update_request.destination_name {
-                        params.push(format!("destination_name = '{}'", destination_name));
-                    }
-
-                    if !params.is_empty() {
-                        query.push_str(", ");
-                        query.push_str(&params.join(", "));
-                    }
-
-                    query.push_str(" WHERE id = $2");
-
-                    let result = sqlx::query(&query)
-                        .bind(now)
-                        .bind(transaction.id)
-                        .execute(&self.db)
-                        .await;
-
-                    if let Ok(_) = result {
-                        affected_count += 1;
-                        info!("Applied rule '{}' to transaction {}", rule.name, transaction.id);
-                    } else if let Err(e) = result {
-                        error!("Failed to update transaction {} when applying rule '{}': {}",
-                               transaction.id, rule.name, e);
-                    }
-                }
-            }
+        // Check if the rule is active
+        if !rule.is_active {
+            info!("Rule '{}' is not active, no transactions will be affected", rule.name);
+            return Ok((0, run_id)); // Rule is not active, no transactions affected
         }
 
-        Ok(affected_count)
+        let affected_count = self.run_rules(ledger_id, RuleRunScope::Rule(rule_id), filter, run_id).await?;
+        Ok((affected_count, run_id))
     }
 
-    /// Apply all active rules to all transactions
-    pub async fn apply_all_rules_to_all_transactions(&self) -> Result<usize, sqlx::Error> {
-        // Get all active rules ordered by priority
-        let rules = sqlx::query_as::<_, Rule>("SELECT * FROM rules WHERE is_active = true ORDER BY priority ASC")
-            .fetch_all(&self.db)
-            .await?;
+    /// Apply the active rules of a group to all transactions passing the filter, nothing when the group
+    /// is inactive. Returns the number of transactions changed and the run ID grouping the changes.
+    pub async fn apply_rule_group_to_all_transactions(
+        &self,
+        ledger_id: Uuid,
+        group_id: Uuid,
+        filter: &RuleRunFilter,
+    ) -> Result<(usize, Uuid), sqlx::Error> {
+        let run_id = Uuid::new_v4();
+        let affected_count = self.run_rules(ledger_id, RuleRunScope::Group(group_id), filter, run_id).await?;
+        Ok((affected_count, run_id))
+    }
 
-        if rules.is_empty() {
-            return Ok(0); // No active rules, no transactions affected
-        }
+    /// Apply all active rules to all transactions passing the filter. Returns the number of transactions
+    /// changed and the run ID grouping the changes in the transaction history, to undo them together.
+    pub async fn apply_all_rules_to_all_transactions(&self, ledger_id: Uuid, filter: &RuleRunFilter) -> Result<(usize, Uuid), sqlx::Error> {
+        let run_id = Uuid::new_v4();
+        let affected_count = self.run_rules(ledger_id, RuleRunScope::All, filter, run_id).await?;
+        Ok((affected_count, run_id))
+    }
 
-        // Get all transactions
-        let transactions = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions")
-            .fetch_all(&self.db)
-            .await?;
+    /// Show what running the rules of a scope on the transactions passing the filter would change,
+    /// without changing anything. Transactions the rules match but leave as they are are not listed.
+    pub async fn preview_rule_run(&self, ledger_id: Uuid, scope: RuleRunScope, filter: &RuleRunFilter) -> Result<RuleRunPreview, sqlx::Error> {
+        let changes: Vec<RuleChangePreview> = self
+            .plan_rule_run(ledger_id, scope, filter)
+            .await?
+            .iter()
+            .filter(|change| change.changes_values())
+            .map(PlannedRuleChange::preview)
+            .collect();
+
+        Ok(RuleRunPreview { affected_transactions: changes.len(), changes })
+    }
 
-        let mut affected_count = 0;
-        let mut affected_transactions = std::collections::HashSet::new();
-
-        // Process each transaction
-        for transaction in transactions {
-            // Initialize an empty update request
-            let mut update_request = UpdateTransactionRequest {
-                destination_account_id: None,
-                destination_name: None,
-                description: None,
-                amount: None,
-                category: None,
-                budget_id: None,
-                transaction_date: None,
-            };
+    /// Apply exactly the changes of a preview of the rules of a scope, made with the same filter. The
+    /// transactions are locked and the rules planned again; when any transaction changed since
+    /// the preview, no longer passes the filter or would now be changed differently, nothing is
+    /// applied and the conflicting transactions are reported. Otherwise all changes are applied
+    /// together under one run ID.
+    pub async fn commit_rule_run(
+        &self,
+        ledger_id: Uuid,
+        scope: RuleRunScope,
+        filter: &RuleRunFilter,
+        changes: Vec<RuleChangePreview>,
+    ) -> Result<CommitRuleRunResult, sqlx::Error> {
+        let mut ids: Vec<Uuid> = changes.iter().map(|change| change.transaction_id).collect();
+        ids.sort();
+        ids.dedup();
+        if ids.len() != changes.len() {
+            return Err(sqlx::Error::Protocol("Invalid preview: a transaction appears more than once".into()));
+        }
 
-            let mut any_rule_applied = false;
-
-            // Process each rule
-            for rule in &rules {
-                // Deserialize conditions and actions
-                let conditions: Vec<RuleCondition> = match serde_json::from_str(&rule.conditions_json) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        error!("Failed to deserialize conditions for rule {}: {}", rule.id, e);
-                        continue;
-                    }
-                };
-
-                let actions: Vec<RuleAction> = match serde_json::from_str(&rule.actions_json) {
-                    Ok(a) => a,
-                    Err(e) => {
-                        error!("Failed to deserialize actions for rule {}: {}", rule.id, e);
-                        continue;
-                    }
-                };
-
-                // Check if all conditions match
-                let all_conditions_match = conditions.iter().all(|condition| {
-                    match condition.condition_type {
-                        ConditionType::DescriptionContains => {
-                            transaction.description.to_lowercase().contains(&condition.value.to_lowercase())
-                        },
-                        ConditionType::DescriptionStartsWith => {
-                            transaction.description.to_lowercase().starts_with(&condition.value.to_lowercase())
-                        },
-                        ConditionType::DescriptionEquals => {
-                            transaction.description.to_lowercase() == condition.value.to_lowercase()
-                        },
-                        ConditionType::SourceAccountEquals => {
-                            transaction.source_account_id.to_string() == condition.value
-                        },
-                        ConditionType::DestinationAccountEquals => {
-                            transaction.destination_account_id.to_string() == condition.value
-                        },
-                        ConditionType::DestinationNameContains => {
-                            match &transaction.destination_name {
-                                Some(name) => name.to_lowercase().contains(&condition.value.to_lowercase()),
-                                None => false,
-                            }
-                        },
-                        ConditionType::DestinationNameEquals => {
-                            match &transaction.destination_name {
-                                Some(name) => name.to_lowercase() == condition.value.to_lowercase(),
-                                None => false,
-                            }
-                        },
-                        ConditionType::AmountGreaterThan => {
-                            match condition.value.parse::<f64>() {
-                                Ok(value) => transaction.amount > value,
-                                Err(_) => false,
-                            }
-                        },
-                        ConditionType::AmountLessThan => {
-                            match condition.value.parse::<f64>() {
-                                Ok(value) => transaction.amount < value,
-                                Err(_) => false,
-                            }
-                        },
-                        ConditionType::AmountEquals => {
-                            match condition.value.parse::<f64>() {
-                                Ok(value) => (transaction.amount - value).abs() < 0.001, // Use a small epsilon for float comparison
-                                Err(_) => false,
-                            }
-                        },
-                    }
-                });
-
-                // If all conditions match, apply the actions
-                if all_conditions_match {
-                    debug!("Rule {} matched for transaction {}", rule.name, transaction.id);
-
-                    for action in actions {
-                        match action.action_type {
-                            ActionType::SetCategory => {
-                                update_request.category = Some(action.value);
-                            },
-                            ActionType::SetBudget => {
-                                // Try to parse the budget ID
-                                match Uuid::parse_str(&action.value) {
-                                    Ok(budget_id) => {
-                                        update_request.budget_id = Some(budget_id);
-                                    },
-                                    Err(e) => {
-                                        error!("Invalid budget ID in rule {}: {}", rule.id, e);
-                                    }
-                                }
-                            },
-                            ActionType::SetDescription => {
-                                update_request.description = Some(action.value);
-                            },
-                            ActionType::SetDestinationName => {
-                                update_request.destination_name = Some(action.value);
-                            },
-                        }
-                    }
-
-                    any_rule_applied = true;
-                }
-            }
+        let rules = self.get_run_rules(ledger_id, scope).await?;
 
-            // If any rule was applied, update the transaction
-            if any_rule_applied {
-                // If any actions were applied, update the transaction
-                if update_request.category.is_some() ||
-                   update_request.budget_id.is_some() ||
-                   update_request.description.is_some() ||
-                   update_request.destination_name.is_some() {
-
-                    // Update the transaction
-                    let now = Utc::now();
-                    let mut query = String::from("UPDATE transactions SET updated_at = $1");
-                    let mut params: Vec<String> = vec![];
-
-                    if let Some(category) = &update_request.category {
-                        params.push(format!("category = '{}'", category));
-                    }
-
-                    if let Some(budget_id) = update_request.budget_id {
-                        params.push(format!("budget_id = '{}'", budget_id));
-                    }
-
-                    if let Some(description) = &update_request.description {
-                        params.push(format!("description = '{}'", description));
-                    }
-
-                    if let Some(destination_name) = &update_request.destination_name {
-                        params.push(format!("destination_name = '{}'", destination_name));
-                    }
-
-                    if !params.is_empty() {
-                        query.push_str(", ");
-                        query.push_str(&params.join(", "));
-                    }
-
-                    query.push_str(" WHERE id = $2");
-
-                    let result = sqlx::query(&query)
-                        .bind(now)
-                        .bind(transaction.id)
-                        .execute(&self.db)
-                        .await;
-
-                    if let Ok(_) = result {
-                        affected_transactions.insert(transaction.id);
-                        info!("Applied rules to transaction {}", transaction.id);
-                    } else if let Err(e) = result {
-                        error!("Failed to update transaction {}: {}", transaction.id, e);
-                    }
-                }
+        // Lock every previewed transaction, including those that no longer pass the filter, before
+        // comparing it to the preview; concurrent edits wait until the changes are committed
+        let mut tx = self.db.begin().await?;
+        sqlx::query("SELECT id FROM transactions WHERE ledger_id = $1 AND id = ANY($2) ORDER BY id FOR UPDATE")
+            .bind(ledger_id)
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        let transactions = self.get_unlocked_transactions(&mut tx, ledger_id, Some(&ids), filter).await?;
+        let mut planned: HashMap<Uuid, PlannedRuleChange> = plan_rule_changes(&rules, transactions)
+            .into_iter()
+            .map(|change| (change.after.id, change))
+            .collect();
+
+        let mut to_apply = Vec::new();
+        let mut conflicts = Vec::new();
+        for previewed in &changes {
+            match planned.remove(&previewed.transaction_id) {
+                Some(change) if change.matches(previewed) => to_apply.push(change),
+                _ => conflicts.push(previewed.transaction_id),
             }
         }
 
-        affected_count = affected_transactions.len();
+        if !conflicts.is_empty() {
+            info!("Rule run preview for ledger {} not applied: {} conflicting transaction(s)", ledger_id, conflicts.len());
+            return Ok(CommitRuleRunResult { applied: false, run_id: None, affected_transactions: 0, conflicts });
+        }
 
-        Ok(affected_count)
+        let run_id = Uuid::new_v4();
+        for change in &to_apply {
+            self.write_rule_change(&mut tx, ledger_id, change, ChangeActor::rules(&change.rule_ids, Some(run_id))).await?;
+        }
+        tx.commit().await?;
+
+        info!("Applied previewed rule run {} to {} transaction(s)", run_id, to_apply.len());
+        Ok(CommitRuleRunResult {
+            applied: true,
+            run_id: Some(run_id),
+            affected_transactions: to_apply.len(),
+            conflicts,
+        })
     }
 
-    /// Get all rules
-    pub async fn get_rules(&self) -> Result<Vec<RuleResponse>, sqlx::Error> {
-        let rules = sqlx::query_as::<_, Rule>("SELECT * FROM rules ORDER BY priority ASC, name ASC")
+    /// Get all rules in a ledger
+    pub async fn get_rules(&self, ledger_id: Uuid) -> Result<Vec<RuleResponse>, sqlx::Error> {
+        let rules = sqlx::query_as::<_, Rule>("SELECT * FROM rules WHERE ledger_id = $1 ORDER BY priority ASC, name ASC")
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await?;
 
@@ -452,9 +272,10 @@ impl RuleService {
     }
 
     /// Get a rule by ID
-    pub async fn get_rule(&self, id: Uuid) -> Result<Option<RuleResponse>, sqlx::Error> {
-        let rule = sqlx::query_as::<_, Rule>("SELECT * FROM rules WHERE id = $1")
+    pub async fn get_rule(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<RuleResponse>, sqlx::Error> {
+        let rule = sqlx::query_as::<_, Rule>("SELECT * FROM rules WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
             .await?;
 
@@ -471,10 +292,14 @@ impl RuleService {
     }
 
     /// Create a new rule
-    pub async fn create_rule(&self, req: CreateRuleRequest) -> Result<RuleResponse, sqlx::Error> {
+    pub async fn create_rule(&self, ledger_id: Uuid, req: CreateRuleRequest) -> Result<RuleResponse, sqlx::Error> {
+        validate_conditions(&req.conditions).map_err(sqlx::Error::Protocol)?;
+        validate_actions(&req.actions).map_err(sqlx::Error::Protocol)?;
+
         let now = Utc::now();
         let id = Uuid::new_v4();
         let priority = req.priority.unwrap_or(100);
+        let triggers = triggers_json(req.triggers.unwrap_or_else(RuleTrigger::all))?;
 
         // Serialize conditions and actions to JSON
         let conditions_json = serde_json::to_string(&req.conditions)
@@ -492,12 +317,13 @@ impl RuleService {
         // Create the rule
         let rule = sqlx::query_as::<_, Rule>(
             r#"
-            INSERT INTO rules (id, name, description, is_active, priority, group_id, conditions_json, actions_json, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO rules (id, ledger_id, name, description, is_active, priority, group_id, conditions_json, actions_json, triggers_json, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             RETURNING *
             "#,
         )
         .bind(id)
+        .bind(ledger_id)
         .bind(&req.name)
         .bind(&req.description)
         .bind(req.is_active)
@@ -505,6 +331,7 @@ impl RuleService {
         .bind(&req.group_id)
         .bind(&conditions_json)
         .bind(&actions_json)
+        .bind(&triggers)
         .bind(now)
         .bind(now)
         .fetch_one(&self.db)
@@ -517,35 +344,42 @@ impl RuleService {
     }
 
     /// Update an existing rule
-    pub async fn update_rule(&self, id: Uuid, req: UpdateRuleRequest) -> Result<Option<RuleResponse>, sqlx::Error> {
+    pub async fn update_rule(&self, ledger_id: Uuid, id: Uuid, req: UpdateRuleRequest) -> Result<Option<RuleResponse>, sqlx::Error> {
+        if let Some(conditions) = &req.conditions {
+            validate_conditions(conditions).map_err(sqlx::Error::Protocol)?;
+        }
+        if let Some(actions) = &req.actions {
+            validate_actions(actions).map_err(sqlx::Error::Protocol)?;
+        }
+
         // First, check if the rule exists
-        let existing_rule = self.get_rule(id).await?;
+        let existing_rule = self.get_rule(ledger_id, id).await?;
         if existing_rule.is_none() {
             return Ok(None);
         }
 
         let now = Utc::now();
-        let mut query = String::from("UPDATE rules SET updated_at = $1");
-        let mut params: Vec<String> = vec![];
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE rules SET updated_at = ");
+        query.push_bind(now);
 
-        if let Some(name) = &req.name {
-            params.push(format!("name = '{}'", name));
+        if let Some(name) = req.name {
+            query.push(", name = ").push_bind(name);
         }
 
-        if let Some(description) = &req.description {
-            params.push(format!("description = '{}'", description));
+        if let Some(description) = req.description {
+            query.push(", description = ").push_bind(description);
         }
 
         if let Some(is_active) = req.is_active {
-            params.push(format!("is_active = {}", is_active));
+            query.push(", is_active = ").push_bind(is_active);
         }
 
         if let Some(priority) = req.priority {
-            params.push(format!("priority = {}", priority));
+            query.push(", priority = ").push_bind(priority);
         }
 
-        if let Some(group_id) = &req.group_id {
-            params.push(format!("group_id = '{}'", group_id));
+        if let Some(group_id) = req.group_id {
+            query.push(", group_id = ").push_bind(group_id);
         }
 
         if let Some(conditions) = &req.conditions {
@@ -554,7 +388,7 @@ impl RuleService {
                     error!("Failed to serialize conditions: {}", e);
                     sqlx::Error::Protocol(format!("Failed to serialize conditions: {}", e))
                 })?;
-            params.push(format!("conditions_json = '{}'", conditions_json.replace("'", "''")));
+            query.push(", conditions_json = ").push_bind(conditions_json);
         }
 
         if let Some(actions) = &req.actions {
@@ -563,20 +397,20 @@ impl RuleService {
                     error!("Failed to serialize actions: {}", e);
                     sqlx::Error::Protocol(format!("Failed to serialize actions: {}", e))
                 })?;
-            params.push(format!("actions_json = '{}'", actions_json.replace("'", "''")));
+            query.push(", actions_json = ").push_bind(actions_json);
         }
 
-        if !params.is_empty() {
-            query.push_str(", ");
-            query.push_str(&params.join(", "));
+        if let Some(triggers) = req.triggers {
+            query.push(", triggers_json = ").push_bind(triggers_json(triggers)?);
         }
 
-        query.push_str(" WHERE id = $2 RETURNING *");
+        query.push(" WHERE id = ").push_bind(id);
+        query.push(" AND ledger_id = ").push_bind(ledger_id);
+        query.push(" RETURNING *");
 
         // Update the rule
-        let updated_rule = sqlx::query_as::<_, Rule>(&query)
-            .bind(now)
-            .bind(id)
+        let updated_rule = query
+            .build_query_as::<Rule>()
             .fetch_optional(&self.db)
             .await?;
 
@@ -593,216 +427,154 @@ impl RuleService {
     }
 
     /// Delete a rule
-    pub async fn delete_rule(&self, id: Uuid) -> Result<bool, sqlx::Error> {
-        let result = sqlx::query("DELETE FROM rules WHERE id = $1")
+    pub async fn delete_rule(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM rules WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .execute(&self.db)
             .await?;
 
         Ok(result.rows_affected() > 0)
     }
 
-    /// Apply rules to a transaction
-    pub async fn apply_rules_to_transaction(&self, transaction: &Transaction) -> Result<Option<UpdateTransactionRequest>, sqlx::Error> {
-        // Get all active rules ordered by priority
-        let rules = sqlx::query_as::<_, Rule>("SELECT * FROM rules WHERE is_active = true ORDER BY priority ASC")
+    /// Apply the rules with the given trigger to a transaction, returning the update to make and the
+    /// IDs of the rules that matched
+    pub async fn apply_rules_to_transaction(
+        &self,
+        ledger_id: Uuid,
+        transaction: &Transaction,
+        trigger: RuleTrigger,
+    ) -> Result<Option<(UpdateTransactionRequest, Vec<Uuid>)>, sqlx::Error> {
+        // Get all active rules of the ledger in the order they apply
+        let mut rules = self.get_run_rules(ledger_id, RuleRunScope::All).await?;
+        rules.retain(|rule| rule.triggers.contains(&trigger));
+        let outcome = evaluate_rules(&rules, transaction);
+        Ok(outcome.map(|outcome| (update_request(transaction, &outcome.transaction), outcome.rule_ids)))
+    }
+
+    /// Test a set of conditions against all transactions and return total matches and a sample (first 100 by date desc)
+    pub async fn test_conditions(&self, ledger_id: Uuid, conditions: Vec<RuleConditionNode>) -> Result<(usize, Vec<Transaction>), sqlx::Error> {
+        validate_conditions(&conditions).map_err(sqlx::Error::Protocol)?;
+
+        // Fetch all transactions of the ledger ordered by most recent first for a helpful sample
+        let transactions = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE ledger_id = $1 ORDER BY transaction_date DESC")
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await?;
 
-        if rules.is_empty() {
-            return Ok(None);
-        }
+        let conditions = compile_conditions(&conditions);
+        let matched: Vec<Transaction> = transactions
+            .into_iter()
+            .filter(|transaction| conditions_match(&conditions, transaction))
+            .collect();
 
-        // Initialize an empty update request
-        let mut update_request = UpdateTransactionRequest {
-            destination_account_id: None,
-            destination_name: None,
-            description: None,
-            amount: None,
-            category: None,
-            budget_id: None,
-            transaction_date: None,
-        };
+        let total = matched.len();
+        let sample: Vec<Transaction> = matched.into_iter().take(100).collect();
+        Ok((total, sample))
+    }
 
-        let mut any_rule_applied = false;
+    /// Load the transactions rules may change (reconciled ones are locked) that pass the filter, with
+    /// their split lines and tags, newest first, optionally only the given ones. Within a database
+    /// transaction the rows stay locked.
+    async fn get_unlocked_transactions(
+        &self,
+        conn: &mut PgConnection,
+        ledger_id: Uuid,
+        transaction_ids: Option<&[Uuid]>,
+        filter: &RuleRunFilter,
+    ) -> Result<Vec<Transaction>, sqlx::Error> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM transactions WHERE status <> 'reconciled' AND ledger_id = ");
+        query.push_bind(ledger_id);
+        if let Some(ids) = transaction_ids {
+            query.push(" AND id = ANY(").push_bind(ids).push(")");
+        }
+        if let Some(account_id) = filter.account_id {
+            query.push(" AND (source_account_id = ").push_bind(account_id);
+            query.push(" OR destination_account_id = ").push_bind(account_id).push(")");
+        }
+        if let Some(start_date) = filter.start_date {
+            query.push(" AND transaction_date >= ").push_bind(start_date.and_time(NaiveTime::MIN).and_utc());
+        }
+        if let Some(end_date) = filter.end_date {
+            // Inclusive of the whole last day
+            query.push(" AND transaction_date < ").push_bind((end_date + chrono::Duration::days(1)).and_time(NaiveTime::MIN).and_utc());
+        }
+        query.push(" ORDER BY transaction_date DESC, id FOR UPDATE");
+        let mut transactions = query.build_query_as::<Transaction>().fetch_all(&mut *conn).await?;
 
-        // Process each rule
-        for rule in rules {
-            // Deserialize conditions and actions
-            let conditions: Vec<RuleCondition> = match serde_json::from_str(&rule.conditions_json) {
-                Ok(c) => c,
-                Err(e) => {
-                    error!("Failed to deserialize conditions for rule {}: {}", rule.id, e);
-                    continue;
-                }
-            };
+        self.transaction_service.attach_splits_and_tags(&mut transactions).await?;
+        Ok(transactions)
+    }
 
-            let actions: Vec<RuleAction> = match serde_json::from_str(&rule.actions_json) {
-                Ok(a) => a,
-                Err(e) => {
-                    error!("Failed to deserialize actions for rule {}: {}", rule.id, e);
-                    continue;
-                }
-            };
+    /// Write a planned change to its transaction like any other update, moving balances when the
+    /// destination account changes and recording the change in the transaction's history
+    async fn write_rule_change(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        change: &PlannedRuleChange,
+        actor: ChangeActor,
+    ) -> Result<(), sqlx::Error> {
+        let req = update_request(&change.before, &change.after);
+        self.transaction_service
+            .update_transaction_in(tx, ledger_id, &change.before, req, actor)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+        Ok(())
+    }
+}
 
-            // Check if all conditions match
-            let all_conditions_match = conditions.iter().all(|condition| {
-                match condition.condition_type {
-                    ConditionType::DescriptionContains => {
-                        transaction.description.to_lowercase().contains(&condition.value.to_lowercase())
-                    },
-                    ConditionType::DescriptionStartsWith => {
-                        transaction.description.to_lowercase().starts_with(&condition.value.to_lowercase())
-                    },
-                    ConditionType::DescriptionEquals => {
-                        transaction.description.to_lowercase() == condition.value.to_lowercase()
-                    },
-                    ConditionType::SourceAccountEquals => {
-                        transaction.source_account_id.to_string() == condition.value
-                    },
-                    ConditionType::DestinationAccountEquals => {
-                        transaction.destination_account_id.to_string() == condition.value
-                    },
-                    ConditionType::DestinationNameContains => {
-                        match &transaction.destination_name {
-                            Some(name) => name.to_lowercase().contains(&condition.value.to_lowercase()),
-                            None => false,
-                        }
-                    },
-                    ConditionType::DestinationNameEquals => {
-                        match &transaction.destination_name {
-                            Some(name) => name.to_lowercase() == condition.value.to_lowercase(),
-                            None => false,
-                        }
-                    },
-                    ConditionType::AmountGreaterThan => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => transaction.amount > value,
-                            Err(_) => false,
-                        }
-                    },
-                    ConditionType::AmountLessThan => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => transaction.amount < value,
-                            Err(_) => false,
-                        }
-                    },
-                    ConditionType::AmountEquals => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => (transaction.amount - value).abs() < 0.001, // Use a small epsilon for float comparison
-                            Err(_) => false,
-                        }
-                    },
-                }
-            });
-
-            // If all conditions match, apply the actions
-            if all_conditions_match {
-                debug!("Rule {} matched for transaction {}", rule.name, transaction.id);
-
-                for action in actions {
-                    match action.action_type {
-                        ActionType::SetCategory => {
-                            update_request.category = Some(action.value);
-                        },
-                        ActionType::SetBudget => {
-                            // Try to parse the budget ID
-                            match Uuid::parse_str(&action.value) {
-                                Ok(budget_id) => {
-                                    update_request.budget_id = Some(budget_id);
-                                },
-                                Err(e) => {
-                                    error!("Invalid budget ID in rule {}: {}", rule.id, e);
-                                }
-                            }
-                        },
-                        ActionType::SetDescription => {
-                            update_request.description = Some(action.value);
-                        },
-                        ActionType::SetDestinationName => {
-                            update_request.destination_name = Some(action.value);
-                        },
-                    }
-                }
+/// Serialize the triggers of a rule, each once and in a stable order
+fn triggers_json(mut triggers: Vec<RuleTrigger>) -> Result<String, sqlx::Error> {
+    triggers.sort();
+    triggers.dedup();
+    serde_json::to_string(&triggers).map_err(|e| {
+        error!("Failed to serialize triggers: {}", e);
+        sqlx::Error::Protocol(format!("Failed to serialize triggers: {}", e))
+    })
+}
 
-                any_rule_applied = true;
-            }
-        }
+/// A transaction as it is, and as the rules of a run would leave it
+struct PlannedRuleChange {
+    before: Transaction,
+    after: Transaction,
+    /// Rules that matched, in the order they were applied
+    rule_ids: Vec<Uuid>,
+}
 
-        if any_rule_applied {
-            Ok(Some(update_request))
-        } else {
-            Ok(None)
+impl PlannedRuleChange {
+    fn preview(&self) -> RuleChangePreview {
+        RuleChangePreview {
+            transaction_id: self.after.id,
+            rule_ids: self.rule_ids.clone(),
+            before: RuleFieldValues::from(&self.before),
+            after: RuleFieldValues::from(&self.after),
         }
     }
 
-    /// Test a set of conditions against all transactions and return total matches and a sample (first 100 by date desc)
-    pub async fn test_conditions(&self, conditions: Vec<RuleCondition>) -> Result<(usize, Vec<Transaction>), sqlx::Error> {
-        // Fetch all transactions ordered by most recent first for a helpful sample
-        let transactions = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions ORDER BY transaction_date DESC")
-            .fetch_all(&self.db)
-            .await?;
-
-        let mut matched: Vec<Transaction> = Vec::new();
-
-        for transaction in transactions.into_iter() {
-            let all_match = conditions.iter().all(|condition| {
-                match condition.condition_type {
-                    ConditionType::DescriptionContains => {
-                        transaction.description.to_lowercase().contains(&condition.value.to_lowercase())
-                    },
-                    ConditionType::DescriptionStartsWith => {
-                        transaction.description.to_lowercase().starts_with(&condition.value.to_lowercase())
-                    },
-                    ConditionType::DescriptionEquals => {
-                        transaction.description.to_lowercase() == condition.value.to_lowercase()
-                    },
-                    ConditionType::SourceAccountEquals => {
-                        transaction.source_account_id.to_string() == condition.value
-                    },
-                    ConditionType::DestinationAccountEquals => {
-                        transaction.destination_account_id.to_string() == condition.value
-                    },
-                    ConditionType::DestinationNameContains => {
-                        match &transaction.destination_name {
-                            Some(name) => name.to_lowercase().contains(&condition.value.to_lowercase()),
-                            None => false,
-                        }
-                    },
-                    ConditionType::DestinationNameEquals => {
-                        match &transaction.destination_name {
-                            Some(name) => name.to_lowercase() == condition.value.to_lowercase(),
-                            None => false,
-                        }
-                    },
-                    ConditionType::AmountGreaterThan => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => transaction.amount > value,
-                            Err(_) => false,
-                        }
-                    },
-                    ConditionType::AmountLessThan => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => transaction.amount < value,
-                            Err(_) => false,
-                        }
-                    },
-                    ConditionType::AmountEquals => {
-                        match condition.value.parse::<f64>() {
-                            Ok(value) => (transaction.amount - value).abs() < 0.001,
-                            Err(_) => false,
-                        }
-                    },
-                }
-            });
-
-            if all_match {
-                matched.push(transaction);
-            }
-        }
+    /// Whether the rules change any value, rather than only matching
+    fn changes_values(&self) -> bool {
+        RuleFieldValues::from(&self.before) != RuleFieldValues::from(&self.after)
+    }
 
-        let total = matched.len();
-        let sample: Vec<Transaction> = matched.into_iter().take(100).collect();
-        Ok((total, sample))
+    /// Whether this is the change shown in a preview
+    fn matches(&self, previewed: &RuleChangePreview) -> bool {
+        let current = self.preview();
+        current.rule_ids == previewed.rule_ids && current.before == previewed.before && current.after == previewed.after
     }
 }
+
+/// Evaluate rules in order against each transaction. Every transaction a rule
+/// matched is returned, even when its values would stay the same.
+fn plan_rule_changes(rules: &[CompiledRule], transactions: Vec<Transaction>) -> Vec<PlannedRuleChange> {
+    transactions
+        .into_iter()
+        .filter_map(|before| {
+            evaluate_rules(rules, &before).map(|outcome| PlannedRuleChange {
+                before,
+                after: outcome.transaction,
+                rule_ids: outcome.rule_ids,
+            })
+        })
+        .collect()
+}
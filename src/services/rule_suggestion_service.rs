@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::models::{
+    ActionType, ConditionType, CreateRuleRequest, RuleAction, RuleCondition, RuleResponse, RuleSuggestion, RuleSuggestionQuery,
+    Transaction,
+};
+use crate::services::RuleService;
+
+/// Number of matching transactions a suggestion needs unless asked otherwise
+const DEFAULT_MIN_SUPPORT: usize = 3;
+/// Share of the matching transactions that must be in one category unless asked otherwise
+const DEFAULT_MIN_CONFIDENCE: f64 = 0.8;
+/// Matching transactions listed with a suggestion
+const SAMPLE_SIZE: usize = 5;
+
+/// Service proposing rules learned from the way transactions have been categorised so far
+///
+/// Transactions are grouped by the words of their destination name, or of their description when
+/// they have no destination name, ignoring case, numbers and punctuation. A group that is large
+/// enough and mostly in one category becomes a suggested rule setting that category, and the
+/// group's budget when it is as dominant. Groups that existing rules already match are left out.
+pub struct RuleSuggestionService {
+    db: Pool<Postgres>,
+    /// Checks the existing rules and creates the accepted suggestions
+    rule_service: Arc<RuleService>,
+}
+
+impl RuleSuggestionService {
+    /// Create a new RuleSuggestionService with the given database pool and rule service
+    pub fn new(db: Pool<Postgres>, rule_service: Arc<RuleService>) -> Self {
+        Self { db, rule_service }
+    }
+
+    /// Suggest rules for a ledger, the best supported first
+    pub async fn suggest_rules(&self, ledger_id: Uuid, query: &RuleSuggestionQuery) -> Result<Vec<RuleSuggestion>, sqlx::Error> {
+        let min_support = query.min_support.unwrap_or(DEFAULT_MIN_SUPPORT).max(1);
+        let min_confidence = query.min_confidence.unwrap_or(DEFAULT_MIN_CONFIDENCE);
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(sqlx::Error::Protocol("min_confidence must be between 0 and 1".into()));
+        }
+
+        // Split transactions carry their categories on their lines, and opening balances are
+        // written when accounts are created rather than categorised, so both are left out
+        let transactions = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT * FROM transactions t
+            WHERE ledger_id = $1 AND category <> 'Initial Balance'
+              AND NOT EXISTS (SELECT 1 FROM transaction_splits ts WHERE ts.transaction_id = t.id)
+            ORDER BY transaction_date DESC, id
+            "#,
+        )
+        .bind(ledger_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let category_names: HashMap<Uuid, String> = sqlx::query_as::<_, (Uuid, String)>("SELECT id, name FROM categories WHERE ledger_id = $1")
+            .bind(ledger_id)
+            .fetch_all(&self.db)
+            .await?
+            .into_iter()
+            .collect();
+
+        let rules = self.rule_service.get_active_rules(ledger_id).await?;
+
+        let mut groups: HashMap<(MatchField, Vec<String>), Vec<&Transaction>> = HashMap::new();
+        for transaction in &transactions {
+            if let Some(key) = group_key(transaction) {
+                groups.entry(key).or_default().push(transaction);
+            }
+        }
+
+        let mut suggestions: Vec<RuleSuggestion> = groups
+            .iter()
+            .filter(|(_, members)| members.len() >= min_support)
+            .filter(|(_, members)| !members.iter().any(|transaction| rules.iter().any(|rule| rule.matches(transaction))))
+            .filter_map(|(key, members)| suggest_rule(key, members, &category_names, min_confidence))
+            .collect();
+        suggestions.sort_by(|a, b| b.support.cmp(&a.support).then_with(|| a.rule.name.cmp(&b.rule.name)).then_with(|| a.id.cmp(&b.id)));
+        Ok(suggestions)
+    }
+
+    /// Create the rule of a suggestion, made again with the same thresholds. Returns `None` when the
+    /// suggestion is no longer made, e.g. because its rule was already created.
+    pub async fn accept_suggestion(
+        &self,
+        ledger_id: Uuid,
+        id: &str,
+        query: &RuleSuggestionQuery,
+    ) -> Result<Option<RuleResponse>, sqlx::Error> {
+        let suggestion = self.suggest_rules(ledger_id, query).await?.into_iter().find(|suggestion| suggestion.id == id);
+        match suggestion {
+            Some(suggestion) => self.rule_service.create_rule(ledger_id, suggestion.rule).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Field a group of similar transactions is matched on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum MatchField {
+    DestinationName,
+    Description,
+}
+
+/// Words a destination name or description is compared by: lower-cased, without numbers and
+/// punctuation, e.g. "POS 1234 Coffee-Bar #12" gives "pos", "coffee" and "bar"
+fn normalised_tokens(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|token| !token.is_empty() && !token.chars().any(char::is_numeric))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Group of a transaction: the words of its destination name, or of its description without one
+fn group_key(transaction: &Transaction) -> Option<(MatchField, Vec<String>)> {
+    let name_tokens = transaction.destination_name.as_deref().map(normalised_tokens).unwrap_or_default();
+    if !name_tokens.is_empty() {
+        return Some((MatchField::DestinationName, name_tokens));
+    }
+    let description_tokens = normalised_tokens(&transaction.description);
+    (!description_tokens.is_empty()).then_some((MatchField::Description, description_tokens))
+}
+
+/// Most common value and how often it occurs; ties go to the value seen first
+fn dominant<T: PartialEq>(values: impl Iterator<Item = T>) -> Option<(T, usize)> {
+    let mut counts: Vec<(T, usize)> = Vec::new();
+    for value in values {
+        match counts.iter_mut().find(|(seen, _)| *seen == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    counts.into_iter().reduce(|best, next| if next.1 > best.1 { next } else { best })
+}
+
+/// The rule suggested for a group of similar transactions, when enough of them share a category
+fn suggest_rule(
+    (field, tokens): &(MatchField, Vec<String>),
+    members: &[&Transaction],
+    category_names: &HashMap<Uuid, String>,
+    min_confidence: f64,
+) -> Option<RuleSuggestion> {
+    let support = members.len();
+    let categories = members.iter().map(|transaction| {
+        transaction
+            .category_id
+            .and_then(|id| category_names.get(&id))
+            .map(String::as_str)
+            .unwrap_or(&transaction.category)
+            .trim()
+    });
+    let (category, count) = dominant(categories.filter(|category| !category.is_empty() && *category != "Uncategorized"))?;
+    let confidence = count as f64 / support as f64;
+    if confidence < min_confidence {
+        return None;
+    }
+
+    // All members have the same words, in the same order; the rule matches them all
+    let values: Vec<&str> = members
+        .iter()
+        .map(|transaction| match field {
+            MatchField::DestinationName => transaction.destination_name.as_deref().unwrap_or_default(),
+            MatchField::Description => transaction.description.as_str(),
+        })
+        .collect();
+    let (value, _) = dominant(values.iter().copied())?;
+    let condition = if values.iter().all(|other| other.to_lowercase() == value.to_lowercase()) {
+        RuleCondition {
+            condition_type: match field {
+                MatchField::DestinationName => ConditionType::DestinationNameEquals,
+                MatchField::Description => ConditionType::DescriptionEquals,
+            },
+            value: value.to_string(),
+        }
+    } else {
+        let words: Vec<String> = tokens.iter().map(|token| regex::escape(token)).collect();
+        RuleCondition {
+            condition_type: match field {
+                MatchField::DestinationName => ConditionType::DestinationNameMatchesRegex,
+                MatchField::Description => ConditionType::DescriptionMatchesRegex,
+            },
+            value: format!(r"\b{}\b", words.join(r"\b.*\b")),
+        }
+    };
+
+    let mut actions = vec![RuleAction {
+        action_type: ActionType::SetCategory,
+        value: category.to_string(),
+        pattern: None,
+        splits: None,
+    }];
+    let budget = dominant(members.iter().filter_map(|transaction| transaction.budget_id))
+        .filter(|(_, count)| *count as f64 / support as f64 >= min_confidence);
+    if let Some((budget_id, _)) = budget {
+        actions.push(RuleAction {
+            action_type: ActionType::SetBudget,
+            value: budget_id.to_string(),
+            pattern: None,
+            splits: None,
+        });
+    }
+
+    let digest = Sha256::digest(format!("{:?}|{}|{}", field, tokens.join(" "), category).as_bytes());
+    Some(RuleSuggestion {
+        id: hex::encode(&digest[..8]),
+        rule: CreateRuleRequest {
+            name: format!("{}: {}", value.trim(), category),
+            description: Some(format!(
+                "Suggested from {} transactions, {:.0}% of them in {}",
+                support,
+                confidence * 100.0,
+                category
+            )),
+            is_active: true,
+            priority: None,
+            group_id: None,
+            conditions: vec![condition.into()],
+            actions,
+            triggers: None,
+        },
+        support,
+        confidence: (confidence * 100.0).round() / 100.0,
+        sample_transaction_ids: members.iter().take(SAMPLE_SIZE).map(|transaction| transaction.id).collect(),
+    })
+}
@@ -1,7 +1,10 @@
 use chrono::Utc;
 use sqlx::{Pool, Postgres};
+use uuid::Uuid;
 
-use crate::models::{Setting, UpdateSettingRequest};
+use crate::models::{Setting, UpdateSettingRequest, DEFAULT_BASE_CURRENCY};
+use crate::services::exchange_rate_service::normalize_currency;
+use rust_decimal::Decimal;
 
 pub struct SettingsService {
     db: Pool<Postgres>,
@@ -12,15 +15,16 @@ impl SettingsService {
         Self { db }
     }
 
-    /// Get a setting by key
-    pub async fn get_setting(&self, key: &str) -> Result<Option<Setting>, sqlx::Error> {
+    /// Get a setting of a ledger by key
+    pub async fn get_setting(&self, ledger_id: Uuid, key: &str) -> Result<Option<Setting>, sqlx::Error> {
         let setting = sqlx::query_as::<_, Setting>(
             r#"
             SELECT * FROM settings
-            WHERE key = $1
+            WHERE key = $1 AND ledger_id = $2
             "#,
         )
         .bind(key)
+        .bind(ledger_id)
         .fetch_optional(&self.db)
         .await?;
 
@@ -28,11 +32,11 @@ impl SettingsService {
     }
 
     /// Update a setting
-    pub async fn update_setting(&self, key: &str, req: UpdateSettingRequest) -> Result<Setting, sqlx::Error> {
+    pub async fn update_setting(&self, ledger_id: Uuid, key: &str, req: UpdateSettingRequest) -> Result<Setting, sqlx::Error> {
         let now = Utc::now();
 
         // Check if the setting exists
-        let setting = self.get_setting(key).await?;
+        let setting = self.get_setting(ledger_id, key).await?;
 
         if let Some(_) = setting {
             // Update existing setting
@@ -40,13 +44,14 @@ impl SettingsService {
                 r#"
                 UPDATE settings
                 SET value = $1, updated_at = $2
-                WHERE key = $3
+                WHERE key = $3 AND ledger_id = $4
                 RETURNING *
                 "#,
             )
             .bind(&req.value)
             .bind(now)
             .bind(key)
+            .bind(ledger_id)
             .fetch_one(&self.db)
             .await?;
 
@@ -55,11 +60,12 @@ impl SettingsService {
             // Create new setting
             let new_setting = sqlx::query_as::<_, Setting>(
                 r#"
-                INSERT INTO settings (key, value, created_at, updated_at)
-                VALUES ($1, $2, $3, $4)
+                INSERT INTO settings (ledger_id, key, value, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5)
                 RETURNING *
                 "#,
             )
+            .bind(ledger_id)
             .bind(key)
             .bind(&req.value)
             .bind(now)
@@ -72,33 +78,50 @@ impl SettingsService {
     }
 
     /// Get the forecasted monthly income
-    pub async fn get_forecasted_monthly_income(&self) -> Result<f64, sqlx::Error> {
-        let setting = self.get_setting("forecasted_monthly_income").await?;
+    pub async fn get_forecasted_monthly_income(&self, ledger_id: Uuid) -> Result<Decimal, sqlx::Error> {
+        let setting = self.get_setting(ledger_id, "forecasted_monthly_income").await?;
 
         match setting {
             Some(s) => {
-                // Parse the value as f64
-                match s.value.parse::<f64>() {
+                // Parse the value as a decimal
+                match s.value.parse::<Decimal>() {
                     Ok(value) => Ok(value),
-                    Err(_) => Ok(0.0), // Default to 0.0 if parsing fails
+                    Err(_) => Ok(Decimal::ZERO), // Default to 0 if parsing fails
                 }
             }
-            None => Ok(0.0), // Default to 0.0 if setting doesn't exist
+            None => Ok(Decimal::ZERO), // Default to 0 if setting doesn't exist
         }
     }
 
     /// Update the forecasted monthly income
-    pub async fn update_forecasted_monthly_income(&self, amount: f64) -> Result<f64, sqlx::Error> {
+    pub async fn update_forecasted_monthly_income(&self, ledger_id: Uuid, amount: Decimal) -> Result<Decimal, sqlx::Error> {
         let req = UpdateSettingRequest {
             value: amount.to_string(),
         };
 
-        let updated = self.update_setting("forecasted_monthly_income", req).await?;
+        let updated = self.update_setting(ledger_id, "forecasted_monthly_income", req).await?;
 
-        // Parse the updated value as f64
-        match updated.value.parse::<f64>() {
+        // Parse the updated value as a decimal
+        match updated.value.parse::<Decimal>() {
             Ok(value) => Ok(value),
-            Err(_) => Ok(0.0), // Default to 0.0 if parsing fails
+            Err(_) => Ok(Decimal::ZERO), // Default to 0 if parsing fails
         }
     }
+
+    /// Get the currency reports are converted into
+    pub async fn get_base_currency(&self, ledger_id: Uuid) -> Result<String, sqlx::Error> {
+        let setting = self.get_setting(ledger_id, "base_currency").await?;
+
+        Ok(setting.map(|s| s.value).unwrap_or_else(|| DEFAULT_BASE_CURRENCY.to_string()))
+    }
+
+    /// Update the currency reports are converted into
+    pub async fn update_base_currency(&self, ledger_id: Uuid, currency: &str) -> Result<String, sqlx::Error> {
+        let req = UpdateSettingRequest {
+            value: normalize_currency(currency)?,
+        };
+
+        let updated = self.update_setting(ledger_id, "base_currency", req).await?;
+        Ok(updated.value)
+    }
 }
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sqlx::{PgConnection, Pool, Postgres};
+use uuid::Uuid;
+
+use crate::models::{CreateTagRequest, Tag, UpdateTagRequest};
+
+/// Columns of a tag row including its usage count, for `SELECT ... FROM tags tg`
+const TAG_COLUMNS_SQL: &str =
+    "tg.*, (SELECT COUNT(*) FROM transaction_tags tt WHERE tt.tag_id = tg.id) AS transaction_count";
+
+/// Trim tag names, dropping empty ones and duplicates while keeping the given order
+fn normalize_tag_names(names: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.trim();
+        if !name.is_empty() && !normalized.iter().any(|n| n == name) {
+            normalized.push(name.to_string());
+        }
+    }
+    normalized
+}
+
+/// Find a tag by name, or create it if it doesn't exist
+async fn find_or_create_tag(conn: &mut PgConnection, ledger_id: Uuid, name: &str) -> Result<Uuid, sqlx::Error> {
+    let now = Utc::now();
+    sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO tags (id, ledger_id, name, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $4)
+        ON CONFLICT (ledger_id, name) DO UPDATE SET name = EXCLUDED.name
+        RETURNING id
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(ledger_id)
+    .bind(name)
+    .bind(now)
+    .fetch_one(conn)
+    .await
+}
+
+/// Replace the tags of a transaction with the given names, creating missing tags.
+/// Returns the normalized tag names now on the transaction, sorted by name.
+pub(crate) async fn set_transaction_tags(
+    conn: &mut PgConnection,
+    ledger_id: Uuid,
+    transaction_id: Uuid,
+    names: &[String],
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query("DELETE FROM transaction_tags WHERE transaction_id = $1")
+        .bind(transaction_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let mut names = normalize_tag_names(names);
+    for name in &names {
+        add_transaction_tag(&mut *conn, ledger_id, transaction_id, name).await?;
+    }
+    names.sort();
+
+    Ok(names)
+}
+
+/// Attach a tag to a transaction, creating the tag if needed; already tagged transactions are left alone
+pub(crate) async fn add_transaction_tag(
+    conn: &mut PgConnection,
+    ledger_id: Uuid,
+    transaction_id: Uuid,
+    name: &str,
+) -> Result<(), sqlx::Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(());
+    }
+    let tag_id = find_or_create_tag(&mut *conn, ledger_id, name).await?;
+    sqlx::query("INSERT INTO transaction_tags (transaction_id, tag_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+        .bind(transaction_id)
+        .bind(tag_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Load the tag names of the given transactions, keyed by transaction ID and sorted by name
+pub(crate) async fn get_transaction_tags<'e, E>(executor: E, transaction_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<String>>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    let rows = sqlx::query_as::<_, (Uuid, String)>(
+        r#"
+        SELECT tt.transaction_id, tg.name
+        FROM transaction_tags tt
+        JOIN tags tg ON tg.id = tt.tag_id
+        WHERE tt.transaction_id = ANY($1)
+        ORDER BY tg.name
+        "#,
+    )
+    .bind(transaction_ids)
+    .fetch_all(executor)
+    .await?;
+
+    let mut tags: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for (transaction_id, name) in rows {
+        tags.entry(transaction_id).or_default().push(name);
+    }
+    Ok(tags)
+}
+
+/// Service for handling tag-related operations
+pub struct TagService {
+    db: Pool<Postgres>,
+}
+
+impl TagService {
+    /// Create a new TagService with the given database pool
+    pub fn new(db: Pool<Postgres>) -> Self {
+        Self { db }
+    }
+
+    /// Get all tags in a ledger
+    pub async fn get_tags(&self, ledger_id: Uuid) -> Result<Vec<Tag>, sqlx::Error> {
+        sqlx::query_as::<_, Tag>(&format!("SELECT {} FROM tags tg WHERE tg.ledger_id = $1 ORDER BY tg.name", TAG_COLUMNS_SQL))
+            .bind(ledger_id)
+            .fetch_all(&self.db)
+            .await
+    }
+
+    /// Get a tag by ID
+    pub async fn get_tag(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Tag>, sqlx::Error> {
+        sqlx::query_as::<_, Tag>(&format!("SELECT {} FROM tags tg WHERE tg.id = $1 AND tg.ledger_id = $2", TAG_COLUMNS_SQL))
+            .bind(id)
+            .bind(ledger_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// Ensure a tag name is usable and not taken by another tag of the ledger
+    async fn validate_name(&self, ledger_id: Uuid, name: &str, id: Option<Uuid>) -> Result<String, sqlx::Error> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(sqlx::Error::Protocol("Invalid tag: name must not be empty".into()));
+        }
+
+        let taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM tags WHERE ledger_id = $1 AND name = $2 AND ($3::UUID IS NULL OR id <> $3))",
+        )
+        .bind(ledger_id)
+        .bind(name)
+        .bind(id)
+        .fetch_one(&self.db)
+        .await?;
+        if taken {
+            return Err(sqlx::Error::Protocol(format!("Invalid tag: '{}' already exists", name)));
+        }
+
+        Ok(name.to_string())
+    }
+
+    /// Create a new tag
+    pub async fn create_tag(&self, ledger_id: Uuid, req: CreateTagRequest) -> Result<Tag, sqlx::Error> {
+        let name = self.validate_name(ledger_id, &req.name, None).await?;
+        let now = Utc::now();
+
+        sqlx::query_as::<_, Tag>(
+            r#"
+            INSERT INTO tags (id, ledger_id, name, description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *, 0::BIGINT AS transaction_count
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(ledger_id)
+        .bind(&name)
+        .bind(&req.description)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Update an existing tag; renaming it renames it on every tagged transaction
+    pub async fn update_tag(&self, ledger_id: Uuid, id: Uuid, req: UpdateTagRequest) -> Result<Option<Tag>, sqlx::Error> {
+        let Some(tag) = self.get_tag(ledger_id, id).await? else {
+            return Ok(None);
+        };
+
+        let name = match &req.name {
+            Some(name) => self.validate_name(ledger_id, name, Some(id)).await?,
+            None => tag.name,
+        };
+        let description = req.description.or(tag.description);
+
+        sqlx::query("UPDATE tags SET name = $1, description = $2, updated_at = $3 WHERE id = $4 AND ledger_id = $5")
+            .bind(&name)
+            .bind(&description)
+            .bind(Utc::now())
+            .bind(id)
+            .bind(ledger_id)
+            .execute(&self.db)
+            .await?;
+
+        self.get_tag(ledger_id, id).await
+    }
+
+    /// Delete a tag, removing it from all transactions
+    pub async fn delete_tag(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM tags WHERE id = $1 AND ledger_id = $2")
+            .bind(id)
+            .bind(ledger_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sqlx::{PgConnection, Postgres};
+use uuid::Uuid;
+
+use crate::models::{
+    ChangeActor, FieldChange, Transaction, TransactionChange, TransactionSplitRequest, UpdateTransactionRequest,
+    CHANGE_TYPE_CREATE, CHANGE_TYPE_UPDATE,
+};
+
+/// Values of the user-visible fields of a transaction, keyed by field name, as recorded in its history
+pub(crate) fn transaction_snapshot(transaction: &Transaction) -> BTreeMap<String, Value> {
+    // Split lines written together share a creation time, so their load order is not stable
+    let mut splits: Vec<Value> = transaction
+        .splits
+        .iter()
+        .map(|split| {
+            json!({
+                "amount": split.amount,
+                "category_id": split.category_id,
+                "budget_id": split.budget_id,
+                "memo": split.memo,
+            })
+        })
+        .collect();
+    splits.sort_by_key(|split| split.to_string());
+
+    BTreeMap::from([
+        ("description".to_string(), json!(transaction.description)),
+        ("amount".to_string(), json!(transaction.amount)),
+        ("category".to_string(), json!(transaction.category)),
+        ("budget_id".to_string(), json!(transaction.budget_id)),
+        ("transaction_date".to_string(), json!(transaction.transaction_date)),
+        ("destination_account_id".to_string(), json!(transaction.destination_account_id)),
+        ("destination_name".to_string(), json!(transaction.destination_name)),
+        ("status".to_string(), json!(transaction.status)),
+        ("foreign_amount".to_string(), json!(transaction.foreign_amount)),
+        ("memo".to_string(), json!(transaction.memo)),
+        ("splits".to_string(), Value::Array(splits)),
+        ("tags".to_string(), json!(transaction.tags)),
+    ])
+}
+
+/// Fields that differ between two snapshots of the same transaction
+pub(crate) fn diff_snapshots(before: &BTreeMap<String, Value>, after: &BTreeMap<String, Value>) -> BTreeMap<String, FieldChange> {
+    after
+        .iter()
+        .filter_map(|(field, new)| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            (old != *new).then(|| (field.clone(), FieldChange { old, new: new.clone() }))
+        })
+        .collect()
+}
+
+/// Record the creation of a transaction with its initial values
+pub(crate) async fn record_transaction_creation(
+    conn: &mut PgConnection,
+    ledger_id: Uuid,
+    transaction: &Transaction,
+    actor: &ChangeActor,
+) -> Result<(), sqlx::Error> {
+    let changes = diff_snapshots(&BTreeMap::new(), &transaction_snapshot(transaction))
+        .into_iter()
+        .filter(|(_, change)| !change.new.is_null())
+        .collect();
+    insert_change(conn, ledger_id, transaction.id, CHANGE_TYPE_CREATE, actor, &changes).await
+}
+
+/// Record the fields an update changed; nothing is recorded when no field changed
+pub(crate) async fn record_transaction_update(
+    conn: &mut PgConnection,
+    ledger_id: Uuid,
+    before: &Transaction,
+    after: &Transaction,
+    actor: &ChangeActor,
+) -> Result<(), sqlx::Error> {
+    let changes = diff_snapshots(&transaction_snapshot(before), &transaction_snapshot(after));
+    if changes.is_empty() {
+        return Ok(());
+    }
+    insert_change(conn, ledger_id, after.id, CHANGE_TYPE_UPDATE, actor, &changes).await
+}
+
+async fn insert_change(
+    conn: &mut PgConnection,
+    ledger_id: Uuid,
+    transaction_id: Uuid,
+    change_type: &str,
+    actor: &ChangeActor,
+    changes: &BTreeMap<String, FieldChange>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO transaction_changes (id, ledger_id, transaction_id, change_type, source, source_id, run_id, changes_json, created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(ledger_id)
+    .bind(transaction_id)
+    .bind(change_type)
+    .bind(actor.source)
+    .bind(actor.source_id)
+    .bind(actor.run_id)
+    .bind(serde_json::to_string(changes).unwrap_or_default())
+    .bind(Utc::now())
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Parse the stored field changes of history rows
+pub(crate) fn parse_changes(mut changes: Vec<TransactionChange>) -> Vec<TransactionChange> {
+    for change in changes.iter_mut() {
+        change.changes = serde_json::from_str(&change.changes_json).unwrap_or_default();
+    }
+    changes
+}
+
+/// Get the change history of a transaction, oldest first
+pub(crate) async fn get_transaction_changes<'e, E>(executor: E, ledger_id: Uuid, transaction_id: Uuid) -> Result<Vec<TransactionChange>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Postgres>,
+{
+    let changes = sqlx::query_as::<_, TransactionChange>(
+        "SELECT * FROM transaction_changes WHERE ledger_id = $1 AND transaction_id = $2 ORDER BY created_at, id",
+    )
+    .bind(ledger_id)
+    .bind(transaction_id)
+    .fetch_all(executor)
+    .await?;
+    Ok(parse_changes(changes))
+}
+
+/// Deserialize the previous value of a field stored in a change
+fn previous<T: DeserializeOwned>(change: &TransactionChange, field: &str, value: &Value) -> Result<T, sqlx::Error> {
+    serde_json::from_value(value.clone())
+        .map_err(|_| sqlx::Error::Protocol(format!("Change {} cannot be undone: invalid previous {}", change.id, field)))
+}
+
+/// Build the update restoring the previous values of a change. Fields whose previous value was
+/// empty cannot be expressed in an update request and are returned separately to be cleared.
+pub(crate) fn revert_request(change: &TransactionChange) -> Result<(UpdateTransactionRequest, Vec<&'static str>), sqlx::Error> {
+    let mut req = UpdateTransactionRequest {
+        destination_account_id: None,
+        destination_name: None,
+        description: None,
+        amount: None,
+        category: None,
+        budget_id: None,
+        transaction_date: None,
+        splits: None,
+        status: None,
+        foreign_amount: None,
+        tags: None,
+        memo: None,
+    };
+    let mut cleared = Vec::new();
+
+    for (field, FieldChange { old, .. }) in &change.changes {
+        match field.as_str() {
+            "description" => req.description = Some(previous(change, field, old)?),
+            "amount" => req.amount = Some(previous::<Decimal>(change, field, old)?),
+            "category" => req.category = Some(previous(change, field, old)?),
+            "budget_id" if old.is_null() => cleared.push("budget_id"),
+            "budget_id" => req.budget_id = Some(previous(change, field, old)?),
+            "transaction_date" => req.transaction_date = Some(previous::<DateTime<Utc>>(change, field, old)?),
+            "destination_account_id" => req.destination_account_id = Some(previous(change, field, old)?),
+            // The name follows a restored destination account
+            "destination_name" if change.changes.contains_key("destination_account_id") => {}
+            "destination_name" if old.is_null() => cleared.push("destination_name"),
+            "destination_name" => req.destination_name = Some(previous(change, field, old)?),
+            "status" => req.status = Some(previous(change, field, old)?),
+            // Converted again from the restored amount when there was none
+            "foreign_amount" if old.is_null() => {}
+            "foreign_amount" => req.foreign_amount = Some(previous::<Decimal>(change, field, old)?),
+            "splits" => req.splits = Some(previous::<Vec<TransactionSplitRequest>>(change, field, old)?),
+            "tags" => req.tags = Some(previous(change, field, old)?),
+            "memo" if old.is_null() => cleared.push("memo"),
+            "memo" => req.memo = Some(previous(change, field, old)?),
+            _ => return Err(sqlx::Error::Protocol(format!("Change {} cannot be undone: unknown field {}", change.id, field))),
+        }
+    }
+
+    Ok((req, cleared))
+}
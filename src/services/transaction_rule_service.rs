@@ -3,8 +3,9 @@ use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 use tracing::{debug, info};
 
-use crate::models::{Transaction, CreateTransactionRequest, UpdateTransactionRequest};
+use crate::models::{Transaction, ChangeActor, RuleTrigger, CHANGE_SOURCE_IMPORT, CreateTransactionRequest, UpdateTransactionRequest, TransactionSearchQuery, TransactionSearchResult, BulkEditTransactionsRequest, BulkEditResult, TransactionChange, UndoRunResult};
 use crate::services::{TransactionService, RuleService};
+use rust_decimal::Decimal;
 
 /// Service for applying rules to transactions
 pub struct TransactionRuleService {
@@ -14,8 +15,8 @@ pub struct TransactionRuleService {
 
 impl TransactionRuleService {
     /// Get monthly incoming transactions (pass-through)
-    pub async fn get_monthly_incoming_transactions(&self, year: i32, month: u32) -> Result<Vec<Transaction>, sqlx::Error> {
-        self.transaction_service.get_monthly_incoming_transactions(year, month).await
+    pub async fn get_monthly_incoming_transactions(&self, ledger_id: Uuid, year: i32, month: u32) -> Result<Vec<Transaction>, sqlx::Error> {
+        self.transaction_service.get_monthly_incoming_transactions(ledger_id, year, month).await
     }
     /// Create a new TransactionRuleService with the given services
     pub fn new(transaction_service: Arc<TransactionService>, rule_service: Arc<RuleService>) -> Self {
@@ -25,35 +26,62 @@ impl TransactionRuleService {
         }
     }
 
-    /// Create a transaction with rule application
-    pub async fn create_transaction(&self, req: CreateTransactionRequest) -> Result<Transaction, sqlx::Error> {
+    /// Create a transaction with rule application; imported transactions get the rules triggered on import
+    pub async fn create_transaction(&self, ledger_id: Uuid, req: CreateTransactionRequest, actor: ChangeActor) -> Result<Transaction, sqlx::Error> {
+        let trigger = if actor.source == CHANGE_SOURCE_IMPORT { RuleTrigger::Import } else { RuleTrigger::Create };
+
         // First, create the transaction
-        let transaction = self.transaction_service.create_transaction(req).await?;
+        let transaction = self.transaction_service.create_transaction(ledger_id, req, actor).await?;
 
         // Then apply rules to the transaction
-        if let Ok(Some(update_request)) = self.rule_service.apply_rules_to_transaction(&transaction).await {
+        Ok(self.apply_creation_rules(ledger_id, transaction, trigger, actor).await)
+    }
+
+    /// Create a transaction within an open database transaction; rules are applied with
+    /// `apply_creation_rules` once it has been committed
+    pub(crate) async fn create_transaction_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        req: CreateTransactionRequest,
+        actor: ChangeActor,
+    ) -> Result<Transaction, sqlx::Error> {
+        self.transaction_service.create_transaction_in(tx, ledger_id, req, actor).await
+    }
+
+    /// Apply the rules for `trigger` to a newly created transaction
+    pub(crate) async fn apply_creation_rules(&self, ledger_id: Uuid, transaction: Transaction, trigger: RuleTrigger, actor: ChangeActor) -> Transaction {
+        if let Ok(Some((update_request, rule_ids))) = self.rule_service.apply_rules_to_transaction(ledger_id, &transaction, trigger).await {
             // If any rules matched, update the transaction
-            if let Ok(Some(updated_transaction)) = self.transaction_service.update_transaction(transaction.id, update_request).await {
+            let rule_actor = ChangeActor::rules(&rule_ids, actor.run_id);
+            if let Ok(Some(updated_transaction)) = self.transaction_service.update_transaction(ledger_id, transaction.id, update_request, rule_actor).await {
                 info!("Applied rules to transaction {}", transaction.id);
-                return Ok(updated_transaction);
+                return updated_transaction;
             }
         }
 
         // If no rules matched or the update failed, return the original transaction
-        Ok(transaction)
+        transaction
     }
 
     /// Update a transaction with rule application
-    pub async fn update_transaction(&self, id: Uuid, req: UpdateTransactionRequest) -> Result<Option<Transaction>, sqlx::Error> {
+    pub async fn update_transaction(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        req: UpdateTransactionRequest,
+        actor: ChangeActor,
+    ) -> Result<Option<Transaction>, sqlx::Error> {
         // First, update the transaction
-        let updated_transaction = self.transaction_service.update_transaction(id, req).await?;
+        let updated_transaction = self.transaction_service.update_transaction(ledger_id, id, req, actor).await?;
 
         // If the transaction was updated successfully
         if let Some(transaction) = updated_transaction {
             // Apply rules to the transaction
-            if let Ok(Some(update_request)) = self.rule_service.apply_rules_to_transaction(&transaction).await {
+            if let Ok(Some((update_request, rule_ids))) = self.rule_service.apply_rules_to_transaction(ledger_id, &transaction, RuleTrigger::Update).await {
                 // If any rules matched, update the transaction again
-                if let Ok(Some(rule_updated_transaction)) = self.transaction_service.update_transaction(transaction.id, update_request).await {
+                let rule_actor = ChangeActor::rules(&rule_ids, actor.run_id);
+                if let Ok(Some(rule_updated_transaction)) = self.transaction_service.update_transaction(ledger_id, transaction.id, update_request, rule_actor).await {
                     info!("Applied rules to updated transaction {}", transaction.id);
                     return Ok(Some(rule_updated_transaction));
                 }
@@ -68,53 +96,93 @@ impl TransactionRuleService {
     }
 
     /// Delete a transaction (pass-through to TransactionService)
-    pub async fn delete_transaction(&self, id: Uuid) -> Result<bool, sqlx::Error> {
-        self.transaction_service.delete_transaction(id).await
+    pub async fn delete_transaction(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        self.transaction_service.delete_transaction(ledger_id, id).await
+    }
+
+    /// Unlock a reconciled transaction (pass-through to TransactionService)
+    pub async fn unlock_transaction(&self, ledger_id: Uuid, id: Uuid, actor: ChangeActor) -> Result<Option<Transaction>, sqlx::Error> {
+        self.transaction_service.unlock_transaction(ledger_id, id, actor).await
     }
 
     /// Get a transaction by ID (pass-through to TransactionService)
-    pub async fn get_transaction(&self, id: Uuid) -> Result<Option<Transaction>, sqlx::Error> {
-        self.transaction_service.get_transaction(id).await
+    pub async fn get_transaction(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Transaction>, sqlx::Error> {
+        self.transaction_service.get_transaction(ledger_id, id).await
     }
 
     /// Get all transactions (pass-through to TransactionService)
     pub async fn get_transactions(
         &self,
+        ledger_id: Uuid,
         source_account_id: Option<Uuid>,
         category: Option<&str>,
+        tag: Option<&str>,
         start_date: Option<chrono::DateTime<chrono::Utc>>,
         end_date: Option<chrono::DateTime<chrono::Utc>>,
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> Result<Vec<Transaction>, sqlx::Error> {
-        self.transaction_service.get_transactions(source_account_id, category, start_date, end_date, limit, offset).await
+        self.transaction_service.get_transactions(ledger_id, source_account_id, category, tag, start_date, end_date, limit, offset).await
+    }
+
+    /// Search transactions (pass-through to TransactionService)
+    pub async fn search_transactions(&self, ledger_id: Uuid, query: &TransactionSearchQuery) -> Result<TransactionSearchResult, sqlx::Error> {
+        self.transaction_service.search_transactions(ledger_id, query).await
+    }
+
+    /// Edit many transactions at once (pass-through; rules are not re-applied to explicit bulk corrections)
+    pub async fn bulk_edit_transactions(
+        &self,
+        ledger_id: Uuid,
+        req: BulkEditTransactionsRequest,
+        actor: ChangeActor,
+    ) -> Result<BulkEditResult, sqlx::Error> {
+        self.transaction_service.bulk_edit_transactions(ledger_id, req, actor).await
+    }
+
+    /// Get the change history of a transaction (pass-through to TransactionService)
+    pub async fn get_transaction_history(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Vec<TransactionChange>>, sqlx::Error> {
+        self.transaction_service.get_transaction_history(ledger_id, id).await
+    }
+
+    /// Undo a single transaction change (pass-through; rules are not re-applied to the restored values)
+    pub async fn undo_transaction_change(&self, ledger_id: Uuid, change_id: Uuid) -> Result<Option<Transaction>, sqlx::Error> {
+        self.transaction_service.undo_transaction_change(ledger_id, change_id).await
+    }
+
+    /// Undo all changes of a rule run or bulk edit (pass-through to TransactionService)
+    pub async fn undo_change_run(&self, ledger_id: Uuid, run_id: Uuid) -> Result<Option<UndoRunResult>, sqlx::Error> {
+        self.transaction_service.undo_change_run(ledger_id, run_id).await
     }
 
     /// Get transactions for a specific account (pass-through to TransactionService)
     pub async fn get_account_transactions(
         &self,
+        ledger_id: Uuid,
         account_id: Uuid,
         limit: Option<i64>,
         offset: Option<i64>
     ) -> Result<Vec<Transaction>, sqlx::Error> {
-        self.transaction_service.get_account_transactions(account_id, limit, offset).await
+        self.transaction_service.get_account_transactions(ledger_id, account_id, limit, offset).await
     }
 
     /// Get spending by category (pass-through to TransactionService)
     pub async fn get_spending_by_category(
         &self,
+        ledger_id: Uuid,
         start_date: Option<chrono::DateTime<chrono::Utc>>,
         end_date: Option<chrono::DateTime<chrono::Utc>>,
-    ) -> Result<Vec<(String, f64)>, sqlx::Error> {
-        self.transaction_service.get_spending_by_category(start_date, end_date).await
+    ) -> Result<Vec<(String, Decimal)>, sqlx::Error> {
+        self.transaction_service.get_spending_by_category(ledger_id, start_date, end_date).await
     }
 
     /// Get unbudgeted transactions (pass-through to ensure base query parity with totals)
     pub async fn get_unbudgeted_transactions(
         &self,
+        ledger_id: Uuid,
         start_date: Option<chrono::DateTime<chrono::Utc>>,
         end_date: Option<chrono::DateTime<chrono::Utc>>,
     ) -> Result<Vec<Transaction>, sqlx::Error> {
-        self.transaction_service.get_unbudgeted_transactions(start_date, end_date).await
+        self.transaction_service.get_unbudgeted_transactions(ledger_id, start_date, end_date).await
     }
 }
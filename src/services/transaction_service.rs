@@ -1,19 +1,87 @@
-use chrono::{DateTime, Utc};
-use sqlx::{Pool, Postgres, Row};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use sqlx::{Acquire, Pool, Postgres, QueryBuilder, Row};
+use tracing::info;
 use uuid::Uuid;
 
-use crate::models::{Transaction, CreateTransactionRequest, UpdateTransactionRequest};
+use crate::models::{
+    BalanceHistoryPoint, NetWorthGroup, NetWorthPoint,
+    Transaction, TransactionSplit, TransactionSplitRequest, CreateTransactionRequest, UpdateTransactionRequest,
+    TransactionSearchQuery, TransactionSearchResult,
+    BulkEditItemResult, BulkEditResult, BulkEditTransactionsRequest, BulkTransactionPatch,
+    ChangeActor, TransactionChange, UndoRunResult, CHANGE_TYPE_UPDATE,
+    DEFAULT_BASE_CURRENCY, TRANSACTION_STATUS_CLEARED, TRANSACTION_STATUS_RECONCILED, TRANSACTION_STATUS_UNCLEARED,
+};
 use crate::services::category_service::CategoryService;
+use crate::services::exchange_rate_service::{converted_amount_sql, find_exchange_rate};
+use crate::services::tag_service::{get_transaction_tags, set_transaction_tags};
+use crate::services::transaction_change_service::{
+    get_transaction_changes, parse_changes, record_transaction_creation, record_transaction_update,
+    revert_request, transaction_snapshot,
+};
+use crate::services::SettingsService;
+use std::sync::Arc;
+use rust_decimal::Decimal;
+
+/// Expands transactions into the lines that carry category and budget assignments:
+/// one row per split line for split transactions, and the transaction itself otherwise.
+/// Used as a derived table (`FROM (...) t`) so aggregations count each split separately.
+pub(crate) const TRANSACTION_LINES_SQL: &str = "
+    SELECT tr.id, tr.ledger_id, tr.source_account_id, tr.destination_account_id, tr.amount, tr.category, tr.category_id, tr.budget_id, tr.transaction_date
+    FROM transactions tr
+    WHERE NOT EXISTS (SELECT 1 FROM transaction_splits ts WHERE ts.transaction_id = tr.id)
+    UNION ALL
+    SELECT tr.id, tr.ledger_id, tr.source_account_id, tr.destination_account_id, ts.amount, NULL, ts.category_id, ts.budget_id, tr.transaction_date
+    FROM transaction_splits ts
+    JOIN transactions tr ON tr.id = ts.transaction_id";
+
+/// Longest range the balance history reports cover, to bound the number of daily periods
+const MAX_BALANCE_HISTORY_DAYS: i64 = 3660;
+
+/// Largest page the transaction search returns
+const MAX_SEARCH_PAGE_SIZE: i64 = 1000;
+
+/// Largest number of transactions one bulk edit may touch
+const MAX_BULK_EDIT_SIZE: usize = 1000;
+
+/// Furthest a bulk edit may move transaction dates, in days (about a hundred years)
+const MAX_BULK_SHIFT_DAYS: i64 = 36600;
+
+/// Searchable text of a transaction; must match the expression of the full-text index
+const SEARCH_DOCUMENT_SQL: &str = "to_tsvector('simple', t.description || ' ' || COALESCE(t.destination_name, ''))";
+
+/// Turn free text into a prefix-matching tsquery ("amaz groc" becomes "amaz:* & groc:*").
+/// Anything but letters and digits separates words, so the result never contains tsquery syntax.
+fn search_text_to_tsquery(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("{}:*", word))
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+/// One account's balance at the end of a period, as computed by get_end_of_period_balances
+#[derive(sqlx::FromRow)]
+struct PeriodBalanceRow {
+    period: String,
+    account_id: Uuid,
+    account_type: String,
+    account_sub_type: Option<String>,
+    /// Balance in the account's own currency
+    balance: Decimal,
+    /// Balance converted into the base currency at the rate of the period's last day
+    converted_balance: Decimal,
+}
 
 /// Service for handling transaction-related operations
 pub struct TransactionService {
     db: Pool<Postgres>,
     category_service: CategoryService,
+    settings_service: Option<Arc<SettingsService>>,
 }
 
 impl TransactionService {
     /// Get monthly incoming transactions to on-budget accounts, excluding on-budget to on-budget transfers
-    pub async fn get_monthly_incoming_transactions(&self, year: i32, month: u32) -> Result<Vec<Transaction>, sqlx::Error> {
+    pub async fn get_monthly_incoming_transactions(&self, ledger_id: Uuid, year: i32, month: u32) -> Result<Vec<Transaction>, sqlx::Error> {
         // Calculate start and end of month in UTC
         let start_naive = chrono::NaiveDate::from_ymd_opt(year, month, 1)
             .unwrap()
@@ -45,12 +113,14 @@ impl TransactionService {
               AND t.amount > 0
               AND t.transaction_date >= $1
               AND t.transaction_date < $2
+              AND t.ledger_id = $3
             ORDER BY t.transaction_date DESC
         "#;
 
         let rows = sqlx::query_as::<_, Transaction>(query)
             .bind(start_date)
             .bind(end_date)
+            .bind(ledger_id)
             .fetch_all(&self.db)
             .await?;
         Ok(rows)
@@ -60,18 +130,36 @@ impl TransactionService {
         Self {
             db: db.clone(),
             category_service: CategoryService::new(db),
+            settings_service: None,
+        }
+    }
+
+    /// Set the settings service, used to look up the base currency for reports
+    pub fn with_settings_service(mut self, settings_service: Arc<SettingsService>) -> Self {
+        self.settings_service = Some(settings_service);
+        self
+    }
+
+    /// Get the currency reports of a ledger are converted into
+    async fn base_currency(&self, ledger_id: Uuid) -> Result<String, sqlx::Error> {
+        match &self.settings_service {
+            Some(settings_service) => settings_service.get_base_currency(ledger_id).await,
+            None => Ok(DEFAULT_BASE_CURRENCY.to_string()),
         }
     }
 
-    /// Get spending by category group (or category), aggregated over time periods, from selected on-budget accounts
+    /// Get spending by category group, category or tag (`group_by`), aggregated over time periods,
+    /// from selected on-budget accounts, in the base currency.
+    /// A transaction with several tags counts towards each of them.
     pub async fn get_spending_over_time(
         &self,
+        ledger_id: Uuid,
         account_ids: Option<Vec<Uuid>>,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
-        group_by_group: bool,
+        group_by: &str,
         period: &str,
-    ) -> Result<Vec<(String, String, f64)>, sqlx::Error> {
+    ) -> Result<Vec<(String, String, Decimal)>, sqlx::Error> {
         // Determine period truncation
         let period_fn = match period {
             "week" => "week",
@@ -79,18 +167,28 @@ impl TransactionService {
             _ => "month",
         };
 
-        // Base query joins source accounts and resolves category/group either by category_id (preferred) or by legacy category name
+        // Base query joins source accounts and resolves category/group either by category_id (preferred) or by legacy category name.
+        // Split transactions contribute one line per split so each part lands in its own category.
+        // Amounts are in the source account's currency and converted into the base currency ($2).
         let mut query = format!(
             "SELECT to_char(date_trunc('{period}', t.transaction_date), 'YYYY-MM-DD') AS period,
                     {{name_expr}} AS name,
-                    SUM(t.amount) AS total_amount
-             FROM transactions t
+                    SUM({amount}) AS total_amount
+             FROM ({lines}) t
              JOIN accounts src ON t.source_account_id = src.id
              LEFT JOIN categories c_id ON c_id.id = t.category_id
-             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category
+             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category AND c_name.ledger_id = t.ledger_id
              LEFT JOIN category_groups cg ON cg.id = COALESCE(c_id.group_id, c_name.group_id)
-             WHERE src.account_type = 'On Budget' AND t.amount > 0",
-            period = period_fn
+             {tag_join}
+             WHERE t.ledger_id = $1 AND src.account_type = 'On Budget' AND t.amount > 0",
+            period = period_fn,
+            lines = TRANSACTION_LINES_SQL,
+            tag_join = if group_by == "tag" {
+                "LEFT JOIN transaction_tags tt ON tt.transaction_id = t.id LEFT JOIN tags tg ON tg.id = tt.tag_id"
+            } else {
+                ""
+            },
+            amount = converted_amount_sql("t.amount", "src.currency", "$2", "t.ledger_id", "t.transaction_date")
         );
 
         // Exclude transfers if present by category label (coalesce current category name or legacy string)
@@ -114,38 +212,46 @@ impl TransactionService {
         }
 
         // Name expression and group by
-        if group_by_group {
-            query = query.replace("{name_expr}", "COALESCE(cg.name, 'Ungrouped')");
-        } else {
-            // Prefer current category name via join; fall back to legacy transaction category if id is null
-            query = query.replace("{name_expr}", "COALESCE(c_id.name, c_name.name, t.category, 'Uncategorized')");
+        match group_by {
+            "group" => {
+                query = query.replace("{name_expr}", "COALESCE(cg.name, 'Ungrouped')");
+            }
+            "tag" => {
+                query = query.replace("{name_expr}", "COALESCE(tg.name, 'Untagged')");
+            }
+            _ => {
+                // Prefer current category name via join; fall back to legacy transaction category if id is null
+                query = query.replace("{name_expr}", "COALESCE(c_id.name, c_name.name, t.category, 'Uncategorized')");
+            }
         }
 
         query.push_str(" GROUP BY 1, 2 ORDER BY 1, 2");
 
-        let rows = sqlx::query(&query).fetch_all(&self.db).await?;
+        let base_currency = self.base_currency(ledger_id).await?;
+        let rows = sqlx::query(&query).bind(ledger_id).bind(&base_currency).fetch_all(&self.db).await?;
 
         let mut result = Vec::new();
         for row in rows {
             let period_str: String = row.get("period");
             let name: String = row.get("name");
-            let amount: f64 = row.get("total_amount");
+            let amount: Decimal = row.get("total_amount");
             result.push((period_str, name, amount));
         }
 
         Ok(result)
     }
 
-    /// Get inflow vs outflow over time for on-budget cash flow.
+    /// Get inflow vs outflow over time for on-budget cash flow, in the base currency.
     /// Inflow: to On Budget destination from non-On Budget (or NULL) source; amount > 0; excludes 'Initial Balance'.
     /// Outflow: from On Budget source; amount > 0; excludes transfers to On Budget and 'Initial Balance'. Includes On->Off transfers.
     pub async fn get_inflow_outflow_over_time(
         &self,
+        ledger_id: Uuid,
         account_ids: Option<Vec<Uuid>>,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
         period: &str,
-    ) -> Result<Vec<(String, f64, f64)>, sqlx::Error> {
+    ) -> Result<Vec<(String, Decimal, Decimal)>, sqlx::Error> {
         let period_fn = match period {
             "week" => "week",
             "day" => "day",
@@ -167,15 +273,19 @@ impl TransactionService {
             }
         }
 
+        // Amounts are in the source account's currency and converted into the base currency ($2)
+        let amount = converted_amount_sql("t.amount", "src.currency", "$2", "t.ledger_id", "t.transaction_date");
+
         // Inflow query
         let inflow_query = format!(
-            "SELECT to_char(date_trunc('{period}', t.transaction_date), 'YYYY-MM-DD') AS period, SUM(t.amount) AS total
+            "SELECT to_char(date_trunc('{period}', t.transaction_date), 'YYYY-MM-DD') AS period, SUM({amount}) AS total
              FROM transactions t
              LEFT JOIN accounts src ON t.source_account_id = src.id
              JOIN accounts dst ON t.destination_account_id = dst.id
              LEFT JOIN categories c_id ON c_id.id = t.category_id
-             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category
-             WHERE dst.account_type = 'On Budget'
+             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category AND c_name.ledger_id = t.ledger_id
+             WHERE t.ledger_id = $1
+               AND dst.account_type = 'On Budget'
                AND (src.account_type IS NULL OR src.account_type <> 'On Budget')
                AND t.amount > 0
                AND (COALESCE(c_id.name, c_name.name, t.category) IS NULL OR COALESCE(c_id.name, c_name.name, t.category) <> 'Initial Balance')
@@ -183,19 +293,21 @@ impl TransactionService {
                {account_filter}
              GROUP BY 1 ORDER BY 1",
             period = period_fn,
+            amount = amount,
             date_filter = date_filter,
             account_filter = inflow_account_filter,
         );
 
         // Outflow query
         let outflow_query = format!(
-            "SELECT to_char(date_trunc('{period}', t.transaction_date), 'YYYY-MM-DD') AS period, SUM(t.amount) AS total
+            "SELECT to_char(date_trunc('{period}', t.transaction_date), 'YYYY-MM-DD') AS period, SUM({amount}) AS total
              FROM transactions t
              JOIN accounts src ON t.source_account_id = src.id
              LEFT JOIN accounts dst ON t.destination_account_id = dst.id
              LEFT JOIN categories c_id ON c_id.id = t.category_id
-             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category
-             WHERE src.account_type = 'On Budget'
+             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category AND c_name.ledger_id = t.ledger_id
+             WHERE t.ledger_id = $1
+               AND src.account_type = 'On Budget'
                AND t.amount > 0
                AND NOT (dst.account_type = 'On Budget')
                AND (COALESCE(c_id.name, c_name.name, t.category) IS NULL OR COALESCE(c_id.name, c_name.name, t.category) NOT IN ('Initial Balance', 'Transfer', 'Transfers'))
@@ -203,45 +315,212 @@ impl TransactionService {
                {account_filter}
              GROUP BY 1 ORDER BY 1",
             period = period_fn,
+            amount = amount,
             date_filter = date_filter,
             account_filter = outflow_account_filter,
         );
 
-        let inflow_rows = sqlx::query(&inflow_query).fetch_all(&self.db).await?;
-        let outflow_rows = sqlx::query(&outflow_query).fetch_all(&self.db).await?;
+        let base_currency = self.base_currency(ledger_id).await?;
+        let inflow_rows = sqlx::query(&inflow_query).bind(ledger_id).bind(&base_currency).fetch_all(&self.db).await?;
+        let outflow_rows = sqlx::query(&outflow_query).bind(ledger_id).bind(&base_currency).fetch_all(&self.db).await?;
 
         use std::collections::BTreeMap;
-        let mut map: BTreeMap<String, (f64, f64)> = BTreeMap::new();
+        let mut map: BTreeMap<String, (Decimal, Decimal)> = BTreeMap::new();
 
         for row in inflow_rows {
             let p: String = row.get("period");
-            let v: f64 = row.get("total");
-            let entry = map.entry(p).or_insert((0.0, 0.0));
+            let v: Decimal = row.get("total");
+            let entry = map.entry(p).or_insert((Decimal::ZERO, Decimal::ZERO));
             entry.0 = v;
         }
         for row in outflow_rows {
             let p: String = row.get("period");
-            let v: f64 = row.get("total");
-            let entry = map.entry(p).or_insert((0.0, 0.0));
+            let v: Decimal = row.get("total");
+            let entry = map.entry(p).or_insert((Decimal::ZERO, Decimal::ZERO));
             entry.1 = v;
         }
 
-        let mut result: Vec<(String, f64, f64)> = map.into_iter().map(|(p, (inflow, outflow))| (p, inflow, outflow)).collect();
+        let result: Vec<(String, Decimal, Decimal)> = map.into_iter().map(|(p, (inflow, outflow))| (p, inflow, outflow)).collect();
         // Already sorted by BTreeMap key order (period string lexicographic aligns with YYYY-MM-DD)
         Ok(result)
     }
 
-    /// Get spending by category, with optional filtering by date range
+    /// Get the end-of-period balance of one account, computed from its transaction history.
+    /// Returns None if the account does not exist in the ledger.
+    pub async fn get_balance_history(
+        &self,
+        ledger_id: Uuid,
+        account_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        period: &str,
+    ) -> Result<Option<Vec<BalanceHistoryPoint>>, sqlx::Error> {
+        let exists = sqlx::query_scalar::<_, Uuid>("SELECT id FROM accounts WHERE id = $1 AND ledger_id = $2")
+            .bind(account_id)
+            .bind(ledger_id)
+            .fetch_optional(&self.db)
+            .await?;
+        if exists.is_none() {
+            return Ok(None);
+        }
+
+        let base_currency = self.base_currency(ledger_id).await?;
+        let rows = self
+            .get_end_of_period_balances(ledger_id, &[account_id], start_date, end_date, period, &base_currency)
+            .await?;
+
+        Ok(Some(
+            rows.into_iter()
+                .map(|row| BalanceHistoryPoint { period: row.period, account_id: row.account_id, balance: row.balance })
+                .collect(),
+        ))
+    }
+
+    /// Get net worth over time in the base currency, grouped by account type and subtype.
+    /// Without `account_ids` every account except the External ones is included.
+    pub async fn get_net_worth_over_time(
+        &self,
+        ledger_id: Uuid,
+        account_ids: Option<Vec<Uuid>>,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        period: &str,
+    ) -> Result<Vec<NetWorthPoint>, sqlx::Error> {
+        let account_ids = match account_ids {
+            Some(ids) => ids,
+            None => {
+                sqlx::query_scalar::<_, Uuid>("SELECT id FROM accounts WHERE ledger_id = $1 AND account_type <> 'External'")
+                    .bind(ledger_id)
+                    .fetch_all(&self.db)
+                    .await?
+            }
+        };
+
+        let base_currency = self.base_currency(ledger_id).await?;
+        let rows = self
+            .get_end_of_period_balances(ledger_id, &account_ids, start_date, end_date, period, &base_currency)
+            .await?;
+
+        use std::collections::BTreeMap;
+        let mut map: BTreeMap<String, BTreeMap<(String, Option<String>), Decimal>> = BTreeMap::new();
+        for row in rows {
+            *map.entry(row.period)
+                .or_default()
+                .entry((row.account_type, row.account_sub_type))
+                .or_insert(Decimal::ZERO) += row.converted_balance;
+        }
+
+        let result = map
+            .into_iter()
+            .map(|(period, groups)| {
+                let groups = groups
+                    .into_iter()
+                    .map(|((account_type, account_sub_type), balance)| NetWorthGroup { account_type, account_sub_type, balance })
+                    .collect::<Vec<_>>();
+                NetWorthPoint {
+                    period,
+                    net_worth: groups.iter().map(|g| g.balance).sum(),
+                    currency: base_currency.clone(),
+                    groups,
+                }
+            })
+            .collect();
+        Ok(result)
+    }
+
+    /// Compute the balance of each account at the end of every period between the two dates.
+    /// Follows the same sign convention as apply_transaction_balance_effects: the amount is added
+    /// to the destination and subtracted from the source, so negative amounts flow the other way.
+    /// Destinations in another currency receive the foreign amount instead.
+    /// Transactions before the start date are folded into the first period as the opening balance,
+    /// and transactions after the end date are ignored.
+    async fn get_end_of_period_balances(
+        &self,
+        ledger_id: Uuid,
+        account_ids: &[Uuid],
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        period: &str,
+        base_currency: &str,
+    ) -> Result<Vec<PeriodBalanceRow>, sqlx::Error> {
+        let period_fn = match period {
+            "week" => "week",
+            "day" => "day",
+            _ => "month",
+        };
+
+        if end_date < start_date {
+            return Err(sqlx::Error::Protocol("end_date must not be before start_date".to_string()));
+        }
+        if (end_date - start_date).num_days() > MAX_BALANCE_HISTORY_DAYS {
+            return Err(sqlx::Error::Protocol(format!(
+                "Date range must not exceed {} days",
+                MAX_BALANCE_HISTORY_DAYS
+            )));
+        }
+
+        let start = start_date.and_time(NaiveTime::MIN).and_utc();
+        let end_exclusive = (end_date + chrono::Duration::days(1)).and_time(NaiveTime::MIN).and_utc();
+
+        let query = format!(
+            "WITH periods AS (
+                 SELECT generate_series(date_trunc('{period}', $3::timestamptz), date_trunc('{period}', $4::timestamptz - INTERVAL '1 second'), INTERVAL '1 {period}') AS period_start
+             ),
+             deltas AS (
+                 SELECT a.id AS account_id,
+                        GREATEST(date_trunc('{period}', t.transaction_date), date_trunc('{period}', $3::timestamptz)) AS bucket,
+                        SUM(CASE WHEN t.destination_account_id = a.id THEN COALESCE(t.foreign_amount, t.amount) ELSE 0 END
+                          - CASE WHEN t.source_account_id = a.id THEN t.amount ELSE 0 END) AS delta
+                 FROM accounts a
+                 JOIN transactions t ON t.ledger_id = a.ledger_id
+                                    AND (t.source_account_id = a.id OR t.destination_account_id = a.id)
+                 WHERE a.ledger_id = $1 AND a.id = ANY($2) AND t.transaction_date < $4
+                 GROUP BY 1, 2
+             )
+             SELECT b.period, b.account_id, b.account_type, b.account_sub_type, b.balance,
+                    {converted_balance} AS converted_balance
+             FROM (
+                 SELECT p.period_start, to_char(p.period_start, 'YYYY-MM-DD') AS period,
+                        LEAST(p.period_start + INTERVAL '1 {period}', $4::timestamptz) - INTERVAL '1 second' AS period_end,
+                        a.id AS account_id, a.ledger_id, a.account_type, a.account_sub_type, a.currency,
+                        COALESCE(SUM(d.delta), 0) AS balance
+                 FROM periods p
+                 CROSS JOIN accounts a
+                 LEFT JOIN deltas d ON d.account_id = a.id AND d.bucket <= p.period_start
+                 WHERE a.ledger_id = $1 AND a.id = ANY($2)
+                 GROUP BY p.period_start, a.id, a.ledger_id, a.account_type, a.account_sub_type, a.currency
+             ) b
+             ORDER BY b.period_start, b.account_type, b.account_sub_type, b.account_id",
+            period = period_fn,
+            converted_balance = converted_amount_sql("b.balance", "b.currency", "$5", "b.ledger_id", "b.period_end")
+        );
+
+        sqlx::query_as::<_, PeriodBalanceRow>(&query)
+            .bind(ledger_id)
+            .bind(account_ids)
+            .bind(start)
+            .bind(end_exclusive)
+            .bind(base_currency)
+            .fetch_all(&self.db)
+            .await
+    }
+
+    /// Get spending by category, in the base currency, with optional filtering by date range
     pub async fn get_spending_by_category(
         &self,
+        ledger_id: Uuid,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
-    ) -> Result<Vec<(String, f64)>, sqlx::Error> {
-        let mut query = String::from(
-            "SELECT COALESCE(c.name, t.category, 'No category') as category, SUM(t.amount) as total_amount
-             FROM transactions t
+    ) -> Result<Vec<(String, Decimal)>, sqlx::Error> {
+        // Amounts are in the source account's currency and converted into the base currency ($2)
+        let mut query = format!(
+            "SELECT COALESCE(c.name, t.category, 'No category') as category, SUM({}) as total_amount
+             FROM ({}) t
+             LEFT JOIN accounts src ON src.id = t.source_account_id
              LEFT JOIN categories c ON c.id = t.category_id
-             WHERE 1=1"
+             WHERE t.ledger_id = $1",
+            converted_amount_sql("t.amount", "src.currency", "$2", "t.ledger_id", "t.transaction_date"),
+            TRANSACTION_LINES_SQL
         );
 
         if let Some(start_date) = start_date {
@@ -254,14 +533,17 @@ impl TransactionService {
 
         query.push_str(" GROUP BY 1 ORDER BY total_amount DESC");
 
+        let base_currency = self.base_currency(ledger_id).await?;
         let rows = sqlx::query(&query)
+            .bind(ledger_id)
+            .bind(&base_currency)
             .fetch_all(&self.db)
             .await?;
 
         let mut result = Vec::new();
         for row in rows {
             let category: String = row.get("category");
-            let amount: f64 = row.get("total_amount");
+            let amount: Decimal = row.get("total_amount");
             result.push((category, amount));
         }
 
@@ -271,14 +553,21 @@ impl TransactionService {
     /// Get all transactions, with optional filtering and pagination
     pub async fn get_transactions(
         &self,
+        ledger_id: Uuid,
         source_account_id: Option<Uuid>,
         category: Option<&str>,
+        tag: Option<&str>,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> Result<Vec<Transaction>, sqlx::Error> {
-        let mut query = String::from("SELECT * FROM transactions WHERE 1=1");
+        let mut query = String::from("SELECT * FROM transactions WHERE ledger_id = $1");
+
+        if tag.is_some() {
+            // Only transactions carrying the tag ($2)
+            query.push_str(" AND EXISTS (SELECT 1 FROM transaction_tags tt JOIN tags tg ON tg.id = tt.tag_id WHERE tt.transaction_id = transactions.id AND tg.name = $2)");
+        }
 
         if let Some(source_account_id) = source_account_id {
             query.push_str(&format!(" AND source_account_id = '{}'", source_account_id));
@@ -308,14 +597,152 @@ impl TransactionService {
             query.push_str(&format!(" OFFSET {}", offset_val));
         }
 
-        sqlx::query_as::<_, Transaction>(&query)
-            .fetch_all(&self.db)
-            .await
+        let mut sql = sqlx::query_as::<_, Transaction>(&query).bind(ledger_id);
+        if let Some(tag) = tag {
+            sql = sql.bind(tag);
+        }
+        let mut transactions = sql.fetch_all(&self.db).await?;
+        self.attach_splits_and_tags(&mut transactions).await?;
+
+        Ok(transactions)
+    }
+
+    /// Search transactions by free text and structured filters, returning one page and the total number of matches
+    pub async fn search_transactions(&self, ledger_id: Uuid, query: &TransactionSearchQuery) -> Result<TransactionSearchResult, sqlx::Error> {
+        let limit = query.limit.unwrap_or(100).clamp(1, MAX_SEARCH_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0).max(0);
+        let sort_column = match query.sort.as_deref().unwrap_or("date") {
+            "date" => "t.transaction_date",
+            "amount" => "t.amount",
+            "description" => "LOWER(t.description)",
+            "created" => "t.created_at",
+            other => return Err(sqlx::Error::Protocol(format!(
+                "Invalid sort '{}': must be \"date\", \"amount\", \"description\" or \"created\"", other
+            ))),
+        };
+        let direction = match query.order.as_deref().unwrap_or("desc") {
+            "desc" => "DESC",
+            "asc" => "ASC",
+            other => return Err(sqlx::Error::Protocol(format!("Invalid order '{}': must be \"asc\" or \"desc\"", other))),
+        };
+        Self::validate_search_status(query)?;
+
+        let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM transactions t");
+        Self::push_search_filters(&mut count_query, ledger_id, query);
+        let total = count_query.build_query_scalar::<i64>().fetch_one(&self.db).await?;
+
+        let mut page_query = QueryBuilder::new("SELECT t.* FROM transactions t");
+        Self::push_search_filters(&mut page_query, ledger_id, query);
+        // The ID keeps pages stable when the sort column has ties
+        page_query.push(format!(" ORDER BY {sort_column} {direction}, t.id {direction} LIMIT "));
+        page_query.push_bind(limit);
+        page_query.push(" OFFSET ");
+        page_query.push_bind(offset);
+        let mut transactions = page_query.build_query_as::<Transaction>().fetch_all(&self.db).await?;
+        self.attach_splits_and_tags(&mut transactions).await?;
+
+        Ok(TransactionSearchResult { total, limit, offset, transactions })
+    }
+
+    /// Reject unknown statuses in a search filter
+    fn validate_search_status(query: &TransactionSearchQuery) -> Result<(), sqlx::Error> {
+        if let Some(status) = query.status.as_deref()
+            && ![TRANSACTION_STATUS_UNCLEARED, TRANSACTION_STATUS_CLEARED, TRANSACTION_STATUS_RECONCILED].contains(&status)
+        {
+            return Err(sqlx::Error::Protocol(format!("Invalid status '{}'", status)));
+        }
+        Ok(())
+    }
+
+    /// Append the WHERE clause of a transaction search; every value is bound as a parameter.
+    /// Category, category group and budget filters also match split lines.
+    fn push_search_filters(builder: &mut QueryBuilder<'_, Postgres>, ledger_id: Uuid, query: &TransactionSearchQuery) {
+        builder.push(" WHERE t.ledger_id = ");
+        builder.push_bind(ledger_id);
+
+        if let Some(text) = query.q.as_deref() {
+            let ts_query = search_text_to_tsquery(text);
+            if !ts_query.is_empty() {
+                builder.push(format!(" AND {SEARCH_DOCUMENT_SQL} @@ to_tsquery('simple', "));
+                builder.push_bind(ts_query);
+                builder.push(")");
+            }
+        }
+        if let Some(min_amount) = query.min_amount {
+            builder.push(" AND t.amount >= ");
+            builder.push_bind(min_amount);
+        }
+        if let Some(max_amount) = query.max_amount {
+            builder.push(" AND t.amount <= ");
+            builder.push_bind(max_amount);
+        }
+        if let Some(source_account_id) = query.source_account_id {
+            builder.push(" AND t.source_account_id = ");
+            builder.push_bind(source_account_id);
+        }
+        if let Some(destination_account_id) = query.destination_account_id {
+            builder.push(" AND t.destination_account_id = ");
+            builder.push_bind(destination_account_id);
+        }
+        if let Some(account_id) = query.account_id {
+            builder.push(" AND (t.source_account_id = ");
+            builder.push_bind(account_id);
+            builder.push(" OR t.destination_account_id = ");
+            builder.push_bind(account_id);
+            builder.push(")");
+        }
+        if let Some(category) = &query.category {
+            builder.push(" AND (COALESCE((SELECT c.name FROM categories c WHERE c.id = t.category_id), t.category) = ");
+            builder.push_bind(category.clone());
+            builder.push(" OR EXISTS (SELECT 1 FROM transaction_splits ts JOIN categories c ON c.id = ts.category_id WHERE ts.transaction_id = t.id AND c.name = ");
+            builder.push_bind(category.clone());
+            builder.push("))");
+        }
+        if let Some(group_id) = query.category_group_id {
+            builder.push(" AND (EXISTS (SELECT 1 FROM categories c WHERE c.ledger_id = t.ledger_id AND c.group_id = ");
+            builder.push_bind(group_id);
+            builder.push(" AND (c.id = t.category_id OR (t.category_id IS NULL AND c.name = t.category)))");
+            builder.push(" OR EXISTS (SELECT 1 FROM transaction_splits ts JOIN categories c ON c.id = ts.category_id WHERE ts.transaction_id = t.id AND c.group_id = ");
+            builder.push_bind(group_id);
+            builder.push("))");
+        }
+        if let Some(budget_id) = query.budget_id {
+            builder.push(" AND (t.budget_id = ");
+            builder.push_bind(budget_id);
+            builder.push(" OR EXISTS (SELECT 1 FROM transaction_splits ts WHERE ts.transaction_id = t.id AND ts.budget_id = ");
+            builder.push_bind(budget_id);
+            builder.push("))");
+        }
+        if query.uncategorized {
+            builder.push(
+                " AND COALESCE(NULLIF((SELECT c.name FROM categories c WHERE c.id = t.category_id), ''), NULLIF(t.category, ''), 'Uncategorized') = 'Uncategorized'
+                  AND NOT EXISTS (SELECT 1 FROM transaction_splits ts WHERE ts.transaction_id = t.id AND ts.category_id IS NOT NULL)",
+            );
+        }
+        if let Some(tag) = &query.tag {
+            builder.push(" AND EXISTS (SELECT 1 FROM transaction_tags tt JOIN tags tg ON tg.id = tt.tag_id WHERE tt.transaction_id = t.id AND tg.name = ");
+            builder.push_bind(tag.clone());
+            builder.push(")");
+        }
+        if let Some(status) = &query.status {
+            builder.push(" AND t.status = ");
+            builder.push_bind(status.clone());
+        }
+        if let Some(start_date) = query.start_date {
+            builder.push(" AND t.transaction_date >= ");
+            builder.push_bind(start_date.and_time(NaiveTime::MIN).and_utc());
+        }
+        if let Some(end_date) = query.end_date {
+            // Inclusive of the whole last day
+            builder.push(" AND t.transaction_date < ");
+            builder.push_bind((end_date + chrono::Duration::days(1)).and_time(NaiveTime::MIN).and_utc());
+        }
     }
 
     /// Get transactions for a specific account (both as source and destination) with pagination
     pub async fn get_account_transactions(
         &self,
+        ledger_id: Uuid,
         account_id: Uuid,
         limit: Option<i64>,
         offset: Option<i64>
@@ -323,7 +750,7 @@ impl TransactionService {
         let mut query = String::from(
             r#"
             SELECT * FROM transactions
-            WHERE source_account_id = $1 OR destination_account_id = $1
+            WHERE (source_account_id = $1 OR destination_account_id = $1) AND ledger_id = $2
             ORDER BY transaction_date DESC
             "#
         );
@@ -337,65 +764,249 @@ impl TransactionService {
             query.push_str(&format!(" OFFSET {}", offset_val));
         }
 
-        sqlx::query_as::<_, Transaction>(&query)
+        let mut transactions = sqlx::query_as::<_, Transaction>(&query)
             .bind(account_id)
+            .bind(ledger_id)
             .fetch_all(&self.db)
-            .await
+            .await?;
+        self.attach_splits_and_tags(&mut transactions).await?;
+
+        Ok(transactions)
     }
 
     /// Get a transaction by ID
-    pub async fn get_transaction(&self, id: Uuid) -> Result<Option<Transaction>, sqlx::Error> {
-        sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1")
+    pub async fn get_transaction(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Transaction>, sqlx::Error> {
+        let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1 AND ledger_id = $2")
             .bind(id)
+            .bind(ledger_id)
             .fetch_optional(&self.db)
-            .await
+            .await?;
+
+        match transaction {
+            Some(transaction) => {
+                let mut transactions = vec![transaction];
+                self.attach_splits_and_tags(&mut transactions).await?;
+                Ok(transactions.pop())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Get a transaction and lock it until the end of the open database transaction
+    pub(crate) async fn lock_transaction(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<Transaction>, sqlx::Error> {
+        let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1 AND ledger_id = $2 FOR UPDATE")
+            .bind(id)
+            .bind(ledger_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        match transaction {
+            Some(transaction) => {
+                let mut transactions = vec![transaction];
+                self.attach_splits_and_tags(&mut transactions).await?;
+                Ok(transactions.pop())
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Load split lines and tags for the given transactions and attach them to their parents
+    pub(crate) async fn attach_splits_and_tags(&self, transactions: &mut [Transaction]) -> Result<(), sqlx::Error> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<Uuid> = transactions.iter().map(|t| t.id).collect();
+        let splits = sqlx::query_as::<_, TransactionSplit>(
+            "SELECT * FROM transaction_splits WHERE transaction_id = ANY($1) ORDER BY created_at, id",
+        )
+        .bind(&ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        let index: std::collections::HashMap<Uuid, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        for split in splits {
+            if let Some(&i) = index.get(&split.transaction_id) {
+                transactions[i].splits.push(split);
+            }
+        }
+
+        let mut tags = get_transaction_tags(&self.db, &ids).await?;
+        for transaction in transactions.iter_mut() {
+            transaction.tags = tags.remove(&transaction.id).unwrap_or_default();
+        }
+
+        Ok(())
+    }
+
+    /// Ensure a referenced row (account, budget or category) exists in the given ledger
+    async fn ensure_in_ledger(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        table: &str,
+        id: Uuid,
+        ledger_id: Uuid
+    ) -> Result<(), sqlx::Error> {
+        let exists = sqlx::query_scalar::<_, bool>(&format!(
+            "SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1 AND ledger_id = $2)",
+            table
+        ))
+        .bind(id)
+        .bind(ledger_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        if !exists {
+            return Err(sqlx::Error::Protocol(format!("Invalid reference: {} {} not found in this ledger", table, id)));
+        }
+        Ok(())
+    }
+
+    /// Validate split lines and ensure they balance against the parent amount
+    fn validate_splits(amount: Decimal, splits: &[TransactionSplitRequest]) -> Result<(), sqlx::Error> {
+        if splits.iter().any(|s| s.amount.is_zero()) {
+            return Err(sqlx::Error::Protocol("Invalid split: amount must be a non-zero number".into()));
+        }
+        if !splits.is_empty() {
+            Self::ensure_split_total(amount, splits.iter().map(|s| s.amount).sum())?;
+        }
+        Ok(())
+    }
+
+    /// Ensure the sum of split lines equals the parent amount
+    fn ensure_split_total(amount: Decimal, split_total: Decimal) -> Result<(), sqlx::Error> {
+        if split_total != amount {
+            return Err(sqlx::Error::Protocol(format!(
+                "Invalid splits: split amounts sum to {} but the transaction amount is {}",
+                split_total, amount
+            )));
+        }
+        Ok(())
+    }
+
+    /// Insert split lines for a transaction, resolving category names to category IDs
+    async fn insert_splits(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        transaction_id: Uuid,
+        splits: &[TransactionSplitRequest],
+        now: DateTime<Utc>
+    ) -> Result<Vec<TransactionSplit>, sqlx::Error> {
+        let mut inserted = Vec::with_capacity(splits.len());
+
+        for split in splits {
+            let category_id = match (split.category_id, split.category.as_deref()) {
+                (Some(id), _) => {
+                    Self::ensure_in_ledger(tx, "categories", id, ledger_id).await?;
+                    Some(id)
+                }
+                (None, Some(name)) if !name.trim().is_empty() => {
+                    Some(self.category_service.find_or_create_category(&mut **tx, ledger_id, name).await?.id)
+                }
+                _ => None,
+            };
+            if let Some(budget_id) = split.budget_id {
+                Self::ensure_in_ledger(tx, "budgets", budget_id, ledger_id).await?;
+            }
+
+            let row = sqlx::query_as::<_, TransactionSplit>(
+                r#"
+                INSERT INTO transaction_splits (id, transaction_id, amount, category_id, budget_id, memo, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                RETURNING *
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(transaction_id)
+            .bind(split.amount)
+            .bind(category_id)
+            .bind(split.budget_id)
+            .bind(&split.memo)
+            .bind(now)
+            .bind(now)
+            .fetch_one(&mut **tx)
+            .await?;
+
+            inserted.push(row);
+        }
+
+        Ok(inserted)
     }
 
     /// Create a new transaction
-    pub async fn create_transaction(&self, req: CreateTransactionRequest) -> Result<Transaction, sqlx::Error> {
+    pub async fn create_transaction(&self, ledger_id: Uuid, req: CreateTransactionRequest, actor: ChangeActor) -> Result<Transaction, sqlx::Error> {
+        // Start a transaction to update both the transaction table and the account balance(s)
+        let mut tx = self.db.begin().await?;
+        let transaction = self.create_transaction_in(&mut tx, ledger_id, req, actor).await?;
+
+        // Commit the transaction
+        tx.commit().await?;
+
+        Ok(transaction)
+    }
+
+    /// Create a transaction within an open database transaction, applying its balance effects
+    /// and recording its creation in the transaction's history
+    pub(crate) async fn create_transaction_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        req: CreateTransactionRequest,
+        actor: ChangeActor,
+    ) -> Result<Transaction, sqlx::Error> {
         let now = chrono::Utc::now();
         let transaction_date = req.transaction_date.unwrap_or(now);
 
-        // Start a transaction to update both the transaction table and the account balance(s)
-        let mut tx = self.db.begin().await?;
+        // Referenced accounts and budgets must belong to the same ledger
+        Self::ensure_in_ledger(tx, "accounts", req.source_account_id, ledger_id).await?;
+        if let Some(budget_id) = req.budget_id {
+            Self::ensure_in_ledger(tx, "budgets", budget_id, ledger_id).await?;
+        }
 
         // Find or create the category and get its ID
-        let category = self.category_service.find_or_create_category(&req.category).await?;
+        let category = self.category_service.find_or_create_category(&mut **tx, ledger_id, &req.category).await?;
 
         // Determine if this is a transfer (destination matches an on or off budget account)
         // or an external account (which should be created if it doesn't exist)
         let destination_account_id = if let Some(dest_id) = req.destination_account_id {
             // If destination_account_id is provided, use it directly
+            Self::ensure_in_ledger(tx, "accounts", dest_id, ledger_id).await?;
             dest_id
         } else {
             // Get the destination name to use for matching or creating an account
             let dest_name = req.destination_name.as_ref().map(|s| s.as_str()).unwrap_or(&req.description);
 
             // Check if there's an existing account that matches the destination name
-            let existing_account = sqlx::query!(
-                "SELECT id FROM accounts WHERE name = $1",
-                dest_name
-            )
-            .fetch_optional(&mut *tx)
-            .await?;
+            let existing_account = sqlx::query_scalar::<_, Uuid>("SELECT id FROM accounts WHERE name = $1 AND ledger_id = $2")
+                .bind(dest_name)
+                .bind(ledger_id)
+                .fetch_optional(&mut **tx)
+                .await?;
 
-            if let Some(record) = existing_account {
+            if let Some(account_id) = existing_account {
                 // Use the existing account
-                record.id
+                account_id
             } else {
-                // Create a new external account
+                // Create a new external account in the source account's currency
                 let new_account_id = Uuid::new_v4();
                 sqlx::query(
                     r#"
-                    INSERT INTO accounts (id, name, account_type, balance, currency, created_at, updated_at)
-                    VALUES ($1, $2, 'External', 0.00, 'USD', $3, $4)
+                    INSERT INTO accounts (id, ledger_id, name, account_type, balance, currency, created_at, updated_at)
+                    SELECT $1, $2, $3, 'External', 0.00, currency, $4, $5 FROM accounts WHERE id = $6
                     "#,
                 )
                 .bind(new_account_id)
+                .bind(ledger_id)
                 .bind(dest_name)
                 .bind(now)
                 .bind(now)
-                .execute(&mut *tx)
+                .bind(req.source_account_id)
+                .execute(&mut **tx)
                 .await?;
 
                 new_account_id
@@ -407,36 +1018,41 @@ impl TransactionService {
             name.clone()
         } else {
             // Look up the destination account name
-            let dest_account = sqlx::query!(
-                "SELECT name FROM accounts WHERE id = $1",
-                destination_account_id
-            )
-            .fetch_optional(&mut *tx)
-            .await?;
+            let dest_account_name = sqlx::query_scalar::<_, String>("SELECT name FROM accounts WHERE id = $1")
+                .bind(destination_account_id)
+                .fetch_optional(&mut **tx)
+                .await?;
 
-            dest_account.map(|a| a.name).unwrap_or_else(|| "".to_string())
+            dest_account_name.unwrap_or_else(|| "".to_string())
         };
 
         // Validate double-entry invariants
-        if !req.amount.is_finite() || req.amount == 0.0 {
-            return Err(sqlx::Error::Protocol("Invalid amount: must be a finite, non-zero number".into()));
+        if req.amount.is_zero() {
+            return Err(sqlx::Error::Protocol("Invalid amount: must be a non-zero number".into()));
         }
         if req.source_account_id == destination_account_id {
             return Err(sqlx::Error::Protocol("Invalid transaction: source and destination accounts must differ".into()));
         }
+        let splits = req.splits.as_deref().unwrap_or_default();
+        Self::validate_splits(req.amount, splits)?;
+        let status = Self::validate_status(req.status.as_deref().unwrap_or(TRANSACTION_STATUS_UNCLEARED))?;
+        let (foreign_amount, foreign_currency) = Self::resolve_foreign_amount(
+            tx, ledger_id, req.source_account_id, destination_account_id, req.amount, req.foreign_amount, transaction_date,
+        ).await?;
 
         // Normalize description by removing trailing whitespace before saving
         let cleaned_description = req.description.trim_end().to_string();
 
         // Create the transaction record
-        let transaction = sqlx::query_as::<_, Transaction>(
+        let mut transaction = sqlx::query_as::<_, Transaction>(
             r#"
-            INSERT INTO transactions (id, account_id, source_account_id, destination_account_id, destination_name, description, amount, category, category_id, budget_id, transaction_date, created_at, updated_at)
-            VALUES ($1, $2, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            INSERT INTO transactions (id, ledger_id, account_id, source_account_id, destination_account_id, destination_name, description, amount, category, category_id, budget_id, transaction_date, created_at, updated_at, status, foreign_amount, foreign_currency, memo)
+            VALUES ($1, $2, $3, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
             RETURNING *
             "#,
         )
         .bind(Uuid::new_v4())
+        .bind(ledger_id)
         .bind(req.source_account_id)
         .bind(destination_account_id)
         .bind(&destination_name)
@@ -448,9 +1064,19 @@ impl TransactionService {
         .bind(transaction_date)
         .bind(now)
         .bind(now)
-        .fetch_one(&mut *tx)
+        .bind(status)
+        .bind(foreign_amount)
+        .bind(&foreign_currency)
+        .bind(&req.memo)
+        .fetch_one(&mut **tx)
         .await?;
 
+        // Store the split lines and tags, if any
+        transaction.splits = self.insert_splits(tx, ledger_id, transaction.id, splits, now).await?;
+        if let Some(tags) = &req.tags {
+            transaction.tags = set_transaction_tags(tx, ledger_id, transaction.id, tags).await?;
+        }
+
         // Apply double-entry accounting:
         //
         // For a POSITIVE amount (expense/transfer out):
@@ -462,10 +1088,12 @@ impl TransactionService {
         // - Decrease destination account balance by the absolute amount (money leaving)
         //
         // This ensures: source_change + destination_change = 0 (double-entry principle)
+        // Between accounts of different currencies the destination moves by the foreign amount instead.
 
         let abs_amount = req.amount.abs();
+        let abs_destination_amount = foreign_amount.unwrap_or(req.amount).abs();
 
-        if req.amount >= 0.0 {
+        if req.amount >= Decimal::ZERO {
             // Positive amount: money flows FROM source TO destination
             // Source account loses money (decrease balance)
             let ra1 = sqlx::query(
@@ -478,7 +1106,7 @@ impl TransactionService {
             .bind(abs_amount)
             .bind(now)
             .bind(req.source_account_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
             if ra1.rows_affected() != 1 { return Err(sqlx::Error::Protocol("Invariant violation: source account update failed".into())); }
 
@@ -490,10 +1118,10 @@ impl TransactionService {
                 WHERE id = $3
                 "#,
             )
-            .bind(abs_amount)
+            .bind(abs_destination_amount)
             .bind(now)
             .bind(destination_account_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
             if ra2.rows_affected() != 1 { return Err(sqlx::Error::Protocol("Invariant violation: destination account update failed".into())); }
         } else {
@@ -509,7 +1137,7 @@ impl TransactionService {
             .bind(abs_amount)
             .bind(now)
             .bind(req.source_account_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
             if ra1.rows_affected() != 1 { return Err(sqlx::Error::Protocol("Invariant violation: source account update failed".into())); }
 
@@ -521,180 +1149,582 @@ impl TransactionService {
                 WHERE id = $3
                 "#,
             )
-            .bind(abs_amount)
+            .bind(abs_destination_amount)
             .bind(now)
             .bind(destination_account_id)
-            .execute(&mut *tx)
+            .execute(&mut **tx)
             .await?;
             if ra2.rows_affected() != 1 { return Err(sqlx::Error::Protocol("Invariant violation: destination account update failed".into())); }
         }
 
-        // Commit the transaction
-        tx.commit().await?;
+        record_transaction_creation(tx, ledger_id, &transaction, &actor).await?;
 
         Ok(transaction)
     }
 
     /// Update an existing transaction
-    pub async fn update_transaction(&self, id: Uuid, req: UpdateTransactionRequest) -> Result<Option<Transaction>, sqlx::Error> {
-        // First, check if the transaction exists and get the original details
-        let original_transaction = self.get_transaction(id).await?;
-
-        if let Some(original) = original_transaction {
-            // Start a database transaction
-            let mut tx = self.db.begin().await?;
-            let now = chrono::Utc::now();
-
-            // First, reverse the original transaction's effect on account balances
-            self.reverse_transaction_balance_effects(&mut tx, &original, now).await?;
+    pub async fn update_transaction(
+        &self,
+        ledger_id: Uuid,
+        id: Uuid,
+        req: UpdateTransactionRequest,
+        actor: ChangeActor,
+    ) -> Result<Option<Transaction>, sqlx::Error> {
+        // Start a database transaction and lock the original, so concurrent changes are not overwritten
+        let mut tx = self.db.begin().await?;
+        let Some(original) = self.lock_transaction(&mut tx, ledger_id, id).await? else {
+            return Ok(None);
+        };
+        let updated_transaction = self.update_transaction_in(&mut tx, ledger_id, &original, req, actor).await?;
 
-            // Build the update query dynamically based on which fields are provided
-            let mut query = String::from("UPDATE transactions SET updated_at = $1");
-            let mut params: Vec<String> = vec![];
+        // Commit the transaction
+        tx.commit().await?;
 
-            // Track the new values (use original values if not updated)
-            let new_amount = req.amount.unwrap_or(original.amount);
-            let new_source_account_id = original.source_account_id; // Source account can't be changed
-            let mut new_destination_account_id = original.destination_account_id;
+        Ok(updated_transaction)
+    }
 
-            if let Some(amount) = req.amount {
-                params.push(format!("amount = {}", amount));
-            }
+    /// Apply an update to a transaction within an open database transaction, moving its balance effects
+    /// and recording the changed fields in the transaction's history
+    pub(crate) async fn update_transaction_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        original: &Transaction,
+        req: UpdateTransactionRequest,
+        actor: ChangeActor,
+    ) -> Result<Option<Transaction>, sqlx::Error> {
+        Self::ensure_unlocked(original)?;
+        let id = original.id;
+        let now = chrono::Utc::now();
 
-            if let Some(description) = &req.description {
-                params.push(format!("description = '{}'", description));
+        // First, reverse the original transaction's effect on account balances
+        self.reverse_transaction_balance_effects(tx, original, now).await?;
+
+        // Build the update query dynamically based on which fields are provided
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE transactions SET updated_at = ");
+        query.push_bind(now);
+
+        // Track the new values (use original values if not updated)
+        let new_amount = req.amount.unwrap_or(original.amount);
+        let new_source_account_id = original.source_account_id; // Source account can't be changed
+        let mut new_destination_account_id = original.destination_account_id;
+
+        // Keep split lines balanced against the (possibly changed) amount
+        let new_splits = match &req.splits {
+            Some(splits) => {
+                Self::validate_splits(new_amount, splits)?;
+                sqlx::query("DELETE FROM transaction_splits WHERE transaction_id = $1")
+                    .bind(id)
+                    .execute(&mut **tx)
+                    .await?;
+                self.insert_splits(tx, ledger_id, id, splits, now).await?
             }
-
-            if let Some(category_name) = &req.category {
-                // Resolve category and set both legacy category name and stable category_id
-                if let Ok(cat) = self.category_service.find_or_create_category(category_name).await {
-                    params.push(format!("category = '{}'", category_name.replace("'", "''")));
-                    params.push(format!("category_id = '{}'", cat.id));
-                } else {
-                    // Fall back to just updating the legacy string if resolution fails
-                    params.push(format!("category = '{}'", category_name.replace("'", "''")));
+            None => {
+                if !original.splits.is_empty() {
+                    Self::ensure_split_total(new_amount, original.splits.iter().map(|s| s.amount).sum())?;
                 }
+                original.splits.clone()
             }
+        };
 
-            if let Some(budget_id) = req.budget_id {
-                params.push(format!("budget_id = '{}'", budget_id));
-            }
+        if let Some(amount) = req.amount {
+            query.push(", amount = ").push_bind(amount);
+        }
 
-            if let Some(transaction_date) = req.transaction_date {
-                params.push(format!("transaction_date = '{}'", transaction_date));
-            }
+        if let Some(description) = &req.description {
+            query.push(", description = ").push_bind(description.clone());
+        }
+
+        if let Some(category_name) = &req.category {
+            // Resolve category and set both legacy category name and stable category_id
+            query.push(", category = ").push_bind(category_name.clone());
+            let category = self.category_service.find_or_create_category(&mut **tx, ledger_id, category_name).await?;
+            query.push(", category_id = ").push_bind(category.id);
+        }
+
+        if let Some(budget_id) = req.budget_id {
+            Self::ensure_in_ledger(tx, "budgets", budget_id, ledger_id).await?;
+            query.push(", budget_id = ").push_bind(budget_id);
+        }
 
-            // Handle destination account updates
-            if let Some(destination_account_id) = req.destination_account_id {
-                // If destination_account_id is provided, use it directly
-                params.push(format!("destination_account_id = '{}'", destination_account_id));
-                new_destination_account_id = destination_account_id;
-
-                // Look up the destination account name and update it
-                if req.destination_name.is_none() {
-                    let dest_account = sqlx::query!(
-                        "SELECT name FROM accounts WHERE id = $1",
-                        destination_account_id
-                    )
-                    .fetch_optional(&mut *tx)
+        if let Some(transaction_date) = req.transaction_date {
+            query.push(", transaction_date = ").push_bind(transaction_date);
+        }
+
+        if let Some(status) = &req.status {
+            query.push(", status = ").push_bind(Self::validate_status(status)?);
+        }
+
+        if let Some(memo) = &req.memo {
+            query.push(", memo = ").push_bind(memo.clone());
+        }
+
+        // Handle destination account updates
+        if let Some(destination_account_id) = req.destination_account_id {
+            // If destination_account_id is provided, use it directly
+            Self::ensure_in_ledger(tx, "accounts", destination_account_id, ledger_id).await?;
+            query.push(", destination_account_id = ").push_bind(destination_account_id);
+            new_destination_account_id = destination_account_id;
+
+            // Look up the destination account name and update it
+            if req.destination_name.is_none() {
+                let dest_account_name = sqlx::query_scalar::<_, String>("SELECT name FROM accounts WHERE id = $1")
+                    .bind(destination_account_id)
+                    .fetch_optional(&mut **tx)
                     .await?;
 
-                    if let Some(account) = dest_account {
-                        params.push(format!("destination_name = '{}'", account.name));
-                    }
+                if let Some(name) = dest_account_name {
+                    query.push(", destination_name = ").push_bind(name);
                 }
-            } else if let Some(dest_name) = &req.destination_name {
-                // If destination_name is provided but not destination_account_id,
-                // check if there's an existing account that matches the destination name
-                let existing_account = sqlx::query!(
-                    "SELECT id FROM accounts WHERE name = $1",
-                    dest_name
+            }
+        } else if let Some(dest_name) = &req.destination_name {
+            // If destination_name is provided but not destination_account_id,
+            // check if there's an existing account that matches the destination name
+            let existing_account = sqlx::query_scalar::<_, Uuid>("SELECT id FROM accounts WHERE name = $1 AND ledger_id = $2")
+                .bind(dest_name)
+                .bind(ledger_id)
+                .fetch_optional(&mut **tx)
+                .await?;
+
+            if let Some(account_id) = existing_account {
+                // Use the existing account
+                query.push(", destination_account_id = ").push_bind(account_id);
+                new_destination_account_id = account_id;
+            } else {
+                // Create a new external account in the source account's currency
+                let new_account_id = Uuid::new_v4();
+                sqlx::query(
+                    r#"
+                    INSERT INTO accounts (id, ledger_id, name, account_type, balance, currency, created_at, updated_at)
+                    SELECT $1, $2, $3, 'External', 0.00, currency, $4, $5 FROM accounts WHERE id = $6
+                    "#,
                 )
-                .fetch_optional(&mut *tx)
+                .bind(new_account_id)
+                .bind(ledger_id)
+                .bind(dest_name)
+                .bind(now)
+                .bind(now)
+                .bind(original.source_account_id)
+                .execute(&mut **tx)
                 .await?;
 
-                if let Some(record) = existing_account {
-                    // Use the existing account
-                    params.push(format!("destination_account_id = '{}'", record.id));
-                    new_destination_account_id = record.id;
-                } else {
-                    // Create a new external account
-                    let new_account_id = Uuid::new_v4();
-                    sqlx::query(
-                        r#"
-                        INSERT INTO accounts (id, name, account_type, balance, currency, created_at, updated_at)
-                        VALUES ($1, $2, 'External', 0.00, 'USD', $3, $4)
-                        "#,
-                    )
-                    .bind(new_account_id)
-                    .bind(dest_name)
-                    .bind(now)
-                    .bind(now)
-                    .execute(&mut *tx)
-                    .await?;
+                query.push(", destination_account_id = ").push_bind(new_account_id);
+                new_destination_account_id = new_account_id;
+            }
 
-                    params.push(format!("destination_account_id = '{}'", new_account_id));
-                    new_destination_account_id = new_account_id;
-                }
+            // Also update the destination_name field in the transaction
+            query.push(", destination_name = ").push_bind(dest_name.clone());
+        }
 
-                // Also update the destination_name field in the transaction
-                params.push(format!("destination_name = '{}'", dest_name));
-            }
+        // A stored foreign amount is kept while the amount, destination and date are unchanged;
+        // otherwise it is converted again unless a new one is given
+        let keep_foreign_amount = req.amount.is_none()
+            && req.transaction_date.is_none()
+            && new_destination_account_id == original.destination_account_id;
+        let requested_foreign_amount = req.foreign_amount.or(if keep_foreign_amount { original.foreign_amount } else { None });
+        let (new_foreign_amount, new_foreign_currency) = Self::resolve_foreign_amount(
+            tx,
+            ledger_id,
+            new_source_account_id,
+            new_destination_account_id,
+            new_amount,
+            requested_foreign_amount,
+            req.transaction_date.unwrap_or(original.transaction_date),
+        ).await?;
+        query.push(", foreign_amount = ").push_bind(new_foreign_amount);
+        query.push(", foreign_currency = ").push_bind(new_foreign_currency);
+        query.push(" WHERE id = ").push_bind(id);
+        query.push(" RETURNING *");
+
+        // Update the transaction
+        let mut updated_transaction = query
+            .build_query_as::<Transaction>()
+            .fetch_optional(&mut **tx)
+            .await?;
+        if let Some(transaction) = updated_transaction.as_mut() {
+            transaction.splits = new_splits;
+            transaction.tags = match &req.tags {
+                Some(tags) => set_transaction_tags(tx, ledger_id, id, tags).await?,
+                None => original.tags.clone(),
+            };
+        }
 
-            if !params.is_empty() {
-                query.push_str(", ");
-                query.push_str(&params.join(", "));
-            }
+        // Apply the new transaction's effect on account balances
+        self.apply_transaction_balance_effects(tx, new_source_account_id, new_destination_account_id, new_amount, new_foreign_amount, now).await?;
 
-            query.push_str(" WHERE id = $2 RETURNING *");
+        if let Some(transaction) = &updated_transaction {
+            record_transaction_update(tx, ledger_id, original, transaction, &actor).await?;
+        }
 
-            // Update the transaction
-            let updated_transaction = sqlx::query_as::<_, Transaction>(&query)
-                .bind(now)
-                .bind(id)
-                .fetch_optional(&mut *tx)
-                .await?;
+        Ok(updated_transaction)
+    }
+
+    /// Apply one patch to many transactions in a single database transaction.
+    /// Each item runs in its own savepoint so every failure can be reported, but the changes are only
+    /// committed when all items succeed. Rules are not re-applied: the patch is an explicit correction.
+    pub async fn bulk_edit_transactions(
+        &self,
+        ledger_id: Uuid,
+        req: BulkEditTransactionsRequest,
+        actor: ChangeActor,
+    ) -> Result<BulkEditResult, sqlx::Error> {
+        let patch = &req.patch;
+        let has_changes = patch.category.is_some()
+            || patch.budget_id.is_some()
+            || patch.destination_name.is_some()
+            || patch.shift_days.is_some_and(|days| days != 0);
+        if patch.delete && has_changes {
+            return Err(sqlx::Error::Protocol("Invalid bulk edit: delete cannot be combined with other changes".into()));
+        }
+        if !patch.delete && !has_changes {
+            return Err(sqlx::Error::Protocol("Invalid bulk edit: the patch does not change anything".into()));
+        }
+        if patch.shift_days.is_some_and(|days| days.abs() > MAX_BULK_SHIFT_DAYS) {
+            return Err(Self::invalid_shift_days());
+        }
 
-            // Apply the new transaction's effect on account balances
-            self.apply_transaction_balance_effects(&mut tx, new_source_account_id, new_destination_account_id, new_amount, now).await?;
+        let ids = match (&req.transaction_ids, &req.filter) {
+            (Some(ids), None) => {
+                let mut unique = Vec::with_capacity(ids.len());
+                for id in ids {
+                    if !unique.contains(id) {
+                        unique.push(*id);
+                    }
+                }
+                unique
+            }
+            (None, Some(filter)) => {
+                Self::validate_search_status(filter)?;
+                let mut query = QueryBuilder::new("SELECT t.id FROM transactions t");
+                Self::push_search_filters(&mut query, ledger_id, filter);
+                query.push(" ORDER BY t.transaction_date, t.id");
+                query.build_query_scalar::<Uuid>().fetch_all(&self.db).await?
+            }
+            _ => return Err(sqlx::Error::Protocol("Invalid bulk edit: provide either transaction_ids or filter".into())),
+        };
+        if ids.len() > MAX_BULK_EDIT_SIZE {
+            return Err(sqlx::Error::Protocol(format!(
+                "Invalid bulk edit: {} transactions selected, at most {} can be edited at once",
+                ids.len(), MAX_BULK_EDIT_SIZE
+            )));
+        }
 
-            // Commit the transaction
-            tx.commit().await?;
+        let mut tx = self.db.begin().await?;
 
-            Ok(updated_transaction)
+        // Lock the selected transactions so concurrent edits cannot interleave with the batch
+        let mut originals = sqlx::query_as::<_, Transaction>(
+            "SELECT * FROM transactions WHERE ledger_id = $1 AND id = ANY($2) FOR UPDATE",
+        )
+        .bind(ledger_id)
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        self.attach_splits_and_tags(&mut originals).await?;
+        let originals: std::collections::HashMap<Uuid, Transaction> = originals.into_iter().map(|t| (t.id, t)).collect();
+
+        // The changes of one bulk edit can be undone together
+        let run_id = Uuid::new_v4();
+        let actor = actor.in_run(run_id);
+
+        let mut items = Vec::with_capacity(ids.len());
+        for id in ids {
+            let Some(original) = originals.get(&id) else {
+                items.push(BulkEditItemResult {
+                    transaction_id: id,
+                    status: "not_found".to_string(),
+                    error: Some("Transaction not found".to_string()),
+                });
+                continue;
+            };
+
+            let mut savepoint = tx.begin().await?;
+            let outcome = if patch.delete {
+                self.delete_transaction_in(&mut savepoint, original).await.map(|_| "deleted")
+            } else {
+                let update = Self::bulk_patch_to_update(patch, original)?;
+                self.update_transaction_in(&mut savepoint, ledger_id, original, update, actor).await.map(|_| "updated")
+            };
+
+            match outcome {
+                Ok(status) => {
+                    savepoint.commit().await?;
+                    items.push(BulkEditItemResult { transaction_id: id, status: status.to_string(), error: None });
+                }
+                Err(sqlx::Error::Protocol(message)) => {
+                    savepoint.rollback().await?;
+                    items.push(BulkEditItemResult { transaction_id: id, status: "failed".to_string(), error: Some(message) });
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let failed = items.iter().filter(|item| item.error.is_some()).count();
+        let applied = failed == 0;
+        if applied {
+            tx.commit().await?;
+            info!("Bulk edited {} transaction(s) in ledger {}", items.len(), ledger_id);
         } else {
-            Ok(None)
+            tx.rollback().await?;
         }
+
+        Ok(BulkEditResult { applied, run_id: applied.then_some(run_id), succeeded: items.len() - failed, failed, items })
+    }
+
+    /// Translate a bulk patch into the update of one transaction
+    fn bulk_patch_to_update(patch: &BulkTransactionPatch, original: &Transaction) -> Result<UpdateTransactionRequest, sqlx::Error> {
+        let transaction_date = match patch.shift_days {
+            Some(days) => Some(
+                chrono::TimeDelta::try_days(days)
+                    .and_then(|shift| original.transaction_date.checked_add_signed(shift))
+                    .ok_or_else(Self::invalid_shift_days)?,
+            ),
+            None => None,
+        };
+
+        Ok(UpdateTransactionRequest {
+            destination_account_id: None,
+            destination_name: patch.destination_name.clone(),
+            description: None,
+            amount: None,
+            category: patch.category.clone(),
+            budget_id: patch.budget_id,
+            transaction_date,
+            splits: None,
+            status: None,
+            foreign_amount: None,
+            tags: None,
+            memo: None,
+        })
+    }
+
+    /// Error for a date shift beyond MAX_BULK_SHIFT_DAYS
+    fn invalid_shift_days() -> sqlx::Error {
+        sqlx::Error::Protocol(format!("Invalid bulk edit: shift_days must be between -{0} and {0}", MAX_BULK_SHIFT_DAYS))
     }
 
     /// Delete a transaction
-    pub async fn delete_transaction(&self, id: Uuid) -> Result<bool, sqlx::Error> {
-        // First, check if the transaction exists and get its details
-        let transaction = self.get_transaction(id).await?;
+    pub async fn delete_transaction(&self, ledger_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        // Start a database transaction and lock the transaction, so its current balance effects are reversed
+        let mut tx = self.db.begin().await?;
+        let transaction = self.lock_transaction(&mut tx, ledger_id, id).await?;
 
         if let Some(transaction) = transaction {
-            // Start a database transaction
-            let mut tx = self.db.begin().await?;
-            let now = chrono::Utc::now();
-
-            // Delete the transaction record
-            let result = sqlx::query("DELETE FROM transactions WHERE id = $1")
-                .bind(id)
-                .execute(&mut *tx)
-                .await?;
-
-            // Reverse the transaction's effect on account balances
-            self.reverse_transaction_balance_effects(&mut tx, &transaction, now).await?;
+            let deleted = self.delete_transaction_in(&mut tx, &transaction).await?;
 
             // Commit the transaction
             tx.commit().await?;
 
-            Ok(result.rows_affected() > 0)
+            Ok(deleted)
         } else {
             Ok(false)
         }
     }
 
+    /// Delete a transaction within an open database transaction, reversing its balance effects
+    async fn delete_transaction_in(&self, tx: &mut sqlx::Transaction<'_, Postgres>, transaction: &Transaction) -> Result<bool, sqlx::Error> {
+        Self::ensure_unlocked(transaction)?;
+        let now = chrono::Utc::now();
+
+        // Delete the transaction record
+        let result = sqlx::query("DELETE FROM transactions WHERE id = $1")
+            .bind(transaction.id)
+            .execute(&mut **tx)
+            .await?;
+
+        // Reverse the transaction's effect on account balances
+        self.reverse_transaction_balance_effects(tx, transaction, now).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unlock a reconciled transaction so it can be edited or deleted again.
+    /// The transaction goes back to "cleared" and is detached from its reconciliation.
+    pub async fn unlock_transaction(&self, ledger_id: Uuid, id: Uuid, actor: ChangeActor) -> Result<Option<Transaction>, sqlx::Error> {
+        let Some(original) = self.get_transaction(ledger_id, id).await? else {
+            return Ok(None);
+        };
+        if original.status != TRANSACTION_STATUS_RECONCILED {
+            return Ok(Some(original));
+        }
+
+        let mut tx = self.db.begin().await?;
+        let unlocked = sqlx::query_as::<_, Transaction>(
+            r#"
+            UPDATE transactions
+            SET status = $1, reconciliation_id = NULL, updated_at = $2
+            WHERE id = $3 AND ledger_id = $4 AND status = $5
+            RETURNING *
+            "#,
+        )
+        .bind(TRANSACTION_STATUS_CLEARED)
+        .bind(chrono::Utc::now())
+        .bind(id)
+        .bind(ledger_id)
+        .bind(TRANSACTION_STATUS_RECONCILED)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // Unlocked concurrently
+        let Some(mut unlocked) = unlocked else {
+            return self.get_transaction(ledger_id, id).await;
+        };
+        unlocked.splits = original.splits.clone();
+        unlocked.tags = original.tags.clone();
+        record_transaction_update(&mut tx, ledger_id, &original, &unlocked, &actor).await?;
+        tx.commit().await?;
+
+        info!("Unlocked reconciled transaction {}", id);
+        Ok(Some(unlocked))
+    }
+
+    /// Get the change history of a transaction, oldest first
+    pub async fn get_transaction_history(&self, ledger_id: Uuid, id: Uuid) -> Result<Option<Vec<TransactionChange>>, sqlx::Error> {
+        if self.get_transaction(ledger_id, id).await?.is_none() {
+            return Ok(None);
+        }
+        get_transaction_changes(&self.db, ledger_id, id).await.map(Some)
+    }
+
+    /// Undo a single change, restoring the previous values of the fields it changed
+    pub async fn undo_transaction_change(&self, ledger_id: Uuid, change_id: Uuid) -> Result<Option<Transaction>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let change = sqlx::query_as::<_, TransactionChange>(
+            "SELECT * FROM transaction_changes WHERE id = $1 AND ledger_id = $2 FOR UPDATE",
+        )
+        .bind(change_id)
+        .bind(ledger_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(change) = parse_changes(change.into_iter().collect()).pop() else {
+            return Ok(None);
+        };
+
+        let transaction = self.undo_change_in(&mut tx, ledger_id, &change, None).await?;
+        tx.commit().await?;
+
+        info!("Undid change {} of transaction {}", change.id, change.transaction_id);
+        Ok(Some(transaction))
+    }
+
+    /// Undo every remaining change of a run (a rule run or bulk edit), newest first.
+    /// Either all of them are undone or, when any cannot be, none is.
+    pub async fn undo_change_run(&self, ledger_id: Uuid, run_id: Uuid) -> Result<Option<UndoRunResult>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let changes = sqlx::query_as::<_, TransactionChange>(
+            r#"
+            SELECT * FROM transaction_changes
+            WHERE run_id = $1 AND ledger_id = $2 AND change_type = $3
+            ORDER BY created_at DESC, id DESC
+            FOR UPDATE
+            "#,
+        )
+        .bind(run_id)
+        .bind(ledger_id)
+        .bind(CHANGE_TYPE_UPDATE)
+        .fetch_all(&mut *tx)
+        .await?;
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        // The undo entries of one run are themselves grouped so they can be redone by undoing them
+        let undo_run_id = Uuid::new_v4();
+        let mut undone = 0;
+        for change in parse_changes(changes).iter().filter(|change| change.undone_at.is_none()) {
+            self.undo_change_in(&mut tx, ledger_id, change, Some(undo_run_id)).await?;
+            undone += 1;
+        }
+        tx.commit().await?;
+
+        info!("Undid {} change(s) of run {} in ledger {}", undone, run_id, ledger_id);
+        Ok(Some(UndoRunResult { run_id, undo_run_id, undone }))
+    }
+
+    /// Revert one change within an open database transaction. The change is refused when the
+    /// transaction was changed again since, so later edits are never silently overwritten.
+    async fn undo_change_in(
+        &self,
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        change: &TransactionChange,
+        undo_run_id: Option<Uuid>,
+    ) -> Result<Transaction, sqlx::Error> {
+        if change.undone_at.is_some() {
+            return Err(sqlx::Error::Protocol(format!("Change {} has already been undone", change.id)));
+        }
+        if change.change_type != CHANGE_TYPE_UPDATE {
+            return Err(sqlx::Error::Protocol(format!(
+                "Change {} created the transaction; delete the transaction instead",
+                change.id
+            )));
+        }
+
+        let current = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1 AND ledger_id = $2 FOR UPDATE")
+            .bind(change.transaction_id)
+            .bind(ledger_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+        let Some(current) = current else {
+            return Err(sqlx::Error::Protocol(format!("Transaction {} of change {} no longer exists", change.transaction_id, change.id)));
+        };
+        let mut current = [current];
+        self.attach_splits_and_tags(&mut current).await?;
+        let [current] = current;
+
+        let snapshot = transaction_snapshot(&current);
+        if let Some(field) = change.changes.iter().find(|(field, c)| snapshot.get(*field) != Some(&c.new)).map(|(field, _)| field) {
+            return Err(sqlx::Error::Protocol(format!(
+                "Change {} cannot be undone: the {} of transaction {} was changed again since",
+                change.id, field, change.transaction_id
+            )));
+        }
+
+        // Values that were empty are cleared first; the update below still records them against `current`
+        let (req, cleared) = revert_request(change)?;
+        for field in cleared {
+            sqlx::query(&format!("UPDATE transactions SET {} = NULL WHERE id = $1", field))
+                .bind(current.id)
+                .execute(&mut **tx)
+                .await?;
+        }
+
+        let reverted = self
+            .update_transaction_in(tx, ledger_id, &current, req, ChangeActor::undo(change.id, undo_run_id))
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+
+        sqlx::query("UPDATE transaction_changes SET undone_at = $1 WHERE id = $2")
+            .bind(chrono::Utc::now())
+            .bind(change.id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(reverted)
+    }
+
+    /// Reconciled transactions may only change after being unlocked
+    fn ensure_unlocked(transaction: &Transaction) -> Result<(), sqlx::Error> {
+        if transaction.status == TRANSACTION_STATUS_RECONCILED {
+            return Err(sqlx::Error::Protocol(format!(
+                "Transaction {} is reconciled and locked; unlock it before making changes",
+                transaction.id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Only "uncleared" and "cleared" can be set directly; "reconciled" is set by reconciling an account
+    fn validate_status(status: &str) -> Result<&'static str, sqlx::Error> {
+        match status {
+            TRANSACTION_STATUS_UNCLEARED => Ok(TRANSACTION_STATUS_UNCLEARED),
+            TRANSACTION_STATUS_CLEARED => Ok(TRANSACTION_STATUS_CLEARED),
+            _ => Err(sqlx::Error::Protocol(format!(
+                "Invalid status '{}': must be \"{}\" or \"{}\"",
+                status, TRANSACTION_STATUS_UNCLEARED, TRANSACTION_STATUS_CLEARED
+            ))),
+        }
+    }
+
     /// Helper method to reverse the balance effects of a transaction
     async fn reverse_transaction_balance_effects(
         &self,
@@ -703,8 +1733,9 @@ impl TransactionService {
         now: DateTime<Utc>
     ) -> Result<(), sqlx::Error> {
         let abs_amount = transaction.amount.abs();
+        let abs_destination_amount = transaction.foreign_amount.unwrap_or(transaction.amount).abs();
 
-        if transaction.amount >= 0.0 {
+        if transaction.amount >= Decimal::ZERO {
             // Original was positive: source lost money, destination gained money
             // Reverse: source gains money back, destination loses money
             sqlx::query(
@@ -727,7 +1758,7 @@ impl TransactionService {
                 WHERE id = $3
                 "#,
             )
-            .bind(abs_amount)
+            .bind(abs_destination_amount)
             .bind(now)
             .bind(transaction.destination_account_id)
             .execute(&mut **tx)
@@ -755,7 +1786,7 @@ impl TransactionService {
                 WHERE id = $3
                 "#,
             )
-            .bind(abs_amount)
+            .bind(abs_destination_amount)
             .bind(now)
             .bind(transaction.destination_account_id)
             .execute(&mut **tx)
@@ -771,12 +1802,14 @@ impl TransactionService {
         tx: &mut sqlx::Transaction<'_, Postgres>,
         source_account_id: Uuid,
         destination_account_id: Uuid,
-        amount: f64,
+        amount: Decimal,
+        foreign_amount: Option<Decimal>,
         now: DateTime<Utc>
     ) -> Result<(), sqlx::Error> {
         let abs_amount = amount.abs();
+        let abs_destination_amount = foreign_amount.unwrap_or(amount).abs();
 
-        if amount >= 0.0 {
+        if amount >= Decimal::ZERO {
             // Positive amount: money flows FROM source TO destination
             // Source account loses money (decrease balance)
             sqlx::query(
@@ -800,7 +1833,7 @@ impl TransactionService {
                 WHERE id = $3
                 "#,
             )
-            .bind(abs_amount)
+            .bind(abs_destination_amount)
             .bind(now)
             .bind(destination_account_id)
             .execute(&mut **tx)
@@ -829,7 +1862,7 @@ impl TransactionService {
                 WHERE id = $3
                 "#,
             )
-            .bind(abs_amount)
+            .bind(abs_destination_amount)
             .bind(now)
             .bind(destination_account_id)
             .execute(&mut **tx)
@@ -839,33 +1872,93 @@ impl TransactionService {
         Ok(())
     }
 
-    /// Get unbudgeted transactions with optional date bounds (uses same criteria as unbudgeted total)
+    /// Work out what the destination receives when the two accounts use different currencies.
+    /// Returns the foreign amount and currency, or (None, None) when both accounts share a currency.
+    /// Without an explicit foreign amount, the amount is converted at the transaction date's rate.
+    async fn resolve_foreign_amount(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        ledger_id: Uuid,
+        source_account_id: Uuid,
+        destination_account_id: Uuid,
+        amount: Decimal,
+        foreign_amount: Option<Decimal>,
+        transaction_date: DateTime<Utc>
+    ) -> Result<(Option<Decimal>, Option<String>), sqlx::Error> {
+        let source_currency = sqlx::query_scalar::<_, String>("SELECT currency FROM accounts WHERE id = $1")
+            .bind(source_account_id)
+            .fetch_one(&mut **tx)
+            .await?;
+        let destination_currency = sqlx::query_scalar::<_, String>("SELECT currency FROM accounts WHERE id = $1")
+            .bind(destination_account_id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        if source_currency == destination_currency {
+            return Ok((None, None));
+        }
+
+        let foreign_amount = match foreign_amount {
+            Some(foreign_amount) => {
+                if foreign_amount.is_zero() || foreign_amount.is_sign_negative() != amount.is_sign_negative() {
+                    return Err(sqlx::Error::Protocol(
+                        "Invalid foreign amount: must be non-zero and have the same sign as the amount".into(),
+                    ));
+                }
+                foreign_amount
+            }
+            None => {
+                let date = transaction_date.date_naive();
+                let rate = find_exchange_rate(&mut **tx, ledger_id, &source_currency, &destination_currency, date)
+                    .await?
+                    .ok_or_else(|| sqlx::Error::Protocol(format!(
+                        "No exchange rate from {} to {} on {}; provide foreign_amount or add a rate",
+                        source_currency, destination_currency, date
+                    )))?;
+                (amount * rate).round_dp(4)
+            }
+        };
+
+        Ok((Some(foreign_amount), Some(destination_currency)))
+    }
+
+    /// Get unbudgeted transactions with optional date bounds (uses same criteria as unbudgeted total).
+    /// A split transaction is included when any of its split lines is unbudgeted.
     pub async fn get_unbudgeted_transactions(
         &self,
+        ledger_id: Uuid,
         start_date: Option<DateTime<Utc>>,
         end_date: Option<DateTime<Utc>>,
     ) -> Result<Vec<Transaction>, sqlx::Error> {
-        let mut query = String::from(
-            "SELECT t.*\n\
-             FROM transactions t\n\
+        let mut query = format!(
+            "SELECT DISTINCT t.id\n\
+             FROM ({}) t\n\
              JOIN accounts src ON t.source_account_id = src.id\n\
              LEFT JOIN accounts dst ON t.destination_account_id = dst.id\n\
              LEFT JOIN categories c_id ON c_id.id = t.category_id\n\
-             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category\n\
+             LEFT JOIN categories c_name ON t.category_id IS NULL AND t.category IS NOT NULL AND c_name.name = t.category AND c_name.ledger_id = t.ledger_id\n\
              WHERE t.budget_id IS NULL\n\
+               AND t.ledger_id = $1\n\
                AND src.account_type = 'On Budget'\n\
                AND t.amount > 0\n\
                AND NOT (dst.account_type = 'On Budget')\n\
-               AND (COALESCE(c_id.name, c_name.name, t.category) IS NULL OR COALESCE(c_id.name, c_name.name, t.category) NOT IN ('Initial Balance', 'Transfer', 'Transfers'))"
+               AND (COALESCE(c_id.name, c_name.name, t.category) IS NULL OR COALESCE(c_id.name, c_name.name, t.category) NOT IN ('Initial Balance', 'Transfer', 'Transfers'))",
+            TRANSACTION_LINES_SQL
         );
 
         if let Some(start) = start_date { query.push_str(&format!(" AND t.transaction_date >= '{}'", start)); }
         if let Some(end) = end_date { query.push_str(&format!(" AND t.transaction_date <= '{}'", end)); }
 
-        query.push_str(" ORDER BY t.transaction_date DESC");
+        let query = format!(
+            "SELECT * FROM transactions WHERE id IN ({}) ORDER BY transaction_date DESC",
+            query
+        );
 
-        sqlx::query_as::<_, Transaction>(&query)
+        let mut transactions = sqlx::query_as::<_, Transaction>(&query)
+            .bind(ledger_id)
             .fetch_all(&self.db)
-            .await
+            .await?;
+        self.attach_splits_and_tags(&mut transactions).await?;
+
+        Ok(transactions)
     }
 }
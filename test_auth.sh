@@ -0,0 +1,135 @@
+#!/bin/bash
+set -e
+
+# Test script for authentication and per-user ledgers
+# Requests without a session must be rejected, and two users must never see each other's data.
+# Run against a server started with ALLOW_REGISTRATION=true so the second user can register.
+
+echo "Testing authentication and ledger isolation..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+SUFFIX=$(date +%s)
+ALICE_JAR=$(mktemp)
+trap 'rm -f "$ALICE_JAR"' EXIT
+
+# Requests without a session are rejected
+echo "Listing accounts without a session..."
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/accounts")
+echo "Status: $STATUS (expected: 401)"
+if [ "$STATUS" != "401" ]; then
+  echo "❌ Test failed: unauthenticated request was not rejected"
+  exit 1
+fi
+
+# Feature flags stay public so the login page can render
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/features")
+echo "Features status: $STATUS (expected: 200)"
+if [ "$STATUS" != "200" ]; then
+  echo "❌ Test failed: /features should not require a session"
+  exit 1
+fi
+
+# Register (or log in) the first user with a cookie session
+echo "Registering alice_$SUFFIX..."
+curl -s -c "$ALICE_JAR" -X POST "$BASE_URL/auth/register" \
+  -H "Content-Type: application/json" \
+  -d "{\"username\":\"alice_$SUFFIX\",\"password\":\"alice-password\"}" | jq '.user'
+
+ALICE_USER=$(curl -s -b "$ALICE_JAR" "$BASE_URL/auth/me" | jq -r '.user.username')
+echo "Logged in as: $ALICE_USER"
+if [ "$ALICE_USER" != "alice_$SUFFIX" ]; then
+  echo "❌ Test failed: cookie session was not accepted"
+  exit 1
+fi
+
+# Register a second user and use a bearer token
+echo "Registering bob_$SUFFIX..."
+BOB_TOKEN=$(curl -s -X POST "$BASE_URL/auth/register" \
+  -H "Content-Type: application/json" \
+  -d "{\"username\":\"bob_$SUFFIX\",\"password\":\"bob-password\"}" | jq -r '.token')
+if [ -z "$BOB_TOKEN" ] || [ "$BOB_TOKEN" == "null" ]; then
+  echo "❌ Test failed: could not register second user (is ALLOW_REGISTRATION=true?)"
+  exit 1
+fi
+
+# Duplicate usernames are rejected regardless of case
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/auth/register" \
+  -H "Content-Type: application/json" \
+  -d "{\"username\":\"BOB_$SUFFIX\",\"password\":\"another-password\"}")
+echo "Duplicate registration status: $STATUS (expected: 409)"
+if [ "$STATUS" != "409" ]; then
+  echo "❌ Test failed: duplicate username was accepted"
+  exit 1
+fi
+
+# Alice creates an account in her ledger
+echo "Creating account for alice..."
+ALICE_ACCOUNT_ID=$(curl -s -b "$ALICE_JAR" -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Auth Alice Checking","account_type":"On Budget","balance":500.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Alice account ID: $ALICE_ACCOUNT_ID"
+
+# Bob must not see, change or delete it
+BOB_SEES=$(curl -s -H "Authorization: Bearer $BOB_TOKEN" "$BASE_URL/accounts" \
+  | jq --arg id "$ALICE_ACCOUNT_ID" '[.[] | select(.id == $id)] | length')
+echo "Alice's account visible to bob: $BOB_SEES (expected: 0)"
+if [ "$BOB_SEES" != "0" ]; then
+  echo "❌ Test failed: account leaked into another ledger"
+  exit 1
+fi
+
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -H "Authorization: Bearer $BOB_TOKEN" \
+  -X DELETE "$BASE_URL/accounts/$ALICE_ACCOUNT_ID")
+echo "Bob deleting alice's account: $STATUS (expected: 404)"
+if [ "$STATUS" != "404" ]; then
+  echo "❌ Test failed: another user could delete the account"
+  exit 1
+fi
+
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -H "Authorization: Bearer $BOB_TOKEN" \
+  -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ALICE_ACCOUNT_ID\",\"destination_name\":\"Test Auth Shop\",\"description\":\"Cross-ledger\",\"amount\":10.0,\"category\":\"Test\"}")
+echo "Bob posting to alice's account: $STATUS (expected: an error status)"
+if [ "$STATUS" -lt 400 ]; then
+  echo "❌ Test failed: transaction was posted to another ledger's account"
+  exit 1
+fi
+
+# Values in an update are stored as given, never run as SQL
+NAME=$(curl -s -b "$ALICE_JAR" -X PUT "$BASE_URL/accounts/$ALICE_ACCOUNT_ID" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"x' || (SELECT string_agg(username, ',') FROM users) || '\"}" \
+  | jq -r '.name')
+echo "Renamed account: $NAME (expected: x' || (SELECT string_agg(username, ',') FROM users) || ')"
+if [ "$NAME" != "x' || (SELECT string_agg(username, ',') FROM users) || '" ]; then
+  echo "❌ Test failed: the account name was evaluated as SQL"
+  exit 1
+fi
+
+# Wrong passwords are rejected
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/auth/login" \
+  -H "Content-Type: application/json" \
+  -d "{\"username\":\"alice_$SUFFIX\",\"password\":\"wrong-password\"}")
+echo "Login with wrong password: $STATUS (expected: 401)"
+if [ "$STATUS" != "401" ]; then
+  echo "❌ Test failed: wrong password was accepted"
+  exit 1
+fi
+
+# Logging out invalidates the token
+curl -s -o /dev/null -H "Authorization: Bearer $BOB_TOKEN" -X POST "$BASE_URL/auth/logout"
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -H "Authorization: Bearer $BOB_TOKEN" "$BASE_URL/accounts")
+echo "Request after logout: $STATUS (expected: 401)"
+if [ "$STATUS" != "401" ]; then
+  echo "❌ Test failed: session still valid after logout"
+  exit 1
+fi
+
+# Clean up
+curl -s -o /dev/null -b "$ALICE_JAR" -X DELETE "$BASE_URL/accounts/$ALICE_ACCOUNT_ID"
+
+echo "✅ Authentication test passed"
@@ -0,0 +1,100 @@
+#!/bin/bash
+set -e
+
+# Test script for balance history and net worth reports
+# End-of-month balances must follow the transaction history: deposits into the account raise
+# the balance, payments out of it lower it, and balances carry over months without activity.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing balance history..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+MONTH_0=$(date -d "$(date +%Y-%m-01) -3 months" +%Y-%m-%d)
+MONTH_1=$(date -d "$(date +%Y-%m-01) -2 months" +%Y-%m-%d)
+MONTH_2=$(date -d "$(date +%Y-%m-01) -1 months" +%Y-%m-%d)
+MONTH_3=$(date +%Y-%m-01)
+
+# Create an empty account so no initial balance transaction is added
+echo "Creating account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Balance History Account","account_type":"Off Budget","account_sub_type":"Savings","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# Create an external account to deposit from
+EXTERNAL_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Balance History Employer","account_type":"External","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+
+# Deposit 1000 three months ago, pay 200 two months ago and 100 this month
+T1=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$EXTERNAL_ID\",\"destination_account_id\":\"$ACCOUNT_ID\",\"description\":\"Test Balance History Deposit\",\"amount\":1000.0,\"category\":\"Income\",\"transaction_date\":\"${MONTH_0}T12:00:00Z\"}" \
+  | jq -r '.id')
+T2=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Balance History Shop\",\"description\":\"Test Balance History Payment\",\"amount\":200.0,\"category\":\"Test\",\"transaction_date\":\"${MONTH_1}T12:00:00Z\"}" \
+  | jq -r '.id')
+T3=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Balance History Shop\",\"description\":\"Test Balance History Payment\",\"amount\":100.0,\"category\":\"Test\",\"transaction_date\":\"${MONTH_3}T00:00:00Z\"}" \
+  | jq -r '.id')
+
+# Monthly history starting the month of the deposit
+echo "Fetching monthly balance history from $MONTH_0..."
+HISTORY=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/balance-history?start_date=$MONTH_0&period=month")
+echo "$HISTORY" | jq -c '.[] | {period, balance}'
+BALANCES=$(echo "$HISTORY" | jq -c '[.[] | .balance | tonumber]')
+echo "Balances: $BALANCES (expected: [1000,800,800,700])"
+if [ "$BALANCES" != "[1000,800,800,700]" ]; then
+  echo "❌ Test failed: balance history does not follow the transactions"
+  exit 1
+fi
+
+# Starting later folds the earlier transactions into the opening balance
+BALANCES=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/balance-history?start_date=$MONTH_2&period=month" \
+  | jq -c '[.[] | .balance | tonumber]')
+echo "Balances from $MONTH_2: $BALANCES (expected: [800,700])"
+if [ "$BALANCES" != "[800,700]" ]; then
+  echo "❌ Test failed: earlier transactions were not carried into the opening balance"
+  exit 1
+fi
+
+# Unknown accounts are not found
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/accounts/00000000-0000-0000-0000-000000000000/balance-history")
+echo "Unknown account: $STATUS (expected: 404)"
+if [ "$STATUS" != "404" ]; then
+  echo "❌ Test failed: unknown account was not rejected"
+  exit 1
+fi
+
+# Net worth for just this account, grouped by type and subtype
+echo "Fetching net worth..."
+NET_WORTH=$(curl -s "$BASE_URL/reports/net-worth?start_date=$MONTH_0&account_ids=$ACCOUNT_ID")
+echo "$NET_WORTH" | jq -c '.[] | {period, net_worth, groups}'
+LAST=$(echo "$NET_WORTH" | jq -c '.[-1] | [(.net_worth | tonumber), .groups[0].account_type, .groups[0].account_sub_type]')
+echo "Last period: $LAST (expected: [700,\"Off Budget\",\"Savings\"])"
+if [ "$LAST" != '[700,"Off Budget","Savings"]' ]; then
+  echo "❌ Test failed: net worth does not match the account balance"
+  exit 1
+fi
+
+# Daily history over a short range
+DAYS=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/balance-history?start_date=$MONTH_3&end_date=$(date -d "$MONTH_3 +6 days" +%Y-%m-%d)&period=day" | jq 'length')
+echo "Daily periods: $DAYS (expected: 7)"
+
+echo "✅ Balance history behaves as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$T1"
+curl -s -X DELETE "$BASE_URL/transactions/$T2"
+curl -s -X DELETE "$BASE_URL/transactions/$T3"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$EXTERNAL_ID"
+
+echo "Test completed successfully!"
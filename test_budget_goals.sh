@@ -0,0 +1,125 @@
+#!/bin/bash
+set -e
+
+# Test script for budget goals
+# A budget can aim for a monthly funding target, a target balance by a date or a spending cap; the
+# budgets and budget group endpoints report the progress in the current month.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing budget goals..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Goals are measured in the current month
+MONTH=$(date -u +%Y-%m)
+TARGET_DATE=$(date -u -d "$MONTH-01 +2 months" +%Y-%m-15)
+
+# Create a group and three budgets starting this month
+echo "Creating test budgets..."
+GROUP_ID=$(curl -s -X POST "$BASE_URL/budget-groups" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Goals Group"}' \
+  | jq -r '.id')
+create_budget() {
+  curl -s -X POST "$BASE_URL/budgets" \
+    -H "Content-Type: application/json" \
+    -d "{\"name\":\"$1\",\"amount\":$2,\"start_date\":\"$MONTH-01T00:00:00Z\",$3}" \
+    | jq -r '.id'
+}
+RENT_ID=$(create_budget "Test Goals Rent" 150.0 "\"group_id\":\"$GROUP_ID\",\"goal_type\":\"monthly_funding\",\"goal_amount\":200.0")
+HOLIDAY_ID=$(create_budget "Test Goals Holiday" 100.0 "\"group_id\":\"$GROUP_ID\",\"goal_type\":\"target_balance\",\"goal_amount\":900.0,\"goal_target_date\":\"$TARGET_DATE\"")
+DINING_ID=$(create_budget "Test Goals Dining" 100.0 "\"goal_type\":\"spending_cap\",\"goal_amount\":50.0")
+echo "Rent ID: $RENT_ID, Holiday ID: $HOLIDAY_ID, Dining ID: $DINING_ID"
+
+# Spend 80 from dining
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Goals Account","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+TRANSACTION_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Goals Restaurant\",\"description\":\"Test Goals Dinner\",\"amount\":80.0,\"category\":\"Test Goals Food\",\"budget_id\":\"$DINING_ID\",\"transaction_date\":\"$MONTH-01T12:00:00Z\"}" \
+  | jq -r '.id')
+
+progress() {
+  curl -s "$BASE_URL/budgets/$1" \
+    | jq -c '.goal_progress | [(.progress | tonumber), (.monthly_needed | tonumber), (.still_needed | tonumber), .underfunded, .over_cap]'
+}
+
+# 150 of the 200 to fund every month
+RENT=$(progress "$RENT_ID")
+echo "Rent: $RENT (expected: [0.75,200,50,true,false])"
+# 900 over three months, 100 funded so far
+HOLIDAY=$(progress "$HOLIDAY_ID")
+echo "Holiday: $HOLIDAY (expected: [0.1111,300,200,true,false])"
+# 80 spent against a cap of 50
+DINING=$(progress "$DINING_ID")
+echo "Dining: $DINING (expected: [1.6,0,0,false,true])"
+if [ "$RENT" != "[0.75,200,50,true,false]" ] || [ "$HOLIDAY" != "[0.1111,300,200,true,false]" ] || [ "$DINING" != "[1.6,0,0,false,true]" ]; then
+  echo "❌ Test failed: the goal progress is wrong"
+  exit 1
+fi
+
+# Funding the rest of the rent meets its goal
+curl -s -o /dev/null -X PUT "$BASE_URL/budgets/$RENT_ID/allocations/$MONTH" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":200.0}'
+RENT=$(progress "$RENT_ID")
+echo "Rent when funded: $RENT (expected: [1,200,0,false,false])"
+if [ "$RENT" != "[1,200,0,false,false]" ]; then
+  echo "❌ Test failed: the funded goal is still underfunded"
+  exit 1
+fi
+
+# The group sums up its budgets' goals, also in the list of budgets
+GROUP=$(curl -s "$BASE_URL/budget-groups/$GROUP_ID" \
+  | jq -c '.goals | [.budgets_with_goals, .underfunded_budgets, (.monthly_needed | tonumber), (.still_needed | tonumber)]')
+GROUP_BUDGETS=$(curl -s "$BASE_URL/budget-groups/$GROUP_ID/budgets" \
+  | jq -c '[.[] | [.name, .goal_progress.underfunded]]')
+echo "Group goals: $GROUP (expected: [2,1,500,200])"
+echo "Group budgets: $GROUP_BUDGETS (expected: [[\"Test Goals Holiday\",true],[\"Test Goals Rent\",false]])"
+if [ "$GROUP" != "[2,1,500,200]" ] || [ "$GROUP_BUDGETS" != '[["Test Goals Holiday",true],["Test Goals Rent",false]]' ]; then
+  echo "❌ Test failed: the group does not report its goals"
+  exit 1
+fi
+
+# Invalid goals are rejected
+create_status() {
+  curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/budgets" \
+    -H "Content-Type: application/json" \
+    -d "{\"name\":\"Test Goals Invalid\",\"amount\":10.0,\"start_date\":\"$MONTH-01T00:00:00Z\",$1}"
+}
+UNKNOWN=$(create_status '"goal_type":"someday","goal_amount":10.0')
+NO_AMOUNT=$(create_status '"goal_type":"monthly_funding"')
+NO_DATE=$(create_status '"goal_type":"target_balance","goal_amount":10.0')
+echo "Invalid statuses: $UNKNOWN $NO_AMOUNT $NO_DATE (expected: 400 400 400)"
+if [ "$UNKNOWN $NO_AMOUNT $NO_DATE" != "400 400 400" ]; then
+  echo "❌ Test failed: an invalid goal was accepted"
+  exit 1
+fi
+
+# An empty goal type removes the goal
+CLEARED=$(curl -s -X PUT "$BASE_URL/budgets/$DINING_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"goal_type":""}' \
+  | jq -c '[.goal_type, .goal_amount, .goal_progress]')
+echo "Cleared goal: $CLEARED (expected: [null,null,null])"
+if [ "$CLEARED" != "[null,null,null]" ]; then
+  echo "❌ Test failed: the goal was not removed"
+  exit 1
+fi
+
+echo "✅ Budget goals behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Goals Restaurant") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+for ID in "$RENT_ID" "$HOLIDAY_ID" "$DINING_ID"; do
+  curl -s -X DELETE "$BASE_URL/budgets/$ID"
+done
+curl -s -X DELETE "$BASE_URL/budget-groups/$GROUP_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,89 @@
+#!/bin/bash
+set -e
+
+# Test script for envelope budgeting with monthly allocations
+# Each month a budget gets an assigned amount; what is left, or overspent, carries over into the
+# next month. A budget without an allocation for its first month keeps its amount there.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing monthly budget allocations..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account and a budget starting in January
+echo "Creating test account and budget..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Envelope Account","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+BUDGET_ID=$(curl -s -X POST "$BASE_URL/budgets" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Envelope Groceries","amount":100.0,"start_date":"2026-01-01T00:00:00Z"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID, Budget ID: $BUDGET_ID"
+
+create_transaction() {
+  curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Envelope Shop\",\"description\":\"Test Envelope Groceries\",\"amount\":$1,\"category\":\"Test Envelope Food\",\"budget_id\":\"$BUDGET_ID\",\"transaction_date\":\"$2T12:00:00Z\"}" \
+    | jq -r '.id'
+}
+JANUARY_ID=$(create_transaction 30.0 "2026-01-15")
+FEBRUARY_ID=$(create_transaction 150.0 "2026-02-10")
+
+# February gets 50 on top of what January left
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X PUT "$BASE_URL/budgets/$BUDGET_ID/allocations/2026-02" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":50.0}')
+echo "Allocation status: $STATUS (expected: 200)"
+
+month_line() {
+  curl -s "$BASE_URL/budgets/month/$1" \
+    | jq -c --arg id "$BUDGET_ID" '.budgets[] | select(.budget_id == $id) | [.carried_over, .assigned, .activity, .available] | map(tonumber)'
+}
+
+JANUARY=$(month_line 2026-01)
+FEBRUARY=$(month_line 2026-02)
+MARCH=$(month_line 2026-03)
+echo "January: $JANUARY (expected: [0,100,30,70])"
+echo "February: $FEBRUARY (expected: [70,50,150,-30])"
+echo "March: $MARCH (expected: [-30,0,0,-30])"
+if [ "$STATUS" != "200" ] || [ "$JANUARY" != "[0,100,30,70]" ] || [ "$FEBRUARY" != "[70,50,150,-30]" ] || [ "$MARCH" != "[-30,0,0,-30]" ]; then
+  echo "❌ Test failed: assigned, activity or available amounts are wrong"
+  exit 1
+fi
+
+# Assigning more in January carries into the later months
+curl -s -o /dev/null -X PUT "$BASE_URL/budgets/$BUDGET_ID/allocations/2026-01" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":130.0}'
+ALLOCATIONS=$(curl -s "$BASE_URL/budgets/$BUDGET_ID/allocations" | jq -c '[.[] | [.month, (.amount | tonumber)]]')
+MARCH=$(month_line 2026-03)
+echo "Allocations: $ALLOCATIONS (expected: [[\"2026-01-01\",130],[\"2026-02-01\",50]])"
+echo "March after more in January: $MARCH (expected: [0,0,0,0])"
+if [ "$ALLOCATIONS" != '[["2026-01-01",130],["2026-02-01",50]]' ] || [ "$MARCH" != "[0,0,0,0]" ]; then
+  echo "❌ Test failed: changing an earlier month did not carry over"
+  exit 1
+fi
+
+# Months must be written as YYYY-MM, with a year the months around it can be computed for
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/budgets/month/march")
+FAR_STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/budgets/month/+262142-12")
+echo "Invalid month status: $STATUS, far future month status: $FAR_STATUS (expected: 400, 400)"
+if [ "$STATUS" != "400" ] || [ "$FAR_STATUS" != "400" ]; then
+  echo "❌ Test failed: an invalid month was accepted"
+  exit 1
+fi
+
+echo "✅ Monthly budget allocations behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$JANUARY_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$FEBRUARY_ID"
+curl -s -X DELETE "$BASE_URL/budgets/$BUDGET_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Envelope Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,77 @@
+#!/bin/bash
+set -e
+
+# Test script for moving money between budgets
+# A move takes an amount from one budget and gives it to another in a month; it carries over like
+# assigned money and is listed in the transfer history.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing budget transfers..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create two budgets starting in March
+echo "Creating test budgets..."
+create_budget() {
+  curl -s -X POST "$BASE_URL/budgets" \
+    -H "Content-Type: application/json" \
+    -d "{\"name\":\"$1\",\"amount\":$2,\"start_date\":\"2026-03-01T00:00:00Z\"}" \
+    | jq -r '.id'
+}
+GROCERIES_ID=$(create_budget "Test Move Groceries" 300.0)
+DINING_ID=$(create_budget "Test Move Dining" 100.0)
+echo "Groceries ID: $GROCERIES_ID, Dining ID: $DINING_ID"
+
+move() {
+  curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/budgets/transfers" \
+    -H "Content-Type: application/json" \
+    -d "$1"
+}
+
+# Move 50 from dining to groceries in March
+STATUS=$(move "{\"from_budget_id\":\"$DINING_ID\",\"to_budget_id\":\"$GROCERIES_ID\",\"month\":\"2026-03\",\"amount\":50.0,\"note\":\"Test Move party\"}")
+echo "Transfer status: $STATUS (expected: 201)"
+
+month_line() {
+  curl -s "$BASE_URL/budgets/month/$1" \
+    | jq -c --arg id "$2" '.budgets[] | select(.budget_id == $id) | [.carried_over, .assigned, .transferred, .activity, .available] | map(tonumber)'
+}
+GROCERIES_MARCH=$(month_line 2026-03 "$GROCERIES_ID")
+DINING_MARCH=$(month_line 2026-03 "$DINING_ID")
+GROCERIES_APRIL=$(month_line 2026-04 "$GROCERIES_ID")
+echo "Groceries in March: $GROCERIES_MARCH (expected: [0,300,50,0,350])"
+echo "Dining in March: $DINING_MARCH (expected: [0,100,-50,0,50])"
+echo "Groceries in April: $GROCERIES_APRIL (expected: [350,0,0,0,350])"
+if [ "$STATUS" != "201" ] || [ "$GROCERIES_MARCH" != "[0,300,50,0,350]" ] || [ "$DINING_MARCH" != "[0,100,-50,0,50]" ] || [ "$GROCERIES_APRIL" != "[350,0,0,0,350]" ]; then
+  echo "❌ Test failed: the transfer was not applied to the available amounts"
+  exit 1
+fi
+
+# The history explains why groceries grew in March
+HISTORY=$(curl -s "$BASE_URL/budgets/transfers?budget_id=$GROCERIES_ID&month=2026-03" \
+  | jq -c --arg from "$DINING_ID" '[.[] | [.from_budget_id == $from, .month, (.amount | tonumber), .note]]')
+echo "History: $HISTORY (expected: [[true,\"2026-03-01\",50,\"Test Move party\"]])"
+if [ "$HISTORY" != '[[true,"2026-03-01",50,"Test Move party"]]' ]; then
+  echo "❌ Test failed: the transfer is not in the history"
+  exit 1
+fi
+
+# Invalid transfers are rejected
+SAME=$(move "{\"from_budget_id\":\"$DINING_ID\",\"to_budget_id\":\"$DINING_ID\",\"month\":\"2026-03\",\"amount\":10.0}")
+NEGATIVE=$(move "{\"from_budget_id\":\"$DINING_ID\",\"to_budget_id\":\"$GROCERIES_ID\",\"month\":\"2026-03\",\"amount\":-10.0}")
+BAD_MONTH=$(move "{\"from_budget_id\":\"$DINING_ID\",\"to_budget_id\":\"$GROCERIES_ID\",\"month\":\"March\",\"amount\":10.0}")
+echo "Invalid statuses: $SAME $NEGATIVE $BAD_MONTH (expected: 400 400 400)"
+if [ "$SAME $NEGATIVE $BAD_MONTH" != "400 400 400" ]; then
+  echo "❌ Test failed: an invalid transfer was accepted"
+  exit 1
+fi
+
+echo "✅ Budget transfers behave as expected"
+
+# Clean up; the transfers go with the budgets
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/budgets/$GROCERIES_ID"
+curl -s -X DELETE "$BASE_URL/budgets/$DINING_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,119 @@
+#!/bin/bash
+set -e
+
+# Test script for creating a month's budgets from another month
+# The new budgets take the amounts of the copied month, last month's spending or the average of the
+# last three months, per budget group, and budgets the month already has are not duplicated.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing budget templates..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# A group and budgets in a quiet stretch of months
+echo "Creating test budgets..."
+GROUP_ID=$(curl -s -X POST "$BASE_URL/budget-groups" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Copy Group"}' \
+  | jq -r '.id')
+create_budget() {
+  curl -s -X POST "$BASE_URL/budgets" \
+    -H "Content-Type: application/json" \
+    -d "{\"name\":\"$1\",\"amount\":$2,\"start_date\":\"$3-01T00:00:00Z\"$4}" \
+    | jq -r '.id'
+}
+GROCERIES_JAN=$(create_budget "Test Copy Groceries" 100.0 2031-01 ",\"group_id\":\"$GROUP_ID\"")
+GROCERIES_FEB=$(create_budget "Test Copy Groceries" 100.0 2031-02 ",\"group_id\":\"$GROUP_ID\"")
+GROCERIES_MAR=$(create_budget "Test Copy Groceries" 130.0 2031-03 ",\"group_id\":\"$GROUP_ID\"")
+RENT_MAR=$(create_budget "Test Copy Rent" 1000.0 2031-03)
+DINING_MAR=$(create_budget "Test Copy Dining" 60.0 2031-03 ",\"end_date\":\"2031-03-31T00:00:00Z\"")
+# April already has its rent
+create_budget "Test Copy Rent" 1000.0 2031-04 > /dev/null
+# Rent was given more in March than its amount
+curl -s -o /dev/null -X PUT "$BASE_URL/budgets/$RENT_MAR/allocations/2031-03" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":1100.0}'
+
+# Spending: groceries 90, 120 and 150, dining 45 in March
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Copy Account","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+spend() {
+  curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Copy Shop\",\"description\":\"Test Copy Purchase\",\"amount\":$2,\"category\":\"Test Copy Misc\",\"budget_id\":\"$1\",\"transaction_date\":\"$3T12:00:00Z\"}" \
+    | jq -r '.id'
+}
+T1=$(spend "$GROCERIES_JAN" 90.0 2031-01-10)
+T2=$(spend "$GROCERIES_FEB" 120.0 2031-02-10)
+T3=$(spend "$GROCERIES_MAR" 150.0 2031-03-10)
+T4=$(spend "$DINING_MAR" 45.0 2031-03-12)
+
+copy_month() {
+  curl -s -X POST "$BASE_URL/budgets/month/$1/copy" \
+    -H "Content-Type: application/json" \
+    -d "$2"
+}
+
+# April from March: last month's spending, the group by its three month average
+APRIL=$(copy_month 2031-04 "{\"amount_source\":\"last_month_spending\",\"group_amount_sources\":{\"$GROUP_ID\":\"three_month_average\"}}")
+CREATED=$(echo "$APRIL" | jq -c '[.created[] | [.name, (.amount | tonumber), .start_date, .end_date]] | sort')
+SKIPPED=$(echo "$APRIL" | jq -c '[.source_month, .skipped]')
+echo "Created in April: $CREATED"
+echo "  (expected: [[\"Test Copy Dining\",45,\"2031-04-01T00:00:00Z\",\"2031-04-30T23:59:59Z\"],[\"Test Copy Groceries\",120,\"2031-04-01T00:00:00Z\",null]])"
+echo "Source and skipped: $SKIPPED (expected: [\"2031-03\",[\"Test Copy Rent\"]])"
+if [ "$CREATED" != '[["Test Copy Dining",45,"2031-04-01T00:00:00Z","2031-04-30T23:59:59Z"],["Test Copy Groceries",120,"2031-04-01T00:00:00Z",null]]' ] || [ "$SKIPPED" != '["2031-03",["Test Copy Rent"]]' ]; then
+  echo "❌ Test failed: April was not filled from March"
+  exit 1
+fi
+
+# Copying again creates nothing
+AGAIN=$(copy_month 2031-04 '{}' | jq -c '[(.created | length), (.skipped | length)]')
+echo "Copying again: $AGAIN (expected: [0,3])"
+if [ "$AGAIN" != "[0,3]" ]; then
+  echo "❌ Test failed: budgets were duplicated"
+  exit 1
+fi
+
+# June from March keeps March's assigned amounts
+JUNE=$(copy_month 2031-06 '{"source_month":"2031-03"}' \
+  | jq -c '[.created[] | [.name, (.amount | tonumber), .group_id != null]] | sort')
+echo "Created in June: $JUNE"
+echo "  (expected: [[\"Test Copy Dining\",60,false],[\"Test Copy Groceries\",130,true],[\"Test Copy Rent\",1100,false]])"
+if [ "$JUNE" != '[["Test Copy Dining",60,false],["Test Copy Groceries",130,true],["Test Copy Rent",1100,false]]' ]; then
+  echo "❌ Test failed: June did not copy March's amounts"
+  exit 1
+fi
+
+# Invalid copies are rejected
+copy_status() {
+  curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/budgets/month/$1/copy" \
+    -H "Content-Type: application/json" \
+    -d "$2"
+}
+UNKNOWN=$(copy_status 2031-07 '{"amount_source":"guess"}')
+SAME=$(copy_status 2031-07 '{"source_month":"2031-07"}')
+BAD_MONTH=$(copy_status July '{}')
+echo "Invalid statuses: $UNKNOWN $SAME $BAD_MONTH (expected: 400 400 400)"
+if [ "$UNKNOWN $SAME $BAD_MONTH" != "400 400 400" ]; then
+  echo "❌ Test failed: an invalid copy was accepted"
+  exit 1
+fi
+
+echo "✅ Budget templates behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+for ID in "$T1" "$T2" "$T3" "$T4"; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Copy Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+for ID in $(curl -s "$BASE_URL/budgets" | jq -r '.[] | select(.name | startswith("Test Copy")) | .id'); do
+  curl -s -X DELETE "$BASE_URL/budgets/$ID"
+done
+curl -s -X DELETE "$BASE_URL/budget-groups/$GROUP_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,143 @@
+#!/bin/bash
+set -e
+
+# Test script for bulk transaction editing
+# A patch is applied to transactions selected by ID or by search filter in one database transaction;
+# any failing item (e.g. a reconciled transaction) rolls the whole batch back and is reported.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing bulk transaction editing..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+TODAY=$(date +%Y-%m-%d)
+YESTERDAY=$(date -d "yesterday" +%Y-%m-%dT12:00:00Z)
+TWO_DAYS_AGO=$(date -d "2 days ago" +%Y-%m-%d)
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Bulk Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+FIRST_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Bulk Shop\",\"description\":\"Test Bulk First\",\"amount\":100.0,\"category\":\"Test Bulk Misc\",\"transaction_date\":\"$YESTERDAY\",\"status\":\"cleared\"}" \
+  | jq -r '.id')
+SECOND_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Bulk Shop\",\"description\":\"Test Bulk Second\",\"amount\":50.0,\"category\":\"Test Bulk Misc\",\"transaction_date\":\"$YESTERDAY\",\"status\":\"cleared\"}" \
+  | jq -r '.id')
+THIRD_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Bulk Shop\",\"description\":\"Test Bulk Third\",\"amount\":25.0,\"category\":\"Test Bulk Misc\",\"transaction_date\":\"$YESTERDAY\"}" \
+  | jq -r '.id')
+
+# Recategorize and move two transactions a day earlier by ID
+RESULT=$(curl -s -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$FIRST_ID\",\"$SECOND_ID\"],\"patch\":{\"category\":\"Test Bulk Groceries\",\"shift_days\":-1}}")
+echo "$RESULT" | jq .
+SUCCEEDED=$(echo "$RESULT" | jq -r '.succeeded')
+CATEGORY=$(curl -s "$BASE_URL/transactions/$FIRST_ID" | jq -r '.category')
+DATE=$(curl -s "$BASE_URL/transactions/$SECOND_ID" | jq -r '.transaction_date[0:10]')
+echo "Updated: $SUCCEEDED, category: $CATEGORY, date: $DATE (expected: 2, Test Bulk Groceries, $TWO_DAYS_AGO)"
+if [ "$SUCCEEDED" != "2" ] || [ "$CATEGORY" != "Test Bulk Groceries" ] || [ "$DATE" != "$TWO_DAYS_AGO" ]; then
+  echo "❌ Test failed: bulk update by ID was not applied"
+  exit 1
+fi
+
+# Select by search filter and change the destination
+RESULT=$(curl -s -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"filter\":{\"q\":\"Test Bulk\",\"source_account_id\":\"$ACCOUNT_ID\"},\"patch\":{\"destination_name\":\"Test Bulk Market\"}}")
+SUCCEEDED=$(echo "$RESULT" | jq -r '.succeeded')
+MARKET=$(curl -s "$BASE_URL/transactions/$THIRD_ID" | jq -r '.destination_name')
+echo "Updated: $SUCCEEDED, destination: $MARKET (expected: 3, Test Bulk Market)"
+if [ "$SUCCEEDED" != "3" ] || [ "$MARKET" != "Test Bulk Market" ]; then
+  echo "❌ Test failed: bulk update by filter was not applied"
+  exit 1
+fi
+
+# Lock the two cleared transactions by reconciling (1000 - 100 - 50)
+curl -s -X POST "$BASE_URL/accounts/$ACCOUNT_ID/reconcile" \
+  -H "Content-Type: application/json" \
+  -d "{\"statement_date\":\"$TODAY\",\"statement_balance\":850.0}" > /dev/null
+
+# A locked item fails the whole batch: nothing is deleted and the failure is reported
+STATUS=$(curl -s -o /tmp/bulk_edit_result.json -w "%{http_code}" -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$THIRD_ID\",\"$FIRST_ID\"],\"patch\":{\"delete\":true}}")
+jq . /tmp/bulk_edit_result.json
+APPLIED=$(jq -r '.applied' /tmp/bulk_edit_result.json)
+FAILED_ID=$(jq -r '.items[] | select(.status == "failed") | .transaction_id' /tmp/bulk_edit_result.json)
+THIRD_STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/transactions/$THIRD_ID")
+BALANCE=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID" | jq -r '.balance')
+echo "Status: $STATUS, applied: $APPLIED, third still there: $THIRD_STATUS, balance: $BALANCE (expected: 400, false, 200, 825)"
+if [ "$STATUS" != "400" ] || [ "$APPLIED" != "false" ] || [ "$FAILED_ID" != "$FIRST_ID" ] \
+  || [ "$THIRD_STATUS" != "200" ] || [ "$(echo "$BALANCE" | jq '. == 825')" != "true" ]; then
+  echo "❌ Test failed: a failing item did not roll the batch back"
+  exit 1
+fi
+
+# Categories created by a batch that is rolled back are rolled back too
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$THIRD_ID\",\"$FIRST_ID\"],\"patch\":{\"category\":\"Test Bulk Rolled Back\"}}")
+LEFTOVER=$(curl -s "$BASE_URL/categories" | jq '[.[] | select(.name == "Test Bulk Rolled Back")] | length')
+echo "Status: $STATUS, categories left behind: $LEFTOVER (expected: 400, 0)"
+if [ "$STATUS" != "400" ] || [ "$LEFTOVER" != "0" ]; then
+  echo "❌ Test failed: a rolled back batch left its new category behind"
+  exit 1
+fi
+
+# Unknown IDs are reported as not found
+UNKNOWN=$(curl -s -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"00000000-0000-0000-0000-000000000000\"],\"patch\":{\"category\":\"Test Bulk Misc\"}}" \
+  | jq -r '.items[0].status')
+# Delete cannot be combined with other changes
+INVALID=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$THIRD_ID\"],\"patch\":{\"delete\":true,\"category\":\"Test Bulk Misc\"}}")
+# Date shifts beyond the allowed range are rejected instead of overflowing
+HUGE_SHIFT=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$THIRD_ID\"],\"patch\":{\"shift_days\":9000000000000}}")
+FAR_SHIFT=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$THIRD_ID\"],\"patch\":{\"shift_days\":100000000}}")
+echo "Unknown ID: $UNKNOWN, invalid patch: $INVALID, out of range shifts: $HUGE_SHIFT $FAR_SHIFT (expected: not_found, 400, 400 400)"
+if [ "$UNKNOWN" != "not_found" ] || [ "$INVALID" != "400" ] || [ "$HUGE_SHIFT" != "400" ] || [ "$FAR_SHIFT" != "400" ]; then
+  echo "❌ Test failed: invalid bulk edits were not rejected"
+  exit 1
+fi
+
+# After unlocking, deleting everything restores the opening balance
+curl -s -X POST "$BASE_URL/transactions/$FIRST_ID/unlock" > /dev/null
+curl -s -X POST "$BASE_URL/transactions/$SECOND_ID/unlock" > /dev/null
+RESULT=$(curl -s -X POST "$BASE_URL/transactions/bulk" \
+  -H "Content-Type: application/json" \
+  -d "{\"transaction_ids\":[\"$FIRST_ID\",\"$SECOND_ID\",\"$THIRD_ID\"],\"patch\":{\"delete\":true}}")
+DELETED=$(echo "$RESULT" | jq '[.items[] | select(.status == "deleted")] | length')
+BALANCE=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID" | jq -r '.balance')
+echo "Deleted: $DELETED, balance: $BALANCE (expected: 3, 1000)"
+if [ "$DELETED" != "3" ] || [ "$(echo "$BALANCE" | jq '. == 1000')" != "true" ]; then
+  echo "❌ Test failed: bulk delete did not restore the balance"
+  exit 1
+fi
+
+echo "✅ Bulk transaction editing behaves as expected"
+
+# Clean up
+echo "Cleaning up..."
+for NAME in "Test Bulk Shop" "Test Bulk Market"; do
+  curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r --arg name "$NAME" '.[] | select(.name == $name) | .id')"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+rm -f /tmp/bulk_edit_result.json
+
+echo "Test completed successfully!"
@@ -0,0 +1,96 @@
+#!/bin/bash
+set -e
+
+# Test script for the ledger integrity checker
+# Overwriting an account balance directly must show up as a mismatch against the balance
+# computed from the transactions, and the repair endpoint must restore the computed balance.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing ledger integrity check..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create an account with an opening balance of 500 and spend 200 from it
+echo "Creating account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Integrity Account","account_type":"On Budget","balance":500.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+TRANSACTION_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Integrity Shop\",\"description\":\"Test Integrity Purchase\",\"amount\":200.0,\"category\":\"Test\"}" \
+  | jq -r '.id')
+echo "Transaction ID: $TRANSACTION_ID"
+
+# The account and the External account holding its opening balance are consistent, and so is
+# the whole ledger
+REPORT=$(curl -s "$BASE_URL/integrity")
+MISMATCH=$(echo "$REPORT" \
+  | jq --arg id "$ACCOUNT_ID" '[.balance_mismatches[] | select(.account_id == $id or .account_name == "Initial Balance")] | length')
+CONSISTENT=$(echo "$REPORT" | jq -r '.is_consistent')
+echo "Mismatches before tampering: $MISMATCH (expected: 0)"
+echo "Ledger consistent: $CONSISTENT (expected: true)"
+if [ "$MISMATCH" != "0" ] || [ "$CONSISTENT" != "true" ]; then
+  echo "$REPORT" | jq '.balance_mismatches'
+  echo "❌ Test failed: fresh account reported as inconsistent"
+  exit 1
+fi
+
+# Overwrite the balance directly, bypassing the transactions
+echo "Overwriting the account balance..."
+curl -s -X PUT "$BASE_URL/accounts/$ACCOUNT_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"balance":999.0}' > /dev/null
+
+REPORT=$(curl -s "$BASE_URL/integrity")
+MISMATCH=$(echo "$REPORT" | jq --arg id "$ACCOUNT_ID" '.balance_mismatches[] | select(.account_id == $id)')
+echo "$MISMATCH" | jq .
+COMPUTED=$(echo "$MISMATCH" | jq '.computed_balance | tonumber == 300')
+echo "Computed balance is 300: $COMPUTED (expected: true)"
+if [ "$COMPUTED" != "true" ]; then
+  echo "❌ Test failed: mismatch was not reported"
+  exit 1
+fi
+
+# Checking alone changes nothing
+BALANCE=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID" | jq '.balance | tonumber')
+echo "Balance after check: $BALANCE (expected: 999)"
+if [ "$BALANCE" != "999" ]; then
+  echo "❌ Test failed: check modified the balance"
+  exit 1
+fi
+
+# Repair restores the computed balance
+echo "Repairing..."
+REPAIRED=$(curl -s -X POST "$BASE_URL/integrity/repair" | jq -r '.repaired')
+BALANCE=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID" | jq '.balance | tonumber')
+echo "Repaired: $REPAIRED, balance: $BALANCE (expected: true, 300)"
+if [ "$REPAIRED" != "true" ] || [ "$BALANCE" != "300" ]; then
+  echo "❌ Test failed: balance was not repaired"
+  exit 1
+fi
+
+MISMATCH=$(curl -s "$BASE_URL/integrity" \
+  | jq --arg id "$ACCOUNT_ID" '[.balance_mismatches[] | select(.account_id == $id)] | length')
+echo "Mismatches after repair: $MISMATCH (expected: 0)"
+if [ "$MISMATCH" != "0" ]; then
+  echo "❌ Test failed: mismatch remains after repair"
+  exit 1
+fi
+
+echo "✅ Integrity check behaves as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Integrity Shop") | .id')"
+# The opening balance comes from an account of its own
+OPENING=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/transactions" | jq -r '.[] | select(.description == "Initial Balance") | "\(.id) \(.source_account_id)"')
+curl -s -X DELETE "$BASE_URL/transactions/${OPENING% *}"
+curl -s -X DELETE "$BASE_URL/accounts/${OPENING#* }"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,153 @@
+#!/bin/bash
+set -e
+
+# Test script for multi-currency accounts
+# Transfers between accounts of different currencies must credit the destination with the
+# converted (or explicitly given) foreign amount, and reports must convert into the base currency.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing multi-currency accounts..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+RATE_DATE=$(date -d "yesterday" +%Y-%m-%d)
+TODAY=$(date +%Y-%m-%d)
+
+curl -s -X PUT "$BASE_URL/settings/base-currency" \
+  -H "Content-Type: application/json" \
+  -d '{"base_currency":"usd"}' | jq .
+
+# A USD and a EUR account
+echo "Creating accounts..."
+USD_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test FX Checking","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+EUR_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test FX Euro Account","account_type":"On Budget","balance":0.0,"currency":"EUR"}' \
+  | jq -r '.id')
+echo "USD account: $USD_ID, EUR account: $EUR_ID"
+
+# Without a rate or a foreign amount the transfer cannot be converted
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$USD_ID\",\"destination_account_id\":\"$EUR_ID\",\"description\":\"Test FX Transfer\",\"amount\":110.0,\"category\":\"Transfer\"}")
+echo "Transfer without a rate: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: transfer without a rate was accepted"
+  exit 1
+fi
+
+# One EUR is worth 1.10 USD; the inverse is used for USD -> EUR
+RATE_ID=$(curl -s -X POST "$BASE_URL/exchange-rates" \
+  -H "Content-Type: application/json" \
+  -d "{\"from_currency\":\"EUR\",\"to_currency\":\"USD\",\"rate\":1.10,\"rate_date\":\"$RATE_DATE\"}" \
+  | jq -r '.id')
+echo "Exchange rate ID: $RATE_ID"
+
+TRANSFER=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$USD_ID\",\"destination_account_id\":\"$EUR_ID\",\"description\":\"Test FX Transfer\",\"amount\":110.0,\"category\":\"Transfer\"}")
+TRANSFER_ID=$(echo "$TRANSFER" | jq -r '.id')
+FOREIGN=$(echo "$TRANSFER" | jq -c '[(.foreign_amount | tonumber), .foreign_currency]')
+echo "Foreign amount: $FOREIGN (expected: [100,\"EUR\"])"
+if [ "$FOREIGN" != '[100,"EUR"]' ]; then
+  echo "❌ Test failed: transfer was not converted at the exchange rate"
+  exit 1
+fi
+
+# An explicit foreign amount takes precedence
+MANUAL_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$USD_ID\",\"destination_account_id\":\"$EUR_ID\",\"description\":\"Test FX Manual Transfer\",\"amount\":55.0,\"foreign_amount\":45.0,\"category\":\"Transfer\"}" \
+  | jq -r '.id')
+
+USD_BALANCE=$(curl -s "$BASE_URL/accounts/$USD_ID" | jq '.balance | tonumber')
+EUR_BALANCE=$(curl -s "$BASE_URL/accounts/$EUR_ID" | jq '.balance | tonumber')
+echo "Balances: USD $USD_BALANCE, EUR $EUR_BALANCE (expected: -165, 145)"
+if [ "$USD_BALANCE" != "-165" ] || [ "$EUR_BALANCE" != "145" ]; then
+  echo "❌ Test failed: balances do not reflect the foreign amounts"
+  exit 1
+fi
+
+# Changing the amount converts again
+curl -s -X PUT "$BASE_URL/transactions/$TRANSFER_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":220.0}' > /dev/null
+EUR_BALANCE=$(curl -s "$BASE_URL/accounts/$EUR_ID" | jq '.balance | tonumber')
+echo "EUR balance after doubling the transfer: $EUR_BALANCE (expected: 245)"
+if [ "$EUR_BALANCE" != "245" ]; then
+  echo "❌ Test failed: foreign amount was not recomputed"
+  exit 1
+fi
+
+# Spending 50 EUR shows up as 55 USD in the reports
+SPEND_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$EUR_ID\",\"destination_name\":\"Test FX Shop\",\"description\":\"Test FX Purchase\",\"amount\":50.0,\"category\":\"Test FX Category\"}" \
+  | jq -r '.id')
+SHOP_CURRENCY=$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test FX Shop") | .currency')
+echo "Auto-created external account currency: $SHOP_CURRENCY (expected: EUR)"
+SPENT=$(curl -s "$BASE_URL/reports/spending?group=false&account_ids=$EUR_ID&start_date=$TODAY" \
+  | jq '[.[] | select(.name == "Test FX Category") | .amount | tonumber] | add')
+echo "Reported spending: $SPENT (expected: 55)"
+if [ "$SHOP_CURRENCY" != "EUR" ] || [ "$SPENT" != "55" ]; then
+  echo "❌ Test failed: spending was not converted into the base currency"
+  exit 1
+fi
+
+# Balances recomputed from the history agree with the stored ones
+MISMATCH=$(curl -s "$BASE_URL/integrity" \
+  | jq --arg usd "$USD_ID" --arg eur "$EUR_ID" '[.balance_mismatches[] | select(.account_id == $usd or .account_id == $eur)] | length')
+echo "Integrity mismatches: $MISMATCH (expected: 0)"
+if [ "$MISMATCH" != "0" ]; then
+  echo "❌ Test failed: integrity check disagrees with foreign amounts"
+  exit 1
+fi
+
+# Without a rate the EUR spending can't be reported in USD instead of being counted 1:1
+curl -s -X DELETE "$BASE_URL/exchange-rates/$RATE_ID"
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/reports/spending?group=false&account_ids=$EUR_ID&start_date=$TODAY")
+echo "Spending report without a rate: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: spending without a rate was converted anyway"
+  exit 1
+fi
+
+# Import rates from a CSV file; the malformed line is reported
+RESULT=$(printf 'date,from_currency,to_currency,rate\n%s,GBP,USD,1.25\n%s,GBP,USD,1.30\n%s,GBP,USD,abc\n' \
+  "$RATE_DATE" "$TODAY" "$TODAY" \
+  | curl -s -X POST "$BASE_URL/exchange-rates/import" -H "Content-Type: text/csv" --data-binary @-)
+echo "$RESULT" | jq .
+IMPORTED=$(echo "$RESULT" | jq '.imported')
+ERRORS=$(echo "$RESULT" | jq '.errors | length')
+echo "Imported: $IMPORTED, errors: $ERRORS (expected: 2, 1)"
+if [ "$IMPORTED" != "2" ] || [ "$ERRORS" != "1" ]; then
+  echo "❌ Test failed: CSV import did not behave as expected"
+  exit 1
+fi
+
+RATE=$(curl -s "$BASE_URL/exchange-rates/rate?from_currency=gbp&to_currency=usd&date=$RATE_DATE" | jq '.rate | tonumber')
+echo "GBP->USD on $RATE_DATE: $RATE (expected: 1.25)"
+if [ "$RATE" != "1.25" ]; then
+  echo "❌ Test failed: rate lookup returned the wrong rate"
+  exit 1
+fi
+
+echo "✅ Multi-currency accounts behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$SPEND_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSFER_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$MANUAL_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test FX Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$USD_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$EUR_ID"
+for ID in $(curl -s "$BASE_URL/exchange-rates" | jq -r '.[] | select(.from_currency == "GBP" or .id == "'"$RATE_ID"'") | .id'); do
+  curl -s -X DELETE "$BASE_URL/exchange-rates/$ID"
+done
+
+echo "Test completed successfully!"
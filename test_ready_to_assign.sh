@@ -0,0 +1,91 @@
+#!/bin/bash
+set -e
+
+# Test script for the money ready to assign
+# Ready to assign is the balance of the On Budget accounts minus what is still in budgets; spending
+# from a budget leaves it unchanged and overspending from earlier months is taken from it.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing ready to assign..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+LAST_MONTH=$(date -u -d "$(date -u +%Y-%m-01) -1 month" +%Y-%m)
+
+ready() {
+  curl -s "$BASE_URL/budgets/ready-to-assign" | jq -c "[.$1 | tonumber]"
+}
+# Difference with the value before the test, rounded to cents
+delta() {
+  jq -n --argjson before "$1" --argjson after "$2" '($after[0] - $before[0]) * 100 | round / 100'
+}
+READY_BEFORE=$(ready ready_to_assign)
+OVERSPENT_BEFORE=$(ready overspent)
+
+# New money in an On Budget account is ready to assign
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Ready Account","account_type":"On Budget","balance":500.0,"currency":"USD"}' \
+  | jq -r '.id')
+DELTA=$(delta "$READY_BEFORE" "$(ready ready_to_assign)")
+echo "Ready to assign after a deposit: +$DELTA (expected: +500)"
+if [ "$DELTA" != "500" ]; then
+  echo "❌ Test failed: the deposit is not ready to assign"
+  exit 1
+fi
+
+# Assigning 200 last month takes it out of the pool
+BUDGET_ID=$(curl -s -X POST "$BASE_URL/budgets" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Ready Dining\",\"amount\":200.0,\"start_date\":\"$LAST_MONTH-01T00:00:00Z\"}" \
+  | jq -r '.id')
+DELTA=$(delta "$READY_BEFORE" "$(ready ready_to_assign)")
+echo "Ready to assign after assigning 200: +$DELTA (expected: +300)"
+if [ "$DELTA" != "300" ]; then
+  echo "❌ Test failed: assigned money is still ready to assign"
+  exit 1
+fi
+
+# Spending 250 from the 200 budget last month takes the 50 overspent from the pool
+TRANSACTION_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Ready Restaurant\",\"description\":\"Test Ready Dinner\",\"amount\":250.0,\"category\":\"Test Ready Food\",\"budget_id\":\"$BUDGET_ID\",\"transaction_date\":\"$LAST_MONTH-15T12:00:00Z\"}" \
+  | jq -r '.id')
+DELTA=$(delta "$READY_BEFORE" "$(ready ready_to_assign)")
+OVERSPENT=$(delta "$OVERSPENT_BEFORE" "$(ready overspent)")
+echo "Ready to assign after spending 250: +$DELTA (expected: +250)"
+echo "Overspent: $OVERSPENT (expected: -50)"
+if [ "$DELTA" != "250" ] || [ "$OVERSPENT" != "-50" ]; then
+  echo "❌ Test failed: the overspending was not taken from the pool"
+  exit 1
+fi
+
+# Covering the overspending this month clears it without taking it from the pool twice
+curl -s -o /dev/null -X PUT "$BASE_URL/budgets/$BUDGET_ID/allocations/$(date -u +%Y-%m)" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":50.0}'
+DELTA=$(delta "$READY_BEFORE" "$(ready ready_to_assign)")
+OVERSPENT=$(delta "$OVERSPENT_BEFORE" "$(ready overspent)")
+echo "Ready to assign after covering: +$DELTA (expected: +250)"
+echo "Overspent after covering: $OVERSPENT (expected: 0)"
+if [ "$DELTA" != "250" ] || [ "$OVERSPENT" != "0" ]; then
+  echo "❌ Test failed: covering the overspending was counted twice"
+  exit 1
+fi
+
+echo "✅ Ready to assign behaves as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+curl -s -X DELETE "$BASE_URL/budgets/$BUDGET_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Ready Restaurant") | .id')"
+# The opening balance comes from an account of its own
+OPENING=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/transactions" | jq -r '.[] | select(.description == "Initial Balance") | "\(.id) \(.source_account_id)"')
+curl -s -X DELETE "$BASE_URL/transactions/${OPENING% *}"
+curl -s -X DELETE "$BASE_URL/accounts/${OPENING#* }"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,123 @@
+#!/bin/bash
+set -e
+
+# Test script for account reconciliation
+# Only cleared transactions count towards the statement balance; a matching statement locks them,
+# and locked transactions can only be edited or deleted after being unlocked.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing account reconciliation..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+TODAY=$(date +%Y-%m-%d)
+YESTERDAY=$(date -d "yesterday" +%Y-%m-%dT12:00:00Z)
+
+# Create an account with an opening balance of 1000
+echo "Creating account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Reconcile Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# One cleared and one uncleared expense
+CLEARED_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Reconcile Shop\",\"description\":\"Cleared purchase\",\"amount\":100.0,\"category\":\"Test\",\"transaction_date\":\"$YESTERDAY\",\"status\":\"cleared\"}" \
+  | jq -r '.id')
+UNCLEARED_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Reconcile Shop\",\"description\":\"Pending purchase\",\"amount\":50.0,\"category\":\"Test\",\"transaction_date\":\"$YESTERDAY\"}" \
+  | jq -r '.id')
+echo "Cleared transaction: $CLEARED_ID"
+echo "Uncleared transaction: $UNCLEARED_ID"
+
+# A statement that does not match reports the difference and locks nothing
+echo "Reconciling against a wrong statement balance..."
+RESULT=$(curl -s -X POST "$BASE_URL/accounts/$ACCOUNT_ID/reconcile" \
+  -H "Content-Type: application/json" \
+  -d "{\"statement_date\":\"$TODAY\",\"statement_balance\":850.0}")
+echo "$RESULT" | jq .
+RECONCILED=$(echo "$RESULT" | jq -r '.reconciled')
+DIFFERENCE=$(echo "$RESULT" | jq -r '.difference')
+echo "Reconciled: $RECONCILED, difference: $DIFFERENCE (expected: false, -50)"
+if [ "$RECONCILED" != "false" ] || [ "$(echo "$DIFFERENCE" | jq '. == -50')" != "true" ]; then
+  echo "❌ Test failed: mismatched statement was not reported correctly"
+  exit 1
+fi
+
+# The cleared balance (opening balance + cleared purchase) matches
+echo "Reconciling against the correct statement balance..."
+RESULT=$(curl -s -X POST "$BASE_URL/accounts/$ACCOUNT_ID/reconcile" \
+  -H "Content-Type: application/json" \
+  -d "{\"statement_date\":\"$TODAY\",\"statement_balance\":900.0}")
+echo "$RESULT" | jq .
+RECONCILED=$(echo "$RESULT" | jq -r '.reconciled')
+COUNT=$(echo "$RESULT" | jq -r '.transactions_reconciled')
+echo "Reconciled: $RECONCILED, locked: $COUNT (expected: true, 2)"
+if [ "$RECONCILED" != "true" ] || [ "$COUNT" != "2" ]; then
+  echo "❌ Test failed: matching statement did not reconcile"
+  exit 1
+fi
+
+STATUS=$(curl -s "$BASE_URL/transactions/$CLEARED_ID" | jq -r '.status')
+echo "Cleared transaction status: $STATUS (expected: reconciled)"
+if [ "$STATUS" != "reconciled" ]; then
+  echo "❌ Test failed: transaction was not marked reconciled"
+  exit 1
+fi
+
+# Locked transactions cannot be edited or deleted
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X PUT "$BASE_URL/transactions/$CLEARED_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":120.0}')
+echo "Editing reconciled transaction: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: reconciled transaction was editable"
+  exit 1
+fi
+
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X DELETE "$BASE_URL/transactions/$CLEARED_ID")
+echo "Deleting reconciled transaction: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: reconciled transaction was deletable"
+  exit 1
+fi
+
+# The uncleared transaction is still editable
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X PUT "$BASE_URL/transactions/$UNCLEARED_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"status":"cleared"}')
+echo "Clearing pending transaction: $STATUS (expected: 200)"
+if [ "$STATUS" != "200" ]; then
+  echo "❌ Test failed: could not clear an unlocked transaction"
+  exit 1
+fi
+
+# Unlocking allows edits again
+STATUS=$(curl -s -X POST "$BASE_URL/transactions/$CLEARED_ID/unlock" | jq -r '.status')
+echo "Status after unlock: $STATUS (expected: cleared)"
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X PUT "$BASE_URL/transactions/$CLEARED_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"description":"Cleared purchase (edited)"}')
+echo "Editing unlocked transaction: $STATUS (expected: 200)"
+if [ "$STATUS" != "200" ]; then
+  echo "❌ Test failed: unlocked transaction was not editable"
+  exit 1
+fi
+
+HISTORY=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/reconciliations" | jq 'length')
+echo "Reconciliations recorded: $HISTORY (expected: 1)"
+
+echo "✅ Reconciliation behaves as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X POST "$BASE_URL/transactions/$UNCLEARED_ID/unlock" > /dev/null
+curl -s -X DELETE "$BASE_URL/transactions/$CLEARED_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$UNCLEARED_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
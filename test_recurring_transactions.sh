@@ -0,0 +1,136 @@
+#!/bin/bash
+set -e
+
+# Test script for recurring transactions
+# A monthly schedule starting in the past must be back-filled by the background task, and
+# skipping or posting an occurrence early must move the next occurrence forward.
+# Start the server with RECURRING_INTERVAL_SECS=2 so due occurrences are posted quickly,
+# and run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing recurring transactions..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+START_DATE=$(date -d "$(date +%Y-%m-01) -2 months" +%Y-%m-%d)
+
+# Create an account to post from
+echo "Creating account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Recurring Account","account_type":"On Budget","balance":5000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# Invalid schedules are rejected
+echo "Creating recurring transaction with an invalid schedule (should fail)..."
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/recurring-transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Recurring Invalid\",\"template\":{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Recurring Landlord\",\"description\":\"Test Recurring Rent\",\"amount\":100.0,\"category\":\"Housing\"},\"recurrence\":{\"frequency\":\"weekly\",\"day_of_month\":5},\"start_date\":\"$START_DATE\"}")
+echo "Status: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: invalid schedule was accepted"
+  exit 1
+fi
+
+# Monthly on the 1st, starting two months ago
+echo "Creating monthly recurring transaction starting $START_DATE..."
+RECURRING_ID=$(curl -s -X POST "$BASE_URL/recurring-transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Recurring Rent\",\"template\":{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Recurring Landlord\",\"description\":\"Test Recurring Rent\",\"amount\":100.0,\"category\":\"Housing\"},\"recurrence\":{\"frequency\":\"monthly\",\"day_of_month\":1},\"start_date\":\"$START_DATE\"}" \
+  | jq -r '.id')
+echo "Recurring transaction ID: $RECURRING_ID"
+
+echo "Waiting for the background task..."
+sleep 5
+
+# The three occurrences up to and including this month's 1st are posted
+POSTED=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/transactions?limit=100" \
+  | jq '[.[] | select(.description == "Test Recurring Rent")] | length')
+echo "Posted occurrences: $POSTED (expected: 3)"
+if [ "$POSTED" != "3" ]; then
+  echo "❌ Test failed: due occurrences were not posted"
+  exit 1
+fi
+
+NEXT=$(curl -s "$BASE_URL/recurring-transactions/$RECURRING_ID" | jq -r '.next_occurrence')
+EXPECTED_NEXT=$(date -d "$(date +%Y-%m-01) +1 month" +%Y-%m-%d)
+echo "Next occurrence: $NEXT (expected: $EXPECTED_NEXT)"
+if [ "$NEXT" != "$EXPECTED_NEXT" ]; then
+  echo "❌ Test failed: next occurrence was not advanced"
+  exit 1
+fi
+
+# Upcoming occurrences over the next ~2 months
+echo "Listing upcoming occurrences..."
+curl -s "$BASE_URL/recurring-transactions/upcoming?days=70" \
+  | jq --arg id "$RECURRING_ID" '[.[] | select(.recurring_transaction_id == $id) | {occurrence_date, status}]'
+
+# Skip the next occurrence
+echo "Skipping the next occurrence..."
+NEXT=$(curl -s -X POST "$BASE_URL/recurring-transactions/$RECURRING_ID/skip" | jq -r '.next_occurrence')
+EXPECTED_NEXT=$(date -d "$(date +%Y-%m-01) +2 months" +%Y-%m-%d)
+echo "Next occurrence: $NEXT (expected: $EXPECTED_NEXT)"
+if [ "$NEXT" != "$EXPECTED_NEXT" ]; then
+  echo "❌ Test failed: skip did not advance the next occurrence"
+  exit 1
+fi
+
+# Post the following one early
+echo "Posting the next occurrence early..."
+EARLY_ID=$(curl -s -X POST "$BASE_URL/recurring-transactions/$RECURRING_ID/post" | jq -r '.id')
+echo "Early transaction ID: $EARLY_ID"
+POSTED=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/transactions?limit=100" \
+  | jq '[.[] | select(.description == "Test Recurring Rent")] | length')
+echo "Posted occurrences: $POSTED (expected: 4)"
+if [ "$POSTED" != "4" ]; then
+  echo "❌ Test failed: early post did not create a transaction"
+  exit 1
+fi
+
+# The same occurrence cannot be posted twice
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST \
+  "$BASE_URL/recurring-transactions/$RECURRING_ID/post?occurrence_date=$EXPECTED_NEXT")
+echo "Posting the same occurrence again: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: occurrence was posted twice"
+  exit 1
+fi
+
+# An end date in the past stops the schedule, and other updates keep it
+YESTERDAY=$(date -d "yesterday" +%Y-%m-%d)
+curl -s -X PUT "$BASE_URL/recurring-transactions/$RECURRING_ID" \
+  -H "Content-Type: application/json" \
+  -d "{\"end_date\":\"$YESTERDAY\"}" > /dev/null
+ENDED=$(curl -s -X PUT "$BASE_URL/recurring-transactions/$RECURRING_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Recurring Rent"}' | jq -c '[.end_date, .next_occurrence]')
+echo "End date and next occurrence: $ENDED (expected: [\"$YESTERDAY\",null])"
+if [ "$ENDED" != "[\"$YESTERDAY\",null]" ]; then
+  echo "❌ Test failed: end date did not stop the schedule or was lost on update"
+  exit 1
+fi
+
+# Clearing the end date makes the schedule open-ended again
+REOPENED=$(curl -s -X PUT "$BASE_URL/recurring-transactions/$RECURRING_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"clear_end_date":true}' | jq -c '[.end_date, .next_occurrence]')
+EXPECTED_NEXT=$(date -d "$(date +%Y-%m-01) +3 months" +%Y-%m-%d)
+echo "End date and next occurrence: $REOPENED (expected: [null,\"$EXPECTED_NEXT\"])"
+if [ "$REOPENED" != "[null,\"$EXPECTED_NEXT\"]" ]; then
+  echo "❌ Test failed: end date could not be removed"
+  exit 1
+fi
+
+echo "✅ Recurring transactions behave as expected"
+
+# Clean up - delete the schedule, its transactions and the account
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/recurring-transactions/$RECURRING_ID"
+for TRANSACTION_ID in $(curl -s "$BASE_URL/accounts/$ACCOUNT_ID/transactions?limit=100" \
+  | jq -r '.[] | select(.description == "Test Recurring Rent") | .id'); do
+  curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
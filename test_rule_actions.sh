@@ -0,0 +1,102 @@
+#!/bin/bash
+set -e
+
+# Test script for the rule actions that move, edit and split transactions
+# A rule can turn a payment into a transfer, edit the description, set a memo, split the amount
+# and stop later rules from applying.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule actions..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create test accounts
+echo "Creating test accounts..."
+CHECKING_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Actions Checking","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+SAVINGS_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Actions Savings","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Checking ID: $CHECKING_ID, Savings ID: $SAVINGS_ID"
+
+create_rule() {
+  curl -s -X POST "$BASE_URL/rules" \
+    -H "Content-Type: application/json" \
+    -d "$1" \
+    | jq -r '.id'
+}
+
+# Moves savings deposits to the savings account and stops there
+TRANSFER_RULE_ID=$(create_rule "{\"name\":\"Test Actions Transfer\",\"is_active\":true,\"priority\":10,\"conditions\":[{\"condition_type\":\"description_contains\",\"value\":\"test actions deposit\"}],\"actions\":[{\"action_type\":\"set_destination_account\",\"value\":\"$SAVINGS_ID\"},{\"action_type\":\"set_memo\",\"value\":\"Moved by rule\"},{\"action_type\":\"stop_processing\"}]}")
+# Would mark every test transaction, but never sees the deposits
+MARK_RULE_ID=$(create_rule '{"name":"Test Actions Mark","is_active":true,"priority":20,"conditions":[{"condition_type":"description_contains","value":"test actions"}],"actions":[{"action_type":"append_description","value":" [checked]"}]}')
+# Cleans up card payments and splits them 60/40
+SPLIT_RULE_ID=$(create_rule '{"name":"Test Actions Split","is_active":true,"priority":30,"conditions":[{"condition_type":"description_contains","value":"test actions shop"}],"actions":[{"action_type":"replace_description","pattern":"^pos \\d+ ","value":""},{"action_type":"split_by_percentage","splits":[{"percentage":60,"memo":"Food"},{"percentage":40,"memo":"Household"}]}]}')
+
+# The deposit becomes a transfer to savings and keeps its description
+DEPOSIT=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$CHECKING_ID\",\"destination_name\":\"Test Actions Bank\",\"description\":\"Test Actions Deposit\",\"amount\":100.0,\"category\":\"Test Actions Misc\"}")
+DEPOSIT_ID=$(echo "$DEPOSIT" | jq -r '.id')
+VALUES=$(echo "$DEPOSIT" | jq -c '[.destination_account_id == "'"$SAVINGS_ID"'", .destination_name, .description, .memo]')
+SAVINGS_BALANCE=$(curl -s "$BASE_URL/accounts/$SAVINGS_ID" | jq -r '.balance | tonumber')
+echo "Deposit: $VALUES (expected: [true,\"Test Actions Savings\",\"Test Actions Deposit\",\"Moved by rule\"])"
+echo "Savings balance: $SAVINGS_BALANCE (expected: 100)"
+if [ "$VALUES" != '[true,"Test Actions Savings","Test Actions Deposit","Moved by rule"]' ] || [ "$SAVINGS_BALANCE" != "100" ]; then
+  echo "❌ Test failed: the deposit was not turned into a transfer"
+  exit 1
+fi
+
+# The card payment is renamed, marked and split
+PAYMENT=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$CHECKING_ID\",\"destination_name\":\"Test Actions Store\",\"description\":\"POS 1234 Test Actions Shop\",\"amount\":50.0,\"category\":\"Test Actions Misc\"}")
+PAYMENT_ID=$(echo "$PAYMENT" | jq -r '.id')
+DESCRIPTION=$(echo "$PAYMENT" | jq -r '.description')
+SPLITS=$(echo "$PAYMENT" | jq -c '[.splits[] | [(.amount | tonumber), .memo]] | sort')
+echo "Payment description: $DESCRIPTION (expected: Test Actions Shop [checked])"
+echo "Payment splits: $SPLITS (expected: [[20,\"Household\"],[30,\"Food\"]])"
+if [ "$DESCRIPTION" != "Test Actions Shop [checked]" ] || [ "$SPLITS" != '[[20,"Household"],[30,"Food"]]' ]; then
+  echo "❌ Test failed: the payment was not edited and split"
+  exit 1
+fi
+
+# Running the rules again changes nothing
+PREVIEW=$(curl -s -X POST "$BASE_URL/rules/run/preview" \
+  | jq -c --arg d "$DEPOSIT_ID" --arg p "$PAYMENT_ID" '[.changes[] | select(.transaction_id == $d or .transaction_id == $p)] | length')
+echo "Changes on a second run: $PREVIEW (expected: 0)"
+if [ "$PREVIEW" != "0" ]; then
+  echo "❌ Test failed: running the rules again would change the transactions"
+  exit 1
+fi
+
+# Split shares must add up to 100 percent
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Actions Invalid","is_active":true,"conditions":[],"actions":[{"action_type":"split_by_percentage","splits":[{"percentage":60},{"percentage":30}]}]}')
+echo "Invalid split status: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: invalid split shares were accepted"
+  exit 1
+fi
+
+echo "✅ Rule actions behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+for ID in "$TRANSFER_RULE_ID" "$MARK_RULE_ID" "$SPLIT_RULE_ID"; do
+  curl -s -X DELETE "$BASE_URL/rules/$ID"
+done
+curl -s -X DELETE "$BASE_URL/transactions/$DEPOSIT_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$PAYMENT_ID"
+for NAME in "Test Actions Bank" "Test Actions Store"; do
+  curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r --arg name "$NAME" '.[] | select(.name == $name) | .id')"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$CHECKING_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$SAVINGS_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,96 @@
+#!/bin/bash
+set -e
+
+# Test script for rule condition groups, regular expressions, negation, dates and weekdays
+# Conditions can be nested in "any" and "all" groups; a plain list still means all must match.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule condition groups..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Tree Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# Saturday, Monday and Saturday
+create_transaction() {
+  curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Tree Shop\",\"description\":\"$1\",\"amount\":5.0,\"category\":\"Test Tree Misc\",\"transaction_date\":\"$2T12:00:00Z\"}" \
+    | jq -r '.id'
+}
+ALPHA_ID=$(create_transaction "Test Tree Coffee Alpha" "2026-03-14")
+BETA_ID=$(create_transaction "Test Tree Coffee Beta" "2026-03-16")
+GROCER_ID=$(create_transaction "Test Tree Grocer" "2026-03-14")
+
+test_matches() {
+  curl -s -X POST "$BASE_URL/rules/test" \
+    -H "Content-Type: application/json" \
+    -d "{\"conditions\":$1}" \
+    | jq -c '[.sample[].description] | sort'
+}
+
+# A plain list with a regular expression and a weekday
+MATCHES=$(test_matches '[{"condition_type":"description_matches_regex","value":"^test tree coffee"},{"condition_type":"weekday_is","value":"sat"}]')
+echo "Saturday coffee: $MATCHES (expected: [\"Test Tree Coffee Alpha\"])"
+if [ "$MATCHES" != '["Test Tree Coffee Alpha"]' ]; then
+  echo "❌ Test failed: regex and weekday conditions did not match"
+  exit 1
+fi
+
+# An "any" group with a nested "all" group
+MATCHES=$(test_matches '[{"condition_type":"description_contains","value":"Test Tree"},{"any":[{"condition_type":"description_equals","value":"Test Tree Grocer"},{"all":[{"condition_type":"description_contains","value":"coffee"},{"condition_type":"date_after","value":"2026-03-15"}]}]}]')
+echo "Grocer or later coffee: $MATCHES (expected: [\"Test Tree Coffee Beta\",\"Test Tree Grocer\"])"
+if [ "$MATCHES" != '["Test Tree Coffee Beta","Test Tree Grocer"]' ]; then
+  echo "❌ Test failed: nested groups did not match"
+  exit 1
+fi
+
+# Invalid patterns are rejected
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rules/test" \
+  -H "Content-Type: application/json" \
+  -d '{"conditions":[{"condition_type":"description_matches_regex","value":"test tree ("}]}')
+echo "Invalid pattern test status: $STATUS (expected: 400)"
+STATUS_CREATE=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Tree Invalid","is_active":true,"conditions":[{"any":[]}],"actions":[{"action_type":"set_category","value":"Test Tree Cat"}]}')
+echo "Empty group create status: $STATUS_CREATE (expected: 400)"
+if [ "$STATUS" != "400" ] || [ "$STATUS_CREATE" != "400" ]; then
+  echo "❌ Test failed: invalid conditions were accepted"
+  exit 1
+fi
+
+# A rule with negation and an empty budget check previews only the matching transactions
+RULE=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Tree Rule","is_active":true,"conditions":[{"condition_type":"description_contains","value":"Test Tree"},{"condition_type":"description_not_contains","value":"grocer"},{"condition_type":"budget_is_empty"}],"actions":[{"action_type":"set_category","value":"Test Tree Cat"}]}')
+RULE_ID=$(echo "$RULE" | jq -r '.id')
+STORED=$(echo "$RULE" | jq -c '.conditions[2]')
+echo "Stored condition: $STORED (expected: {\"condition_type\":\"budget_is_empty\",\"value\":\"\"})"
+PREVIEW=$(curl -s -X POST "$BASE_URL/rules/$RULE_ID/run/preview" \
+  | jq -c '[.changes[].transaction_id] | sort')
+EXPECTED=$(jq -cn --arg a "$ALPHA_ID" --arg b "$BETA_ID" '[$a, $b] | sort')
+echo "Previewed: $PREVIEW (expected: $EXPECTED)"
+if [ "$STORED" != '{"condition_type":"budget_is_empty","value":""}' ] || [ "$PREVIEW" != "$EXPECTED" ]; then
+  echo "❌ Test failed: the rule did not match the expected transactions"
+  exit 1
+fi
+
+echo "✅ Rule condition groups behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/rules/$RULE_ID"
+for ID in "$ALPHA_ID" "$BETA_ID" "$GROCER_ID"; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Tree Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,116 @@
+#!/bin/bash
+set -e
+
+# Test script for rule group order, active flags, first-match groups and per-group runs
+# Groups run in their run order; inactive groups are skipped and a first-match group applies one rule.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule group runs..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account and transactions before the rules, so nothing applies on creation
+echo "Creating test account and transactions..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Groups Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+create_transaction() {
+  curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Groups Shop\",\"description\":\"$1\",\"amount\":5.0,\"category\":\"Test Groups Misc\"}" \
+    | jq -r '.id'
+}
+COFFEE_ID=$(create_transaction "Test Groups Coffee 123")
+OTHER_ID=$(create_transaction "Test Groups Bakery 456")
+
+create_group() {
+  curl -s -X POST "$BASE_URL/rule-groups" \
+    -H "Content-Type: application/json" \
+    -d "$1" \
+    | jq -r '.id'
+}
+create_rule() {
+  curl -s -X POST "$BASE_URL/rules" \
+    -H "Content-Type: application/json" \
+    -d "{\"name\":\"$1\",\"is_active\":true,\"priority\":$2,\"group_id\":\"$3\",\"conditions\":[{\"condition_type\":\"description_contains\",\"value\":\"$4\"}],\"actions\":[$5]}" \
+    | jq -r '.id'
+}
+
+# Payee cleanup runs first and applies only its first matching rule
+CLEANUP_ID=$(create_group '{"name":"Test Groups Cleanup","run_order":10,"stop_after_first_match":true}')
+CATEGORISE_ID=$(create_group '{"name":"Test Groups Categorise","run_order":20}')
+OFF_ID=$(create_group '{"name":"Test Groups Off","run_order":5,"is_active":false}')
+RULE_IDS=(
+  "$(create_rule "Test Groups Coffee" 1 "$CLEANUP_ID" "test groups coffee" '{"action_type":"set_description","value":"Test Groups Coffee Shop"}')"
+  "$(create_rule "Test Groups Any" 2 "$CLEANUP_ID" "test groups" '{"action_type":"set_description","value":"Test Groups Payee"}')"
+  "$(create_rule "Test Groups Category" 1 "$CATEGORISE_ID" "test groups" '{"action_type":"set_category","value":"Test Groups Cat"}')"
+  "$(create_rule "Test Groups Tag" 1 "$OFF_ID" "test groups" '{"action_type":"add_tag","value":"test-groups-off"}')"
+)
+
+# Groups are listed in run order
+ORDER=$(curl -s "$BASE_URL/rule-groups" | jq -c '[.[] | select(.name | startswith("Test Groups")) | .name]')
+echo "Group order: $ORDER (expected: [\"Test Groups Off\",\"Test Groups Cleanup\",\"Test Groups Categorise\"])"
+if [ "$ORDER" != '["Test Groups Off","Test Groups Cleanup","Test Groups Categorise"]' ]; then
+  echo "❌ Test failed: groups are not listed in run order"
+  exit 1
+fi
+
+changes() {
+  jq -c --arg c "$COFFEE_ID" --arg o "$OTHER_ID" \
+    '[.changes[] | select(.transaction_id == $c or .transaction_id == $o) | [.after.description, .after.category, .after.tags]] | sort'
+}
+
+# Testing the cleanup group applies one rule per transaction
+TESTED=$(curl -s -X POST "$BASE_URL/rule-groups/$CLEANUP_ID/test" | changes)
+echo "Cleanup test: $TESTED"
+if [ "$TESTED" != '[["Test Groups Coffee Shop","Test Groups Misc",[]],["Test Groups Payee","Test Groups Misc",[]]]' ]; then
+  echo "❌ Test failed: the first-match group did not stop after its first rule"
+  exit 1
+fi
+
+# A full run skips the inactive group, and testing it shows nothing
+PREVIEW=$(curl -s -X POST "$BASE_URL/rules/run/preview" | changes)
+OFF_CHANGES=$(curl -s -X POST "$BASE_URL/rule-groups/$OFF_ID/test" | jq '.affected_transactions')
+echo "Full run: $PREVIEW"
+echo "Inactive group changes: $OFF_CHANGES (expected: 0)"
+if [ "$PREVIEW" != '[["Test Groups Coffee Shop","Test Groups Cat",[]],["Test Groups Payee","Test Groups Cat",[]]]' ] || [ "$OFF_CHANGES" != "0" ]; then
+  echo "❌ Test failed: the inactive group was not skipped"
+  exit 1
+fi
+
+# Running one group changes only what its rules set
+AFFECTED=$(curl -s -X POST "$BASE_URL/rule-groups/$CATEGORISE_ID/run" | jq '.affected_transactions')
+COFFEE=$(curl -s "$BASE_URL/transactions/$COFFEE_ID" | jq -c '[.description, .category]')
+echo "Categorise run affected: $AFFECTED (expected: at least 2)"
+echo "Coffee after the run: $COFFEE (expected: [\"Test Groups Coffee 123\",\"Test Groups Cat\"])"
+if [ "$AFFECTED" -lt 2 ] || [ "$COFFEE" != '["Test Groups Coffee 123","Test Groups Cat"]' ]; then
+  echo "❌ Test failed: the group run did not apply only its own rules"
+  exit 1
+fi
+
+# Unknown groups cannot be run
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rule-groups/00000000-0000-0000-0000-000000000000/run")
+echo "Unknown group status: $STATUS (expected: 404)"
+if [ "$STATUS" != "404" ]; then
+  echo "❌ Test failed: an unknown group was run"
+  exit 1
+fi
+
+echo "✅ Rule group runs behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+for ID in "${RULE_IDS[@]}"; do
+  curl -s -X DELETE "$BASE_URL/rules/$ID"
+done
+for ID in "$CLEANUP_ID" "$CATEGORISE_ID" "$OFF_ID"; do
+  curl -s -X DELETE "$BASE_URL/rule-groups/$ID"
+done
+curl -s -X DELETE "$BASE_URL/transactions/$COFFEE_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$OTHER_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Groups Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,101 @@
+#!/bin/bash
+set -e
+
+# Test script for previewing rule runs
+# A preview lists the before and after values of every transaction a run would change without
+# changing anything; committing it applies exactly those changes, or nothing when they went stale.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule run previews..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Preview Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# Two transactions the rule matches and one it does not
+TRANSACTION_IDS=()
+for DESCRIPTION in "Test Preview Coffee" "Test Preview Coffee Beans" "Test Preview Rent"; do
+  TRANSACTION_IDS+=($(curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Preview Shop\",\"description\":\"$DESCRIPTION\",\"amount\":10.0,\"category\":\"Test Preview Misc\"}" \
+    | jq -r '.id'))
+done
+
+RULE_ID=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Preview Rule","is_active":true,"conditions":[{"condition_type":"description_contains","value":"Test Preview Coffee"}],"actions":[{"action_type":"set_category","value":"Test Preview Cafe"},{"action_type":"add_tag","value":"test-preview"}]}' \
+  | jq -r '.id')
+
+# The preview shows the proposed values but changes nothing
+PREVIEW=$(curl -s -X POST "$BASE_URL/rules/$RULE_ID/run/preview")
+echo "$PREVIEW" | jq .
+AFFECTED=$(echo "$PREVIEW" | jq -r '.affected_transactions')
+VALUES=$(echo "$PREVIEW" | jq -c '[.changes[0] | .before.category, .after.category, .after.tags]')
+CATEGORY=$(curl -s "$BASE_URL/transactions/${TRANSACTION_IDS[0]}" | jq -r '.category')
+echo "Affected: $AFFECTED, values: $VALUES, current category: $CATEGORY (expected: 2, [\"Test Preview Misc\",\"Test Preview Cafe\",[\"test-preview\"]], Test Preview Misc)"
+if [ "$AFFECTED" != "2" ] || [ "$VALUES" != '["Test Preview Misc","Test Preview Cafe",["test-preview"]]' ] || [ "$CATEGORY" != "Test Preview Misc" ]; then
+  echo "❌ Test failed: the preview is wrong or changed transactions"
+  exit 1
+fi
+
+# Once the rule changed, the preview no longer describes the run and nothing is applied
+curl -s -X PUT "$BASE_URL/rules/$RULE_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"actions":[{"action_type":"set_category","value":"Test Preview Bakery"}]}' > /dev/null
+STATUS=$(curl -s -o /tmp/rule_preview_commit.json -w "%{http_code}" -X POST "$BASE_URL/rules/$RULE_ID/run/commit" \
+  -H "Content-Type: application/json" \
+  -d "{\"changes\":$(echo "$PREVIEW" | jq -c '.changes')}")
+CONFLICTS=$(jq -r '.conflicts | length' /tmp/rule_preview_commit.json)
+CATEGORY=$(curl -s "$BASE_URL/transactions/${TRANSACTION_IDS[0]}" | jq -r '.category')
+echo "Stale commit: $STATUS, conflicts: $CONFLICTS, category: $CATEGORY (expected: 400, 2, Test Preview Misc)"
+if [ "$STATUS" != "400" ] || [ "$CONFLICTS" != "2" ] || [ "$CATEGORY" != "Test Preview Misc" ]; then
+  echo "❌ Test failed: a stale preview was applied"
+  exit 1
+fi
+
+# A fresh preview of all rules is applied exactly, under one run
+PREVIEW=$(curl -s -X POST "$BASE_URL/rules/run/preview")
+COMMIT=$(curl -s -X POST "$BASE_URL/rules/run/commit" \
+  -H "Content-Type: application/json" \
+  -d "{\"changes\":$(echo "$PREVIEW" | jq -c '[.changes[] | select(.before.category == "Test Preview Misc")]')}")
+echo "$COMMIT" | jq .
+APPLIED=$(echo "$COMMIT" | jq -r '.applied')
+CATEGORIES=$(for ID in "${TRANSACTION_IDS[@]}"; do curl -s "$BASE_URL/transactions/$ID" | jq -r '.category'; done | paste -sd, -)
+SOURCE=$(curl -s "$BASE_URL/transactions/${TRANSACTION_IDS[0]}/history" | jq -r '.[-1] | .source + ":" + .run_id')
+echo "Applied: $APPLIED, categories: $CATEGORIES, history: $SOURCE"
+echo "(expected: true, Test Preview Bakery,Test Preview Bakery,Test Preview Misc, rule:$(echo "$COMMIT" | jq -r '.run_id'))"
+if [ "$APPLIED" != "true" ] || [ "$CATEGORIES" != "Test Preview Bakery,Test Preview Bakery,Test Preview Misc" ] \
+  || [ "$SOURCE" != "rule:$(echo "$COMMIT" | jq -r '.run_id')" ]; then
+  echo "❌ Test failed: the previewed changes were not applied"
+  exit 1
+fi
+
+# Nothing is left to change, and unknown rules cannot be previewed
+AFFECTED=$(curl -s -X POST "$BASE_URL/rules/$RULE_ID/run/preview" | jq -r '.affected_transactions')
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rules/00000000-0000-0000-0000-000000000000/run/preview")
+echo "Affected after commit: $AFFECTED, unknown rule: $STATUS (expected: 0, 404)"
+if [ "$AFFECTED" != "0" ] || [ "$STATUS" != "404" ]; then
+  echo "❌ Test failed: the preview after committing is wrong"
+  exit 1
+fi
+
+echo "✅ Rule run previews behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+rm -f /tmp/rule_preview_commit.json
+curl -s -X DELETE "$BASE_URL/rules/$RULE_ID"
+for ID in "${TRANSACTION_IDS[@]}"; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Preview Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,62 @@
+#!/bin/bash
+set -e
+
+# Test script for rule values containing quotes
+# Rule names and the values rules write are bound as query parameters, so apostrophes are stored
+# as they are instead of breaking the update.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule values with apostrophes..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Quotes Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+RULE_ID=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Quotes Rule\",\"is_active\":true,\"conditions\":[{\"condition_type\":\"description_contains\",\"value\":\"test quotes joe's\"}],\"actions\":[{\"action_type\":\"set_description\",\"value\":\"Test Quotes Joe's Coffee\"},{\"action_type\":\"set_destination_name\",\"value\":\"Test Quotes O'Brien's\"},{\"action_type\":\"set_category\",\"value\":\"Test Quotes Kids' Treats\"}]}" \
+  | jq -r '.id')
+
+# Renaming the rule to a name with an apostrophe
+NAME=$(curl -s -X PUT "$BASE_URL/rules/$RULE_ID" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Quotes Joe's Rule\",\"description\":\"It's for Joe's\"}" \
+  | jq -r '.name')
+echo "Rule name: $NAME (expected: Test Quotes Joe's Rule)"
+if [ "$NAME" != "Test Quotes Joe's Rule" ]; then
+  echo "❌ Test failed: the rule could not be renamed"
+  exit 1
+fi
+
+# The rule applies to a new transaction and writes its values as they are
+TRANSACTION=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Quotes Shop\",\"description\":\"Test Quotes Joe's\",\"amount\":5.0,\"category\":\"Test Quotes Misc\"}")
+TRANSACTION_ID=$(echo "$TRANSACTION" | jq -r '.id')
+VALUES=$(echo "$TRANSACTION" | jq -c '[.description, .destination_name, .category]')
+echo "Values: $VALUES"
+echo "(expected: [\"Test Quotes Joe's Coffee\",\"Test Quotes O'Brien's\",\"Test Quotes Kids' Treats\"])"
+if [ "$VALUES" != "[\"Test Quotes Joe's Coffee\",\"Test Quotes O'Brien's\",\"Test Quotes Kids' Treats\"]" ]; then
+  echo "❌ Test failed: the rule did not write the quoted values"
+  exit 1
+fi
+
+echo "✅ Rule values with apostrophes behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/rules/$RULE_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+for NAME in "Test Quotes Shop" "Test Quotes O'Brien's"; do
+  curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r --arg name "$NAME" '.[] | select(.name == $name) | .id')"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
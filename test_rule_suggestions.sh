@@ -0,0 +1,91 @@
+#!/bin/bash
+set -e
+
+# Test script for rule suggestions learned from categorised transactions
+# Transactions to the same payee that mostly share a category give a suggested rule, which can be
+# accepted as a new rule; accepted suggestions are no longer made.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule suggestions..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Suggest Account","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+create_transaction() {
+  curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"$1\",\"description\":\"Test Suggest Payment\",\"amount\":5.0,\"category\":\"$2\"}" \
+    | jq -r '.id'
+}
+# Three of four coffee payments are in one category, under varying payee names
+TRANSACTION_IDS=(
+  "$(create_transaction "Test Suggest Coffee 101" "Test Suggest Coffee")"
+  "$(create_transaction "TEST SUGGEST COFFEE #202" "Test Suggest Coffee")"
+  "$(create_transaction "Test Suggest Coffee 303" "Test Suggest Coffee")"
+  "$(create_transaction "Test Suggest Coffee 404" "Test Suggest Misc")"
+)
+
+suggestion() {
+  curl -s "$BASE_URL/rules/suggestions?min_confidence=$1" \
+    | jq -c '[.[] | select(.rule.actions[0].value | startswith("Test Suggest"))]'
+}
+
+# Not suggested when 80% must share the category
+COUNT=$(suggestion 0.8 | jq 'length')
+echo "Suggestions at 80%: $COUNT (expected: 0)"
+if [ "$COUNT" != "0" ]; then
+  echo "❌ Test failed: a suggestion below the confidence threshold was made"
+  exit 1
+fi
+
+# Suggested when 70% is enough, matching all payee names
+SUGGESTIONS=$(suggestion 0.7)
+VALUES=$(echo "$SUGGESTIONS" | jq -c '[length, .[0].support, .[0].confidence, .[0].rule.conditions[0].condition_type, .[0].rule.actions[0].value]')
+echo "Suggestion: $VALUES (expected: [1,4,0.75,\"destination_name_matches_regex\",\"Test Suggest Coffee\"])"
+if [ "$VALUES" != '[1,4,0.75,"destination_name_matches_regex","Test Suggest Coffee"]' ]; then
+  echo "❌ Test failed: the expected suggestion was not made"
+  exit 1
+fi
+SUGGESTION_ID=$(echo "$SUGGESTIONS" | jq -r '.[0].id')
+
+# Accepting creates the rule, which matches all four payments
+RULE=$(curl -s -X POST "$BASE_URL/rules/suggestions/$SUGGESTION_ID/accept?min_confidence=0.7")
+RULE_ID=$(echo "$RULE" | jq -r '.id')
+MATCHED=$(curl -s -X POST "$BASE_URL/rules/$RULE_ID/test" | jq -r '.total_matches')
+echo "Accepted rule matches: $MATCHED (expected: 4)"
+if [ "$RULE_ID" == "null" ] || [ "$MATCHED" != "4" ]; then
+  echo "❌ Test failed: the accepted rule does not match the payments"
+  exit 1
+fi
+
+# The suggestion is no longer made once its rule exists
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rules/suggestions/$SUGGESTION_ID/accept?min_confidence=0.7")
+COUNT=$(suggestion 0.7 | jq 'length')
+echo "Accept again status: $STATUS (expected: 404), suggestions left: $COUNT (expected: 0)"
+if [ "$STATUS" != "404" ] || [ "$COUNT" != "0" ]; then
+  echo "❌ Test failed: the accepted suggestion is still made"
+  exit 1
+fi
+
+echo "✅ Rule suggestions behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/rules/$RULE_ID"
+for ID in "${TRANSACTION_IDS[@]}"; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name | ascii_downcase | startswith("test suggest coffee")) | .id' | while read -r ID; do
+  curl -s -X DELETE "$BASE_URL/accounts/$ID"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,111 @@
+#!/bin/bash
+set -e
+
+# Test script for rule triggers and rule runs restricted to a date range and an account
+# A rule only applies automatically on its triggers; a run by hand can be limited to recent
+# transactions of one account.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing rule triggers and run filters..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create test accounts
+echo "Creating test accounts..."
+CHECKING_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Triggers Checking","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+SAVINGS_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Triggers Savings","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+
+# Only runs by hand
+MANUAL=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Triggers Manual","is_active":true,"triggers":[],"conditions":[{"condition_type":"description_contains","value":"test triggers"}],"actions":[{"action_type":"set_category","value":"Test Triggers Cat"}]}')
+MANUAL_ID=$(echo "$MANUAL" | jq -r '.id')
+# Only applies when a transaction is updated
+UPDATE_ID=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Triggers Update","is_active":true,"triggers":["update","update"],"conditions":[{"condition_type":"description_contains","value":"test triggers"}],"actions":[{"action_type":"add_tag","value":"test-triggers-updated"}]}' \
+  | jq -r '.id')
+TRIGGERS=$(echo "$MANUAL" | jq -c '.triggers')
+UPDATE_TRIGGERS=$(curl -s "$BASE_URL/rules/$UPDATE_ID" | jq -c '.triggers')
+echo "Stored triggers: $TRIGGERS and $UPDATE_TRIGGERS (expected: [] and [\"update\"])"
+if [ "$TRIGGERS" != "[]" ] || [ "$UPDATE_TRIGGERS" != '["update"]' ]; then
+  echo "❌ Test failed: the triggers were not stored"
+  exit 1
+fi
+
+create_transaction() {
+  curl -s -X POST "$BASE_URL/transactions" \
+    -H "Content-Type: application/json" \
+    -d "{\"source_account_id\":\"$1\",\"destination_name\":\"Test Triggers Shop\",\"description\":\"$2\",\"amount\":5.0,\"category\":\"Test Triggers Misc\",\"transaction_date\":\"$3T12:00:00Z\"}"
+}
+
+# Neither rule applies on creation
+OLD=$(create_transaction "$CHECKING_ID" "Test Triggers Old" "2026-01-10")
+OLD_ID=$(echo "$OLD" | jq -r '.id')
+RECENT_ID=$(create_transaction "$CHECKING_ID" "Test Triggers Recent" "2026-10-01" | jq -r '.id')
+OTHER_ID=$(create_transaction "$SAVINGS_ID" "Test Triggers Other" "2026-10-01" | jq -r '.id')
+CREATED=$(echo "$OLD" | jq -c '[.category, .tags]')
+echo "After creation: $CREATED (expected: [\"Test Triggers Misc\",[]])"
+if [ "$CREATED" != '["Test Triggers Misc",[]]' ]; then
+  echo "❌ Test failed: a rule applied on creation without the trigger"
+  exit 1
+fi
+
+# Only the update rule applies on update
+UPDATED=$(curl -s -X PUT "$BASE_URL/transactions/$OLD_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"description":"Test Triggers Old Edited"}' \
+  | jq -c '[.category, .tags]')
+echo "After update: $UPDATED (expected: [\"Test Triggers Misc\",[\"test-triggers-updated\"]])"
+if [ "$UPDATED" != '["Test Triggers Misc",["test-triggers-updated"]]' ]; then
+  echo "❌ Test failed: the update trigger did not apply as expected"
+  exit 1
+fi
+
+# A run by hand limited to recent checking transactions
+FILTER="start_date=2026-09-01&end_date=2026-10-31&account_id=$CHECKING_ID"
+PREVIEW=$(curl -s -X POST "$BASE_URL/rules/$MANUAL_ID/run/preview?$FILTER" | jq -c '[.changes[].transaction_id]')
+echo "Previewed: $PREVIEW (expected: [\"$RECENT_ID\"])"
+if [ "$PREVIEW" != "[\"$RECENT_ID\"]" ]; then
+  echo "❌ Test failed: the preview did not respect the filter"
+  exit 1
+fi
+AFFECTED=$(curl -s -X POST "$BASE_URL/rules/$MANUAL_ID/run?$FILTER" | jq '.affected_transactions')
+CATEGORIES=$(for ID in "$OLD_ID" "$RECENT_ID" "$OTHER_ID"; do curl -s "$BASE_URL/transactions/$ID" | jq -r '.category'; done | jq -R . | jq -sc .)
+echo "Run affected: $AFFECTED (expected: 1)"
+echo "Categories: $CATEGORIES (expected: [\"Test Triggers Misc\",\"Test Triggers Cat\",\"Test Triggers Misc\"])"
+if [ "$AFFECTED" != "1" ] || [ "$CATEGORIES" != '["Test Triggers Misc","Test Triggers Cat","Test Triggers Misc"]' ]; then
+  echo "❌ Test failed: the run changed transactions outside the filter"
+  exit 1
+fi
+
+# Invalid dates are rejected
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/rules/run/preview?start_date=yesterday")
+echo "Invalid date status: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: an invalid date was accepted"
+  exit 1
+fi
+
+echo "✅ Rule triggers and run filters behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/rules/$MANUAL_ID"
+curl -s -X DELETE "$BASE_URL/rules/$UPDATE_ID"
+for ID in "$OLD_ID" "$RECENT_ID" "$OTHER_ID"; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+curl -s -X DELETE "$BASE_URL/tags/$(curl -s "$BASE_URL/tags" | jq -r '.[] | select(.name == "test-triggers-updated") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test Triggers Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$CHECKING_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$SAVINGS_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,121 @@
+#!/bin/bash
+set -e
+
+# Test script for split transactions
+# A single transaction is split across two budgets; budget spent and unbudgeted totals
+# must be computed per split line, and splits must always balance against the parent amount.
+
+echo "Testing split transactions..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+YEAR=$(date +%Y)
+MONTH=$(date +%-m)
+START_DATE=$(date +%Y-%m-01T00:00:00Z)
+
+# Create an on-budget account
+echo "Creating on-budget account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Split Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# Create two budgets for the current month
+echo "Creating budgets..."
+FOOD_BUDGET_ID=$(curl -s -X POST "$BASE_URL/budgets" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Split Food\",\"description\":null,\"amount\":300.0,\"start_date\":\"$START_DATE\",\"end_date\":null}" \
+  | jq -r '.id')
+HOUSEHOLD_BUDGET_ID=$(curl -s -X POST "$BASE_URL/budgets" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Split Household\",\"description\":null,\"amount\":100.0,\"start_date\":\"$START_DATE\",\"end_date\":null}" \
+  | jq -r '.id')
+echo "Food budget ID: $FOOD_BUDGET_ID"
+echo "Household budget ID: $HOUSEHOLD_BUDGET_ID"
+
+UNBUDGETED_BEFORE=$(curl -s "$BASE_URL/budgets/unbudgeted-spent?year=$YEAR&month=$MONTH")
+
+# Unbalanced splits must be rejected
+echo "Creating transaction with unbalanced splits (should fail)..."
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Split Grocery Store\",\"description\":\"Unbalanced\",\"amount\":100.0,\"category\":\"Groceries\",\"transaction_date\":null,\"splits\":[{\"amount\":60.0,\"budget_id\":\"$FOOD_BUDGET_ID\"},{\"amount\":30.0}]}")
+echo "Status: $STATUS (expected: an error status)"
+if [ "$STATUS" -lt 400 ]; then
+  echo "❌ Test failed: unbalanced splits were accepted"
+  exit 1
+fi
+
+# Create a grocery receipt split between food, household and an unbudgeted line
+echo "Creating split transaction..."
+RESPONSE=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Split Grocery Store\",\"description\":\"Weekly shop\",\"amount\":100.0,\"category\":\"Groceries\",\"transaction_date\":null,\"splits\":[{\"amount\":60.0,\"category\":\"Groceries\",\"budget_id\":\"$FOOD_BUDGET_ID\",\"memo\":\"Food\"},{\"amount\":30.0,\"category\":\"Household\",\"budget_id\":\"$HOUSEHOLD_BUDGET_ID\",\"memo\":\"Cleaning supplies\"},{\"amount\":10.0,\"category\":\"Snacks\"}]}")
+echo "Response: $RESPONSE"
+TRANSACTION_ID=$(echo "$RESPONSE" | jq -r '.id')
+SPLIT_COUNT=$(echo "$RESPONSE" | jq '.splits | length')
+echo "Split count: $SPLIT_COUNT (expected: 3)"
+[ "$SPLIT_COUNT" -eq 3 ] || { echo "❌ Test failed: expected 3 splits"; exit 1; }
+
+# Account balance is affected by the parent amount only once
+BALANCE=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID" | jq -r '.balance')
+echo "Account balance: $BALANCE (expected: 900.0)"
+if (( $(echo "$BALANCE != 900.0" | bc -l) )); then
+  echo "❌ Test failed: unexpected account balance"
+  exit 1
+fi
+
+# Budget spent is computed per split line
+FOOD_SPENT=$(curl -s "$BASE_URL/budgets/$FOOD_BUDGET_ID/spent?year=$YEAR&month=$MONTH")
+HOUSEHOLD_SPENT=$(curl -s "$BASE_URL/budgets/$HOUSEHOLD_BUDGET_ID/spent?year=$YEAR&month=$MONTH")
+echo "Food spent: $FOOD_SPENT (expected: 60.0)"
+echo "Household spent: $HOUSEHOLD_SPENT (expected: 30.0)"
+if (( $(echo "$FOOD_SPENT != 60.0 || $HOUSEHOLD_SPENT != 30.0" | bc -l) )); then
+  echo "❌ Test failed: budget spent not computed per split"
+  exit 1
+fi
+
+# The unbudgeted split line counts as unbudgeted spending
+UNBUDGETED_AFTER=$(curl -s "$BASE_URL/budgets/unbudgeted-spent?year=$YEAR&month=$MONTH")
+echo "Unbudgeted spent increased by: $(echo "$UNBUDGETED_AFTER - $UNBUDGETED_BEFORE" | bc -l) (expected: 10.0)"
+if (( $(echo "$UNBUDGETED_AFTER - $UNBUDGETED_BEFORE != 10.0" | bc -l) )); then
+  echo "❌ Test failed: unbudgeted split line not counted"
+  exit 1
+fi
+
+# Changing the amount without new splits must be rejected because the splits no longer balance
+echo "Updating amount without splits (should fail)..."
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X PUT "$BASE_URL/transactions/$TRANSACTION_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":120.0}')
+echo "Status: $STATUS (expected: an error status)"
+if [ "$STATUS" -lt 400 ]; then
+  echo "❌ Test failed: update left splits unbalanced"
+  exit 1
+fi
+
+# Changing the amount together with balanced splits succeeds
+echo "Updating amount with balanced splits..."
+RESPONSE=$(curl -s -X PUT "$BASE_URL/transactions/$TRANSACTION_ID" \
+  -H "Content-Type: application/json" \
+  -d "{\"amount\":120.0,\"splits\":[{\"amount\":80.0,\"budget_id\":\"$FOOD_BUDGET_ID\"},{\"amount\":40.0,\"budget_id\":\"$HOUSEHOLD_BUDGET_ID\"}]}")
+echo "Response: $RESPONSE"
+FOOD_SPENT=$(curl -s "$BASE_URL/budgets/$FOOD_BUDGET_ID/spent?year=$YEAR&month=$MONTH")
+echo "Food spent: $FOOD_SPENT (expected: 80.0)"
+if (( $(echo "$FOOD_SPENT != 80.0" | bc -l) )); then
+  echo "❌ Test failed: budget spent not updated after split change"
+  exit 1
+fi
+
+echo "✅ Split transactions behave as expected"
+
+# Clean up - delete the transaction, budgets and account
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+curl -s -X DELETE "$BASE_URL/budgets/$FOOD_BUDGET_ID"
+curl -s -X DELETE "$BASE_URL/budgets/$HOUSEHOLD_BUDGET_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,145 @@
+#!/bin/bash
+set -e
+
+# Test script for transaction tags
+# Tags are attached by name on create/update, filter the transaction list, can be added by rules
+# and group the spending report.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing transaction tags..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+TODAY=$(date +%Y-%m-%d)
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Tags Account","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+# Create a tag explicitly; a duplicate name is rejected
+TAG_ID=$(curl -s -X POST "$BASE_URL/tags" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"test-vacation","description":"Test trip"}' | jq -r '.id')
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/tags" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"test-vacation"}')
+echo "Duplicate tag: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: duplicate tag name was accepted"
+  exit 1
+fi
+
+# Tags on create; unknown tags are created on the fly
+HOTEL_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Tags Hotel\",\"description\":\"Test Tags Hotel\",\"amount\":200.0,\"category\":\"Test Tags Lodging\",\"tags\":[\"test-vacation\",\"test-business\"]}" \
+  | jq -r '.id')
+DINNER_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Tags Restaurant\",\"description\":\"Test Tags Dinner\",\"amount\":50.0,\"category\":\"Test Tags Food\",\"tags\":[\"test-vacation\"]}" \
+  | jq -r '.id')
+GROCERY_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Tags Market\",\"description\":\"Test Tags Groceries\",\"amount\":30.0,\"category\":\"Test Tags Food\"}" \
+  | jq -r '.id')
+
+TAGS=$(curl -s "$BASE_URL/transactions/$HOTEL_ID" | jq -c '.tags')
+echo "Hotel tags: $TAGS (expected: [\"test-business\",\"test-vacation\"])"
+if [ "$TAGS" != '["test-business","test-vacation"]' ]; then
+  echo "❌ Test failed: tags were not stored on create"
+  exit 1
+fi
+
+# Filter the transaction list by tag
+COUNT=$(curl -s "$BASE_URL/transactions?tag=test-vacation" | jq 'length')
+echo "Transactions tagged test-vacation: $COUNT (expected: 2)"
+if [ "$COUNT" != "2" ]; then
+  echo "❌ Test failed: tag filter returned the wrong transactions"
+  exit 1
+fi
+
+# Replacing the tags on update; other fields keep their tags untouched
+curl -s -X PUT "$BASE_URL/transactions/$HOTEL_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"tags":["test-vacation"]}' > /dev/null
+curl -s -X PUT "$BASE_URL/transactions/$DINNER_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"description":"Test Tags Dinner Out"}' > /dev/null
+HOTEL_TAGS=$(curl -s "$BASE_URL/transactions/$HOTEL_ID" | jq -c '.tags')
+DINNER_TAGS=$(curl -s "$BASE_URL/transactions/$DINNER_ID" | jq -c '.tags')
+echo "Tags after update: $HOTEL_TAGS, $DINNER_TAGS (expected: [\"test-vacation\"], [\"test-vacation\"])"
+if [ "$HOTEL_TAGS" != '["test-vacation"]' ] || [ "$DINNER_TAGS" != '["test-vacation"]' ]; then
+  echo "❌ Test failed: tags were not updated as expected"
+  exit 1
+fi
+
+# A rule adding a tag
+RULE_ID=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Tags Rule","is_active":true,"conditions":[{"condition_type":"description_contains","value":"Test Tags Groceries"}],"actions":[{"action_type":"add_tag","value":"test-household"}]}' \
+  | jq -r '.id')
+curl -s -X POST "$BASE_URL/rules/$RULE_ID/run" > /dev/null
+GROCERY_TAGS=$(curl -s "$BASE_URL/transactions/$GROCERY_ID" | jq -c '.tags')
+echo "Groceries tags after running the rule: $GROCERY_TAGS (expected: [\"test-household\"])"
+if [ "$GROCERY_TAGS" != '["test-household"]' ]; then
+  echo "❌ Test failed: add_tag rule action did not tag the transaction"
+  exit 1
+fi
+
+# New transactions get the tag through the rule as well
+SECOND_GROCERY_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Tags Market\",\"description\":\"Test Tags Groceries\",\"amount\":20.0,\"category\":\"Test Tags Food\",\"tags\":[\"test-vacation\"]}" \
+  | jq -r '.id')
+SECOND_TAGS=$(curl -s "$BASE_URL/transactions/$SECOND_GROCERY_ID" | jq -c '.tags')
+echo "New groceries tags: $SECOND_TAGS (expected: [\"test-household\",\"test-vacation\"])"
+if [ "$SECOND_TAGS" != '["test-household","test-vacation"]' ]; then
+  echo "❌ Test failed: add_tag rule action did not apply on create"
+  exit 1
+fi
+
+# Spending grouped by tag, across categories
+REPORT=$(curl -s "$BASE_URL/reports/spending?group_by=tag&account_ids=$ACCOUNT_ID&start_date=$TODAY")
+echo "$REPORT" | jq .
+VACATION=$(echo "$REPORT" | jq '[.[] | select(.name == "test-vacation") | .amount | tonumber] | add')
+HOUSEHOLD=$(echo "$REPORT" | jq '[.[] | select(.name == "test-household") | .amount | tonumber] | add')
+echo "Spending: test-vacation $VACATION, test-household $HOUSEHOLD (expected: 270, 50)"
+if [ "$VACATION" != "270" ] || [ "$HOUSEHOLD" != "50" ]; then
+  echo "❌ Test failed: spending report by tag is wrong"
+  exit 1
+fi
+
+# Tag list with usage counts; renaming keeps the transactions tagged
+USAGE=$(curl -s "$BASE_URL/tags/$TAG_ID" | jq '.transaction_count')
+curl -s -X PUT "$BASE_URL/tags/$TAG_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"test-vacation-2026"}' > /dev/null
+RENAMED=$(curl -s "$BASE_URL/transactions?tag=test-vacation-2026" | jq 'length')
+echo "Usage: $USAGE, after rename: $RENAMED (expected: 3, 3)"
+if [ "$USAGE" != "3" ] || [ "$RENAMED" != "3" ]; then
+  echo "❌ Test failed: tag usage or rename is wrong"
+  exit 1
+fi
+
+echo "✅ Transaction tags behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/rules/$RULE_ID"
+for ID in $HOTEL_ID $DINNER_ID $GROCERY_ID $SECOND_GROCERY_ID; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+for ID in $(curl -s "$BASE_URL/tags" | jq -r '.[] | select(.name | startswith("test-")) | .id'); do
+  curl -s -X DELETE "$BASE_URL/tags/$ID"
+done
+for NAME in "Test Tags Hotel" "Test Tags Restaurant" "Test Tags Market"; do
+  curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r --arg name "$NAME" '.[] | select(.name == $name) | .id')"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
@@ -0,0 +1,102 @@
+#!/bin/bash
+set -e
+
+# Test script for transaction history and undo
+# Every create and update is recorded with who or what made it; single changes and whole rule runs
+# can be undone, but not after the transaction was changed again.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing transaction history and undo..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+# Create a test account
+echo "Creating test account..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test History Account","account_type":"On Budget","balance":1000.0,"currency":"USD"}' \
+  | jq -r '.id')
+echo "Account ID: $ACCOUNT_ID"
+
+TRANSACTION_ID=$(curl -s -X POST "$BASE_URL/transactions" \
+  -H "Content-Type: application/json" \
+  -d "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test History Shop\",\"description\":\"Test History Coffee\",\"amount\":100.0,\"category\":\"Test History Misc\"}" \
+  | jq -r '.id')
+
+# A user edit is recorded with the previous and new values
+curl -s -X PUT "$BASE_URL/transactions/$TRANSACTION_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"amount":120.0,"category":"Test History Food"}' > /dev/null
+HISTORY=$(curl -s "$BASE_URL/transactions/$TRANSACTION_ID/history")
+echo "$HISTORY" | jq .
+ENTRIES=$(echo "$HISTORY" | jq -c '[.[] | .change_type + ":" + .source]')
+CHANGE_ID=$(echo "$HISTORY" | jq -r '.[1].id')
+OLD_CATEGORY=$(echo "$HISTORY" | jq -r '.[1].changes.category.old')
+echo "Entries: $ENTRIES, previous category: $OLD_CATEGORY (expected: [\"create:user\",\"update:user\"], Test History Misc)"
+if [ "$ENTRIES" != '["create:user","update:user"]' ] || [ "$OLD_CATEGORY" != "Test History Misc" ]; then
+  echo "❌ Test failed: the edit was not recorded"
+  exit 1
+fi
+
+# Undoing the edit restores the values and the balance; it cannot be undone twice
+RESTORED=$(curl -s -X POST "$BASE_URL/transaction-changes/$CHANGE_ID/undo")
+CATEGORY=$(echo "$RESTORED" | jq -r '.category')
+BALANCE=$(curl -s "$BASE_URL/accounts/$ACCOUNT_ID" | jq -r '.balance')
+AGAIN=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transaction-changes/$CHANGE_ID/undo")
+echo "Category: $CATEGORY, balance: $BALANCE, undo again: $AGAIN (expected: Test History Misc, 900, 400)"
+if [ "$CATEGORY" != "Test History Misc" ] || [ "$(echo "$BALANCE" | jq '. == 900')" != "true" ] || [ "$AGAIN" != "400" ]; then
+  echo "❌ Test failed: undoing the edit did not restore the transaction"
+  exit 1
+fi
+
+# A rule run is recorded against the rule and can be undone as a whole
+RULE_ID=$(curl -s -X POST "$BASE_URL/rules" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test History Rule","is_active":true,"conditions":[{"condition_type":"description_contains","value":"Test History Coffee"}],"actions":[{"action_type":"set_category","value":"Test History Cafe"},{"action_type":"add_tag","value":"test-history"}]}' \
+  | jq -r '.id')
+RUN_ID=$(curl -s -X POST "$BASE_URL/rules/$RULE_ID/run" | jq -r '.run_id')
+SOURCE=$(curl -s "$BASE_URL/transactions/$TRANSACTION_ID/history" | jq -r '.[-1] | .source + ":" + .source_id')
+echo "Rule change source: $SOURCE (expected: rule:$RULE_ID)"
+if [ "$SOURCE" != "rule:$RULE_ID" ]; then
+  echo "❌ Test failed: the rule change was not attributed to the rule"
+  exit 1
+fi
+UNDONE=$(curl -s -X POST "$BASE_URL/transaction-changes/runs/$RUN_ID/undo" | jq -r '.undone')
+RESTORED=$(curl -s "$BASE_URL/transactions/$TRANSACTION_ID" | jq -c '[.category, .tags]')
+echo "Undone: $UNDONE, restored: $RESTORED (expected: 1, [\"Test History Misc\",[]])"
+if [ "$UNDONE" != "1" ] || [ "$RESTORED" != '["Test History Misc",[]]' ]; then
+  echo "❌ Test failed: undoing the rule run did not restore the transaction"
+  exit 1
+fi
+
+# A run cannot be undone once its transactions were edited again
+# (the rule is deactivated first so it does not re-apply on the edit)
+RUN_ID=$(curl -s -X POST "$BASE_URL/rules/$RULE_ID/run" | jq -r '.run_id')
+curl -s -X PUT "$BASE_URL/rules/$RULE_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"is_active":false}' > /dev/null
+curl -s -X PUT "$BASE_URL/transactions/$TRANSACTION_ID" \
+  -H "Content-Type: application/json" \
+  -d '{"category":"Test History Treats"}' > /dev/null
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" -X POST "$BASE_URL/transaction-changes/runs/$RUN_ID/undo")
+CATEGORY=$(curl -s "$BASE_URL/transactions/$TRANSACTION_ID" | jq -r '.category')
+echo "Undo after edit: $STATUS, category: $CATEGORY (expected: 400, Test History Treats)"
+if [ "$STATUS" != "400" ] || [ "$CATEGORY" != "Test History Treats" ]; then
+  echo "❌ Test failed: undo overwrote a later edit"
+  exit 1
+fi
+
+echo "✅ Transaction history and undo behave as expected"
+
+# Clean up
+echo "Cleaning up..."
+curl -s -X DELETE "$BASE_URL/rules/$RULE_ID"
+curl -s -X DELETE "$BASE_URL/transactions/$TRANSACTION_ID"
+for ID in $(curl -s "$BASE_URL/tags" | jq -r '.[] | select(.name == "test-history") | .id'); do
+  curl -s -X DELETE "$BASE_URL/tags/$ID"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r '.[] | select(.name == "Test History Shop") | .id')"
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+
+echo "Test completed successfully!"
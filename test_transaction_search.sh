@@ -0,0 +1,104 @@
+#!/bin/bash
+set -e
+
+# Test script for the transaction search endpoint
+# Free text, amount range, account, category group, budget, uncategorized and tag filters must combine,
+# and the total count must cover all matches regardless of the page.
+# Run with CURL_HOME pointing at a logged in session (see README).
+
+echo "Testing transaction search..."
+
+# Base URL for the API
+BASE_URL="http://localhost:3000/api"
+
+TODAY=$(date +%Y-%m-%d)
+
+# Create test accounts, a category group with a category and a budget
+echo "Creating test data..."
+ACCOUNT_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Search Checking","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+SAVINGS_ID=$(curl -s -X POST "$BASE_URL/accounts" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Search Savings","account_type":"On Budget","balance":0.0,"currency":"USD"}' \
+  | jq -r '.id')
+GROUP_ID=$(curl -s -X POST "$BASE_URL/category-groups" \
+  -H "Content-Type: application/json" \
+  -d '{"name":"Test Search Group"}' | jq -r '.id')
+CATEGORY_ID=$(curl -s -X POST "$BASE_URL/categories" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Search Groceries\",\"group_id\":\"$GROUP_ID\"}" | jq -r '.id')
+BUDGET_ID=$(curl -s -X POST "$BASE_URL/budgets" \
+  -H "Content-Type: application/json" \
+  -d "{\"name\":\"Test Search Budget\",\"amount\":100.0,\"start_date\":\"${TODAY}T00:00:00Z\"}" | jq -r '.id')
+
+create_transaction() {
+  curl -s -X POST "$BASE_URL/transactions" -H "Content-Type: application/json" -d "$1" | jq -r '.id'
+}
+
+T1=$(create_transaction "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Search Supermarket\",\"description\":\"Weekly groceries zqxsearch\",\"amount\":45.50,\"category\":\"Test Search Groceries\",\"budget_id\":\"$BUDGET_ID\"}")
+T2=$(create_transaction "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Search Bakery\",\"description\":\"Bread zqxsearch\",\"amount\":5.25,\"category\":\"Test Search Groceries\",\"tags\":[\"test-search-tag\"]}")
+T3=$(create_transaction "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_name\":\"Test Search Electronics\",\"description\":\"Headphones zqxsearch\",\"amount\":120.00,\"category\":\"\"}")
+T4=$(create_transaction "{\"source_account_id\":\"$ACCOUNT_ID\",\"destination_account_id\":\"$SAVINGS_ID\",\"description\":\"Savings zqxsearch\",\"amount\":300.00,\"category\":\"Transfer\"}")
+
+check() {
+  local label="$1" query="$2" expected="$3"
+  local actual
+  actual=$(curl -s "$BASE_URL/transactions/search?$query" | jq -c '[.total, ([.transactions[].description] | sort)]')
+  echo "$label: $actual"
+  if [ "$actual" != "$expected" ]; then
+    echo "❌ Test failed: $label, expected $expected"
+    exit 1
+  fi
+}
+
+# Free text matches word prefixes in description and destination name
+check "Text" "q=zqxsearch%20headph" '[1,["Headphones zqxsearch"]]'
+check "Destination text" "q=zqxsearch%20bakery" '[1,["Bread zqxsearch"]]'
+check "Amount range" "q=zqxsearch&min_amount=5&max_amount=50" '[2,["Bread zqxsearch","Weekly groceries zqxsearch"]]'
+check "Destination account" "destination_account_id=$SAVINGS_ID" '[1,["Savings zqxsearch"]]'
+check "Either side of an account" "q=zqxsearch&account_id=$SAVINGS_ID" '[1,["Savings zqxsearch"]]'
+check "Category group" "category_group_id=$GROUP_ID" '[2,["Bread zqxsearch","Weekly groceries zqxsearch"]]'
+check "Budget" "budget_id=$BUDGET_ID" '[1,["Weekly groceries zqxsearch"]]'
+check "Uncategorized" "q=zqxsearch&uncategorized=true" '[1,["Headphones zqxsearch"]]'
+check "Tag" "q=zqxsearch&tag=test-search-tag" '[1,["Bread zqxsearch"]]'
+check "Date range" "q=zqxsearch&start_date=2000-01-01&end_date=2000-12-31" '[0,[]]'
+
+# The total counts all matches while the page is limited; sorting by amount
+PAGE=$(curl -s "$BASE_URL/transactions/search?q=zqxsearch&sort=amount&order=asc&limit=2&offset=1")
+RESULT=$(echo "$PAGE" | jq -c '[.total, [.transactions[].amount | tonumber]]')
+echo "Page 2 by amount: $RESULT (expected: [4,[45.5,120]])"
+if [ "$RESULT" != "[4,[45.5,120]]" ]; then
+  echo "❌ Test failed: paging or sorting is wrong"
+  exit 1
+fi
+
+# Invalid sort fields are rejected
+STATUS=$(curl -s -o /dev/null -w "%{http_code}" "$BASE_URL/transactions/search?sort=color")
+echo "Invalid sort: $STATUS (expected: 400)"
+if [ "$STATUS" != "400" ]; then
+  echo "❌ Test failed: invalid sort was accepted"
+  exit 1
+fi
+
+echo "✅ Transaction search behaves as expected"
+
+# Clean up
+echo "Cleaning up..."
+for ID in $T1 $T2 $T3 $T4; do
+  curl -s -X DELETE "$BASE_URL/transactions/$ID"
+done
+for NAME in "Test Search Supermarket" "Test Search Bakery" "Test Search Electronics"; do
+  curl -s -X DELETE "$BASE_URL/accounts/$(curl -s "$BASE_URL/accounts" | jq -r --arg name "$NAME" '.[] | select(.name == $name) | .id')"
+done
+curl -s -X DELETE "$BASE_URL/accounts/$ACCOUNT_ID"
+curl -s -X DELETE "$BASE_URL/accounts/$SAVINGS_ID"
+curl -s -X DELETE "$BASE_URL/budgets/$BUDGET_ID"
+curl -s -X DELETE "$BASE_URL/categories/$CATEGORY_ID"
+# The uncategorized transaction created an empty-named category
+curl -s -X DELETE "$BASE_URL/categories/$(curl -s "$BASE_URL/categories" | jq -r '.[] | select(.name == "") | .id')"
+curl -s -X DELETE "$BASE_URL/category-groups/$GROUP_ID"
+curl -s -X DELETE "$BASE_URL/tags/$(curl -s "$BASE_URL/tags" | jq -r '.[] | select(.name == "test-search-tag") | .id')"
+
+echo "Test completed successfully!"